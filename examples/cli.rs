@@ -15,7 +15,8 @@
 use simple_logger;
 
 ///! A small command-line app to run the parser.
-/// Run with `cargo run --example cli`
+/// Run with `cargo run --example cli`, adding `--features serde` to also
+/// print the AST as JSON
 use std::fs;
 
 use sqlparser::dialect::*;
@@ -59,6 +60,11 @@ fn main() {
                     .join("\n")
             );
             println!("Parse results:\n{:#?}", statements);
+            #[cfg(feature = "serde")]
+            {
+                let serialized = serde_json::to_string_pretty(&statements).unwrap();
+                println!("Serialized as JSON:\n{}", serialized);
+            }
             std::process::exit(0);
         }
         Err(e) => {
@@ -0,0 +1,329 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The tables a statement reads and writes, e.g. to check its privileges.
+//!
+//! ```
+//! use sqlparser::access::table_access;
+//! use sqlparser::dialect::GenericDialect;
+//! use sqlparser::parser::Parser;
+//!
+//! let sql = "INSERT INTO archive WITH old AS (SELECT * FROM orders) \
+//!            SELECT * FROM old WHERE id IN (SELECT order_id FROM refunds)";
+//! let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+//! let access = table_access(&statements[0]);
+//! let names = |names: &[_]| names.iter().map(ToString::to_string).collect::<Vec<_>>();
+//! assert_eq!(names(&access.reads), vec!["orders", "refunds"]);
+//! assert_eq!(names(&access.writes), vec!["archive"]);
+//! ```
+
+use crate::ast::*;
+use crate::semantic::normalize_ident;
+use std::ops::ControlFlow;
+
+/// The result of [table_access]. Each list has every name once, spelled as
+/// it first appears in the statement.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableAccess {
+    /// The tables and views the statement reads, including in subqueries,
+    /// CTEs and joins, and the tables referred to by its foreign keys
+    pub reads: Vec<ObjectName>,
+    /// The tables the statement changes or creates, and the objects it
+    /// drops or renames, of whatever type
+    pub writes: Vec<ObjectName>,
+}
+
+/// Return the tables that `statement` reads and writes. References to
+/// common table expressions are not tables, so they are left out, but a
+/// reference in a CTE to itself or to a later CTE is to a table, as the
+/// parser does not support `WITH RECURSIVE`. Table-valued functions count
+/// as tables they read, as the parser cannot tell them apart from tables
+/// with MS SQL hints such as `t (NOLOCK)`.
+pub fn table_access(statement: &Statement) -> TableAccess {
+    let mut reader = Reader::default();
+    let _ = walk_statement(&mut reader, statement);
+    let mut access = TableAccess {
+        reads: reader.reads,
+        writes: vec![],
+    };
+    access.add_statement(statement);
+    access
+}
+
+impl TableAccess {
+    /// Add the tables that `statement` writes, and the ones its foreign
+    /// keys and `SHOW` read, which the [Reader] does not see
+    fn add_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Insert { table_name, .. }
+            | Statement::Copy { table_name, .. }
+            | Statement::Update { table_name, .. }
+            | Statement::Delete { table_name, .. }
+            | Statement::CreateIndex { table_name, .. } => add(&mut self.writes, table_name),
+            Statement::CreateView { name, .. } => add(&mut self.writes, name),
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                ..
+            } => {
+                add(&mut self.writes, name);
+                for column in columns {
+                    self.add_column(&column.node);
+                }
+                for constraint in constraints {
+                    self.add_constraint(constraint);
+                }
+            }
+            Statement::AlterTable {
+                name, operation, ..
+            } => {
+                add(&mut self.writes, name);
+                match operation {
+                    AlterTableOperation::AddConstraint(constraint) => {
+                        self.add_constraint(constraint)
+                    }
+                    AlterTableOperation::AddColumn { column_def } => self.add_column(column_def),
+                    AlterTableOperation::RenameTable { table_name } => {
+                        add(&mut self.writes, table_name)
+                    }
+                    _ => {}
+                }
+            }
+            Statement::Drop { names, .. } => {
+                for name in names {
+                    add(&mut self.writes, name);
+                }
+            }
+            Statement::ShowColumns { table_name, .. } => add(&mut self.reads, table_name),
+            Statement::Commented { statement, .. } => self.add_statement(statement),
+            _ => {}
+        }
+    }
+
+    fn add_column(&mut self, column: &ColumnDef) {
+        for option in &column.options {
+            if let ColumnOption::ForeignKey { foreign_table, .. } = &option.option {
+                add(&mut self.reads, foreign_table);
+            }
+        }
+    }
+
+    fn add_constraint(&mut self, constraint: &TableConstraint) {
+        if let TableConstraint::ForeignKey { foreign_table, .. } = constraint {
+            add(&mut self.reads, foreign_table);
+        }
+    }
+}
+
+fn add(names: &mut Vec<ObjectName>, name: &ObjectName) {
+    if !names.contains(name) {
+        names.push(name.clone());
+    }
+}
+
+/// Collects the tables in FROM clauses that are not CTEs
+#[derive(Default)]
+struct Reader {
+    reads: Vec<ObjectName>,
+    ctes: Ctes,
+}
+
+impl Visitor for Reader {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<()> {
+        self.ctes.enter(query);
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &Query) -> ControlFlow<()> {
+        self.ctes.exit();
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table_factor(&mut self, table_factor: &TableFactor) -> ControlFlow<()> {
+        if let TableFactor::Table { name, .. } = table_factor {
+            if !self.ctes.contains(name) {
+                add(&mut self.reads, name);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// The CTEs visible where a visitor is in a statement, which it keeps up to
+/// date by calling [Ctes::enter] and [Ctes::exit] as it enters and leaves
+/// each query
+#[derive(Default)]
+pub(crate) struct Ctes {
+    /// The queries being walked, innermost last
+    queries: Vec<QueryScope>,
+}
+
+struct QueryScope {
+    /// The names of the CTEs of the query
+    ctes: Vec<String>,
+    /// How many of the queries directly inside the query have been walked.
+    /// The CTEs are walked first, so a CTE sees as many of the CTEs before
+    /// it as there are walked queries.
+    walked: usize,
+}
+
+impl QueryScope {
+    fn visible_ctes(&self) -> &[String] {
+        &self.ctes[..self.walked.min(self.ctes.len())]
+    }
+}
+
+impl Ctes {
+    pub(crate) fn enter(&mut self, query: &Query) {
+        self.queries.push(QueryScope {
+            ctes: query
+                .ctes
+                .iter()
+                .map(|cte| normalize_ident(&cte.alias.name))
+                .collect(),
+            walked: 0,
+        });
+    }
+
+    pub(crate) fn exit(&mut self) {
+        self.queries.pop();
+        if let Some(parent) = self.queries.last_mut() {
+            parent.walked += 1;
+        }
+    }
+
+    /// Whether the table reference `name` refers to a CTE
+    pub(crate) fn contains(&self, name: &ObjectName) -> bool {
+        match name.0.as_slice() {
+            [ident] => {
+                let name = normalize_ident(ident);
+                self.queries
+                    .iter()
+                    .any(|query| query.visible_ctes().contains(&name))
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::PostgreSqlDialect;
+    use crate::parser::Parser;
+
+    fn access(sql: &str) -> (Vec<String>, Vec<String>) {
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql.to_string()).unwrap();
+        let access = table_access(&statements[0]);
+        let names = |names: Vec<ObjectName>| names.iter().map(ToString::to_string).collect();
+        (names(access.reads), names(access.writes))
+    }
+
+    fn reads(sql: &str) -> Vec<String> {
+        let (reads, writes) = access(sql);
+        assert_eq!(writes, Vec::<String>::new(), "{}", sql);
+        reads
+    }
+
+    #[test]
+    fn test_reads() {
+        assert_eq!(
+            reads(
+                "SELECT * FROM a JOIN (b CROSS JOIN s.c) ON TRUE, \
+                 (SELECT * FROM d) AS x \
+                 WHERE EXISTS (SELECT 1 FROM e) AND y IN (SELECT y FROM f) \
+                 AND z = (SELECT MAX(z) FROM a)"
+            ),
+            vec!["a", "b", "s.c", "d", "e", "f"]
+        );
+        assert_eq!(
+            reads("SELECT * FROM a UNION SELECT * FROM generate_series(1, 10)"),
+            vec!["a", "generate_series"]
+        );
+        assert_eq!(reads("SHOW COLUMNS FROM t"), vec!["t"]);
+        assert_eq!(reads("COMMIT"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_ctes_are_not_tables() {
+        assert_eq!(
+            reads(
+                "WITH x AS (SELECT * FROM a), y AS (SELECT * FROM x JOIN b ON TRUE) \
+                 SELECT * FROM y, X, (SELECT * FROM x) AS sub, s.x"
+            ),
+            vec!["a", "b", "s.x"]
+        );
+        // A CTE only sees the CTEs before it, so these are tables
+        assert_eq!(
+            reads("WITH x AS (SELECT * FROM x), y AS (SELECT * FROM z), z AS (SELECT 1) SELECT * FROM y"),
+            vec!["x", "z"]
+        );
+        // CTEs are visible in nested queries, and only there
+        assert_eq!(
+            reads(
+                "SELECT * FROM (WITH x AS (SELECT * FROM a) SELECT * FROM x) AS sub \
+                 WHERE EXISTS (SELECT * FROM x)"
+            ),
+            vec!["a", "x"]
+        );
+        assert_eq!(
+            reads("WITH \"X\" AS (SELECT 1) SELECT * FROM \"X\", x"),
+            vec!["x"]
+        );
+    }
+
+    #[test]
+    fn test_writes() {
+        let both = |reads: &[&str], writes: &[&str]| {
+            let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+            (names(reads), names(writes))
+        };
+        assert_eq!(
+            access("INSERT INTO t (a) SELECT a FROM t JOIN u ON TRUE"),
+            both(&["t", "u"], &["t"])
+        );
+        assert_eq!(
+            access("UPDATE t SET a = (SELECT b FROM u) WHERE c IN (SELECT c FROM v)"),
+            both(&["u", "v"], &["t"])
+        );
+        assert_eq!(
+            access("DELETE FROM t WHERE EXISTS (SELECT 1 FROM u)"),
+            both(&["u"], &["t"])
+        );
+        assert_eq!(access("COPY t (a) FROM stdin;\n\\.\n"), both(&[], &["t"]));
+        assert_eq!(
+            access(
+                "CREATE TABLE t (a INT REFERENCES u (id), b INT, \
+                 FOREIGN KEY (b) REFERENCES v (id))"
+            ),
+            both(&["u", "v"], &["t"])
+        );
+        assert_eq!(
+            access("CREATE VIEW w AS SELECT * FROM t"),
+            both(&["t"], &["w"])
+        );
+        assert_eq!(access("CREATE INDEX i ON t (a)"), both(&[], &["t"]));
+        assert_eq!(
+            access("ALTER TABLE t ADD CONSTRAINT fk FOREIGN KEY (a) REFERENCES u (id)"),
+            both(&["u"], &["t"])
+        );
+        assert_eq!(
+            access("ALTER TABLE t RENAME TO t2"),
+            both(&[], &["t", "t2"])
+        );
+        assert_eq!(access("DROP TABLE t, s.u"), both(&[], &["t", "s.u"]));
+        assert_eq!(access("-- clean up\nDROP VIEW w"), both(&[], &["w"]));
+    }
+}
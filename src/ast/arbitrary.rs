@@ -0,0 +1,927 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Random ASTs, generated with the [arbitrary] crate
+//!
+//! The generated ASTs are limited to ones the parser can produce, so that
+//! printing one and parsing the result with the `GenericDialect` gives the
+//! same AST back, as checked by [crate::test_utils::assert_round_trip]. In
+//! particular, operands are wrapped in `Expr::Nested` where their precedence
+//! requires it. Comments, `COPY` statements, custom operators and the data
+//! types without a parser (e.g. `BLOB`) are never generated.
+//!
+//! The depth of the generated trees and the length of the lists in them are
+//! bounded, so that each value stays small enough to print and reparse.
+
+use super::*;
+use crate::dialect::keywords::ALL_KEYWORDS;
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+/// The maximum nesting of expressions, queries and table factors
+const MAX_DEPTH: usize = 4;
+/// The maximum number of items in a list, e.g. the projection of a `SELECT`
+const MAX_LIST_LEN: usize = 3;
+/// The maximum length of a generated identifier or string
+const MAX_STRING_LEN: usize = 8;
+
+const IDENT_START: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const IDENT_PART: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789_";
+const QUOTED_IDENT_CHARS: &[u8] = b"abcxyzABCXYZ019 _-.'";
+const STRING_CHARS: &[u8] = b"abcxyzABCXYZ019 _-%'\"";
+const HEX_CHARS: &[u8] = b"0123456789abcdefABCDEF";
+const DIGITS: &[u8] = b"0123456789";
+
+fn set_precedence(op: &SetOperator) -> u8 {
+    match op {
+        SetOperator::Union | SetOperator::Except => 10,
+        SetOperator::Intersect => 20,
+    }
+}
+
+/// Generates ASTs from unstructured data, keeping track of the nesting depth
+#[derive(Default)]
+struct Generator {
+    depth: usize,
+}
+
+impl Generator {
+    fn deep(&self) -> bool {
+        self.depth >= MAX_DEPTH
+    }
+
+    fn nested<'a, T, F>(&mut self, u: &mut Unstructured<'a>, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self, &mut Unstructured<'a>) -> Result<T>,
+    {
+        self.depth += 1;
+        let result = f(self, u);
+        self.depth -= 1;
+        result
+    }
+
+    /// Choose one of `0..=max`, or 0 when the tree is already deep enough
+    fn choice(&self, u: &mut Unstructured, max: u8) -> Result<u8> {
+        if self.deep() {
+            Ok(0)
+        } else {
+            u.int_in_range(0..=max)
+        }
+    }
+
+    fn list<'a, T, F>(&mut self, u: &mut Unstructured<'a>, min: usize, mut f: F) -> Result<Vec<T>>
+    where
+        F: FnMut(&mut Self, &mut Unstructured<'a>) -> Result<T>,
+    {
+        let len = if self.deep() {
+            min
+        } else {
+            u.int_in_range(min..=MAX_LIST_LEN.max(min))?
+        };
+        (0..len).map(|_| f(self, u)).collect()
+    }
+
+    fn option<'a, T, F>(&mut self, u: &mut Unstructured<'a>, f: F) -> Result<Option<T>>
+    where
+        F: FnOnce(&mut Self, &mut Unstructured<'a>) -> Result<T>,
+    {
+        if u.arbitrary()? {
+            Ok(Some(f(self, u)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn string(&mut self, u: &mut Unstructured, chars: &[u8], min: usize) -> Result<String> {
+        let len = u.int_in_range(min..=MAX_STRING_LEN)?;
+        (0..len).map(|_| Ok(*u.choose(chars)? as char)).collect()
+    }
+
+    fn ident(&mut self, u: &mut Unstructured) -> Result<Ident> {
+        if u.int_in_range(0..=7)? == 7 {
+            let value = self.string(u, QUOTED_IDENT_CHARS, 1)?;
+            return Ok(Ident::with_quote('"', value));
+        }
+        let mut value = (*u.choose(IDENT_START)? as char).to_string();
+        value.push_str(&self.string(u, IDENT_PART, 0)?);
+        while ALL_KEYWORDS
+            .binary_search(&value.to_uppercase().as_str())
+            .is_ok()
+        {
+            value.push('_');
+        }
+        Ok(Ident::new(value))
+    }
+
+    fn idents(&mut self, u: &mut Unstructured, min: usize) -> Result<Vec<Ident>> {
+        self.list(u, min, Self::ident)
+    }
+
+    fn object_name(&mut self, u: &mut Unstructured) -> Result<ObjectName> {
+        let len = u.int_in_range(1..=3)?;
+        Ok(ObjectName(
+            (0..len).map(|_| self.ident(u)).collect::<Result<_>>()?,
+        ))
+    }
+
+    fn number(&mut self, u: &mut Unstructured) -> Result<Value> {
+        let mut n = self.string(u, DIGITS, 1)?;
+        if u.arbitrary()? {
+            n.push('.');
+            n.push_str(&self.string(u, DIGITS, 1)?);
+        }
+        Ok(Value::Number(n.parse().unwrap()))
+    }
+
+    /// A value accepted where only a literal is allowed, e.g. in `SET`
+    fn literal_value(&mut self, u: &mut Unstructured) -> Result<Value> {
+        Ok(match u.int_in_range(0..=5)? {
+            0 => self.number(u)?,
+            1 => Value::SingleQuotedString(self.string(u, STRING_CHARS, 0)?),
+            2 => Value::NationalStringLiteral(self.string(u, STRING_CHARS, 0)?),
+            3 => Value::HexStringLiteral(self.string(u, HEX_CHARS, 0)?),
+            4 => Value::Boolean(u.arbitrary()?),
+            _ => Value::Null,
+        })
+    }
+
+    fn value(&mut self, u: &mut Unstructured) -> Result<Value> {
+        Ok(match u.int_in_range(0..=9)? {
+            0 => Value::Date(self.string(u, STRING_CHARS, 0)?),
+            1 => Value::Time(self.string(u, STRING_CHARS, 0)?),
+            2 => Value::Timestamp(self.string(u, STRING_CHARS, 0)?),
+            3 => self.interval(u)?,
+            _ => self.literal_value(u)?,
+        })
+    }
+
+    /// A placeholder in one of the styles of [crate::dialect::GenericDialect]
+    fn placeholder(&mut self, u: &mut Unstructured) -> Result<String> {
+        let n: u8 = u.int_in_range(1..=20)?;
+        Ok(match u.int_in_range(0..=2)? {
+            0 => "?".to_string(),
+            1 => format!("${}", n),
+            _ => format!(":p{}", n),
+        })
+    }
+
+    /// An `INTERVAL` literal with a qualifier the parser accepts: `SECOND`
+    /// has the `(<leading_precision>, <fractional_seconds_precision>)` form,
+    /// and the other fields only take a fractional seconds precision after
+    /// `TO SECOND`.
+    fn interval(&mut self, u: &mut Unstructured) -> Result<Value> {
+        let value = self.string(u, STRING_CHARS, 0)?;
+        let leading_field = DateTimeField::arbitrary(u)?;
+        let leading_precision = self.option(u, |_, u| u.int_in_range(0..=9))?;
+        let (last_field, fractional_seconds_precision) = if leading_field == DateTimeField::Second {
+            let fsec = match leading_precision {
+                Some(_) => self.option(u, |_, u| u.int_in_range(0..=9))?,
+                None => None,
+            };
+            (None, fsec)
+        } else {
+            let last_field = self.option(u, |_, u| DateTimeField::arbitrary(u))?;
+            let fsec = match last_field {
+                Some(DateTimeField::Second) => self.option(u, |_, u| u.int_in_range(0..=9))?,
+                _ => None,
+            };
+            (last_field, fsec)
+        };
+        Ok(Value::Interval {
+            value,
+            leading_field,
+            leading_precision,
+            last_field,
+            fractional_seconds_precision,
+        })
+    }
+
+    fn data_type(&mut self, u: &mut Unstructured) -> Result<DataType> {
+        let length = |u: &mut Unstructured| u.int_in_range(1..=255);
+        Ok(match u.int_in_range(0..=19)? {
+            0 => DataType::Char(self.option(u, |_, u| length(u))?),
+            1 => DataType::Varchar(self.option(u, |_, u| length(u))?),
+            2 => DataType::Uuid,
+            3 => match self.option(u, |_, u| length(u))? {
+                Some(precision) => {
+                    DataType::Decimal(Some(precision), self.option(u, |_, u| length(u))?)
+                }
+                None => DataType::Decimal(None, None),
+            },
+            4 => DataType::Float(self.option(u, |_, u| length(u))?),
+            5 => DataType::SmallInt,
+            6 => DataType::Int,
+            7 => DataType::BigInt,
+            8 => DataType::Real,
+            9 => DataType::Double,
+            10 => DataType::Boolean,
+            11 => DataType::Date,
+            12 => DataType::Time,
+            13 => DataType::Timestamp,
+            14 => DataType::Interval,
+            15 => DataType::Regclass,
+            16 => DataType::Text,
+            17 => DataType::Bytea,
+            18 => DataType::Array(Box::new(DataType::Text)),
+            _ => DataType::Custom(self.object_name(u)?),
+        })
+    }
+
+    fn expr(&mut self, u: &mut Unstructured) -> Result<Expr> {
+        let choice = self.choice(u, 20)?;
+        self.nested(u, |g, u| {
+            Ok(match choice {
+                0..=2 => Expr::Identifier(g.ident(u)?),
+                3 => Expr::CompoundIdentifier(g.list(u, 2, Self::ident)?),
+                4..=5 => Expr::Value(g.value(u)?, NodeSpan::empty()),
+                6 => Expr::Placeholder(g.placeholder(u)?, NodeSpan::empty()),
+                7 => {
+                    let op = BinaryOperator::arbitrary(u)?;
+                    let precedence = op.precedence();
+                    Expr::BinaryOp {
+                        left: Box::new(g.left_operand(u, precedence)?),
+                        op,
+                        right: Box::new(g.operand(u, precedence + 1)?),
+                    }
+                }
+                8 => {
+                    let op = UnaryOperator::arbitrary(u)?;
+                    let precedence = op.precedence();
+                    Expr::UnaryOp {
+                        op,
+                        expr: Box::new(g.operand(u, precedence + 1)?),
+                        span: NodeSpan::empty(),
+                    }
+                }
+                9 => {
+                    let expr = Box::new(g.left_operand(u, precedence::IS)?);
+                    if u.arbitrary()? {
+                        Expr::IsNotNull(expr, NodeSpan::empty())
+                    } else {
+                        Expr::IsNull(expr, NodeSpan::empty())
+                    }
+                }
+                10 => Expr::InList {
+                    expr: Box::new(g.left_operand(u, precedence::COMPARISON)?),
+                    list: g.list(u, 1, Self::expr)?,
+                    negated: u.arbitrary()?,
+                    span: NodeSpan::empty(),
+                },
+                11 => Expr::InSubquery {
+                    expr: Box::new(g.left_operand(u, precedence::COMPARISON)?),
+                    subquery: Box::new(g.subquery(u)?),
+                    negated: u.arbitrary()?,
+                    span: NodeSpan::empty(),
+                },
+                12 => Expr::Between {
+                    expr: Box::new(g.left_operand(u, precedence::COMPARISON)?),
+                    negated: u.arbitrary()?,
+                    low: Box::new(g.operand(u, precedence::COMPARISON + 1)?),
+                    high: Box::new(g.operand(u, precedence::COMPARISON + 1)?),
+                },
+                13 => Expr::Cast {
+                    expr: Box::new(g.expr(u)?),
+                    data_type: g.data_type(u)?,
+                    span: NodeSpan::empty(),
+                },
+                14 => Expr::Extract {
+                    field: DateTimeField::arbitrary(u)?,
+                    expr: Box::new(g.expr(u)?),
+                    span: NodeSpan::empty(),
+                },
+                // COLLATE can only follow a prefix expression
+                15 => Expr::Collate {
+                    expr: Box::new(g.left_operand(u, 100)?),
+                    collation: g.object_name(u)?,
+                },
+                16 => Expr::Nested(Box::new(g.expr(u)?), NodeSpan::empty()),
+                17 => Expr::Function(g.function(u)?),
+                18 => g.case(u)?,
+                19 => Expr::Exists(Box::new(g.subquery(u)?), NodeSpan::empty()),
+                _ => Expr::Subquery(Box::new(g.subquery(u)?), NodeSpan::empty()),
+            })
+        })
+    }
+
+    /// An expression that binds at least as tightly as `min_precedence`,
+    /// parenthesizing it if needed
+    fn operand(&mut self, u: &mut Unstructured, min_precedence: u8) -> Result<Expr> {
+        Ok(self.expr(u)?.operand(min_precedence))
+    }
+
+    /// Like [Generator::operand], for an operand followed by an operator
+    fn left_operand(&mut self, u: &mut Unstructured, min_precedence: u8) -> Result<Expr> {
+        Ok(self.expr(u)?.left_operand(min_precedence))
+    }
+
+    fn function(&mut self, u: &mut Unstructured) -> Result<Function> {
+        Ok(Function {
+            name: self.object_name(u)?,
+            args: self.list(u, 0, |g, u| {
+                Ok(match u.int_in_range(0..=7)? {
+                    0 => Expr::Wildcard(NodeSpan::empty()),
+                    1 => Expr::QualifiedWildcard(g.list(u, 1, Self::ident)?, NodeSpan::empty()),
+                    _ => g.expr(u)?,
+                })
+            })?,
+            over: self.option(u, Self::window_spec)?,
+            distinct: u.arbitrary()?,
+            span: NodeSpan::empty(),
+        })
+    }
+
+    fn window_spec(&mut self, u: &mut Unstructured) -> Result<WindowSpec> {
+        Ok(WindowSpec {
+            partition_by: self.list(u, 0, Self::expr)?,
+            order_by: self.list(u, 0, Self::order_by_expr)?,
+            window_frame: self.option(u, |_, u| {
+                Ok(WindowFrame {
+                    units: WindowFrameUnits::arbitrary(u)?,
+                    start_bound: WindowFrameBound::arbitrary(u)?,
+                    end_bound: Option::<WindowFrameBound>::arbitrary(u)?,
+                })
+            })?,
+        })
+    }
+
+    fn case(&mut self, u: &mut Unstructured) -> Result<Expr> {
+        let operand = self.option(u, Self::expr)?.map(Box::new);
+        let len = u.int_in_range(1..=MAX_LIST_LEN)?;
+        let mut conditions = vec![];
+        let mut results = vec![];
+        for _ in 0..len {
+            conditions.push(self.expr(u)?);
+            results.push(self.expr(u)?);
+        }
+        Ok(Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result: self.option(u, Self::expr)?.map(Box::new),
+            span: NodeSpan::empty(),
+        })
+    }
+
+    fn order_by_expr(&mut self, u: &mut Unstructured) -> Result<OrderByExpr> {
+        Ok(OrderByExpr {
+            expr: self.expr(u)?,
+            asc: u.arbitrary()?,
+        })
+    }
+
+    fn query(&mut self, u: &mut Unstructured) -> Result<Query> {
+        self.query_with(u, false)
+    }
+
+    /// A query starting with `SELECT` or `WITH`, as required after the
+    /// opening parenthesis of a subquery expression
+    fn subquery(&mut self, u: &mut Unstructured) -> Result<Query> {
+        self.query_with(u, true)
+    }
+
+    fn query_with(&mut self, u: &mut Unstructured, leading_select: bool) -> Result<Query> {
+        self.nested(u, |g, u| {
+            let ctes = g.list(u, 0, |g, u| {
+                Ok(Cte {
+                    alias: g.table_alias(u)?,
+                    query: g.query(u)?,
+                })
+            })?;
+            let body = g.set_expr(u, 0, leading_select && ctes.is_empty())?;
+            let number = |g: &mut Self, u: &mut Unstructured| {
+                Ok(Expr::Value(g.number(u)?, NodeSpan::empty()))
+            };
+            Ok(Query {
+                ctes,
+                body,
+                order_by: g.list(u, 0, Self::order_by_expr)?,
+                limit: g.option(u, number)?,
+                offset: g.option(u, number)?,
+                fetch: g.option(u, |g, u| {
+                    let quantity = g.option(u, number)?;
+                    Ok(Fetch {
+                        with_ties: u.arbitrary()?,
+                        percent: quantity.is_some() && u.arbitrary()?,
+                        quantity,
+                    })
+                })?,
+                span: NodeSpan::empty(),
+            })
+        })
+    }
+
+    /// A query body whose set operations bind at least as tightly as
+    /// `min_precedence`
+    fn set_expr(
+        &mut self,
+        u: &mut Unstructured,
+        min_precedence: u8,
+        leading_select: bool,
+    ) -> Result<SetExpr> {
+        let ops: &[SetOperator] = match min_precedence {
+            0..=10 => &[
+                SetOperator::Union,
+                SetOperator::Except,
+                SetOperator::Intersect,
+            ],
+            11..=20 => &[SetOperator::Intersect],
+            _ => &[],
+        };
+        let choice = self.choice(u, 6)?;
+        self.nested(u, |g, u| {
+            Ok(match choice {
+                3 if !leading_select => {
+                    SetExpr::Values(Values(g.list(u, 1, |g, u| g.list(u, 1, Self::expr))?))
+                }
+                4 if !leading_select => SetExpr::Query(Box::new(g.query(u)?)),
+                5 | 6 if !ops.is_empty() => {
+                    let op = u.choose(ops)?.clone();
+                    let precedence = set_precedence(&op);
+                    SetExpr::SetOperation {
+                        left: Box::new(g.set_expr(u, precedence, leading_select)?),
+                        all: u.arbitrary()?,
+                        right: Box::new(g.set_expr(u, precedence + 1, false)?),
+                        op,
+                    }
+                }
+                _ => SetExpr::Select(Box::new(g.select(u)?)),
+            })
+        })
+    }
+
+    fn select(&mut self, u: &mut Unstructured) -> Result<Select> {
+        Ok(Select {
+            distinct: u.arbitrary()?,
+            projection: self.list(u, 1, Self::select_item)?,
+            from: self.list(u, 0, Self::table_with_joins)?,
+            selection: self.option(u, Self::expr)?,
+            group_by: self.list(u, 0, Self::expr)?,
+            having: self.option(u, Self::expr)?,
+            comments: ClauseComments::default(),
+            span: NodeSpan::empty(),
+        })
+    }
+
+    fn select_item(&mut self, u: &mut Unstructured) -> Result<SelectItem> {
+        Ok(match u.int_in_range(0..=7)? {
+            0..=3 => SelectItem::UnnamedExpr(self.expr(u)?),
+            4 | 5 => SelectItem::ExprWithAlias {
+                expr: self.expr(u)?,
+                alias: self.ident(u)?,
+            },
+            6 => SelectItem::QualifiedWildcard(self.object_name(u)?, NodeSpan::empty()),
+            _ => SelectItem::Wildcard(NodeSpan::empty()),
+        })
+    }
+
+    fn table_with_joins(&mut self, u: &mut Unstructured) -> Result<TableWithJoins> {
+        Ok(TableWithJoins {
+            relation: self.table_factor(u)?,
+            joins: self.list(u, 0, Self::join)?,
+        })
+    }
+
+    fn table_factor(&mut self, u: &mut Unstructured) -> Result<TableFactor> {
+        let choice = self.choice(u, 5)?;
+        self.nested(u, |g, u| {
+            Ok(match choice {
+                4 => TableFactor::Derived {
+                    lateral: u.arbitrary()?,
+                    subquery: Box::new(g.subquery(u)?),
+                    alias: g.option(u, Self::table_alias)?,
+                    span: NodeSpan::empty(),
+                },
+                // A parenthesized table must be followed by at least one join
+                5 => TableFactor::NestedJoin(
+                    Box::new(TableWithJoins {
+                        relation: g.table(u)?,
+                        joins: g.list(u, 1, Self::join)?,
+                    }),
+                    NodeSpan::empty(),
+                ),
+                _ => g.table(u)?,
+            })
+        })
+    }
+
+    fn table(&mut self, u: &mut Unstructured) -> Result<TableFactor> {
+        Ok(TableFactor::Table {
+            name: self.object_name(u)?,
+            alias: self.option(u, Self::table_alias)?,
+            args: self.list(u, 0, Self::expr)?,
+            with_hints: self.list(u, 0, Self::expr)?,
+            span: NodeSpan::empty(),
+        })
+    }
+
+    fn table_alias(&mut self, u: &mut Unstructured) -> Result<TableAlias> {
+        Ok(TableAlias {
+            name: self.ident(u)?,
+            columns: self.idents(u, 0)?,
+        })
+    }
+
+    fn join(&mut self, u: &mut Unstructured) -> Result<Join> {
+        let relation = self.table_factor(u)?;
+        let constraint = |g: &mut Self, u: &mut Unstructured| -> Result<JoinConstraint> {
+            Ok(match u.int_in_range(0..=2)? {
+                0 => JoinConstraint::On(g.expr(u)?),
+                1 => JoinConstraint::Using(g.idents(u, 1)?),
+                _ => JoinConstraint::Natural,
+            })
+        };
+        let join_operator = match u.int_in_range(0..=6)? {
+            0 => JoinOperator::Inner(constraint(self, u)?),
+            1 => JoinOperator::LeftOuter(constraint(self, u)?),
+            2 => JoinOperator::RightOuter(constraint(self, u)?),
+            3 => JoinOperator::FullOuter(constraint(self, u)?),
+            4 => JoinOperator::CrossJoin,
+            5 => JoinOperator::CrossApply,
+            _ => JoinOperator::OuterApply,
+        };
+        Ok(Join {
+            relation,
+            join_operator,
+        })
+    }
+
+    fn statement(&mut self, u: &mut Unstructured) -> Result<Statement> {
+        Ok(match u.int_in_range(0..=16)? {
+            0..=1 => Statement::Query(Box::new(self.query(u)?)),
+            2 => Statement::Insert {
+                table_name: self.object_name(u)?,
+                columns: self.idents(u, 0)?,
+                source: Box::new(self.subquery(u)?),
+                span: NodeSpan::empty(),
+            },
+            3 => Statement::Update {
+                table_name: self.object_name(u)?,
+                assignments: self.list(u, 1, |g, u| {
+                    Ok(Assignment {
+                        id: g.ident(u)?,
+                        value: g.expr(u)?,
+                    })
+                })?,
+                selection: self.option(u, Self::expr)?,
+                span: NodeSpan::empty(),
+            },
+            4 => Statement::Delete {
+                table_name: self.object_name(u)?,
+                selection: self.option(u, Self::expr)?,
+                span: NodeSpan::empty(),
+            },
+            5 => Statement::CreateView {
+                name: self.object_name(u)?,
+                columns: self.idents(u, 0)?,
+                query: Box::new(self.query(u)?),
+                materialized: u.arbitrary()?,
+                with_options: self.list(u, 0, Self::sql_option)?,
+                span: NodeSpan::empty(),
+            },
+            6 => self.create_table(u)?,
+            7 => Statement::AlterTable {
+                name: self.object_name(u)?,
+                operation: self.alter_table_operation(u)?,
+                span: NodeSpan::empty(),
+            },
+            8 => Statement::Drop {
+                object_type: ObjectType::arbitrary(u)?,
+                if_exists: u.arbitrary()?,
+                names: self.list(u, 1, Self::object_name)?,
+                cascade: u.arbitrary()?,
+                span: NodeSpan::empty(),
+            },
+            9 => Statement::SetVariable {
+                local: u.arbitrary()?,
+                variable: self.ident(u)?,
+                value: if u.arbitrary()? {
+                    SetVariableValue::Ident(self.ident(u)?)
+                } else {
+                    SetVariableValue::Literal(self.literal_value(u)?)
+                },
+                span: NodeSpan::empty(),
+            },
+            10 => Statement::ShowVariable {
+                variable: self.ident(u)?,
+                span: NodeSpan::empty(),
+            },
+            11 => Statement::ShowColumns {
+                extended: u.arbitrary()?,
+                full: u.arbitrary()?,
+                table_name: self.object_name(u)?,
+                filter: self.option(u, |g, u| {
+                    Ok(if u.arbitrary()? {
+                        ShowStatementFilter::Like(g.string(u, STRING_CHARS, 0)?)
+                    } else {
+                        ShowStatementFilter::Where(g.expr(u)?)
+                    })
+                })?,
+                span: NodeSpan::empty(),
+            },
+            12 => Statement::StartTransaction {
+                modes: self.list(u, 0, |_, u| TransactionMode::arbitrary(u))?,
+                span: NodeSpan::empty(),
+            },
+            13 => Statement::SetTransaction {
+                modes: self.list(u, 0, |_, u| TransactionMode::arbitrary(u))?,
+                span: NodeSpan::empty(),
+            },
+            14 => Statement::Commit {
+                chain: u.arbitrary()?,
+                span: NodeSpan::empty(),
+            },
+            15 => Statement::Rollback {
+                chain: u.arbitrary()?,
+                span: NodeSpan::empty(),
+            },
+            _ => Statement::CreateIndex {
+                name: self.object_name(u)?,
+                table_name: self.object_name(u)?,
+                columns: self.list(u, 1, Self::order_by_expr)?,
+                unique: u.arbitrary()?,
+                if_not_exists: u.arbitrary()?,
+                span: NodeSpan::empty(),
+            },
+        })
+    }
+
+    fn alter_table_operation(&mut self, u: &mut Unstructured) -> Result<AlterTableOperation> {
+        Ok(match u.int_in_range(0..=6)? {
+            0 => AlterTableOperation::AddConstraint(self.table_constraint(u)?),
+            1 => AlterTableOperation::AddColumn {
+                column_def: self.column_def(u)?,
+            },
+            2 => AlterTableOperation::DropConstraint {
+                name: self.ident(u)?,
+            },
+            3 => AlterTableOperation::DropColumn {
+                column_name: self.ident(u)?,
+                if_exists: u.arbitrary()?,
+                cascade: u.arbitrary()?,
+            },
+            4 => AlterTableOperation::RenameColumn {
+                old_column_name: self.ident(u)?,
+                new_column_name: self.ident(u)?,
+            },
+            5 => AlterTableOperation::RenameTable {
+                table_name: self.object_name(u)?,
+            },
+            _ => AlterTableOperation::AlterColumn {
+                column_name: self.ident(u)?,
+                op: match u.int_in_range(0..=4)? {
+                    0 => AlterColumnOperation::SetNotNull,
+                    1 => AlterColumnOperation::DropNotNull,
+                    2 => AlterColumnOperation::SetDefault {
+                        value: self.expr(u)?,
+                    },
+                    3 => AlterColumnOperation::DropDefault,
+                    _ => AlterColumnOperation::SetDataType {
+                        data_type: self.data_type(u)?,
+                    },
+                },
+            },
+        })
+    }
+
+    fn create_table(&mut self, u: &mut Unstructured) -> Result<Statement> {
+        let name = self.object_name(u)?;
+        let columns = self.list(u, 0, Self::column_def)?;
+        let columns = columns.into_iter().map(Commented::new).collect();
+        let constraints = self.list(u, 0, Self::table_constraint)?;
+        // External tables take a file format and location, but no options
+        Ok(if u.arbitrary()? {
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                with_options: vec![],
+                external: true,
+                file_format: Some(FileFormat::arbitrary(u)?),
+                location: Some(self.string(u, STRING_CHARS, 0)?),
+                span: NodeSpan::empty(),
+            }
+        } else {
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                with_options: self.list(u, 0, Self::sql_option)?,
+                external: false,
+                file_format: None,
+                location: None,
+                span: NodeSpan::empty(),
+            }
+        })
+    }
+
+    fn column_def(&mut self, u: &mut Unstructured) -> Result<ColumnDef> {
+        Ok(ColumnDef {
+            name: self.ident(u)?,
+            data_type: self.data_type(u)?,
+            collation: self.option(u, Self::object_name)?,
+            options: self.list(u, 0, |g, u| {
+                Ok(ColumnOptionDef {
+                    name: g.option(u, Self::ident)?,
+                    option: match u.int_in_range(0..=5)? {
+                        0 => ColumnOption::Null,
+                        1 => ColumnOption::NotNull,
+                        2 => ColumnOption::Default(g.expr(u)?),
+                        3 => ColumnOption::Unique {
+                            is_primary: u.arbitrary()?,
+                        },
+                        4 => ColumnOption::ForeignKey {
+                            foreign_table: g.object_name(u)?,
+                            referred_columns: g.idents(u, 1)?,
+                        },
+                        _ => ColumnOption::Check(g.expr(u)?),
+                    },
+                })
+            })?,
+        })
+    }
+
+    fn table_constraint(&mut self, u: &mut Unstructured) -> Result<TableConstraint> {
+        let name = self.option(u, Self::ident)?;
+        Ok(match u.int_in_range(0..=2)? {
+            0 => TableConstraint::Unique {
+                name,
+                columns: self.idents(u, 1)?,
+                is_primary: u.arbitrary()?,
+            },
+            1 => TableConstraint::ForeignKey {
+                name,
+                columns: self.idents(u, 1)?,
+                foreign_table: self.object_name(u)?,
+                referred_columns: self.idents(u, 1)?,
+            },
+            _ => TableConstraint::Check {
+                name,
+                expr: Box::new(self.expr(u)?),
+            },
+        })
+    }
+
+    fn sql_option(&mut self, u: &mut Unstructured) -> Result<SqlOption> {
+        Ok(SqlOption {
+            name: self.ident(u)?,
+            value: self.literal_value(u)?,
+        })
+    }
+}
+
+/// Implement [Arbitrary] for each type with the given [Generator] method
+macro_rules! impl_arbitrary {
+    ($($ty:ty => $method:ident),* $(,)?) => {
+        $(
+            impl<'a> Arbitrary<'a> for $ty {
+                fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                    Generator::default().$method(u)
+                }
+            }
+        )*
+    };
+}
+
+impl_arbitrary!(
+    Statement => statement,
+    Query => query,
+    SetExpr => query_body,
+    Select => select,
+    SelectItem => select_item,
+    TableWithJoins => table_with_joins,
+    TableFactor => table_factor,
+    TableAlias => table_alias,
+    Join => join,
+    OrderByExpr => order_by_expr,
+    Expr => expr,
+    Function => function,
+    WindowSpec => window_spec,
+    Value => value,
+    DataType => data_type,
+    Ident => ident,
+    ObjectName => object_name,
+    ColumnDef => column_def,
+    TableConstraint => table_constraint,
+    SqlOption => sql_option,
+);
+
+impl Generator {
+    fn query_body(&mut self, u: &mut Unstructured) -> Result<SetExpr> {
+        self.set_expr(u, 0, false)
+    }
+}
+
+/// Implement [Arbitrary] for fieldless enums by choosing one of the variants
+macro_rules! impl_arbitrary_choice {
+    ($($ty:ident => [$($variant:ident),*]),* $(,)?) => {
+        $(
+            impl<'a> Arbitrary<'a> for $ty {
+                fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                    Ok(u.choose(&[$($ty::$variant),*])?.clone())
+                }
+            }
+        )*
+    };
+}
+
+impl_arbitrary_choice!(
+    UnaryOperator => [Plus, Minus, Not],
+    BinaryOperator => [
+        Plus, Minus, Multiply, Divide, Modulus, Gt, Lt, GtEq, LtEq, Eq, NotEq, And, Or, Like,
+        NotLike
+    ],
+    DateTimeField => [Year, Month, Day, Hour, Minute, Second],
+    SetOperator => [Union, Except, Intersect],
+    WindowFrameUnits => [Rows, Range, Groups],
+    ObjectType => [Table, View, Index],
+    FileFormat => [TEXTFILE, SEQUENCEFILE, ORC, PARQUET, AVRO, RCFILE, JSONFILE],
+    TransactionAccessMode => [ReadOnly, ReadWrite],
+    TransactionIsolationLevel => [ReadUncommitted, ReadCommitted, RepeatableRead, Serializable],
+);
+
+impl<'a> Arbitrary<'a> for WindowFrameBound {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let rows = |u: &mut Unstructured| -> Result<Option<u64>> {
+            Ok(if u.arbitrary()? {
+                Some(u.int_in_range(0..=100)?)
+            } else {
+                None
+            })
+        };
+        Ok(match u.int_in_range(0..=2)? {
+            0 => WindowFrameBound::CurrentRow,
+            1 => WindowFrameBound::Preceding(rows(u)?),
+            _ => WindowFrameBound::Following(rows(u)?),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for TransactionMode {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(if u.arbitrary()? {
+            TransactionMode::AccessMode(TransactionAccessMode::arbitrary(u)?)
+        } else {
+            TransactionMode::IsolationLevel(TransactionIsolationLevel::arbitrary(u)?)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::formatter::{FormatOptions, Formatter};
+    use crate::parser::Parser;
+    use crate::test_utils::assert_round_trip;
+
+    /// Deterministic pseudo-random bytes (xorshift), so that failures are
+    /// reproducible
+    fn random_bytes(seed: u64, len: usize) -> Vec<u8> {
+        let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 32) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arbitrary_statements_round_trip() {
+        for seed in 0..2000 {
+            let bytes = random_bytes(seed, 1024);
+            let statement = Statement::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            assert_round_trip(&GenericDialect {}, &statement);
+        }
+    }
+
+    #[test]
+    fn arbitrary_statements_format_round_trip() {
+        let formatter = Formatter::new(FormatOptions::default());
+        for seed in 0..2000 {
+            let bytes = random_bytes(seed, 1024);
+            let statement = Statement::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            let sql = formatter.format_statement(&statement).unwrap();
+            let parsed = Parser::parse_sql(&GenericDialect {}, sql.clone())
+                .unwrap_or_else(|e| panic!("Failed to reparse {:?}: {}", sql, e));
+            assert_eq!(vec![statement], parsed, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn arbitrary_from_empty_input() {
+        let statement = Statement::arbitrary(&mut Unstructured::new(&[])).unwrap();
+        assert_eq!("SELECT a", statement.to_string());
+        assert_round_trip(&GenericDialect {}, &statement);
+    }
+}
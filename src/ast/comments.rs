@@ -0,0 +1,173 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Comments kept from the source text
+//!
+//! The parser attaches each comment to the nearest statement, select item,
+//! column definition or `SELECT` clause: the comments on the lines before a
+//! node (or before its first token on the same line) lead it, and the ones
+//! following it on the same line, possibly after a comma or semicolon, trail
+//! it. Comments anywhere else, e.g. in the middle of an expression or in an
+//! `ORDER BY` clause, trail the nearest of these nodes or clauses that
+//! encloses them, or else the statement.
+//!
+//! The comments around a node are kept in a wrapper around it: the
+//! `Commented` variant of [Statement](super::Statement) and
+//! [SelectItem](super::SelectItem), and [Commented] for column definitions.
+//! The comments around the clause keywords of a `SELECT`, which are not
+//! nodes, are kept in [ClauseComments](super::ClauseComments).
+
+use super::{walk_query_mut, walk_statement_mut, ClauseComments, Query, Select, SelectItem};
+use super::{NodeSpan, Statement, VisitorMut};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::ControlFlow;
+
+/// A comment in the source text
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Comment {
+    /// `-- text`, up to but excluding the end of the line
+    SingleLine(String),
+    /// `/* text */`
+    MultiLine(String),
+}
+
+impl fmt::Display for Comment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Comment::SingleLine(text) => write!(f, "--{}", text),
+            Comment::MultiLine(text) => write!(f, "/*{}*/", text),
+        }
+    }
+}
+
+/// A node with the comments around it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Commented<T> {
+    pub comments: Comments,
+    pub node: T,
+}
+
+impl<T> Commented<T> {
+    /// A node without comments
+    pub fn new(node: T) -> Self {
+        Commented {
+            comments: Comments::default(),
+            node,
+        }
+    }
+}
+
+impl<T> From<T> for Commented<T> {
+    fn from(node: T) -> Self {
+        Commented::new(node)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Commented<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.comments.fmt_leading(f)?;
+        write!(f, "{}", self.node)?;
+        self.comments.fmt_trailing(f)
+    }
+}
+
+/// The comments attached to a node, see the [module documentation](self)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Comments {
+    /// The comments before the node
+    pub leading: Vec<Comment>,
+    /// The comments after the node, on the same line as its end
+    pub trailing: Vec<Comment>,
+}
+
+impl Comments {
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_empty() && self.trailing.is_empty()
+    }
+
+    /// Write the leading comments, each followed by a space, or a line break
+    /// for single-line comments
+    pub(crate) fn fmt_leading(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for comment in &self.leading {
+            match comment {
+                Comment::SingleLine(_) => writeln!(f, "{}", comment)?,
+                Comment::MultiLine(_) => write!(f, "{} ", comment)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the trailing comments, each preceded by a space, and followed
+    /// by a line break for single-line comments
+    pub(crate) fn fmt_trailing(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for comment in &self.trailing {
+            match comment {
+                Comment::SingleLine(_) => writeln!(f, " {}", comment)?,
+                Comment::MultiLine(_) => write!(f, " {}", comment)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Remove the comments from `statement` and from the nodes in it
+pub(crate) fn strip_comments(statement: &mut Statement) {
+    while let Statement::Commented {
+        statement: inner, ..
+    } = statement
+    {
+        let inner = std::mem::replace(
+            &mut **inner,
+            Statement::Commit {
+                chain: false,
+                span: NodeSpan::empty(),
+            },
+        );
+        *statement = inner;
+    }
+    let _ = walk_statement_mut(&mut CommentStripper, statement);
+}
+
+/// Remove the comments from the nodes in `query`
+pub(crate) fn strip_query_comments(query: &mut Query) {
+    let _ = walk_query_mut(&mut CommentStripper, query);
+}
+
+struct CommentStripper;
+
+impl VisitorMut for CommentStripper {
+    type Break = ();
+
+    fn pre_visit_statement(&mut self, statement: &mut Statement) -> ControlFlow<()> {
+        if let Statement::CreateTable { columns, .. } = statement {
+            for column in columns {
+                column.comments = Comments::default();
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_select(&mut self, select: &mut Select) -> ControlFlow<()> {
+        select.comments = ClauseComments::default();
+        for item in &mut select.projection {
+            while let SelectItem::Commented { item: inner, .. } = item {
+                *item = std::mem::replace(inner, SelectItem::Wildcard(NodeSpan::empty()));
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
@@ -12,24 +12,40 @@
 
 //! SQL Abstract Syntax Tree (AST) types
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod comments;
 mod data_type;
 mod ddl;
 mod operator;
 mod query;
+mod spans;
 mod value;
+mod visitor;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
+pub(crate) use self::comments::{strip_comments, strip_query_comments};
+pub use self::comments::{Comment, Commented, Comments};
 pub use self::data_type::DataType;
 pub use self::ddl::{
-    AlterTableOperation, ColumnDef, ColumnOption, ColumnOptionDef, TableConstraint,
+    AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, ColumnOptionDef,
+    TableConstraint,
 };
+pub(crate) use self::operator::precedence;
 pub use self::operator::{BinaryOperator, UnaryOperator};
 pub use self::query::{
-    Cte, Fetch, Join, JoinConstraint, JoinOperator, OrderByExpr, Query, Select, SelectItem,
-    SetExpr, SetOperator, TableAlias, TableFactor, TableWithJoins, Values,
+    ClauseComments, Cte, Fetch, Join, JoinConstraint, JoinOperator, OrderByExpr, Query, Select,
+    SelectItem, SetExpr, SetOperator, TableAlias, TableFactor, TableWithJoins, Values,
 };
+pub use self::spans::{NodeSpan, Spanned};
+pub(crate) use self::value::escape_single_quote_string;
 pub use self::value::{DateTimeField, Value};
+pub use self::visitor::*;
+pub use crate::tokenizer::{Location, Span};
 
 struct DisplaySeparated<'a, T>
 where
@@ -69,13 +85,20 @@ where
 }
 
 /// An identifier, decomposed into its value or character data and the quote style.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Two identifiers are equal if their values and quote styles are equal; the
+/// `span` is not taken into account.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Ident {
     /// The value of the identifier without quotes.
     pub value: String,
     /// The starting quote if any. Valid quote characters are the single quote,
     /// double quote, backtick, and opening square bracket.
     pub quote_style: Option<char>,
+    /// The location of the identifier in the source text, or `Span::empty()`
+    /// if it was not produced by the parser.
+    pub span: Span,
 }
 
 impl Ident {
@@ -87,6 +110,7 @@ impl Ident {
         Ident {
             value: value.into(),
             quote_style: None,
+            span: Span::empty(),
         }
     }
 
@@ -100,16 +124,34 @@ impl Ident {
         Ident {
             value: value.into(),
             quote_style: Some(quote),
+            span: Span::empty(),
         }
     }
+
+    /// Return the identifier with its span replaced by `span`.
+    pub fn with_span(self, span: Span) -> Self {
+        Ident { span, ..self }
+    }
+}
+
+impl PartialEq for Ident {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.quote_style == other.quote_style
+    }
+}
+
+impl Eq for Ident {}
+
+impl Hash for Ident {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+        self.quote_style.hash(state);
+    }
 }
 
 impl From<&str> for Ident {
     fn from(value: &str) -> Self {
-        Ident {
-            value: value.to_string(),
-            quote_style: None,
-        }
+        Ident::new(value)
     }
 }
 
@@ -126,6 +168,7 @@ impl fmt::Display for Ident {
 
 /// A name of a table, view, custom type, etc., possibly multi-part, i.e. db.schema.obj
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ObjectName(pub Vec<Ident>);
 
 impl fmt::Display for ObjectName {
@@ -139,7 +182,11 @@ impl fmt::Display for ObjectName {
 /// The parser does not distinguish between expressions of different types
 /// (e.g. boolean vs string), so the caller must handle expressions of
 /// inappropriate type, like `WHERE 1` or `SELECT 1=1`, as necessary.
+///
+/// Variants with keywords, punctuation or a literal of their own record
+/// their location in a [NodeSpan]; the others span their operands.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Expr {
     /// Identifier e.g. table name or column name
     Identifier(Ident),
@@ -149,27 +196,29 @@ pub enum Expr {
     ///
     /// ...but we currently also accept it in contexts where it doesn't make
     /// sense, such as `* + *`
-    Wildcard,
+    Wildcard(NodeSpan),
     /// Qualified wildcard, e.g. `alias.*` or `schema.table.*`.
     /// (Same caveats apply to `QualifiedWildcard` as to `Wildcard`.)
-    QualifiedWildcard(Vec<Ident>),
+    QualifiedWildcard(Vec<Ident>, NodeSpan),
     /// Multi-part identifier, e.g. `table_alias.column` or `schema.table.col`
     CompoundIdentifier(Vec<Ident>),
     /// `IS NULL` expression
-    IsNull(Box<Expr>),
+    IsNull(Box<Expr>, NodeSpan),
     /// `IS NOT NULL` expression
-    IsNotNull(Box<Expr>),
+    IsNotNull(Box<Expr>, NodeSpan),
     /// `[ NOT ] IN (val1, val2, ...)`
     InList {
         expr: Box<Expr>,
         list: Vec<Expr>,
         negated: bool,
+        span: NodeSpan,
     },
     /// `[ NOT ] IN (SELECT ...)`
     InSubquery {
         expr: Box<Expr>,
         subquery: Box<Query>,
         negated: bool,
+        span: NodeSpan,
     },
     /// `<expr> [ NOT ] BETWEEN <low> AND <high>`
     Between {
@@ -185,15 +234,21 @@ pub enum Expr {
         right: Box<Expr>,
     },
     /// Unary operation e.g. `NOT foo`
-    UnaryOp { op: UnaryOperator, expr: Box<Expr> },
+    UnaryOp {
+        op: UnaryOperator,
+        expr: Box<Expr>,
+        span: NodeSpan,
+    },
     /// CAST an expression to a different data type e.g. `CAST(foo AS VARCHAR(123))`
     Cast {
         expr: Box<Expr>,
         data_type: DataType,
+        span: NodeSpan,
     },
     Extract {
         field: DateTimeField,
         expr: Box<Expr>,
+        span: NodeSpan,
     },
     /// `expr COLLATE collation`
     Collate {
@@ -201,9 +256,12 @@ pub enum Expr {
         collation: ObjectName,
     },
     /// Nested expression e.g. `(foo > bar)` or `(1)`
-    Nested(Box<Expr>),
+    Nested(Box<Expr>, NodeSpan),
     /// A literal value, such as string, number, date or NULL
-    Value(Value),
+    Value(Value, NodeSpan),
+    /// A bind parameter placeholder, spelled as in the source, e.g. `?`,
+    /// `$1`, `:name` or `@name`
+    Placeholder(String, NodeSpan),
     /// Scalar function call e.g. `LEFT(foo, 5)`
     Function(Function),
     /// `CASE [<operand>] WHEN <condition> THEN <result> ... [ELSE <result>] END`
@@ -216,28 +274,83 @@ pub enum Expr {
         conditions: Vec<Expr>,
         results: Vec<Expr>,
         else_result: Option<Box<Expr>>,
+        span: NodeSpan,
     },
     /// An exists expression `EXISTS(SELECT ...)`, used in expressions like
     /// `WHERE EXISTS (SELECT ...)`.
-    Exists(Box<Query>),
+    Exists(Box<Query>, NodeSpan),
     /// A parenthesized subquery `(SELECT ...)`, used in expression like
     /// `SELECT (subquery) AS x` or `WHERE (subquery) = x`
-    Subquery(Box<Query>),
+    Subquery(Box<Query>, NodeSpan),
+}
+
+impl Expr {
+    /// The binding power of the expression as an operand: the parser needs
+    /// it to be at least that of the operator on its left, and higher than
+    /// that of the operator on its right. An operand that an operator
+    /// follows must also not end in a prefix operator that would take it,
+    /// see [Expr::left_operand].
+    pub(crate) fn precedence(&self) -> u8 {
+        match self {
+            Expr::BinaryOp { op, .. } => op.precedence(),
+            Expr::IsNull(_, _) | Expr::IsNotNull(_, _) => precedence::IS,
+            Expr::InList { .. } | Expr::InSubquery { .. } | Expr::Between { .. } => {
+                precedence::COMPARISON
+            }
+            // `COLLATE` may only follow a prefix expression
+            Expr::Collate { .. } => 99,
+            _ => 100,
+        }
+    }
+
+    /// The binding power of the prefix operator the expression ends in, if
+    /// any: its operand takes the operators of higher precedence that follow
+    /// the expression, as in `NOT a = b` or `-1 * b`.
+    fn trailing_precedence(&self) -> u8 {
+        match self {
+            Expr::UnaryOp { op, expr, .. } => op.precedence().min(expr.trailing_precedence()),
+            Expr::BinaryOp { right, .. } => right.trailing_precedence(),
+            Expr::Between { high, .. } => high.trailing_precedence(),
+            _ => 100,
+        }
+    }
+
+    /// Return the expression, wrapped in `Expr::Nested` unless it binds at
+    /// least as tightly as `min_precedence`
+    pub(crate) fn operand(self, min_precedence: u8) -> Expr {
+        if self.precedence() < min_precedence {
+            Expr::Nested(Box::new(self), NodeSpan::empty())
+        } else {
+            self
+        }
+    }
+
+    /// Like [Expr::operand], for an operand followed by an operator of
+    /// precedence `min_precedence`, such as the left operand of a binary
+    /// operator or the operand of `IS NULL`
+    pub(crate) fn left_operand(self, min_precedence: u8) -> Expr {
+        if self.precedence().min(self.trailing_precedence()) < min_precedence {
+            Expr::Nested(Box::new(self), NodeSpan::empty())
+        } else {
+            self
+        }
+    }
 }
 
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Expr::Identifier(s) => write!(f, "{}", s),
-            Expr::Wildcard => f.write_str("*"),
-            Expr::QualifiedWildcard(q) => write!(f, "{}.*", display_separated(q, ".")),
+            Expr::Wildcard(_) => f.write_str("*"),
+            Expr::QualifiedWildcard(q, _) => write!(f, "{}.*", display_separated(q, ".")),
             Expr::CompoundIdentifier(s) => write!(f, "{}", display_separated(s, ".")),
-            Expr::IsNull(ast) => write!(f, "{} IS NULL", ast),
-            Expr::IsNotNull(ast) => write!(f, "{} IS NOT NULL", ast),
+            Expr::IsNull(ast, _) => write!(f, "{} IS NULL", ast),
+            Expr::IsNotNull(ast, _) => write!(f, "{} IS NOT NULL", ast),
             Expr::InList {
                 expr,
                 list,
                 negated,
+                ..
             } => write!(
                 f,
                 "{} {}IN ({})",
@@ -249,6 +362,7 @@ impl fmt::Display for Expr {
                 expr,
                 subquery,
                 negated,
+                ..
             } => write!(
                 f,
                 "{} {}IN ({})",
@@ -270,18 +384,22 @@ impl fmt::Display for Expr {
                 high
             ),
             Expr::BinaryOp { left, op, right } => write!(f, "{} {} {}", left, op, right),
-            Expr::UnaryOp { op, expr } => write!(f, "{} {}", op, expr),
-            Expr::Cast { expr, data_type } => write!(f, "CAST({} AS {})", expr, data_type),
-            Expr::Extract { field, expr } => write!(f, "EXTRACT({} FROM {})", field, expr),
+            Expr::UnaryOp { op, expr, .. } => write!(f, "{} {}", op, expr),
+            Expr::Cast {
+                expr, data_type, ..
+            } => write!(f, "CAST({} AS {})", expr, data_type),
+            Expr::Extract { field, expr, .. } => write!(f, "EXTRACT({} FROM {})", field, expr),
             Expr::Collate { expr, collation } => write!(f, "{} COLLATE {}", expr, collation),
-            Expr::Nested(ast) => write!(f, "({})", ast),
-            Expr::Value(v) => write!(f, "{}", v),
+            Expr::Nested(ast, _) => write!(f, "({})", ast),
+            Expr::Placeholder(text, _) => f.write_str(text),
+            Expr::Value(v, _) => write!(f, "{}", v),
             Expr::Function(fun) => write!(f, "{}", fun),
             Expr::Case {
                 operand,
                 conditions,
                 results,
                 else_result,
+                ..
             } => {
                 f.write_str("CASE")?;
                 if let Some(operand) = operand {
@@ -296,14 +414,15 @@ impl fmt::Display for Expr {
                 }
                 f.write_str(" END")
             }
-            Expr::Exists(s) => write!(f, "EXISTS ({})", s),
-            Expr::Subquery(s) => write!(f, "({})", s),
+            Expr::Exists(s, _) => write!(f, "EXISTS ({})", s),
+            Expr::Subquery(s, _) => write!(f, "({})", s),
         }
     }
 }
 
 /// A window specification (i.e. `OVER (PARTITION BY .. ORDER BY .. etc.)`)
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct WindowSpec {
     pub partition_by: Vec<Expr>,
     pub order_by: Vec<OrderByExpr>,
@@ -349,6 +468,7 @@ impl fmt::Display for WindowSpec {
 /// Note: The parser does not validate the specified bounds; the caller should
 /// reject invalid bounds like `ROWS UNBOUNDED FOLLOWING` before execution.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct WindowFrame {
     pub units: WindowFrameUnits,
     pub start_bound: WindowFrameBound,
@@ -360,6 +480,7 @@ pub struct WindowFrame {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum WindowFrameUnits {
     Rows,
     Range,
@@ -384,16 +505,17 @@ impl FromStr for WindowFrameUnits {
             "ROWS" => Ok(WindowFrameUnits::Rows),
             "RANGE" => Ok(WindowFrameUnits::Range),
             "GROUPS" => Ok(WindowFrameUnits::Groups),
-            _ => Err(ParserError::ParserError(format!(
-                "Expected ROWS, RANGE, or GROUPS, found: {}",
-                s
-            ))),
+            _ => Err(ParserError::new(
+                ParserErrorKind::UnexpectedToken,
+                format!("Expected ROWS, RANGE, or GROUPS, found: {}", s),
+            )),
         }
     }
 }
 
 /// Specifies [WindowFrame]'s `start_bound` and `end_bound`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum WindowFrameBound {
     /// `CURRENT ROW`
     CurrentRow,
@@ -416,8 +538,11 @@ impl fmt::Display for WindowFrameBound {
 }
 
 /// A top-level statement (SELECT, INSERT, CREATE, etc.)
+///
+/// Statements other than queries record their location in a [NodeSpan].
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Statement {
     /// SELECT
     Query(Box<Query>),
@@ -429,6 +554,7 @@ pub enum Statement {
         columns: Vec<Ident>,
         /// A SQL query that specifies what to insert
         source: Box<Query>,
+        span: NodeSpan,
     },
     Copy {
         /// TABLE
@@ -437,6 +563,7 @@ pub enum Statement {
         columns: Vec<Ident>,
         /// VALUES a vector of values to be copied
         values: Vec<Option<String>>,
+        span: NodeSpan,
     },
     /// UPDATE
     Update {
@@ -446,6 +573,7 @@ pub enum Statement {
         assignments: Vec<Assignment>,
         /// WHERE
         selection: Option<Expr>,
+        span: NodeSpan,
     },
     /// DELETE
     Delete {
@@ -453,6 +581,7 @@ pub enum Statement {
         table_name: ObjectName,
         /// WHERE
         selection: Option<Expr>,
+        span: NodeSpan,
     },
     /// CREATE VIEW
     CreateView {
@@ -462,24 +591,39 @@ pub enum Statement {
         query: Box<Query>,
         materialized: bool,
         with_options: Vec<SqlOption>,
+        span: NodeSpan,
     },
     /// CREATE TABLE
     CreateTable {
         /// Table name
         name: ObjectName,
         /// Optional schema
-        columns: Vec<ColumnDef>,
+        columns: Vec<Commented<ColumnDef>>,
         constraints: Vec<TableConstraint>,
         with_options: Vec<SqlOption>,
         external: bool,
         file_format: Option<FileFormat>,
         location: Option<String>,
+        span: NodeSpan,
+    },
+    /// CREATE INDEX
+    CreateIndex {
+        /// Index name
+        name: ObjectName,
+        /// The table the index is on
+        table_name: ObjectName,
+        /// The indexed columns or expressions
+        columns: Vec<OrderByExpr>,
+        unique: bool,
+        if_not_exists: bool,
+        span: NodeSpan,
     },
     /// ALTER TABLE
     AlterTable {
         /// Table name
         name: ObjectName,
         operation: AlterTableOperation,
+        span: NodeSpan,
     },
     /// DROP
     Drop {
@@ -492,6 +636,7 @@ pub enum Statement {
         /// Whether `CASCADE` was specified. This will be `false` when
         /// `RESTRICT` or no drop behavior at all was specified.
         cascade: bool,
+        span: NodeSpan,
     },
     /// SET <variable>
     ///
@@ -502,11 +647,12 @@ pub enum Statement {
         local: bool,
         variable: Ident,
         value: SetVariableValue,
+        span: NodeSpan,
     },
     /// SHOW <variable>
     ///
     /// Note: this is a PostgreSQL-specific statement.
-    ShowVariable { variable: Ident },
+    ShowVariable { variable: Ident, span: NodeSpan },
     /// SHOW COLUMNS
     ///
     /// Note: this is a MySQL-specific statement.
@@ -515,15 +661,44 @@ pub enum Statement {
         full: bool,
         table_name: ObjectName,
         filter: Option<ShowStatementFilter>,
+        span: NodeSpan,
     },
     /// `{ BEGIN [ TRANSACTION | WORK ] | START TRANSACTION } ...`
-    StartTransaction { modes: Vec<TransactionMode> },
+    StartTransaction {
+        modes: Vec<TransactionMode>,
+        span: NodeSpan,
+    },
     /// `SET TRANSACTION ...`
-    SetTransaction { modes: Vec<TransactionMode> },
+    SetTransaction {
+        modes: Vec<TransactionMode>,
+        span: NodeSpan,
+    },
     /// `COMMIT [ TRANSACTION | WORK ] [ AND [ NO ] CHAIN ]`
-    Commit { chain: bool },
+    Commit { chain: bool, span: NodeSpan },
     /// `ROLLBACK [ TRANSACTION | WORK ] [ AND [ NO ] CHAIN ]`
-    Rollback { chain: bool },
+    Rollback { chain: bool, span: NodeSpan },
+    /// A statement with the comments around it. It is displayed without a
+    /// terminating `;`; printers of several statements, such as
+    /// [Formatter::format_statements](crate::formatter::Formatter::format_statements),
+    /// put it before the trailing comments.
+    Commented {
+        comments: Comments,
+        statement: Box<Statement>,
+    },
+}
+
+impl Statement {
+    /// Attach `comments` to the statement, unless there are none.
+    pub fn with_comments(self, comments: Comments) -> Self {
+        if comments.is_empty() {
+            self
+        } else {
+            Statement::Commented {
+                comments,
+                statement: Box::new(self),
+            }
+        }
+    }
 }
 
 impl fmt::Display for Statement {
@@ -537,6 +712,7 @@ impl fmt::Display for Statement {
                 table_name,
                 columns,
                 source,
+                ..
             } => {
                 write!(f, "INSERT INTO {} ", table_name)?;
                 if !columns.is_empty() {
@@ -548,6 +724,7 @@ impl fmt::Display for Statement {
                 table_name,
                 columns,
                 values,
+                ..
             } => {
                 write!(f, "COPY {}", table_name)?;
                 if !columns.is_empty() {
@@ -573,6 +750,7 @@ impl fmt::Display for Statement {
                 table_name,
                 assignments,
                 selection,
+                ..
             } => {
                 write!(f, "UPDATE {}", table_name)?;
                 if !assignments.is_empty() {
@@ -587,6 +765,7 @@ impl fmt::Display for Statement {
             Statement::Delete {
                 table_name,
                 selection,
+                ..
             } => {
                 write!(f, "DELETE FROM {}", table_name)?;
                 if let Some(selection) = selection {
@@ -600,6 +779,7 @@ impl fmt::Display for Statement {
                 query,
                 materialized,
                 with_options,
+                ..
             } => {
                 write!(f, "CREATE")?;
                 if *materialized {
@@ -608,14 +788,14 @@ impl fmt::Display for Statement {
 
                 write!(f, " VIEW {}", name)?;
 
-                if !with_options.is_empty() {
-                    write!(f, " WITH ({})", display_comma_separated(with_options))?;
-                }
-
                 if !columns.is_empty() {
                     write!(f, " ({})", display_comma_separated(columns))?;
                 }
 
+                if !with_options.is_empty() {
+                    write!(f, " WITH ({})", display_comma_separated(with_options))?;
+                }
+
                 write!(f, " AS {}", query)
             }
             Statement::CreateTable {
@@ -626,6 +806,7 @@ impl fmt::Display for Statement {
                 external,
                 file_format,
                 location,
+                ..
             } => {
                 write!(
                     f,
@@ -635,16 +816,21 @@ impl fmt::Display for Statement {
                     display_comma_separated(columns)
                 )?;
                 if !constraints.is_empty() {
-                    write!(f, ", {}", display_comma_separated(constraints))?;
+                    if !columns.is_empty() {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", display_comma_separated(constraints))?;
                 }
                 write!(f, ")")?;
 
-                if *external {
+                if let Some(file_format) = file_format {
+                    write!(f, " STORED AS {}", file_format)?;
+                }
+                if let Some(location) = location {
                     write!(
                         f,
-                        " STORED AS {} LOCATION '{}'",
-                        file_format.as_ref().unwrap(),
-                        location.as_ref().unwrap()
+                        " LOCATION '{}'",
+                        value::escape_single_quote_string(location)
                     )?;
                 }
                 if !with_options.is_empty() {
@@ -652,7 +838,25 @@ impl fmt::Display for Statement {
                 }
                 Ok(())
             }
-            Statement::AlterTable { name, operation } => {
+            Statement::CreateIndex {
+                name,
+                table_name,
+                columns,
+                unique,
+                if_not_exists,
+                ..
+            } => write!(
+                f,
+                "CREATE {}INDEX {}{} ON {} ({})",
+                if *unique { "UNIQUE " } else { "" },
+                if *if_not_exists { "IF NOT EXISTS " } else { "" },
+                name,
+                table_name,
+                display_comma_separated(columns)
+            ),
+            Statement::AlterTable {
+                name, operation, ..
+            } => {
                 write!(f, "ALTER TABLE {} {}", name, operation)
             }
             Statement::Drop {
@@ -660,6 +864,7 @@ impl fmt::Display for Statement {
                 if_exists,
                 names,
                 cascade,
+                ..
             } => write!(
                 f,
                 "DROP {}{} {}{}",
@@ -672,6 +877,7 @@ impl fmt::Display for Statement {
                 local,
                 variable,
                 value,
+                ..
             } => {
                 f.write_str("SET ")?;
                 if *local {
@@ -679,12 +885,13 @@ impl fmt::Display for Statement {
                 }
                 write!(f, "{} = {}", variable, value)
             }
-            Statement::ShowVariable { variable } => write!(f, "SHOW {}", variable),
+            Statement::ShowVariable { variable, .. } => write!(f, "SHOW {}", variable),
             Statement::ShowColumns {
                 extended,
                 full,
                 table_name,
                 filter,
+                ..
             } => {
                 f.write_str("SHOW ")?;
                 if *extended {
@@ -699,32 +906,41 @@ impl fmt::Display for Statement {
                 }
                 Ok(())
             }
-            Statement::StartTransaction { modes } => {
+            Statement::StartTransaction { modes, .. } => {
                 write!(f, "START TRANSACTION")?;
                 if !modes.is_empty() {
                     write!(f, " {}", display_comma_separated(modes))?;
                 }
                 Ok(())
             }
-            Statement::SetTransaction { modes } => {
+            Statement::SetTransaction { modes, .. } => {
                 write!(f, "SET TRANSACTION")?;
                 if !modes.is_empty() {
                     write!(f, " {}", display_comma_separated(modes))?;
                 }
                 Ok(())
             }
-            Statement::Commit { chain } => {
+            Statement::Commit { chain, .. } => {
                 write!(f, "COMMIT{}", if *chain { " AND CHAIN" } else { "" },)
             }
-            Statement::Rollback { chain } => {
+            Statement::Rollback { chain, .. } => {
                 write!(f, "ROLLBACK{}", if *chain { " AND CHAIN" } else { "" },)
             }
+            Statement::Commented {
+                comments,
+                statement,
+            } => {
+                comments.fmt_leading(f)?;
+                write!(f, "{}", statement)?;
+                comments.fmt_trailing(f)
+            }
         }
     }
 }
 
 /// SQL assignment `foo = expr` as used in SQLUpdate
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Assignment {
     pub id: Ident,
     pub value: Expr,
@@ -738,12 +954,14 @@ impl fmt::Display for Assignment {
 
 /// A function call
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Function {
     pub name: ObjectName,
     pub args: Vec<Expr>,
     pub over: Option<WindowSpec>,
     // aggregate functions may specify eg `COUNT(DISTINCT x)`
     pub distinct: bool,
+    pub span: NodeSpan,
 }
 
 impl fmt::Display for Function {
@@ -764,6 +982,7 @@ impl fmt::Display for Function {
 
 /// External table's available file format
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum FileFormat {
     TEXTFILE,
     SEQUENCEFILE,
@@ -784,12 +1003,12 @@ impl fmt::Display for FileFormat {
             PARQUET => "PARQUET",
             AVRO => "AVRO",
             RCFILE => "RCFILE",
-            JSONFILE => "TEXTFILE",
+            JSONFILE => "JSONFILE",
         })
     }
 }
 
-use crate::parser::ParserError;
+use crate::parser::{ParserError, ParserErrorKind};
 use std::str::FromStr;
 impl FromStr for FileFormat {
     type Err = ParserError;
@@ -804,18 +1023,20 @@ impl FromStr for FileFormat {
             "AVRO" => Ok(AVRO),
             "RCFILE" => Ok(RCFILE),
             "JSONFILE" => Ok(JSONFILE),
-            _ => Err(ParserError::ParserError(format!(
-                "Unexpected file format: {}",
-                s
-            ))),
+            _ => Err(ParserError::new(
+                ParserErrorKind::UnexpectedToken,
+                format!("Unexpected file format: {}", s),
+            )),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ObjectType {
     Table,
     View,
+    Index,
 }
 
 impl fmt::Display for ObjectType {
@@ -823,11 +1044,13 @@ impl fmt::Display for ObjectType {
         f.write_str(match self {
             ObjectType::Table => "TABLE",
             ObjectType::View => "VIEW",
+            ObjectType::Index => "INDEX",
         })
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SqlOption {
     pub name: Ident,
     pub value: Value,
@@ -840,6 +1063,7 @@ impl fmt::Display for SqlOption {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TransactionMode {
     AccessMode(TransactionAccessMode),
     IsolationLevel(TransactionIsolationLevel),
@@ -856,6 +1080,7 @@ impl fmt::Display for TransactionMode {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TransactionAccessMode {
     ReadOnly,
     ReadWrite,
@@ -872,6 +1097,7 @@ impl fmt::Display for TransactionAccessMode {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TransactionIsolationLevel {
     ReadUncommitted,
     ReadCommitted,
@@ -892,6 +1118,7 @@ impl fmt::Display for TransactionIsolationLevel {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ShowStatementFilter {
     Like(String),
     Where(Expr),
@@ -908,6 +1135,7 @@ impl fmt::Display for ShowStatementFilter {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SetVariableValue {
     Ident(Ident),
     Literal(Value),
@@ -10,16 +10,46 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
+/// The binding powers of the operators in the parser, from the loosest to
+/// the tightest. An operand binds at least as tightly as the operator on its
+/// left, and more tightly than the one on its right, or is parenthesized.
+pub(crate) mod precedence {
+    pub const OR: u8 = 5;
+    pub const AND: u8 = 10;
+    pub const UNARY_NOT: u8 = 15;
+    pub const IS: u8 = 17;
+    /// Comparisons, `[NOT] LIKE`, `[NOT] IN` and `[NOT] BETWEEN`
+    pub const COMPARISON: u8 = 20;
+    /// Binary and unary plus and minus
+    pub const PLUS_MINUS: u8 = 30;
+    pub const MULTIPLY_DIVIDE: u8 = 40;
+    pub const DOUBLE_COLON: u8 = 50;
+}
+
 /// Unary operators
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum UnaryOperator {
     Plus,
     Minus,
     Not,
 }
 
+impl UnaryOperator {
+    /// The binding power of the operator in the parser, i.e. of the
+    /// operators that its operand may contain without parentheses
+    pub(crate) fn precedence(&self) -> u8 {
+        match self {
+            UnaryOperator::Plus | UnaryOperator::Minus => precedence::PLUS_MINUS,
+            UnaryOperator::Not => precedence::UNARY_NOT,
+        }
+    }
+}
+
 impl fmt::Display for UnaryOperator {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(match self {
@@ -32,6 +62,7 @@ impl fmt::Display for UnaryOperator {
 
 /// Binary operators
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BinaryOperator {
     Plus,
     Minus,
@@ -48,6 +79,25 @@ pub enum BinaryOperator {
     Or,
     Like,
     NotLike,
+    /// An operator parsed by a dialect's [crate::dialect::Dialect::parse_infix]
+    /// hook, displayed as the given string
+    Custom(String),
+}
+
+impl BinaryOperator {
+    /// The binding power of the operator in the parser, or 0 for a custom
+    /// one, whose precedence is up to the dialect
+    pub(crate) fn precedence(&self) -> u8 {
+        use BinaryOperator::*;
+        match self {
+            Or => precedence::OR,
+            And => precedence::AND,
+            Gt | Lt | GtEq | LtEq | Eq | NotEq | Like | NotLike => precedence::COMPARISON,
+            Plus | Minus => precedence::PLUS_MINUS,
+            Multiply | Divide | Modulus => precedence::MULTIPLY_DIVIDE,
+            Custom(_) => 0,
+        }
+    }
 }
 
 impl fmt::Display for BinaryOperator {
@@ -68,6 +118,7 @@ impl fmt::Display for BinaryOperator {
             BinaryOperator::Or => "OR",
             BinaryOperator::Like => "LIKE",
             BinaryOperator::NotLike => "NOT LIKE",
+            BinaryOperator::Custom(op) => op,
         })
     }
 }
@@ -15,6 +15,7 @@ use super::*;
 /// The most complete variant of a `SELECT` query expression, optionally
 /// including `WITH`, `UNION` / other set operations, and `ORDER BY`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Query {
     /// WITH (common table expressions, or CTEs)
     pub ctes: Vec<Cte>,
@@ -28,6 +29,8 @@ pub struct Query {
     pub offset: Option<Expr>,
     /// `FETCH { FIRST | NEXT } <N> [ PERCENT ] { ROW | ROWS } | { ONLY | WITH TIES }`
     pub fetch: Option<Fetch>,
+    /// The location of the query in the source text
+    pub span: NodeSpan,
 }
 
 impl fmt::Display for Query {
@@ -55,6 +58,7 @@ impl fmt::Display for Query {
 /// A node in a tree, representing a "query body" expression, roughly:
 /// `SELECT ... [ {UNION|EXCEPT|INTERSECT} SELECT ...]`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SetExpr {
     /// Restricted SELECT .. FROM .. HAVING (no ORDER BY or set operations)
     Select(Box<Select>),
@@ -92,6 +96,7 @@ impl fmt::Display for SetExpr {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SetOperator {
     Union,
     Except,
@@ -112,6 +117,7 @@ impl fmt::Display for SetOperator {
 /// appear either as the only body item of an `SQLQuery`, or as an operand
 /// to a set operation like `UNION`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Select {
     pub distinct: bool,
     /// projection expressions
@@ -124,6 +130,10 @@ pub struct Select {
     pub group_by: Vec<Expr>,
     /// HAVING
     pub having: Option<Expr>,
+    /// The comments around the clauses other than the projection
+    pub comments: ClauseComments,
+    /// The location of the `SELECT` in the source text
+    pub span: NodeSpan,
 }
 
 impl fmt::Display for Select {
@@ -135,26 +145,49 @@ impl fmt::Display for Select {
             display_comma_separated(&self.projection)
         )?;
         if !self.from.is_empty() {
-            write!(f, " FROM {}", display_comma_separated(&self.from))?;
+            f.write_str(" ")?;
+            self.comments.from.fmt_leading(f)?;
+            write!(f, "FROM {}", display_comma_separated(&self.from))?;
+            self.comments.from.fmt_trailing(f)?;
         }
         if let Some(ref selection) = self.selection {
-            write!(f, " WHERE {}", selection)?;
+            f.write_str(" ")?;
+            self.comments.selection.fmt_leading(f)?;
+            write!(f, "WHERE {}", selection)?;
+            self.comments.selection.fmt_trailing(f)?;
         }
         if !self.group_by.is_empty() {
-            write!(f, " GROUP BY {}", display_comma_separated(&self.group_by))?;
+            f.write_str(" ")?;
+            self.comments.group_by.fmt_leading(f)?;
+            write!(f, "GROUP BY {}", display_comma_separated(&self.group_by))?;
+            self.comments.group_by.fmt_trailing(f)?;
         }
         if let Some(ref having) = self.having {
-            write!(f, " HAVING {}", having)?;
+            f.write_str(" ")?;
+            self.comments.having.fmt_leading(f)?;
+            write!(f, "HAVING {}", having)?;
+            self.comments.having.fmt_trailing(f)?;
         }
         Ok(())
     }
 }
 
+/// The comments around the clauses of a [Select]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClauseComments {
+    pub from: Comments,
+    pub selection: Comments,
+    pub group_by: Comments,
+    pub having: Comments,
+}
+
 /// A single CTE (used after `WITH`): `alias [(col1, col2, ...)] AS ( query )`
 /// The names in the column list before `AS`, when specified, replace the names
 /// of the columns returned by the query. The parser does not validate that the
 /// number of columns in the query matches the number of columns in the query.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Cte {
     pub alias: TableAlias,
     pub query: Query,
@@ -168,15 +201,35 @@ impl fmt::Display for Cte {
 
 /// One item of the comma-separated list following `SELECT`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SelectItem {
     /// Any expression, not followed by `[ AS ] alias`
     UnnamedExpr(Expr),
     /// An expression, followed by `[ AS ] alias`
     ExprWithAlias { expr: Expr, alias: Ident },
     /// `alias.*` or even `schema.table.*`
-    QualifiedWildcard(ObjectName),
+    QualifiedWildcard(ObjectName, NodeSpan),
     /// An unqualified `*`
-    Wildcard,
+    Wildcard(NodeSpan),
+    /// A select item with the comments around it
+    Commented {
+        comments: Comments,
+        item: Box<SelectItem>,
+    },
+}
+
+impl SelectItem {
+    /// Attach `comments` to the select item, unless there are none.
+    pub fn with_comments(self, comments: Comments) -> Self {
+        if comments.is_empty() {
+            self
+        } else {
+            SelectItem::Commented {
+                comments,
+                item: Box::new(self),
+            }
+        }
+    }
 }
 
 impl fmt::Display for SelectItem {
@@ -184,13 +237,19 @@ impl fmt::Display for SelectItem {
         match &self {
             SelectItem::UnnamedExpr(expr) => write!(f, "{}", expr),
             SelectItem::ExprWithAlias { expr, alias } => write!(f, "{} AS {}", expr, alias),
-            SelectItem::QualifiedWildcard(prefix) => write!(f, "{}.*", prefix),
-            SelectItem::Wildcard => write!(f, "*"),
+            SelectItem::QualifiedWildcard(prefix, _) => write!(f, "{}.*", prefix),
+            SelectItem::Wildcard(_) => write!(f, "*"),
+            SelectItem::Commented { comments, item } => {
+                comments.fmt_leading(f)?;
+                write!(f, "{}", item)?;
+                comments.fmt_trailing(f)
+            }
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TableWithJoins {
     pub relation: TableFactor,
     pub joins: Vec<Join>,
@@ -208,6 +267,7 @@ impl fmt::Display for TableWithJoins {
 
 /// A table name or a parenthesized subquery with an optional alias
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TableFactor {
     Table {
         name: ObjectName,
@@ -218,17 +278,19 @@ pub enum TableFactor {
         args: Vec<Expr>,
         /// MSSQL-specific `WITH (...)` hints such as NOLOCK.
         with_hints: Vec<Expr>,
+        span: NodeSpan,
     },
     Derived {
         lateral: bool,
         subquery: Box<Query>,
         alias: Option<TableAlias>,
+        span: NodeSpan,
     },
     /// Represents a parenthesized join expression, such as
     /// `(foo <JOIN> bar [ <JOIN> baz ... ])`.
     /// The inner `TableWithJoins` can have no joins only if its
     /// `relation` is itself a `TableFactor::NestedJoin`.
-    NestedJoin(Box<TableWithJoins>),
+    NestedJoin(Box<TableWithJoins>, NodeSpan),
 }
 
 impl fmt::Display for TableFactor {
@@ -239,6 +301,7 @@ impl fmt::Display for TableFactor {
                 alias,
                 args,
                 with_hints,
+                ..
             } => {
                 write!(f, "{}", name)?;
                 if !args.is_empty() {
@@ -256,6 +319,7 @@ impl fmt::Display for TableFactor {
                 lateral,
                 subquery,
                 alias,
+                ..
             } => {
                 if *lateral {
                     write!(f, "LATERAL ")?;
@@ -266,12 +330,13 @@ impl fmt::Display for TableFactor {
                 }
                 Ok(())
             }
-            TableFactor::NestedJoin(table_reference) => write!(f, "({})", table_reference),
+            TableFactor::NestedJoin(table_reference, _) => write!(f, "({})", table_reference),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TableAlias {
     pub name: Ident,
     pub columns: Vec<Ident>,
@@ -288,6 +353,7 @@ impl fmt::Display for TableAlias {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Join {
     pub relation: TableFactor,
     pub join_operator: JoinOperator,
@@ -353,6 +419,7 @@ impl fmt::Display for Join {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum JoinOperator {
     Inner(JoinConstraint),
     LeftOuter(JoinConstraint),
@@ -366,6 +433,7 @@ pub enum JoinOperator {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum JoinConstraint {
     On(Expr),
     Using(Vec<Ident>),
@@ -374,6 +442,7 @@ pub enum JoinConstraint {
 
 /// SQL ORDER BY expression
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OrderByExpr {
     pub expr: Expr,
     pub asc: Option<bool>,
@@ -390,6 +459,7 @@ impl fmt::Display for OrderByExpr {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Fetch {
     pub with_ties: bool,
     pub percent: bool,
@@ -409,6 +479,7 @@ impl fmt::Display for Fetch {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Values(pub Vec<Vec<Expr>>);
 
 impl fmt::Display for Values {
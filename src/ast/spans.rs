@@ -0,0 +1,754 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Source locations of AST nodes.
+//!
+//! The parser records where each node starts and ends. [Ident]s store their
+//! span, and so do the nodes with keywords, punctuation or literal values of
+//! their own, in a [NodeSpan] field: `a + 1` spans the whole expression, and
+//! the `SELECT` query it is in spans from the `SELECT` keyword on. The span of
+//! any other node, like that binary operation, is the smallest span covering
+//! its parts.
+//!
+//! Nodes that were built rather than parsed have an empty span, i.e. an
+//! unknown location (see [Span::is_empty]); their parents fall back to the
+//! spans of their parts, so a hand-built node that contains parsed nodes
+//! spans those.
+
+use super::*;
+
+/// A node in the AST that can report where in the source text it came from
+pub trait Spanned {
+    /// Return the span of this node, or `Span::empty()` if it is unknown,
+    /// which [Span::is_empty] tells apart from any location in the source.
+    fn span(&self) -> Span;
+}
+
+/// The span recorded by the parser for an AST node, or `Span::empty()` if the
+/// node was not produced by the parser.
+///
+/// Like the span of an [Ident], it is not part of the value of the node: all
+/// `NodeSpan`s are equal and hash the same, so that the same SQL parsed from
+/// different places, or built by hand, gives equal ASTs.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NodeSpan(pub Span);
+
+impl NodeSpan {
+    /// The span of a node that was not produced by the parser
+    pub fn empty() -> Self {
+        NodeSpan(Span::empty())
+    }
+
+    /// Return the recorded span, or `parts()` if there is none.
+    fn or_else(self, parts: impl FnOnce() -> Span) -> Span {
+        if self.0.is_empty() {
+            parts()
+        } else {
+            self.0
+        }
+    }
+}
+
+impl From<Span> for NodeSpan {
+    fn from(span: Span) -> Self {
+        NodeSpan(span)
+    }
+}
+
+impl PartialEq for NodeSpan {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for NodeSpan {}
+
+impl Hash for NodeSpan {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+fn union_spans<I: Iterator<Item = Span>>(iter: I) -> Span {
+    iter.fold(Span::empty(), |acc, span| acc.union(&span))
+}
+
+fn spans_of<'a, T: Spanned + 'a>(nodes: impl IntoIterator<Item = &'a T>) -> Span {
+    union_spans(nodes.into_iter().map(Spanned::span))
+}
+
+impl<T: Spanned> Spanned for &T {
+    fn span(&self) -> Span {
+        (**self).span()
+    }
+}
+
+impl<T: Spanned> Spanned for Box<T> {
+    fn span(&self) -> Span {
+        (**self).span()
+    }
+}
+
+impl<T: Spanned> Spanned for Option<T> {
+    fn span(&self) -> Span {
+        self.as_ref().map_or_else(Span::empty, Spanned::span)
+    }
+}
+
+impl Spanned for Ident {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl Spanned for ObjectName {
+    fn span(&self) -> Span {
+        spans_of(&self.0)
+    }
+}
+
+impl Spanned for Expr {
+    fn span(&self) -> Span {
+        match self {
+            Expr::Identifier(ident) => ident.span,
+            Expr::CompoundIdentifier(idents) => spans_of(idents),
+            Expr::Wildcard(span) | Expr::Value(_, span) | Expr::Placeholder(_, span) => span.0,
+            Expr::QualifiedWildcard(idents, span) => span.or_else(|| spans_of(idents)),
+            Expr::IsNull(expr, span) | Expr::IsNotNull(expr, span) | Expr::Nested(expr, span) => {
+                span.or_else(|| expr.span())
+            }
+            Expr::InList {
+                expr, list, span, ..
+            } => span.or_else(|| expr.span().union(&spans_of(list))),
+            Expr::InSubquery {
+                expr,
+                subquery,
+                span,
+                ..
+            } => span.or_else(|| expr.span().union(&subquery.span())),
+            Expr::Between {
+                expr, low, high, ..
+            } => union_spans([expr.span(), low.span(), high.span()].iter().copied()),
+            Expr::BinaryOp { left, right, .. } => left.span().union(&right.span()),
+            Expr::UnaryOp { expr, span, .. } | Expr::Extract { expr, span, .. } => {
+                span.or_else(|| expr.span())
+            }
+            Expr::Cast {
+                expr,
+                data_type,
+                span,
+            } => span.or_else(|| expr.span().union(&data_type.span())),
+            Expr::Collate { expr, collation } => expr.span().union(&collation.span()),
+            Expr::Function(function) => function.span(),
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+                span,
+            } => span.or_else(|| {
+                union_spans(
+                    [
+                        operand.span(),
+                        spans_of(conditions),
+                        spans_of(results),
+                        else_result.span(),
+                    ]
+                    .iter()
+                    .copied(),
+                )
+            }),
+            Expr::Exists(query, span) | Expr::Subquery(query, span) => {
+                span.or_else(|| query.span())
+            }
+        }
+    }
+}
+
+impl Spanned for Function {
+    fn span(&self) -> Span {
+        self.span.or_else(|| {
+            let mut span = self.name.span().union(&spans_of(&self.args));
+            if let Some(over) = &self.over {
+                span = span.union(&spans_of(&over.partition_by));
+                span = span.union(&spans_of(&over.order_by));
+            }
+            span
+        })
+    }
+}
+
+impl Spanned for DataType {
+    fn span(&self) -> Span {
+        match self {
+            DataType::Custom(name) => name.span(),
+            DataType::Array(inner) => inner.span(),
+            _ => Span::empty(),
+        }
+    }
+}
+
+impl Spanned for OrderByExpr {
+    fn span(&self) -> Span {
+        self.expr.span()
+    }
+}
+
+impl Spanned for Query {
+    fn span(&self) -> Span {
+        self.span.or_else(|| {
+            union_spans(
+                [
+                    spans_of(&self.ctes),
+                    self.body.span(),
+                    spans_of(&self.order_by),
+                    self.limit.span(),
+                    self.offset.span(),
+                    self.fetch.as_ref().and_then(|f| f.quantity.as_ref()).span(),
+                ]
+                .iter()
+                .copied(),
+            )
+        })
+    }
+}
+
+impl Spanned for Cte {
+    fn span(&self) -> Span {
+        self.alias.span().union(&self.query.span())
+    }
+}
+
+impl<T: Spanned> Spanned for Commented<T> {
+    fn span(&self) -> Span {
+        self.node.span()
+    }
+}
+
+impl Spanned for TableAlias {
+    fn span(&self) -> Span {
+        self.name.span().union(&spans_of(&self.columns))
+    }
+}
+
+impl Spanned for SetExpr {
+    fn span(&self) -> Span {
+        match self {
+            SetExpr::Select(select) => select.span(),
+            SetExpr::Query(query) => query.span(),
+            SetExpr::SetOperation { left, right, .. } => left.span().union(&right.span()),
+            SetExpr::Values(Values(rows)) => union_spans(rows.iter().map(spans_of)),
+        }
+    }
+}
+
+impl Spanned for Select {
+    fn span(&self) -> Span {
+        self.span.or_else(|| {
+            union_spans(
+                [
+                    spans_of(&self.projection),
+                    spans_of(&self.from),
+                    self.selection.span(),
+                    spans_of(&self.group_by),
+                    self.having.span(),
+                ]
+                .iter()
+                .copied(),
+            )
+        })
+    }
+}
+
+impl Spanned for SelectItem {
+    fn span(&self) -> Span {
+        match self {
+            SelectItem::UnnamedExpr(expr) => expr.span(),
+            SelectItem::ExprWithAlias { expr, alias } => expr.span().union(&alias.span),
+            SelectItem::QualifiedWildcard(prefix, span) => span.or_else(|| prefix.span()),
+            SelectItem::Wildcard(span) => span.0,
+            SelectItem::Commented { item, .. } => item.span(),
+        }
+    }
+}
+
+impl Spanned for TableWithJoins {
+    fn span(&self) -> Span {
+        self.relation.span().union(&spans_of(&self.joins))
+    }
+}
+
+impl Spanned for Join {
+    fn span(&self) -> Span {
+        let constraint = match &self.join_operator {
+            JoinOperator::Inner(constraint)
+            | JoinOperator::LeftOuter(constraint)
+            | JoinOperator::RightOuter(constraint)
+            | JoinOperator::FullOuter(constraint) => match constraint {
+                JoinConstraint::On(expr) => expr.span(),
+                JoinConstraint::Using(columns) => spans_of(columns),
+                JoinConstraint::Natural => Span::empty(),
+            },
+            JoinOperator::CrossJoin | JoinOperator::CrossApply | JoinOperator::OuterApply => {
+                Span::empty()
+            }
+        };
+        self.relation.span().union(&constraint)
+    }
+}
+
+impl Spanned for TableFactor {
+    fn span(&self) -> Span {
+        match self {
+            TableFactor::Table {
+                name,
+                alias,
+                args,
+                with_hints,
+                span,
+            } => span.or_else(|| {
+                union_spans(
+                    [
+                        name.span(),
+                        alias.span(),
+                        spans_of(args),
+                        spans_of(with_hints),
+                    ]
+                    .iter()
+                    .copied(),
+                )
+            }),
+            TableFactor::Derived {
+                subquery,
+                alias,
+                span,
+                ..
+            } => span.or_else(|| subquery.span().union(&alias.span())),
+            TableFactor::NestedJoin(table, span) => span.or_else(|| table.span()),
+        }
+    }
+}
+
+impl Spanned for Assignment {
+    fn span(&self) -> Span {
+        self.id.span.union(&self.value.span())
+    }
+}
+
+impl Spanned for SqlOption {
+    fn span(&self) -> Span {
+        self.name.span
+    }
+}
+
+impl Spanned for ColumnDef {
+    fn span(&self) -> Span {
+        union_spans(
+            [
+                self.name.span,
+                self.data_type.span(),
+                self.collation.span(),
+                spans_of(&self.options),
+            ]
+            .iter()
+            .copied(),
+        )
+    }
+}
+
+impl Spanned for ColumnOptionDef {
+    fn span(&self) -> Span {
+        let option = match &self.option {
+            ColumnOption::Default(expr) | ColumnOption::Check(expr) => expr.span(),
+            ColumnOption::ForeignKey {
+                foreign_table,
+                referred_columns,
+            } => foreign_table.span().union(&spans_of(referred_columns)),
+            ColumnOption::Null | ColumnOption::NotNull | ColumnOption::Unique { .. } => {
+                Span::empty()
+            }
+        };
+        self.name.span().union(&option)
+    }
+}
+
+impl Spanned for TableConstraint {
+    fn span(&self) -> Span {
+        match self {
+            TableConstraint::Unique { name, columns, .. } => name.span().union(&spans_of(columns)),
+            TableConstraint::ForeignKey {
+                name,
+                columns,
+                foreign_table,
+                referred_columns,
+            } => union_spans(
+                [
+                    name.span(),
+                    spans_of(columns),
+                    foreign_table.span(),
+                    spans_of(referred_columns),
+                ]
+                .iter()
+                .copied(),
+            ),
+            TableConstraint::Check { name, expr } => name.span().union(&expr.span()),
+        }
+    }
+}
+
+impl Spanned for Statement {
+    fn span(&self) -> Span {
+        match self {
+            Statement::Query(query) => query.span(),
+            Statement::Insert {
+                table_name,
+                columns,
+                source,
+                span,
+            } => span.or_else(|| {
+                union_spans(
+                    [table_name.span(), spans_of(columns), source.span()]
+                        .iter()
+                        .copied(),
+                )
+            }),
+            Statement::Copy {
+                table_name,
+                columns,
+                span,
+                ..
+            } => span.or_else(|| table_name.span().union(&spans_of(columns))),
+            Statement::Update {
+                table_name,
+                assignments,
+                selection,
+                span,
+            } => span.or_else(|| {
+                union_spans(
+                    [table_name.span(), spans_of(assignments), selection.span()]
+                        .iter()
+                        .copied(),
+                )
+            }),
+            Statement::Delete {
+                table_name,
+                selection,
+                span,
+            } => span.or_else(|| table_name.span().union(&selection.span())),
+            Statement::CreateView {
+                name,
+                columns,
+                query,
+                with_options,
+                span,
+                ..
+            } => span.or_else(|| {
+                union_spans(
+                    [
+                        name.span(),
+                        spans_of(columns),
+                        spans_of(with_options),
+                        query.span(),
+                    ]
+                    .iter()
+                    .copied(),
+                )
+            }),
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                with_options,
+                span,
+                ..
+            } => span.or_else(|| {
+                union_spans(
+                    [
+                        name.span(),
+                        spans_of(columns),
+                        spans_of(constraints),
+                        spans_of(with_options),
+                    ]
+                    .iter()
+                    .copied(),
+                )
+            }),
+            Statement::CreateIndex {
+                name,
+                table_name,
+                columns,
+                span,
+                ..
+            } => span.or_else(|| {
+                union_spans(
+                    [name.span(), table_name.span(), spans_of(columns)]
+                        .iter()
+                        .copied(),
+                )
+            }),
+            Statement::AlterTable {
+                name,
+                operation,
+                span,
+            } => span.or_else(|| name.span().union(&operation.span())),
+            Statement::Drop { names, span, .. } => span.or_else(|| spans_of(names)),
+            Statement::SetVariable {
+                variable,
+                value,
+                span,
+                ..
+            } => span.or_else(|| match value {
+                SetVariableValue::Ident(ident) => variable.span.union(&ident.span),
+                SetVariableValue::Literal(_) => variable.span,
+            }),
+            Statement::ShowVariable { variable, span } => span.or_else(|| variable.span),
+            Statement::ShowColumns {
+                table_name,
+                filter,
+                span,
+                ..
+            } => span.or_else(|| match filter {
+                Some(ShowStatementFilter::Where(expr)) => table_name.span().union(&expr.span()),
+                _ => table_name.span(),
+            }),
+            Statement::StartTransaction { span, .. }
+            | Statement::SetTransaction { span, .. }
+            | Statement::Commit { span, .. }
+            | Statement::Rollback { span, .. } => span.0,
+            Statement::Commented { statement, .. } => statement.span(),
+        }
+    }
+}
+
+impl Statement {
+    /// Record `span` as the location of the statement. A query records its
+    /// own location, and a commented statement has that of the statement.
+    pub(crate) fn with_span(mut self, span: NodeSpan) -> Self {
+        match &mut self {
+            Statement::Insert { span: node, .. }
+            | Statement::Copy { span: node, .. }
+            | Statement::Update { span: node, .. }
+            | Statement::Delete { span: node, .. }
+            | Statement::CreateView { span: node, .. }
+            | Statement::CreateTable { span: node, .. }
+            | Statement::CreateIndex { span: node, .. }
+            | Statement::AlterTable { span: node, .. }
+            | Statement::Drop { span: node, .. }
+            | Statement::SetVariable { span: node, .. }
+            | Statement::ShowVariable { span: node, .. }
+            | Statement::ShowColumns { span: node, .. }
+            | Statement::StartTransaction { span: node, .. }
+            | Statement::SetTransaction { span: node, .. }
+            | Statement::Commit { span: node, .. }
+            | Statement::Rollback { span: node, .. } => *node = span,
+            Statement::Query(_) | Statement::Commented { .. } => (),
+        }
+        self
+    }
+}
+
+impl Spanned for AlterTableOperation {
+    fn span(&self) -> Span {
+        match self {
+            AlterTableOperation::AddConstraint(constraint) => constraint.span(),
+            AlterTableOperation::AddColumn { column_def } => column_def.span(),
+            AlterTableOperation::DropConstraint { name } => name.span,
+            AlterTableOperation::DropColumn { column_name, .. } => column_name.span,
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => old_column_name.span.union(&new_column_name.span),
+            AlterTableOperation::RenameTable { table_name } => table_name.span(),
+            AlterTableOperation::AlterColumn { column_name, op } => {
+                let op = match op {
+                    AlterColumnOperation::SetDefault { value } => value.span(),
+                    AlterColumnOperation::SetDataType { data_type } => data_type.span(),
+                    _ => Span::empty(),
+                };
+                column_name.span.union(&op)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+
+    fn parse(sql: &str) -> Statement {
+        Parser::parse_sql(&GenericDialect {}, sql.to_string())
+            .unwrap()
+            .pop()
+            .unwrap()
+    }
+
+    #[test]
+    fn ident_spans() {
+        let sql = "SELECT a,\n  \"b c\" FROM t";
+        let select = match parse(sql) {
+            Statement::Query(query) => match query.body {
+                SetExpr::Select(select) => select,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let b = select.projection[1].span();
+        assert_eq!(b.source_text(sql), "\"b c\"");
+        assert_eq!((b.start.line, b.start.column), (2, 3));
+        assert_eq!((b.end.line, b.end.column), (2, 8));
+        assert_eq!(select.from[0].span().source_text(sql), "t");
+    }
+
+    #[test]
+    fn compound_spans() {
+        let sql = "SELECT x FROM s.t AS u WHERE u.a = lower(b) AND c IS NULL";
+        let statement = parse(sql);
+        assert_eq!(statement.span().source_text(sql), sql);
+        match statement {
+            Statement::Query(query) => match query.body {
+                SetExpr::Select(select) => {
+                    assert_eq!(select.from[0].relation.span().source_text(sql), "s.t AS u");
+                    assert_eq!(
+                        select.selection.span().source_text(sql),
+                        "u.a = lower(b) AND c IS NULL"
+                    );
+                }
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn literal_and_keyword_spans() {
+        let items = [
+            "1",
+            "a + 1",
+            "-a",
+            "NOT a",
+            "'x' = 'y'",
+            "DATE '2020-01-01'",
+            "INTERVAL '1' DAY",
+            "CAST(a AS INT)",
+            "a::TEXT",
+            "EXTRACT(YEAR FROM a)",
+            "CASE WHEN a THEN 1 END",
+            "a IS NOT NULL",
+            "a NOT IN (1, 2)",
+            "a IN (SELECT 1)",
+            "EXISTS (SELECT 1)",
+            "(SELECT 1)",
+            "(a)",
+            "count(*)",
+            "?",
+            "*",
+            "t.*",
+        ];
+        let sql = format!("SELECT {} FROM t", items.join(", "));
+        let projection = match parse(&sql) {
+            Statement::Query(query) => match query.body {
+                SetExpr::Select(select) => select.projection,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        for (item, expected) in projection.iter().zip(items.iter()) {
+            assert_eq!(item.span().source_text(&sql), *expected);
+        }
+    }
+
+    #[test]
+    fn table_factor_spans() {
+        let sql = "SELECT * FROM t AS u WITH (NOLOCK), LATERAL (SELECT 1) AS d, (a JOIN b ON x)";
+        let from = match parse(sql) {
+            Statement::Query(query) => match query.body {
+                SetExpr::Select(select) => select.from,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let spans: Vec<_> = from
+            .iter()
+            .map(|table| table.relation.span().source_text(sql))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                "t AS u WITH (NOLOCK)",
+                "LATERAL (SELECT 1) AS d",
+                "(a JOIN b ON x)"
+            ]
+        );
+    }
+
+    #[test]
+    fn statement_spans() {
+        for sql in &[
+            "WITH c AS (SELECT 1) SELECT * FROM c ORDER BY 1 OFFSET 2 ROWS",
+            "(SELECT 1) UNION (SELECT 2)",
+            "INSERT INTO t VALUES (1)",
+            "CREATE TABLE t (a INT)",
+            "ALTER TABLE t DROP COLUMN a CASCADE",
+            "DROP TABLE t",
+            "COMMIT",
+        ] {
+            let source = format!("-- comment\n  {} ; ", sql);
+            let statement = parse(&source);
+            assert_eq!(statement.span().source_text(&source), *sql);
+        }
+    }
+
+    #[test]
+    fn no_spans_without_locations() {
+        assert_eq!(Expr::Identifier(Ident::new("a")).span(), Span::empty());
+        assert_eq!(
+            Expr::Value(Value::Null, NodeSpan::empty()).span(),
+            Span::empty()
+        );
+    }
+
+    #[test]
+    fn built_nodes_span_their_parsed_parts() {
+        let sql = "SELECT a + b";
+        let expr = match parse(sql) {
+            Statement::Query(query) => match query.body {
+                SetExpr::Select(mut select) => match select.projection.remove(0) {
+                    SelectItem::UnnamedExpr(expr) => expr,
+                    _ => unreachable!(),
+                },
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let nested = Expr::Nested(Box::new(expr), NodeSpan::empty());
+        assert_eq!(nested.span().source_text(sql), "a + b");
+    }
+
+    #[test]
+    fn empty_spans_are_not_locations() {
+        // The start of the input is a location, unlike the empty span
+        let start = Span::new(Location::start(), Location::start());
+        assert!(!start.is_empty());
+        assert_ne!(start, Span::empty());
+        assert_eq!(Span::empty().union(&start), start);
+    }
+
+    #[test]
+    fn spans_do_not_affect_equality() {
+        let one = |sql: &str| match parse(sql) {
+            Statement::Commented { statement, .. } => *statement,
+            statement => statement,
+        };
+        assert_eq!(one("SELECT 1 IS NULL"), one("SELECT  1  IS  NULL"));
+    }
+}
@@ -0,0 +1,1348 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recursive traversal of the AST.
+//!
+//! Implement [Visitor] (or [VisitorMut] to rewrite the tree in place),
+//! overriding only the hooks for the node types you are interested in, then
+//! pass it to one of the `walk_*` functions. The walk functions call the
+//! `pre_visit_*` hook of a node, recurse into all of its children, and then
+//! call the `post_visit_*` hook. Any hook can stop the traversal early by
+//! returning `ControlFlow::Break`, which is then returned from the walk.
+//!
+//! For example, to collect the names of all the tables referenced by a
+//! statement:
+//!
+//! ```
+//! use std::ops::ControlFlow;
+//! use sqlparser::ast::{walk_statement, ObjectName, Visitor};
+//! use sqlparser::dialect::GenericDialect;
+//! use sqlparser::parser::Parser;
+//!
+//! #[derive(Default)]
+//! struct Tables(Vec<String>);
+//!
+//! impl Visitor for Tables {
+//!     type Break = ();
+//!
+//!     fn pre_visit_relation(&mut self, relation: &ObjectName) -> ControlFlow<()> {
+//!         self.0.push(relation.to_string());
+//!         ControlFlow::Continue(())
+//!     }
+//! }
+//!
+//! let sql = "SELECT * FROM foo WHERE x IN (SELECT y FROM bar)";
+//! let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+//! let mut tables = Tables::default();
+//! walk_statement(&mut tables, &statements[0]);
+//! assert_eq!(tables.0, vec!["foo", "bar"]);
+//! ```
+
+use std::ops::ControlFlow;
+
+use super::*;
+
+/// A read-only visitor over the AST. See the [module documentation](self).
+///
+/// All hooks default to doing nothing and continuing the traversal.
+pub trait Visitor {
+    /// The value returned when a hook stops the traversal early.
+    type Break;
+
+    /// Invoked before visiting the children of a [Statement]
+    fn pre_visit_statement(&mut self, _statement: &Statement) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of a [Statement]
+    fn post_visit_statement(&mut self, _statement: &Statement) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting the children of a [Query]
+    fn pre_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of a [Query]
+    fn post_visit_query(&mut self, _query: &Query) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting the children of a [Select]
+    fn pre_visit_select(&mut self, _select: &Select) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of a [Select]
+    fn post_visit_select(&mut self, _select: &Select) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting the children of a [TableFactor]
+    fn pre_visit_table_factor(&mut self, _table_factor: &TableFactor) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of a [TableFactor]
+    fn post_visit_table_factor(&mut self, _table_factor: &TableFactor) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting an [ObjectName] that names a table or a view,
+    /// e.g. in `FROM`, `INSERT INTO`, `CREATE TABLE` or `REFERENCES`
+    fn pre_visit_relation(&mut self, _relation: &ObjectName) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting an [ObjectName] that names a table or a view
+    fn post_visit_relation(&mut self, _relation: &ObjectName) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting the children of an [Expr]
+    fn pre_visit_expr(&mut self, _expr: &Expr) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of an [Expr]
+    fn post_visit_expr(&mut self, _expr: &Expr) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting the children of a [DataType]
+    fn pre_visit_data_type(&mut self, _data_type: &DataType) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of a [DataType]
+    fn post_visit_data_type(&mut self, _data_type: &DataType) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked for every [Ident], including the parts of an [ObjectName]
+    fn visit_ident(&mut self, _ident: &Ident) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// A visitor that can modify the AST in place. See the [module documentation](self).
+///
+/// The `pre_visit_*` hooks may replace the node entirely, in which case the
+/// walk continues into the children of the replacement.
+pub trait VisitorMut {
+    /// The value returned when a hook stops the traversal early.
+    type Break;
+
+    /// Invoked before visiting the children of a [Statement]
+    fn pre_visit_statement(&mut self, _statement: &mut Statement) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of a [Statement]
+    fn post_visit_statement(&mut self, _statement: &mut Statement) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting the children of a [Query]
+    fn pre_visit_query(&mut self, _query: &mut Query) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of a [Query]
+    fn post_visit_query(&mut self, _query: &mut Query) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting the children of a [Select]
+    fn pre_visit_select(&mut self, _select: &mut Select) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of a [Select]
+    fn post_visit_select(&mut self, _select: &mut Select) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting the children of a [TableFactor]
+    fn pre_visit_table_factor(
+        &mut self,
+        _table_factor: &mut TableFactor,
+    ) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of a [TableFactor]
+    fn post_visit_table_factor(
+        &mut self,
+        _table_factor: &mut TableFactor,
+    ) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting an [ObjectName] that names a table or a view,
+    /// e.g. in `FROM`, `INSERT INTO`, `CREATE TABLE` or `REFERENCES`
+    fn pre_visit_relation(&mut self, _relation: &mut ObjectName) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting an [ObjectName] that names a table or a view
+    fn post_visit_relation(&mut self, _relation: &mut ObjectName) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting the children of an [Expr]
+    fn pre_visit_expr(&mut self, _expr: &mut Expr) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of an [Expr]
+    fn post_visit_expr(&mut self, _expr: &mut Expr) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked before visiting the children of a [DataType]
+    fn pre_visit_data_type(&mut self, _data_type: &mut DataType) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked after visiting the children of a [DataType]
+    fn post_visit_data_type(&mut self, _data_type: &mut DataType) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+
+    /// Invoked for every [Ident], including the parts of an [ObjectName]
+    fn visit_ident(&mut self, _ident: &mut Ident) -> ControlFlow<Self::Break> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// Walk a [Statement] and all of its children with a [Visitor]
+pub fn walk_statement<V: Visitor>(visitor: &mut V, statement: &Statement) -> ControlFlow<V::Break> {
+    // Visitors see through the comments around a statement
+    if let Statement::Commented { statement, .. } = statement {
+        return walk_statement(visitor, statement);
+    }
+    visitor.pre_visit_statement(statement)?;
+    match statement {
+        Statement::Query(query) => walk_query(visitor, query)?,
+        Statement::Insert {
+            table_name,
+            columns,
+            source,
+            ..
+        } => {
+            walk_relation(visitor, table_name)?;
+            walk_idents(visitor, columns)?;
+            walk_query(visitor, source)?;
+        }
+        Statement::Copy {
+            table_name,
+            columns,
+            ..
+        } => {
+            walk_relation(visitor, table_name)?;
+            walk_idents(visitor, columns)?;
+        }
+        Statement::Update {
+            table_name,
+            assignments,
+            selection,
+            ..
+        } => {
+            walk_relation(visitor, table_name)?;
+            for assignment in assignments {
+                visitor.visit_ident(&assignment.id)?;
+                walk_expr(visitor, &assignment.value)?;
+            }
+            walk_opt_expr(visitor, selection)?;
+        }
+        Statement::Delete {
+            table_name,
+            selection,
+            ..
+        } => {
+            walk_relation(visitor, table_name)?;
+            walk_opt_expr(visitor, selection)?;
+        }
+        Statement::CreateView {
+            name,
+            columns,
+            query,
+            with_options,
+            ..
+        } => {
+            walk_relation(visitor, name)?;
+            walk_idents(visitor, columns)?;
+            walk_sql_options(visitor, with_options)?;
+            walk_query(visitor, query)?;
+        }
+        Statement::CreateTable {
+            name,
+            columns,
+            constraints,
+            with_options,
+            ..
+        } => {
+            walk_relation(visitor, name)?;
+            for column in columns {
+                walk_column_def(visitor, &column.node)?;
+            }
+            for constraint in constraints {
+                walk_table_constraint(visitor, constraint)?;
+            }
+            walk_sql_options(visitor, with_options)?;
+        }
+        Statement::CreateIndex {
+            name,
+            table_name,
+            columns,
+            ..
+        } => {
+            walk_object_name(visitor, name)?;
+            walk_relation(visitor, table_name)?;
+            walk_order_by(visitor, columns)?;
+        }
+        Statement::AlterTable {
+            name, operation, ..
+        } => {
+            walk_relation(visitor, name)?;
+            match operation {
+                AlterTableOperation::AddConstraint(constraint) => {
+                    walk_table_constraint(visitor, constraint)?
+                }
+                AlterTableOperation::AddColumn { column_def } => {
+                    walk_column_def(visitor, column_def)?
+                }
+                AlterTableOperation::DropConstraint { name } => visitor.visit_ident(name)?,
+                AlterTableOperation::DropColumn { column_name, .. } => {
+                    visitor.visit_ident(column_name)?
+                }
+                AlterTableOperation::RenameColumn {
+                    old_column_name,
+                    new_column_name,
+                } => {
+                    visitor.visit_ident(old_column_name)?;
+                    visitor.visit_ident(new_column_name)?;
+                }
+                AlterTableOperation::RenameTable { table_name } => {
+                    walk_relation(visitor, table_name)?
+                }
+                AlterTableOperation::AlterColumn { column_name, op } => {
+                    visitor.visit_ident(column_name)?;
+                    match op {
+                        AlterColumnOperation::SetDefault { value } => walk_expr(visitor, value)?,
+                        AlterColumnOperation::SetDataType { data_type } => {
+                            walk_data_type(visitor, data_type)?
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Statement::Drop {
+            object_type, names, ..
+        } => {
+            for name in names {
+                match object_type {
+                    ObjectType::Index => walk_object_name(visitor, name)?,
+                    _ => walk_relation(visitor, name)?,
+                }
+            }
+        }
+        Statement::SetVariable {
+            variable, value, ..
+        } => {
+            visitor.visit_ident(variable)?;
+            if let SetVariableValue::Ident(ident) = value {
+                visitor.visit_ident(ident)?;
+            }
+        }
+        Statement::ShowVariable { variable, .. } => visitor.visit_ident(variable)?,
+        Statement::ShowColumns {
+            table_name, filter, ..
+        } => {
+            walk_relation(visitor, table_name)?;
+            if let Some(ShowStatementFilter::Where(expr)) = filter {
+                walk_expr(visitor, expr)?;
+            }
+        }
+        Statement::StartTransaction { .. }
+        | Statement::SetTransaction { .. }
+        | Statement::Commit { .. }
+        | Statement::Rollback { .. }
+        | Statement::Commented { .. } => {}
+    }
+    visitor.post_visit_statement(statement)
+}
+
+/// Walk a [Query] and all of its children with a [Visitor]
+pub fn walk_query<V: Visitor>(visitor: &mut V, query: &Query) -> ControlFlow<V::Break> {
+    visitor.pre_visit_query(query)?;
+    for cte in &query.ctes {
+        walk_table_alias(visitor, &cte.alias)?;
+        walk_query(visitor, &cte.query)?;
+    }
+    walk_set_expr(visitor, &query.body)?;
+    walk_order_by(visitor, &query.order_by)?;
+    walk_opt_expr(visitor, &query.limit)?;
+    walk_opt_expr(visitor, &query.offset)?;
+    if let Some(fetch) = &query.fetch {
+        walk_opt_expr(visitor, &fetch.quantity)?;
+    }
+    visitor.post_visit_query(query)
+}
+
+/// Walk a [SetExpr] and all of its children with a [Visitor]
+pub fn walk_set_expr<V: Visitor>(visitor: &mut V, set_expr: &SetExpr) -> ControlFlow<V::Break> {
+    match set_expr {
+        SetExpr::Select(select) => walk_select(visitor, select),
+        SetExpr::Query(query) => walk_query(visitor, query),
+        SetExpr::SetOperation { left, right, .. } => {
+            walk_set_expr(visitor, left)?;
+            walk_set_expr(visitor, right)
+        }
+        SetExpr::Values(Values(rows)) => {
+            for row in rows {
+                walk_exprs(visitor, row)?;
+            }
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+/// Walk a [Select] and all of its children with a [Visitor]
+pub fn walk_select<V: Visitor>(visitor: &mut V, select: &Select) -> ControlFlow<V::Break> {
+    visitor.pre_visit_select(select)?;
+    for item in &select.projection {
+        walk_select_item(visitor, item)?;
+    }
+    for table in &select.from {
+        walk_table_with_joins(visitor, table)?;
+    }
+    walk_opt_expr(visitor, &select.selection)?;
+    walk_exprs(visitor, &select.group_by)?;
+    walk_opt_expr(visitor, &select.having)?;
+    visitor.post_visit_select(select)
+}
+
+fn walk_select_item<V: Visitor>(visitor: &mut V, item: &SelectItem) -> ControlFlow<V::Break> {
+    match item {
+        SelectItem::UnnamedExpr(expr) => walk_expr(visitor, expr)?,
+        SelectItem::ExprWithAlias { expr, alias } => {
+            walk_expr(visitor, expr)?;
+            visitor.visit_ident(alias)?;
+        }
+        SelectItem::QualifiedWildcard(prefix, _) => walk_object_name(visitor, prefix)?,
+        SelectItem::Wildcard(_) => {}
+        SelectItem::Commented { item, .. } => walk_select_item(visitor, item)?,
+    }
+    ControlFlow::Continue(())
+}
+
+/// Walk a [TableWithJoins] and all of its children with a [Visitor]
+pub fn walk_table_with_joins<V: Visitor>(
+    visitor: &mut V,
+    table: &TableWithJoins,
+) -> ControlFlow<V::Break> {
+    walk_table_factor(visitor, &table.relation)?;
+    for join in &table.joins {
+        walk_table_factor(visitor, &join.relation)?;
+        match &join.join_operator {
+            JoinOperator::Inner(constraint)
+            | JoinOperator::LeftOuter(constraint)
+            | JoinOperator::RightOuter(constraint)
+            | JoinOperator::FullOuter(constraint) => match constraint {
+                JoinConstraint::On(expr) => walk_expr(visitor, expr)?,
+                JoinConstraint::Using(columns) => walk_idents(visitor, columns)?,
+                JoinConstraint::Natural => {}
+            },
+            JoinOperator::CrossJoin | JoinOperator::CrossApply | JoinOperator::OuterApply => {}
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+/// Walk a [TableFactor] and all of its children with a [Visitor]
+pub fn walk_table_factor<V: Visitor>(
+    visitor: &mut V,
+    table_factor: &TableFactor,
+) -> ControlFlow<V::Break> {
+    visitor.pre_visit_table_factor(table_factor)?;
+    match table_factor {
+        TableFactor::Table {
+            name,
+            alias,
+            args,
+            with_hints,
+            ..
+        } => {
+            walk_relation(visitor, name)?;
+            if let Some(alias) = alias {
+                walk_table_alias(visitor, alias)?;
+            }
+            walk_exprs(visitor, args)?;
+            walk_exprs(visitor, with_hints)?;
+        }
+        TableFactor::Derived {
+            subquery, alias, ..
+        } => {
+            walk_query(visitor, subquery)?;
+            if let Some(alias) = alias {
+                walk_table_alias(visitor, alias)?;
+            }
+        }
+        TableFactor::NestedJoin(table, _) => walk_table_with_joins(visitor, table)?,
+    }
+    visitor.post_visit_table_factor(table_factor)
+}
+
+/// Walk an [Expr] and all of its children with a [Visitor]
+pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr) -> ControlFlow<V::Break> {
+    visitor.pre_visit_expr(expr)?;
+    match expr {
+        Expr::Identifier(ident) => visitor.visit_ident(ident)?,
+        Expr::Wildcard(_) | Expr::Value(_, _) | Expr::Placeholder(_, _) => {}
+        Expr::QualifiedWildcard(idents, _) | Expr::CompoundIdentifier(idents) => {
+            walk_idents(visitor, idents)?
+        }
+        Expr::IsNull(expr, _) | Expr::IsNotNull(expr, _) | Expr::Nested(expr, _) => {
+            walk_expr(visitor, expr)?
+        }
+        Expr::InList { expr, list, .. } => {
+            walk_expr(visitor, expr)?;
+            walk_exprs(visitor, list)?;
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            walk_expr(visitor, expr)?;
+            walk_query(visitor, subquery)?;
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            walk_expr(visitor, expr)?;
+            walk_expr(visitor, low)?;
+            walk_expr(visitor, high)?;
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            walk_expr(visitor, left)?;
+            walk_expr(visitor, right)?;
+        }
+        Expr::UnaryOp { expr, .. } | Expr::Extract { expr, .. } => walk_expr(visitor, expr)?,
+        Expr::Cast {
+            expr, data_type, ..
+        } => {
+            walk_expr(visitor, expr)?;
+            walk_data_type(visitor, data_type)?;
+        }
+        Expr::Collate { expr, collation } => {
+            walk_expr(visitor, expr)?;
+            walk_object_name(visitor, collation)?;
+        }
+        Expr::Function(function) => {
+            walk_object_name(visitor, &function.name)?;
+            walk_exprs(visitor, &function.args)?;
+            if let Some(over) = &function.over {
+                walk_exprs(visitor, &over.partition_by)?;
+                walk_order_by(visitor, &over.order_by)?;
+            }
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+            ..
+        } => {
+            if let Some(operand) = operand {
+                walk_expr(visitor, operand)?;
+            }
+            for (condition, result) in conditions.iter().zip(results) {
+                walk_expr(visitor, condition)?;
+                walk_expr(visitor, result)?;
+            }
+            if let Some(else_result) = else_result {
+                walk_expr(visitor, else_result)?;
+            }
+        }
+        Expr::Exists(query, _) | Expr::Subquery(query, _) => walk_query(visitor, query)?,
+    }
+    visitor.post_visit_expr(expr)
+}
+
+/// Walk a [DataType] and all of its children with a [Visitor]
+pub fn walk_data_type<V: Visitor>(visitor: &mut V, data_type: &DataType) -> ControlFlow<V::Break> {
+    visitor.pre_visit_data_type(data_type)?;
+    match data_type {
+        DataType::Custom(name) => walk_object_name(visitor, name)?,
+        DataType::Array(inner) => walk_data_type(visitor, inner)?,
+        _ => {}
+    }
+    visitor.post_visit_data_type(data_type)
+}
+
+/// Walk all the parts of an [ObjectName] with a [Visitor]
+pub fn walk_object_name<V: Visitor>(visitor: &mut V, name: &ObjectName) -> ControlFlow<V::Break> {
+    walk_idents(visitor, &name.0)
+}
+
+fn walk_relation<V: Visitor>(visitor: &mut V, relation: &ObjectName) -> ControlFlow<V::Break> {
+    visitor.pre_visit_relation(relation)?;
+    walk_object_name(visitor, relation)?;
+    visitor.post_visit_relation(relation)
+}
+
+fn walk_column_def<V: Visitor>(visitor: &mut V, column: &ColumnDef) -> ControlFlow<V::Break> {
+    visitor.visit_ident(&column.name)?;
+    walk_data_type(visitor, &column.data_type)?;
+    if let Some(collation) = &column.collation {
+        walk_object_name(visitor, collation)?;
+    }
+    for option in &column.options {
+        if let Some(name) = &option.name {
+            visitor.visit_ident(name)?;
+        }
+        match &option.option {
+            ColumnOption::Default(expr) | ColumnOption::Check(expr) => walk_expr(visitor, expr)?,
+            ColumnOption::ForeignKey {
+                foreign_table,
+                referred_columns,
+            } => {
+                walk_relation(visitor, foreign_table)?;
+                walk_idents(visitor, referred_columns)?;
+            }
+            ColumnOption::Null | ColumnOption::NotNull | ColumnOption::Unique { .. } => {}
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_table_constraint<V: Visitor>(
+    visitor: &mut V,
+    constraint: &TableConstraint,
+) -> ControlFlow<V::Break> {
+    match constraint {
+        TableConstraint::Unique { name, columns, .. } => {
+            walk_opt_ident(visitor, name)?;
+            walk_idents(visitor, columns)
+        }
+        TableConstraint::ForeignKey {
+            name,
+            columns,
+            foreign_table,
+            referred_columns,
+        } => {
+            walk_opt_ident(visitor, name)?;
+            walk_idents(visitor, columns)?;
+            walk_relation(visitor, foreign_table)?;
+            walk_idents(visitor, referred_columns)
+        }
+        TableConstraint::Check { name, expr } => {
+            walk_opt_ident(visitor, name)?;
+            walk_expr(visitor, expr)
+        }
+    }
+}
+
+fn walk_table_alias<V: Visitor>(visitor: &mut V, alias: &TableAlias) -> ControlFlow<V::Break> {
+    visitor.visit_ident(&alias.name)?;
+    walk_idents(visitor, &alias.columns)
+}
+
+fn walk_sql_options<V: Visitor>(visitor: &mut V, options: &[SqlOption]) -> ControlFlow<V::Break> {
+    for option in options {
+        visitor.visit_ident(&option.name)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_order_by<V: Visitor>(visitor: &mut V, order_by: &[OrderByExpr]) -> ControlFlow<V::Break> {
+    for order_by_expr in order_by {
+        walk_expr(visitor, &order_by_expr.expr)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_exprs<V: Visitor>(visitor: &mut V, exprs: &[Expr]) -> ControlFlow<V::Break> {
+    for expr in exprs {
+        walk_expr(visitor, expr)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_opt_expr<V: Visitor>(visitor: &mut V, expr: &Option<Expr>) -> ControlFlow<V::Break> {
+    if let Some(expr) = expr {
+        walk_expr(visitor, expr)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_idents<V: Visitor>(visitor: &mut V, idents: &[Ident]) -> ControlFlow<V::Break> {
+    for ident in idents {
+        visitor.visit_ident(ident)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_opt_ident<V: Visitor>(visitor: &mut V, ident: &Option<Ident>) -> ControlFlow<V::Break> {
+    if let Some(ident) = ident {
+        visitor.visit_ident(ident)?;
+    }
+    ControlFlow::Continue(())
+}
+
+/// Walk a [Statement] and all of its children with a [VisitorMut]
+pub fn walk_statement_mut<V: VisitorMut>(
+    visitor: &mut V,
+    statement: &mut Statement,
+) -> ControlFlow<V::Break> {
+    // Visitors see through the comments around a statement
+    if let Statement::Commented { statement, .. } = statement {
+        return walk_statement_mut(visitor, statement);
+    }
+    visitor.pre_visit_statement(statement)?;
+    match statement {
+        Statement::Query(query) => walk_query_mut(visitor, query)?,
+        Statement::Insert {
+            table_name,
+            columns,
+            source,
+            ..
+        } => {
+            walk_relation_mut(visitor, table_name)?;
+            walk_idents_mut(visitor, columns)?;
+            walk_query_mut(visitor, source)?;
+        }
+        Statement::Copy {
+            table_name,
+            columns,
+            ..
+        } => {
+            walk_relation_mut(visitor, table_name)?;
+            walk_idents_mut(visitor, columns)?;
+        }
+        Statement::Update {
+            table_name,
+            assignments,
+            selection,
+            ..
+        } => {
+            walk_relation_mut(visitor, table_name)?;
+            for assignment in assignments {
+                visitor.visit_ident(&mut assignment.id)?;
+                walk_expr_mut(visitor, &mut assignment.value)?;
+            }
+            walk_opt_expr_mut(visitor, selection)?;
+        }
+        Statement::Delete {
+            table_name,
+            selection,
+            ..
+        } => {
+            walk_relation_mut(visitor, table_name)?;
+            walk_opt_expr_mut(visitor, selection)?;
+        }
+        Statement::CreateView {
+            name,
+            columns,
+            query,
+            with_options,
+            ..
+        } => {
+            walk_relation_mut(visitor, name)?;
+            walk_idents_mut(visitor, columns)?;
+            walk_sql_options_mut(visitor, with_options)?;
+            walk_query_mut(visitor, query)?;
+        }
+        Statement::CreateTable {
+            name,
+            columns,
+            constraints,
+            with_options,
+            ..
+        } => {
+            walk_relation_mut(visitor, name)?;
+            for column in columns {
+                walk_column_def_mut(visitor, &mut column.node)?;
+            }
+            for constraint in constraints {
+                walk_table_constraint_mut(visitor, constraint)?;
+            }
+            walk_sql_options_mut(visitor, with_options)?;
+        }
+        Statement::CreateIndex {
+            name,
+            table_name,
+            columns,
+            ..
+        } => {
+            walk_object_name_mut(visitor, name)?;
+            walk_relation_mut(visitor, table_name)?;
+            walk_order_by_mut(visitor, columns)?;
+        }
+        Statement::AlterTable {
+            name, operation, ..
+        } => {
+            walk_relation_mut(visitor, name)?;
+            match operation {
+                AlterTableOperation::AddConstraint(constraint) => {
+                    walk_table_constraint_mut(visitor, constraint)?
+                }
+                AlterTableOperation::AddColumn { column_def } => {
+                    walk_column_def_mut(visitor, column_def)?
+                }
+                AlterTableOperation::DropConstraint { name } => visitor.visit_ident(name)?,
+                AlterTableOperation::DropColumn { column_name, .. } => {
+                    visitor.visit_ident(column_name)?
+                }
+                AlterTableOperation::RenameColumn {
+                    old_column_name,
+                    new_column_name,
+                } => {
+                    visitor.visit_ident(old_column_name)?;
+                    visitor.visit_ident(new_column_name)?;
+                }
+                AlterTableOperation::RenameTable { table_name } => {
+                    walk_relation_mut(visitor, table_name)?
+                }
+                AlterTableOperation::AlterColumn { column_name, op } => {
+                    visitor.visit_ident(column_name)?;
+                    match op {
+                        AlterColumnOperation::SetDefault { value } => {
+                            walk_expr_mut(visitor, value)?
+                        }
+                        AlterColumnOperation::SetDataType { data_type } => {
+                            walk_data_type_mut(visitor, data_type)?
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Statement::Drop {
+            object_type, names, ..
+        } => {
+            for name in names {
+                match object_type {
+                    ObjectType::Index => walk_object_name_mut(visitor, name)?,
+                    _ => walk_relation_mut(visitor, name)?,
+                }
+            }
+        }
+        Statement::SetVariable {
+            variable, value, ..
+        } => {
+            visitor.visit_ident(variable)?;
+            if let SetVariableValue::Ident(ident) = value {
+                visitor.visit_ident(ident)?;
+            }
+        }
+        Statement::ShowVariable { variable, .. } => visitor.visit_ident(variable)?,
+        Statement::ShowColumns {
+            table_name, filter, ..
+        } => {
+            walk_relation_mut(visitor, table_name)?;
+            if let Some(ShowStatementFilter::Where(expr)) = filter {
+                walk_expr_mut(visitor, expr)?;
+            }
+        }
+        Statement::StartTransaction { .. }
+        | Statement::SetTransaction { .. }
+        | Statement::Commit { .. }
+        | Statement::Rollback { .. }
+        | Statement::Commented { .. } => {}
+    }
+    visitor.post_visit_statement(statement)
+}
+
+/// Walk a [Query] and all of its children with a [VisitorMut]
+pub fn walk_query_mut<V: VisitorMut>(visitor: &mut V, query: &mut Query) -> ControlFlow<V::Break> {
+    visitor.pre_visit_query(query)?;
+    for cte in &mut query.ctes {
+        walk_table_alias_mut(visitor, &mut cte.alias)?;
+        walk_query_mut(visitor, &mut cte.query)?;
+    }
+    walk_set_expr_mut(visitor, &mut query.body)?;
+    walk_order_by_mut(visitor, &mut query.order_by)?;
+    walk_opt_expr_mut(visitor, &mut query.limit)?;
+    walk_opt_expr_mut(visitor, &mut query.offset)?;
+    if let Some(fetch) = &mut query.fetch {
+        walk_opt_expr_mut(visitor, &mut fetch.quantity)?;
+    }
+    visitor.post_visit_query(query)
+}
+
+/// Walk a [SetExpr] and all of its children with a [VisitorMut]
+pub fn walk_set_expr_mut<V: VisitorMut>(
+    visitor: &mut V,
+    set_expr: &mut SetExpr,
+) -> ControlFlow<V::Break> {
+    match set_expr {
+        SetExpr::Select(select) => walk_select_mut(visitor, select),
+        SetExpr::Query(query) => walk_query_mut(visitor, query),
+        SetExpr::SetOperation { left, right, .. } => {
+            walk_set_expr_mut(visitor, left)?;
+            walk_set_expr_mut(visitor, right)
+        }
+        SetExpr::Values(Values(rows)) => {
+            for row in rows {
+                walk_exprs_mut(visitor, row)?;
+            }
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+/// Walk a [Select] and all of its children with a [VisitorMut]
+pub fn walk_select_mut<V: VisitorMut>(
+    visitor: &mut V,
+    select: &mut Select,
+) -> ControlFlow<V::Break> {
+    visitor.pre_visit_select(select)?;
+    for item in &mut select.projection {
+        walk_select_item_mut(visitor, item)?;
+    }
+    for table in &mut select.from {
+        walk_table_with_joins_mut(visitor, table)?;
+    }
+    walk_opt_expr_mut(visitor, &mut select.selection)?;
+    walk_exprs_mut(visitor, &mut select.group_by)?;
+    walk_opt_expr_mut(visitor, &mut select.having)?;
+    visitor.post_visit_select(select)
+}
+
+fn walk_select_item_mut<V: VisitorMut>(
+    visitor: &mut V,
+    item: &mut SelectItem,
+) -> ControlFlow<V::Break> {
+    match item {
+        SelectItem::UnnamedExpr(expr) => walk_expr_mut(visitor, expr)?,
+        SelectItem::ExprWithAlias { expr, alias } => {
+            walk_expr_mut(visitor, expr)?;
+            visitor.visit_ident(alias)?;
+        }
+        SelectItem::QualifiedWildcard(prefix, _) => walk_object_name_mut(visitor, prefix)?,
+        SelectItem::Wildcard(_) => {}
+        SelectItem::Commented { item, .. } => walk_select_item_mut(visitor, item)?,
+    }
+    ControlFlow::Continue(())
+}
+
+/// Walk a [TableWithJoins] and all of its children with a [VisitorMut]
+pub fn walk_table_with_joins_mut<V: VisitorMut>(
+    visitor: &mut V,
+    table: &mut TableWithJoins,
+) -> ControlFlow<V::Break> {
+    walk_table_factor_mut(visitor, &mut table.relation)?;
+    for join in &mut table.joins {
+        walk_table_factor_mut(visitor, &mut join.relation)?;
+        match &mut join.join_operator {
+            JoinOperator::Inner(constraint)
+            | JoinOperator::LeftOuter(constraint)
+            | JoinOperator::RightOuter(constraint)
+            | JoinOperator::FullOuter(constraint) => match constraint {
+                JoinConstraint::On(expr) => walk_expr_mut(visitor, expr)?,
+                JoinConstraint::Using(columns) => walk_idents_mut(visitor, columns)?,
+                JoinConstraint::Natural => {}
+            },
+            JoinOperator::CrossJoin | JoinOperator::CrossApply | JoinOperator::OuterApply => {}
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+/// Walk a [TableFactor] and all of its children with a [VisitorMut]
+pub fn walk_table_factor_mut<V: VisitorMut>(
+    visitor: &mut V,
+    table_factor: &mut TableFactor,
+) -> ControlFlow<V::Break> {
+    visitor.pre_visit_table_factor(table_factor)?;
+    match table_factor {
+        TableFactor::Table {
+            name,
+            alias,
+            args,
+            with_hints,
+            ..
+        } => {
+            walk_relation_mut(visitor, name)?;
+            if let Some(alias) = alias {
+                walk_table_alias_mut(visitor, alias)?;
+            }
+            walk_exprs_mut(visitor, args)?;
+            walk_exprs_mut(visitor, with_hints)?;
+        }
+        TableFactor::Derived {
+            subquery, alias, ..
+        } => {
+            walk_query_mut(visitor, subquery)?;
+            if let Some(alias) = alias {
+                walk_table_alias_mut(visitor, alias)?;
+            }
+        }
+        TableFactor::NestedJoin(table, _) => walk_table_with_joins_mut(visitor, table)?,
+    }
+    visitor.post_visit_table_factor(table_factor)
+}
+
+/// Walk an [Expr] and all of its children with a [VisitorMut]
+pub fn walk_expr_mut<V: VisitorMut>(visitor: &mut V, expr: &mut Expr) -> ControlFlow<V::Break> {
+    visitor.pre_visit_expr(expr)?;
+    match expr {
+        Expr::Identifier(ident) => visitor.visit_ident(ident)?,
+        Expr::Wildcard(_) | Expr::Value(_, _) | Expr::Placeholder(_, _) => {}
+        Expr::QualifiedWildcard(idents, _) | Expr::CompoundIdentifier(idents) => {
+            walk_idents_mut(visitor, idents)?
+        }
+        Expr::IsNull(expr, _) | Expr::IsNotNull(expr, _) | Expr::Nested(expr, _) => {
+            walk_expr_mut(visitor, expr)?
+        }
+        Expr::InList { expr, list, .. } => {
+            walk_expr_mut(visitor, expr)?;
+            walk_exprs_mut(visitor, list)?;
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            walk_expr_mut(visitor, expr)?;
+            walk_query_mut(visitor, subquery)?;
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            walk_expr_mut(visitor, expr)?;
+            walk_expr_mut(visitor, low)?;
+            walk_expr_mut(visitor, high)?;
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            walk_expr_mut(visitor, left)?;
+            walk_expr_mut(visitor, right)?;
+        }
+        Expr::UnaryOp { expr, .. } | Expr::Extract { expr, .. } => walk_expr_mut(visitor, expr)?,
+        Expr::Cast {
+            expr, data_type, ..
+        } => {
+            walk_expr_mut(visitor, expr)?;
+            walk_data_type_mut(visitor, data_type)?;
+        }
+        Expr::Collate { expr, collation } => {
+            walk_expr_mut(visitor, expr)?;
+            walk_object_name_mut(visitor, collation)?;
+        }
+        Expr::Function(function) => {
+            walk_object_name_mut(visitor, &mut function.name)?;
+            walk_exprs_mut(visitor, &mut function.args)?;
+            if let Some(over) = &mut function.over {
+                walk_exprs_mut(visitor, &mut over.partition_by)?;
+                walk_order_by_mut(visitor, &mut over.order_by)?;
+            }
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+            ..
+        } => {
+            if let Some(operand) = operand {
+                walk_expr_mut(visitor, operand)?;
+            }
+            for (condition, result) in conditions.iter_mut().zip(results) {
+                walk_expr_mut(visitor, condition)?;
+                walk_expr_mut(visitor, result)?;
+            }
+            if let Some(else_result) = else_result {
+                walk_expr_mut(visitor, else_result)?;
+            }
+        }
+        Expr::Exists(query, _) | Expr::Subquery(query, _) => walk_query_mut(visitor, query)?,
+    }
+    visitor.post_visit_expr(expr)
+}
+
+/// Walk a [DataType] and all of its children with a [VisitorMut]
+pub fn walk_data_type_mut<V: VisitorMut>(
+    visitor: &mut V,
+    data_type: &mut DataType,
+) -> ControlFlow<V::Break> {
+    visitor.pre_visit_data_type(data_type)?;
+    match data_type {
+        DataType::Custom(name) => walk_object_name_mut(visitor, name)?,
+        DataType::Array(inner) => walk_data_type_mut(visitor, inner)?,
+        _ => {}
+    }
+    visitor.post_visit_data_type(data_type)
+}
+
+/// Walk all the parts of an [ObjectName] with a [VisitorMut]
+pub fn walk_object_name_mut<V: VisitorMut>(
+    visitor: &mut V,
+    name: &mut ObjectName,
+) -> ControlFlow<V::Break> {
+    walk_idents_mut(visitor, &mut name.0)
+}
+
+fn walk_relation_mut<V: VisitorMut>(
+    visitor: &mut V,
+    relation: &mut ObjectName,
+) -> ControlFlow<V::Break> {
+    visitor.pre_visit_relation(relation)?;
+    walk_object_name_mut(visitor, relation)?;
+    visitor.post_visit_relation(relation)
+}
+
+fn walk_column_def_mut<V: VisitorMut>(
+    visitor: &mut V,
+    column: &mut ColumnDef,
+) -> ControlFlow<V::Break> {
+    visitor.visit_ident(&mut column.name)?;
+    walk_data_type_mut(visitor, &mut column.data_type)?;
+    if let Some(collation) = &mut column.collation {
+        walk_object_name_mut(visitor, collation)?;
+    }
+    for option in &mut column.options {
+        if let Some(name) = &mut option.name {
+            visitor.visit_ident(name)?;
+        }
+        match &mut option.option {
+            ColumnOption::Default(expr) | ColumnOption::Check(expr) => {
+                walk_expr_mut(visitor, expr)?
+            }
+            ColumnOption::ForeignKey {
+                foreign_table,
+                referred_columns,
+            } => {
+                walk_relation_mut(visitor, foreign_table)?;
+                walk_idents_mut(visitor, referred_columns)?;
+            }
+            ColumnOption::Null | ColumnOption::NotNull | ColumnOption::Unique { .. } => {}
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_table_constraint_mut<V: VisitorMut>(
+    visitor: &mut V,
+    constraint: &mut TableConstraint,
+) -> ControlFlow<V::Break> {
+    match constraint {
+        TableConstraint::Unique { name, columns, .. } => {
+            walk_opt_ident_mut(visitor, name)?;
+            walk_idents_mut(visitor, columns)
+        }
+        TableConstraint::ForeignKey {
+            name,
+            columns,
+            foreign_table,
+            referred_columns,
+        } => {
+            walk_opt_ident_mut(visitor, name)?;
+            walk_idents_mut(visitor, columns)?;
+            walk_relation_mut(visitor, foreign_table)?;
+            walk_idents_mut(visitor, referred_columns)
+        }
+        TableConstraint::Check { name, expr } => {
+            walk_opt_ident_mut(visitor, name)?;
+            walk_expr_mut(visitor, expr)
+        }
+    }
+}
+
+fn walk_table_alias_mut<V: VisitorMut>(
+    visitor: &mut V,
+    alias: &mut TableAlias,
+) -> ControlFlow<V::Break> {
+    visitor.visit_ident(&mut alias.name)?;
+    walk_idents_mut(visitor, &mut alias.columns)
+}
+
+fn walk_sql_options_mut<V: VisitorMut>(
+    visitor: &mut V,
+    options: &mut [SqlOption],
+) -> ControlFlow<V::Break> {
+    for option in options {
+        visitor.visit_ident(&mut option.name)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_order_by_mut<V: VisitorMut>(
+    visitor: &mut V,
+    order_by: &mut [OrderByExpr],
+) -> ControlFlow<V::Break> {
+    for order_by_expr in order_by {
+        walk_expr_mut(visitor, &mut order_by_expr.expr)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_exprs_mut<V: VisitorMut>(visitor: &mut V, exprs: &mut [Expr]) -> ControlFlow<V::Break> {
+    for expr in exprs {
+        walk_expr_mut(visitor, expr)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_opt_expr_mut<V: VisitorMut>(
+    visitor: &mut V,
+    expr: &mut Option<Expr>,
+) -> ControlFlow<V::Break> {
+    if let Some(expr) = expr {
+        walk_expr_mut(visitor, expr)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_idents_mut<V: VisitorMut>(visitor: &mut V, idents: &mut [Ident]) -> ControlFlow<V::Break> {
+    for ident in idents {
+        visitor.visit_ident(ident)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_opt_ident_mut<V: VisitorMut>(
+    visitor: &mut V,
+    ident: &mut Option<Ident>,
+) -> ControlFlow<V::Break> {
+    if let Some(ident) = ident {
+        visitor.visit_ident(ident)?;
+    }
+    ControlFlow::Continue(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+
+    fn parse(sql: &str) -> Statement {
+        let mut statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        assert_eq!(statements.len(), 1);
+        statements.pop().unwrap()
+    }
+
+    #[derive(Default)]
+    struct Trace(Vec<String>);
+
+    impl Visitor for Trace {
+        type Break = ();
+
+        fn pre_visit_query(&mut self, _query: &Query) -> ControlFlow<()> {
+            self.0.push("PRE QUERY".to_string());
+            ControlFlow::Continue(())
+        }
+
+        fn post_visit_query(&mut self, _query: &Query) -> ControlFlow<()> {
+            self.0.push("POST QUERY".to_string());
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_relation(&mut self, relation: &ObjectName) -> ControlFlow<()> {
+            self.0.push(format!("RELATION {}", relation));
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+            self.0.push(format!("PRE EXPR {}", expr));
+            ControlFlow::Continue(())
+        }
+
+        fn post_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+            self.0.push(format!("POST EXPR {}", expr));
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_data_type(&mut self, data_type: &DataType) -> ControlFlow<()> {
+            self.0.push(format!("DATA TYPE {}", data_type));
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[test]
+    fn visit_order() {
+        let mut trace = Trace::default();
+        let statement = parse("SELECT CAST(a AS INT) FROM t WHERE EXISTS (SELECT 1 FROM u)");
+        assert_eq!(
+            walk_statement(&mut trace, &statement),
+            ControlFlow::Continue(())
+        );
+        assert_eq!(
+            trace.0,
+            vec![
+                "PRE QUERY",
+                "PRE EXPR CAST(a AS int)",
+                "PRE EXPR a",
+                "POST EXPR a",
+                "DATA TYPE int",
+                "POST EXPR CAST(a AS int)",
+                "RELATION t",
+                "PRE EXPR EXISTS (SELECT 1 FROM u)",
+                "PRE QUERY",
+                "PRE EXPR 1",
+                "POST EXPR 1",
+                "RELATION u",
+                "POST QUERY",
+                "POST EXPR EXISTS (SELECT 1 FROM u)",
+                "POST QUERY",
+            ]
+        );
+    }
+
+    #[test]
+    fn visit_break() {
+        struct FirstFunction;
+
+        impl Visitor for FirstFunction {
+            type Break = String;
+
+            fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<String> {
+                match expr {
+                    Expr::Function(f) => ControlFlow::Break(f.name.to_string()),
+                    _ => ControlFlow::Continue(()),
+                }
+            }
+        }
+
+        let statement = parse("UPDATE t SET a = lower(b) WHERE c = upper(d)");
+        assert_eq!(
+            walk_statement(&mut FirstFunction, &statement),
+            ControlFlow::Break("lower".to_string())
+        );
+    }
+
+    #[test]
+    fn visit_mut_rewrites_in_place() {
+        struct Rename;
+
+        impl VisitorMut for Rename {
+            type Break = ();
+
+            fn pre_visit_relation(&mut self, relation: &mut ObjectName) -> ControlFlow<()> {
+                relation.0.insert(0, Ident::new("archive"));
+                ControlFlow::Continue(())
+            }
+
+            fn post_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<()> {
+                if let Expr::Value(Value::Null, _) = expr {
+                    *expr = Expr::Value(Value::Boolean(false), NodeSpan::empty());
+                }
+                ControlFlow::Continue(())
+            }
+        }
+
+        let mut statement = parse("SELECT * FROM a JOIN (SELECT NULL FROM b) AS c ON NULL");
+        let _ = walk_statement_mut(&mut Rename, &mut statement);
+        assert_eq!(
+            statement.to_string(),
+            "SELECT * FROM archive.a JOIN (SELECT false FROM archive.b) AS c ON false"
+        );
+    }
+}
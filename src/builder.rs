@@ -0,0 +1,756 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Build queries programmatically, without spelling out every AST field.
+//!
+//! The builders and helpers produce ordinary AST values, shaped the same as
+//! the ones the parser produces for the equivalent SQL. In particular, the
+//! operands of the [Expr] methods are wrapped in `Expr::Nested` where the
+//! precedence of the operators requires it, so that `a.plus(b).multiply(c)`
+//! prints as `(a + b) * c`. Names are split on their periods, and the parts
+//! that are keywords or not plain words are double-quoted, so that any
+//! string is read back as the same name; see [table].
+//!
+//! ```
+//! use sqlparser::builder::{col, lit, select, table};
+//!
+//! let query = select([col("x.a"), col("y.b")])
+//!     .from(table("t").alias("x"))
+//!     .join(table("u").alias("y"), col("x.id").equal(col("y.id")))
+//!     .filter(col("x.a").gt(lit(1)))
+//!     .order_by([col("y.b").desc()])
+//!     .limit(10)
+//!     .build();
+//!
+//! assert_eq!(
+//!     query.to_string(),
+//!     "SELECT x.a, y.b FROM t AS x JOIN u AS y ON x.id = y.id \
+//!      WHERE x.a > 1 ORDER BY y.b DESC LIMIT 10"
+//! );
+//! ```
+
+use crate::ast::*;
+use crate::dialect::keywords::Keyword;
+
+/// Builds a [Query] with a single `SELECT` as its body, see [select]
+#[derive(Debug, Clone)]
+pub struct SelectBuilder {
+    select: Select,
+    order_by: Vec<OrderByExpr>,
+    limit: Option<Expr>,
+    offset: Option<Expr>,
+}
+
+/// Start building a `SELECT` of the given items. Expressions are converted
+/// to unnamed select items; use [Expr::alias] to name them.
+pub fn select<I, T>(items: I) -> SelectBuilder
+where
+    I: IntoIterator<Item = T>,
+    T: Into<SelectItem>,
+{
+    SelectBuilder {
+        select: Select {
+            distinct: false,
+            projection: items.into_iter().map(Into::into).collect(),
+            from: vec![],
+            selection: None,
+            group_by: vec![],
+            having: None,
+            comments: ClauseComments::default(),
+            span: NodeSpan::empty(),
+        },
+        order_by: vec![],
+        limit: None,
+        offset: None,
+    }
+}
+
+impl SelectBuilder {
+    /// Make this a `SELECT DISTINCT`
+    pub fn distinct(mut self) -> Self {
+        self.select.distinct = true;
+        self
+    }
+
+    /// Add a relation to the `FROM` clause
+    pub fn from(mut self, relation: TableFactor) -> Self {
+        self.select.from.push(TableWithJoins {
+            relation,
+            joins: vec![],
+        });
+        self
+    }
+
+    fn push_join(mut self, relation: TableFactor, join_operator: JoinOperator) -> Self {
+        self.select
+            .from
+            .last_mut()
+            .expect("a join must follow a relation in the FROM clause")
+            .joins
+            .push(Join {
+                relation,
+                join_operator,
+            });
+        self
+    }
+
+    /// Join `relation` to the last relation added with [SelectBuilder::from]
+    /// with an `[INNER] JOIN .. ON`
+    ///
+    /// # Panics
+    ///
+    /// Panics if no relation has been added, as a join needs one on its
+    /// left. The other joins panic in the same case.
+    pub fn join(self, relation: TableFactor, on: Expr) -> Self {
+        self.push_join(relation, JoinOperator::Inner(JoinConstraint::On(on)))
+    }
+
+    /// Like [SelectBuilder::join], but with a `LEFT JOIN`
+    pub fn left_join(self, relation: TableFactor, on: Expr) -> Self {
+        self.push_join(relation, JoinOperator::LeftOuter(JoinConstraint::On(on)))
+    }
+
+    /// Like [SelectBuilder::join], but with a `RIGHT JOIN`
+    pub fn right_join(self, relation: TableFactor, on: Expr) -> Self {
+        self.push_join(relation, JoinOperator::RightOuter(JoinConstraint::On(on)))
+    }
+
+    /// Like [SelectBuilder::join], but with a `FULL JOIN`
+    pub fn full_join(self, relation: TableFactor, on: Expr) -> Self {
+        self.push_join(relation, JoinOperator::FullOuter(JoinConstraint::On(on)))
+    }
+
+    /// Like [SelectBuilder::join], but with an `[INNER] JOIN .. USING`
+    pub fn join_using<'a, I>(self, relation: TableFactor, columns: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let columns = columns.into_iter().map(ident).collect();
+        self.push_join(
+            relation,
+            JoinOperator::Inner(JoinConstraint::Using(columns)),
+        )
+    }
+
+    /// Like [SelectBuilder::join], but with a `CROSS JOIN`
+    pub fn cross_join(self, relation: TableFactor) -> Self {
+        self.push_join(relation, JoinOperator::CrossJoin)
+    }
+
+    /// Set the `WHERE` condition, or add to it with `AND` if it is set
+    pub fn filter(mut self, condition: Expr) -> Self {
+        self.select.selection = Some(and_with(self.select.selection.take(), condition));
+        self
+    }
+
+    /// Add expressions to the `GROUP BY` clause
+    pub fn group_by<I>(mut self, exprs: I) -> Self
+    where
+        I: IntoIterator<Item = Expr>,
+    {
+        self.select.group_by.extend(exprs);
+        self
+    }
+
+    /// Set the `HAVING` condition, or add to it with `AND` if it is set
+    pub fn having(mut self, condition: Expr) -> Self {
+        self.select.having = Some(and_with(self.select.having.take(), condition));
+        self
+    }
+
+    /// Add expressions to the `ORDER BY` clause. Expressions are converted
+    /// with the default order; use [Expr::asc] or [Expr::desc] for another.
+    pub fn order_by<I, T>(mut self, exprs: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OrderByExpr>,
+    {
+        self.order_by.extend(exprs.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the `LIMIT`
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(lit(limit));
+        self
+    }
+
+    /// Set the `OFFSET`
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(lit(offset));
+        self
+    }
+
+    /// Return the built query
+    pub fn build(self) -> Query {
+        Query {
+            ctes: vec![],
+            body: SetExpr::Select(Box::new(self.select)),
+            order_by: self.order_by,
+            limit: self.limit,
+            offset: self.offset,
+            fetch: None,
+            span: NodeSpan::empty(),
+        }
+    }
+}
+
+impl From<SelectBuilder> for Query {
+    fn from(builder: SelectBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<SelectBuilder> for Statement {
+    fn from(builder: SelectBuilder) -> Self {
+        Statement::Query(Box::new(builder.build()))
+    }
+}
+
+fn and_with(left: Option<Expr>, right: Expr) -> Expr {
+    match left {
+        Some(left) => left.and(right),
+        None => right,
+    }
+}
+
+/// An identifier spelled `name`, double-quoted unless it is a word that is
+/// not a keyword, so that the parser reads it back as the same identifier
+fn ident(name: &str) -> Ident {
+    if is_word(name) && !is_keyword(name) {
+        Ident::new(name)
+    } else {
+        quoted(name)
+    }
+}
+
+/// Whether `name` is a word that needs no quotes, if it is not a keyword
+fn is_word(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_keyword(name: &str) -> bool {
+    Keyword::lookup(name) != Keyword::NoKeyword
+}
+
+/// The double-quoted identifier `name`. Panics if it contains a double
+/// quote, which cannot be quoted.
+fn quoted(name: &str) -> Ident {
+    assert!(
+        !name.contains('"'),
+        "the identifier {:?} cannot be quoted",
+        name
+    );
+    Ident::with_quote('"', name)
+}
+
+/// Split a possibly qualified name like `schema.table` on its periods, see
+/// [ident]
+fn idents(name: &str) -> Vec<Ident> {
+    name.split('.').map(ident).collect()
+}
+
+/// A table reference, possibly qualified like `schema.table`. Parts of the
+/// name that are keywords or not plain words are double-quoted.
+///
+/// # Panics
+///
+/// Panics if the name contains a double quote, which cannot be quoted.
+pub fn table(name: &str) -> TableFactor {
+    TableFactor::Table {
+        name: ObjectName(idents(name)),
+        alias: None,
+        args: vec![],
+        with_hints: vec![],
+        span: NodeSpan::empty(),
+    }
+}
+
+/// A derived table, i.e. a subquery in the `FROM` clause, with an alias
+pub fn derived(subquery: impl Into<Query>, alias: &str) -> TableFactor {
+    TableFactor::Derived {
+        lateral: false,
+        subquery: Box::new(subquery.into()),
+        alias: Some(TableAlias {
+            name: ident(alias),
+            columns: vec![],
+        }),
+        span: NodeSpan::empty(),
+    }
+}
+
+impl TableFactor {
+    /// Return the table or derived table with the given alias, quoted like
+    /// the names of [table]
+    ///
+    /// # Panics
+    ///
+    /// Panics for a nested join, which cannot have an alias, and if the alias
+    /// contains a double quote.
+    pub fn alias(mut self, name: &str) -> Self {
+        let alias = Some(TableAlias {
+            name: ident(name),
+            columns: vec![],
+        });
+        match &mut self {
+            TableFactor::Table { alias: a, .. } | TableFactor::Derived { alias: a, .. } => {
+                *a = alias
+            }
+            TableFactor::NestedJoin(_, _) => panic!("a nested join cannot have an alias"),
+        }
+        self
+    }
+}
+
+/// A column reference, possibly qualified like `table.column`, quoted like
+/// the names of [table]
+///
+/// # Panics
+///
+/// Panics if the name contains a double quote, which cannot be quoted.
+pub fn col(name: &str) -> Expr {
+    let mut parts = idents(name);
+    if parts.len() == 1 {
+        Expr::Identifier(parts.pop().unwrap())
+    } else {
+        Expr::CompoundIdentifier(parts)
+    }
+}
+
+/// A literal value. Negative numbers become a unary minus applied to a
+/// positive number, which is how the parser reads them.
+pub fn lit(value: impl Into<Value>) -> Expr {
+    match value.into() {
+        Value::Number(n) if n.to_string().starts_with('-') => Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr: Box::new(Expr::Value(
+                Value::Number(n.to_string()[1..].parse().unwrap()),
+                NodeSpan::empty(),
+            )),
+            span: NodeSpan::empty(),
+        },
+        value => Expr::Value(value, NodeSpan::empty()),
+    }
+}
+
+/// The `NULL` literal
+pub fn null() -> Expr {
+    Expr::Value(Value::Null, NodeSpan::empty())
+}
+
+/// A call of the function with the given, possibly qualified, name. Parts
+/// of the name that are not plain words are double-quoted, but keywords are
+/// not, as many functions such as `COUNT` are named by one.
+///
+/// # Panics
+///
+/// Panics if the name contains a double quote, which cannot be quoted.
+pub fn func<I>(name: &str, args: I) -> Expr
+where
+    I: IntoIterator<Item = Expr>,
+{
+    let name = name
+        .split('.')
+        .map(|part| {
+            if is_word(part) {
+                Ident::new(part)
+            } else {
+                quoted(part)
+            }
+        })
+        .collect();
+    Expr::Function(Function {
+        name: ObjectName(name),
+        args: args.into_iter().collect(),
+        over: None,
+        distinct: false,
+        span: NodeSpan::empty(),
+    })
+}
+
+/// `NOT expr`
+pub fn not(expr: Expr) -> Expr {
+    Expr::UnaryOp {
+        op: UnaryOperator::Not,
+        expr: Box::new(expr.operand(precedence::UNARY_NOT + 1)),
+        span: NodeSpan::empty(),
+    }
+}
+
+/// `EXISTS (subquery)`
+pub fn exists(subquery: impl Into<Query>) -> Expr {
+    Expr::Exists(Box::new(subquery.into()), NodeSpan::empty())
+}
+
+/// A parenthesized subquery used as an expression
+pub fn subquery(subquery: impl Into<Query>) -> Expr {
+    Expr::Subquery(Box::new(subquery.into()), NodeSpan::empty())
+}
+
+/// The unqualified `*` select item
+pub fn wildcard() -> SelectItem {
+    SelectItem::Wildcard(NodeSpan::empty())
+}
+
+/// A qualified `table.*` select item
+pub fn qualified_wildcard(table: &str) -> SelectItem {
+    SelectItem::QualifiedWildcard(ObjectName(idents(table)), NodeSpan::empty())
+}
+
+impl Expr {
+    fn binary_op(self, op: BinaryOperator, right: Expr) -> Expr {
+        let precedence = op.precedence();
+        Expr::BinaryOp {
+            left: Box::new(self.left_operand(precedence)),
+            op,
+            right: Box::new(right.operand(precedence + 1)),
+        }
+    }
+
+    /// `self = right`. Named so as not to hide [PartialEq::eq].
+    pub fn equal(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::Eq, right)
+    }
+
+    /// `self <> right`
+    pub fn not_equal(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::NotEq, right)
+    }
+
+    /// `self < right`
+    pub fn lt(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::Lt, right)
+    }
+
+    /// `self <= right`
+    pub fn lt_eq(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::LtEq, right)
+    }
+
+    /// `self > right`
+    pub fn gt(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::Gt, right)
+    }
+
+    /// `self >= right`
+    pub fn gt_eq(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::GtEq, right)
+    }
+
+    /// `self AND right`
+    pub fn and(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::And, right)
+    }
+
+    /// `self OR right`
+    pub fn or(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::Or, right)
+    }
+
+    /// `self LIKE pattern`
+    pub fn like(self, pattern: Expr) -> Expr {
+        self.binary_op(BinaryOperator::Like, pattern)
+    }
+
+    /// `self NOT LIKE pattern`
+    pub fn not_like(self, pattern: Expr) -> Expr {
+        self.binary_op(BinaryOperator::NotLike, pattern)
+    }
+
+    /// `self + right`
+    pub fn plus(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::Plus, right)
+    }
+
+    /// `self - right`
+    pub fn minus(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::Minus, right)
+    }
+
+    /// `self * right`
+    pub fn multiply(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::Multiply, right)
+    }
+
+    /// `self / right`
+    pub fn divide(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::Divide, right)
+    }
+
+    /// `self % right`
+    pub fn modulus(self, right: Expr) -> Expr {
+        self.binary_op(BinaryOperator::Modulus, right)
+    }
+
+    /// `self IS NULL`
+    pub fn is_null(self) -> Expr {
+        Expr::IsNull(
+            Box::new(self.left_operand(precedence::IS)),
+            NodeSpan::empty(),
+        )
+    }
+
+    /// `self IS NOT NULL`
+    pub fn is_not_null(self) -> Expr {
+        Expr::IsNotNull(
+            Box::new(self.left_operand(precedence::IS)),
+            NodeSpan::empty(),
+        )
+    }
+
+    /// `self IN (list)`
+    pub fn in_list<I>(self, list: I) -> Expr
+    where
+        I: IntoIterator<Item = Expr>,
+    {
+        Expr::InList {
+            expr: Box::new(self.left_operand(precedence::COMPARISON)),
+            list: list.into_iter().collect(),
+            negated: false,
+            span: NodeSpan::empty(),
+        }
+    }
+
+    /// `self IN (subquery)`
+    pub fn in_subquery(self, subquery: impl Into<Query>) -> Expr {
+        Expr::InSubquery {
+            expr: Box::new(self.left_operand(precedence::COMPARISON)),
+            subquery: Box::new(subquery.into()),
+            negated: false,
+            span: NodeSpan::empty(),
+        }
+    }
+
+    /// `self BETWEEN low AND high`
+    pub fn between(self, low: Expr, high: Expr) -> Expr {
+        Expr::Between {
+            expr: Box::new(self.left_operand(precedence::COMPARISON)),
+            negated: false,
+            low: Box::new(low.operand(precedence::COMPARISON + 1)),
+            high: Box::new(high.operand(precedence::COMPARISON + 1)),
+        }
+    }
+
+    /// `CAST(self AS data_type)`
+    pub fn cast(self, data_type: DataType) -> Expr {
+        Expr::Cast {
+            expr: Box::new(self),
+            data_type,
+            span: NodeSpan::empty(),
+        }
+    }
+
+    /// `(self)`, to override the precedence of the operators around it
+    pub fn nested(self) -> Expr {
+        Expr::Nested(Box::new(self), NodeSpan::empty())
+    }
+
+    /// The select item `self AS alias`
+    pub fn alias(self, alias: &str) -> SelectItem {
+        SelectItem::ExprWithAlias {
+            expr: self,
+            alias: ident(alias),
+        }
+    }
+
+    /// The ordering `self ASC`
+    pub fn asc(self) -> OrderByExpr {
+        OrderByExpr {
+            expr: self,
+            asc: Some(true),
+        }
+    }
+
+    /// The ordering `self DESC`
+    pub fn desc(self) -> OrderByExpr {
+        OrderByExpr {
+            expr: self,
+            asc: Some(false),
+        }
+    }
+}
+
+impl From<Expr> for SelectItem {
+    fn from(expr: Expr) -> Self {
+        SelectItem::UnnamedExpr(expr)
+    }
+}
+
+impl From<Expr> for OrderByExpr {
+    fn from(expr: Expr) -> Self {
+        OrderByExpr { expr, asc: None }
+    }
+}
+
+macro_rules! number_from {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(n: $ty) -> Self {
+                    // The call to parse() returns a bigdecimal when the
+                    // bigdecimal feature is enabled, and is otherwise a no-op.
+                    Value::Number(n.to_string().parse().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+number_from!(i32, i64, u32, u64);
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Boolean(b)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::SingleQuotedString(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::SingleQuotedString(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+
+    fn parse(sql: &str) -> Statement {
+        let mut statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        assert_eq!(statements.len(), 1);
+        statements.pop().unwrap()
+    }
+
+    #[test]
+    fn test_select_matches_parser() {
+        let statement: Statement = select(vec![
+            col("x.a").into(),
+            func("count", vec![col("b")]).alias("n"),
+            qualified_wildcard("y"),
+        ])
+        .distinct()
+        .from(table("s.t").alias("x"))
+        .left_join(table("u").alias("y"), col("x.id").equal(col("y.id")))
+        .join_using(table("v"), vec!["id"])
+        .from(table("w"))
+        .filter(col("a").gt(lit(1)))
+        .filter(col("b").is_not_null().or(col("c").like(lit("%z"))).nested())
+        .group_by(vec![col("x.a")])
+        .having(func("count", vec![col("b")]).gt_eq(lit(2)))
+        .order_by(vec![col("n").desc(), col("x.a").into()])
+        .limit(10)
+        .offset(5)
+        .into();
+        let sql = "SELECT DISTINCT x.a, count(b) AS n, y.* \
+                   FROM s.t AS x LEFT JOIN u AS y ON x.id = y.id JOIN v USING(id), w \
+                   WHERE a > 1 AND (b IS NOT NULL OR c LIKE '%z') \
+                   GROUP BY x.a HAVING count(b) >= 2 \
+                   ORDER BY n DESC, x.a LIMIT 10 OFFSET 5 ROWS";
+        assert_eq!(statement.to_string(), sql);
+        assert_eq!(statement, parse(sql));
+    }
+
+    #[test]
+    fn test_expressions_match_parser() {
+        let subquery = select(vec![col("id")]).from(table("t"));
+        let statement: Statement = select(vec![wildcard()])
+            .from(derived(subquery.clone(), "d"))
+            .filter(
+                not(col("a").in_list(vec![lit(1), lit(-2)]))
+                    .and(col("b").in_subquery(subquery.clone()))
+                    .and(col("c").between(lit(0), lit(true)))
+                    .and(exists(subquery))
+                    .and(col("d").plus(lit(1)).multiply(lit(2)).lt(null()))
+                    .and(col("e").cast(DataType::Int).not_equal(lit("x"))),
+            )
+            .into();
+        let sql = "SELECT * FROM (SELECT id FROM t) AS d \
+                   WHERE NOT a IN (1, - 2) AND b IN (SELECT id FROM t) \
+                   AND c BETWEEN 0 AND true AND EXISTS (SELECT id FROM t) \
+                   AND (d + 1) * 2 < NULL AND CAST(e AS int) <> 'x'";
+        assert_eq!(statement.to_string(), sql);
+        assert_eq!(statement, parse(sql));
+    }
+
+    #[test]
+    fn test_prefix_operators_match_parser() {
+        let statement: Statement = select(vec![
+            col("a").plus(lit(-1)),
+            lit(-1).plus(col("a")),
+            lit(-1).multiply(col("a")),
+            col("a").multiply(lit(-1)).is_null(),
+            col("a").equal(not(col("b"))).equal(col("c")),
+            not(col("a")).and(col("b")),
+        ])
+        .into();
+        let sql = "SELECT a + - 1, - 1 + a, (- 1) * a, a * - 1 IS NULL, \
+                   (a = NOT b) = c, NOT a AND b";
+        assert_eq!(statement.to_string(), sql);
+        assert_eq!(statement, parse(sql));
+    }
+
+    #[test]
+    fn test_names_are_quoted() {
+        let statement: Statement = select(vec![
+            col("t.a b").into(),
+            col("null").into(),
+            func("s.count", vec![col("x;y")]).alias("from"),
+            qualified_wildcard("t"),
+        ])
+        .from(table("t").alias("1t"))
+        .join_using(table("u v"), vec!["id", "select"])
+        .into();
+        let sql = "SELECT t.\"a b\", \"null\", s.count(\"x;y\") AS \"from\", t.* \
+                   FROM t AS \"1t\" JOIN \"u v\" USING(id, \"select\")";
+        assert_eq!(statement.to_string(), sql);
+        assert_eq!(statement, parse(sql));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be quoted")]
+    fn test_unquotable_name() {
+        let _ = col("a\" OR 1 = 1 --");
+    }
+
+    #[test]
+    #[should_panic(expected = "a nested join cannot have an alias")]
+    fn test_nested_join_alias() {
+        let _ = TableFactor::NestedJoin(
+            Box::new(TableWithJoins {
+                relation: table("t"),
+                joins: vec![],
+            }),
+            NodeSpan::empty(),
+        )
+        .alias("x");
+    }
+
+    #[test]
+    fn test_partial_eq_is_not_hidden() {
+        assert!(col("a").eq(&col("a")));
+        assert!(!col("a").equal(col("b")).eq(&col("a")));
+    }
+
+    #[test]
+    #[should_panic(expected = "a join must follow a relation in the FROM clause")]
+    fn test_join_without_from() {
+        let _ = select(vec![col("a")]).join(table("t"), col("a").equal(col("b")));
+    }
+}
@@ -10,7 +10,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dialect::Dialect;
+use crate::dialect::{Dialect, PlaceholderStyle, RowLimitSyntax};
 
 #[derive(Debug)]
 pub struct AnsiDialect {}
@@ -26,4 +26,20 @@ impl Dialect for AnsiDialect {
             || (ch >= '0' && ch <= '9')
             || ch == '_'
     }
+
+    fn identifier_quote_style(&self) -> Option<char> {
+        Some('"')
+    }
+
+    fn row_limit_syntax(&self) -> RowLimitSyntax {
+        RowLimitSyntax::Fetch
+    }
+
+    fn supports_placeholder(&self, style: PlaceholderStyle) -> bool {
+        style == PlaceholderStyle::QuestionMark || style == PlaceholderStyle::Colon
+    }
+
+    fn backslash_escapes(&self) -> Option<bool> {
+        Some(false)
+    }
 }
@@ -10,7 +10,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dialect::Dialect;
+use crate::dialect::{Dialect, PlaceholderStyle};
 
 #[derive(Debug)]
 pub struct GenericDialect {}
@@ -29,4 +29,9 @@ impl Dialect for GenericDialect {
             || ch == '#'
             || ch == '_'
     }
+
+    /// `@name` is an identifier in this dialect
+    fn supports_placeholder(&self, style: PlaceholderStyle) -> bool {
+        style != PlaceholderStyle::At
+    }
 }
@@ -21,8 +21,16 @@
 ///
 ///     As a matter of fact, most of these keywords are not used at all
 ///     and could be removed.
-/// 3) a `RESERVED_FOR_TABLE_ALIAS` array with keywords reserved in a
+/// 3) a `Keyword` enum with a variant for every keyword, and an
+/// `ALL_KEYWORDS_INDEX` array mapping each entry of `ALL_KEYWORDS` to its
+/// variant.
+/// 4) a `RESERVED_FOR_TABLE_ALIAS` array with keywords reserved in a
 /// "table alias" context.
+use std::cmp::Ordering;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Defines a string constant for a single keyword: `kw_def!(SELECT);`
 /// expands to `pub const SELECT = "SELECT";`
@@ -35,12 +43,38 @@ macro_rules! kw_def {
     };
 }
 
-/// Expands to a list of `kw_def!()` invocations for each keyword
-/// and defines an ALL_KEYWORDS array of the defined constants.
+/// Expands to a list of `kw_def!()` invocations for each keyword, the
+/// `Keyword` enum, and the ALL_KEYWORDS and ALL_KEYWORDS_INDEX arrays.
+/// The keywords must be listed in the (ASCII) order of their strings, so
+/// that ALL_KEYWORDS can be binary searched.
 macro_rules! define_keywords {
     ($(
         $ident:ident $(= $string_keyword:expr)?
     ),*) => {
+        /// A SQL keyword, or `NoKeyword` for a word that isn't one
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        #[allow(non_camel_case_types)]
+        pub enum Keyword {
+            NoKeyword,
+            $($ident),*
+        }
+
+        impl Keyword {
+            /// Return the keyword as it is written in SQL, or an empty string
+            /// for `NoKeyword`
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    Keyword::NoKeyword => "",
+                    $(Keyword::$ident => $ident),*
+                }
+            }
+        }
+
+        pub const ALL_KEYWORDS_INDEX: &[Keyword] = &[
+            $(Keyword::$ident),*
+        ];
+
         $(kw_def!($ident $(= $string_keyword)?);)*
 
         pub const ALL_KEYWORDS: &[&str] = &[
@@ -49,10 +83,32 @@ macro_rules! define_keywords {
     }
 }
 
+impl Keyword {
+    /// Look up the keyword matching `word` case-insensitively, or return
+    /// `Keyword::NoKeyword` if there is none.
+    pub fn lookup(word: &str) -> Keyword {
+        ALL_KEYWORDS
+            .binary_search_by(|keyword| cmp_uppercase(keyword, word))
+            .map_or(Keyword::NoKeyword, |index| ALL_KEYWORDS_INDEX[index])
+    }
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Compare `keyword` to `word` converted to uppercase, without allocating
+fn cmp_uppercase(keyword: &str, word: &str) -> Ordering {
+    keyword
+        .bytes()
+        .cmp(word.bytes().map(|b| b.to_ascii_uppercase()))
+}
+
 define_keywords!(
     ABS,
     ADD,
-    ASC,
     ALL,
     ALLOCATE,
     ALTER,
@@ -64,6 +120,7 @@ define_keywords!(
     ARRAY_AGG,
     ARRAY_MAX_CARDINALITY,
     AS,
+    ASC,
     ASENSITIVE,
     ASYMMETRIC,
     AT,
@@ -92,9 +149,9 @@ define_keywords!(
     CEILING,
     CHAIN,
     CHAR,
-    CHAR_LENGTH,
     CHARACTER,
     CHARACTER_LENGTH,
+    CHAR_LENGTH,
     CHECK,
     CLOB,
     CLOSE,
@@ -135,6 +192,7 @@ define_keywords!(
     CURRENT_USER,
     CURSOR,
     CYCLE,
+    DATA,
     DATE,
     DAY,
     DEALLOCATE,
@@ -157,6 +215,7 @@ define_keywords!(
     ELEMENT,
     ELSE,
     END,
+    END_EXEC = "END-EXEC",
     END_FRAME,
     END_PARTITION,
     EQUALS,
@@ -173,8 +232,8 @@ define_keywords!(
     FALSE,
     FETCH,
     FIELDS,
-    FIRST,
     FILTER,
+    FIRST,
     FIRST_VALUE,
     FLOAT,
     FLOOR,
@@ -200,6 +259,7 @@ define_keywords!(
     IDENTITY,
     IF,
     IN,
+    INDEX,
     INDICATOR,
     INNER,
     INOUT,
@@ -250,8 +310,8 @@ define_keywords!(
     NATURAL,
     NCHAR,
     NCLOB,
-    NEXT,
     NEW,
+    NEXT,
     NO,
     NONE,
     NORMALIZE,
@@ -262,8 +322,8 @@ define_keywords!(
     NULLIF,
     NUMERIC,
     OBJECT,
-    OCTET_LENGTH,
     OCCURRENCES_REGEX,
+    OCTET_LENGTH,
     OF,
     OFFSET,
     OLD,
@@ -278,12 +338,12 @@ define_keywords!(
     OVERLAPS,
     OVERLAY,
     PARAMETER,
-    PARTITION,
     PARQUET,
+    PARTITION,
     PERCENT,
-    PERCENT_RANK,
     PERCENTILE_CONT,
     PERCENTILE_DISC,
+    PERCENT_RANK,
     PERIOD,
     PORTION,
     POSITION,
@@ -315,6 +375,7 @@ define_keywords!(
     REGR_SXY,
     REGR_SYY,
     RELEASE,
+    RENAME,
     REPEATABLE,
     RESTRICT,
     RESULT,
@@ -325,8 +386,8 @@ define_keywords!(
     ROLLBACK,
     ROLLUP,
     ROW,
-    ROW_NUMBER,
     ROWS,
+    ROW_NUMBER,
     SAVEPOINT,
     SCOPE,
     SCROLL,
@@ -381,10 +442,11 @@ define_keywords!(
     TRANSLATION,
     TREAT,
     TRIGGER,
-    TRUNCATE,
     TRIM,
     TRIM_ARRAY,
     TRUE,
+    TRUNCATE,
+    TYPE,
     UESCAPE,
     UNBOUNDED,
     UNCOMMITTED,
@@ -400,11 +462,11 @@ define_keywords!(
     VALUE,
     VALUES,
     VALUE_OF,
-    VAR_POP,
-    VAR_SAMP,
     VARBINARY,
     VARCHAR,
     VARYING,
+    VAR_POP,
+    VAR_SAMP,
     VERSIONING,
     VIEW,
     WHEN,
@@ -415,29 +477,88 @@ define_keywords!(
     WITH,
     WITHIN,
     WITHOUT,
-    WRITE,
     WORK,
+    WRITE,
     YEAR,
-    ZONE,
-    END_EXEC = "END-EXEC"
+    ZONE
 );
 
 /// These keywords can't be used as a table alias, so that `FROM table_name alias`
 /// can be parsed unambiguously without looking ahead.
-pub const RESERVED_FOR_TABLE_ALIAS: &[&str] = &[
+pub const RESERVED_FOR_TABLE_ALIAS: &[Keyword] = &[
     // Reserved as both a table and a column alias:
-    WITH, SELECT, WHERE, GROUP, HAVING, ORDER, LIMIT, OFFSET, FETCH, UNION, EXCEPT, INTERSECT,
+    Keyword::WITH,
+    Keyword::SELECT,
+    Keyword::WHERE,
+    Keyword::GROUP,
+    Keyword::HAVING,
+    Keyword::ORDER,
+    Keyword::LIMIT,
+    Keyword::OFFSET,
+    Keyword::FETCH,
+    Keyword::UNION,
+    Keyword::EXCEPT,
+    Keyword::INTERSECT,
     // Reserved only as a table alias in the `FROM`/`JOIN` clauses:
-    ON, JOIN, INNER, CROSS, FULL, LEFT, RIGHT, NATURAL, USING,
+    Keyword::ON,
+    Keyword::JOIN,
+    Keyword::INNER,
+    Keyword::CROSS,
+    Keyword::FULL,
+    Keyword::LEFT,
+    Keyword::RIGHT,
+    Keyword::NATURAL,
+    Keyword::USING,
     // for MSSQL-specific OUTER APPLY (seems reserved in most dialects)
-    OUTER,
+    Keyword::OUTER,
 ];
 
 /// Can't be used as a column alias, so that `SELECT <expr> alias`
 /// can be parsed unambiguously without looking ahead.
-pub const RESERVED_FOR_COLUMN_ALIAS: &[&str] = &[
+pub const RESERVED_FOR_COLUMN_ALIAS: &[Keyword] = &[
     // Reserved as both a table and a column alias:
-    WITH, SELECT, WHERE, GROUP, HAVING, ORDER, LIMIT, OFFSET, FETCH, UNION, EXCEPT, INTERSECT,
+    Keyword::WITH,
+    Keyword::SELECT,
+    Keyword::WHERE,
+    Keyword::GROUP,
+    Keyword::HAVING,
+    Keyword::ORDER,
+    Keyword::LIMIT,
+    Keyword::OFFSET,
+    Keyword::FETCH,
+    Keyword::UNION,
+    Keyword::EXCEPT,
+    Keyword::INTERSECT,
     // Reserved only as a column alias in the `SELECT` clause:
-    FROM,
+    Keyword::FROM,
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_are_sorted() {
+        for pair in ALL_KEYWORDS.windows(2) {
+            assert!(
+                pair[0] < pair[1],
+                "{} must come before {}",
+                pair[1],
+                pair[0]
+            );
+        }
+    }
+
+    #[test]
+    fn lookup() {
+        assert_eq!(Keyword::SELECT, Keyword::lookup("select"));
+        assert_eq!(Keyword::END_EXEC, Keyword::lookup("End-Exec"));
+        assert_eq!(Keyword::ZONE, Keyword::lookup("ZONE"));
+        assert_eq!(Keyword::NoKeyword, Keyword::lookup("SELECTS"));
+        assert_eq!(Keyword::NoKeyword, Keyword::lookup(""));
+        for (keyword, string) in ALL_KEYWORDS_INDEX.iter().zip(ALL_KEYWORDS) {
+            assert_eq!(*keyword, Keyword::lookup(string));
+            assert_eq!(*string, keyword.as_str());
+        }
+    }
+}
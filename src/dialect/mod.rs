@@ -19,12 +19,44 @@ mod postgresql;
 
 use std::fmt::Debug;
 
+use crate::ast::{DataType, Expr, Ident, ObjectName, Statement, TableConstraint};
+use crate::formatter::FormatError;
+use crate::parser::{Parser, ParserError};
+
 pub use self::ansi::AnsiDialect;
 pub use self::generic::GenericDialect;
 pub use self::mssql::MsSqlDialect;
 pub use self::mysql::MySqlDialect;
 pub use self::postgresql::PostgreSqlDialect;
 
+/// How the rows returned by a query are limited in SQL rendered for a dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowLimitSyntax {
+    /// `LIMIT n`, `OFFSET m ROWS` and `FETCH FIRST n ROWS ONLY`, as parsed
+    Any,
+    /// `OFFSET m ROWS FETCH FIRST n ROWS ONLY`, as in ANSI SQL
+    Fetch,
+    /// `LIMIT n OFFSET m`, as in MySQL
+    Limit,
+    /// `SELECT TOP (n)`, or `OFFSET m ROWS FETCH NEXT n ROWS ONLY` if there
+    /// is an offset, as in MS SQL
+    Top,
+}
+
+/// The spellings of a bind parameter placeholder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlaceholderStyle {
+    /// `?`, numbered by its position in the statement, as in MySQL
+    QuestionMark,
+    /// `$1`, `$2`, ..., as in PostgreSQL. `$0` and numbers with leading
+    /// zeros are rejected.
+    Dollar,
+    /// `:name`, as in embedded SQL
+    Colon,
+    /// `@name`, as in MS SQL
+    At,
+}
+
 pub trait Dialect: Debug {
     /// Determine if a character starts a quoted identifier. The default
     /// implementation, accepting "double quoted" ids is both ANSI-compliant
@@ -38,4 +70,80 @@ pub trait Dialect: Debug {
     fn is_identifier_start(&self, ch: char) -> bool;
     /// Determine if a character is a valid unquoted identifier character
     fn is_identifier_part(&self, ch: char) -> bool;
+    /// Parse a statement that the dialect handles differently from the
+    /// default parser. Called before the parser looks at the first token of
+    /// every statement; return `None` (without consuming any tokens) to fall
+    /// back to the built-in rules.
+    fn parse_statement(&self, _parser: &mut Parser) -> Option<Result<Statement, ParserError>> {
+        None
+    }
+    /// Parse an expression prefix (a literal, identifier, function call,
+    /// unary operator, etc.). Called at the start of every expression; return
+    /// `None` (without consuming any tokens) to fall back to the built-in rules.
+    fn parse_prefix(&self, _parser: &mut Parser) -> Option<Result<Expr, ParserError>> {
+        None
+    }
+    /// Parse the operator following `expr` and its right-hand side, if any.
+    /// `precedence` is the value returned by [Dialect::get_next_precedence]
+    /// (or the built-in rules) for the operator. Return `None` (without
+    /// consuming any tokens) to fall back to the built-in rules.
+    fn parse_infix(
+        &self,
+        _parser: &mut Parser,
+        _expr: &Expr,
+        _precedence: u8,
+    ) -> Option<Result<Expr, ParserError>> {
+        None
+    }
+    /// Return the precedence of the operator at the parser's next token, or
+    /// `None` to fall back to the built-in rules. A precedence of 0 ends the
+    /// expression; the built-in operators use 5 for `OR`, 10 for `AND`, 20 for
+    /// comparisons, 30 for `+` and `-`, 40 for `*`, `/` and `%`, and 50 for `::`.
+    fn get_next_precedence(&self, _parser: &Parser) -> Option<Result<u8, ParserError>> {
+        None
+    }
+    /// The quote used for delimited identifiers in SQL rendered for this
+    /// dialect by [crate::formatter::Formatter], or `None` to keep the quotes
+    /// they were parsed with. Must be one of the characters listed in
+    /// `Word::matching_end_quote`.
+    fn identifier_quote_style(&self) -> Option<char> {
+        None
+    }
+    /// How SQL rendered for this dialect limits the rows returned by a query
+    fn row_limit_syntax(&self) -> RowLimitSyntax {
+        RowLimitSyntax::Any
+    }
+    /// Whether SQL rendered for this dialect can use `TRUE` and `FALSE`.
+    /// If not, they are rendered as `1` and `0`.
+    fn supports_boolean_literals(&self) -> bool {
+        true
+    }
+    /// Whether the tokenizer should read bind parameter placeholders spelled
+    /// in `style` as [crate::tokenizer::Token::Placeholder]
+    fn supports_placeholder(&self, _style: PlaceholderStyle) -> bool {
+        false
+    }
+    /// Whether a backslash in a string literal escapes the character after
+    /// it, as in MySQL, or `None` if that depends on how the database is set
+    /// up. Values bound to placeholders by [crate::placeholders::bind] have
+    /// their backslashes escaped if it does, and are rejected if unsure.
+    fn backslash_escapes(&self) -> Option<bool> {
+        None
+    }
+    /// Spell a data type in SQL rendered for this dialect, or return an error
+    /// if the dialect has no equivalent type. Return `None` to fall back to
+    /// the type's `Display` spelling.
+    fn render_data_type(&self, _data_type: &DataType) -> Option<Result<String, FormatError>> {
+        None
+    }
+    /// The name the database gives `constraint` of `table` if it is declared
+    /// without one, which [crate::semantic::diff_schemas] needs to drop it,
+    /// or `None` if it cannot be told from the constraint
+    fn default_constraint_name(
+        &self,
+        _table: &ObjectName,
+        _constraint: &TableConstraint,
+    ) -> Option<Ident> {
+        None
+    }
 }
@@ -10,7 +10,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dialect::Dialect;
+use crate::ast::DataType;
+use crate::dialect::{Dialect, PlaceholderStyle, RowLimitSyntax};
+use crate::formatter::FormatError;
 
 #[derive(Debug)]
 pub struct MsSqlDialect {}
@@ -35,4 +37,41 @@ impl Dialect for MsSqlDialect {
             || ch == '#'
             || ch == '_'
     }
+
+    fn identifier_quote_style(&self) -> Option<char> {
+        Some('[')
+    }
+
+    fn row_limit_syntax(&self) -> RowLimitSyntax {
+        RowLimitSyntax::Top
+    }
+
+    fn supports_boolean_literals(&self) -> bool {
+        false
+    }
+
+    /// Variables are spelled the same way, but `@@name` stays an identifier
+    fn supports_placeholder(&self, style: PlaceholderStyle) -> bool {
+        style == PlaceholderStyle::At
+    }
+
+    fn backslash_escapes(&self) -> Option<bool> {
+        Some(false)
+    }
+
+    fn render_data_type(&self, data_type: &DataType) -> Option<Result<String, FormatError>> {
+        match data_type {
+            DataType::Varchar(None) | DataType::Clob(_) => Some(Ok("varchar(max)".to_string())),
+            DataType::Text => Some(Ok("nvarchar(max)".to_string())),
+            DataType::Blob(_) | DataType::Bytea => Some(Ok("varbinary(max)".to_string())),
+            DataType::Uuid => Some(Ok("uniqueidentifier".to_string())),
+            DataType::Boolean => Some(Ok("bit".to_string())),
+            DataType::Double => Some(Ok("float(53)".to_string())),
+            DataType::Timestamp => Some(Ok("datetime2".to_string())),
+            DataType::Interval | DataType::Regclass | DataType::Array(_) => Some(Err(
+                FormatError::new(format!("MS SQL has no {} type", data_type)),
+            )),
+            _ => None,
+        }
+    }
 }
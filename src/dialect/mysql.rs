@@ -10,7 +10,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dialect::Dialect;
+use crate::ast::DataType;
+use crate::dialect::{Dialect, PlaceholderStyle, RowLimitSyntax};
+use crate::formatter::FormatError;
 
 #[derive(Debug)]
 pub struct MySqlDialect {}
@@ -30,4 +32,40 @@ impl Dialect for MySqlDialect {
     fn is_identifier_part(&self, ch: char) -> bool {
         self.is_identifier_start(ch) || (ch >= '0' && ch <= '9')
     }
+
+    fn is_delimited_identifier_start(&self, ch: char) -> bool {
+        ch == '"' || ch == '`'
+    }
+
+    fn identifier_quote_style(&self) -> Option<char> {
+        Some('`')
+    }
+
+    fn row_limit_syntax(&self) -> RowLimitSyntax {
+        RowLimitSyntax::Limit
+    }
+
+    fn supports_placeholder(&self, style: PlaceholderStyle) -> bool {
+        style == PlaceholderStyle::QuestionMark
+    }
+
+    /// Unless the `NO_BACKSLASH_ESCAPES` SQL mode is set
+    fn backslash_escapes(&self) -> Option<bool> {
+        Some(true)
+    }
+
+    fn render_data_type(&self, data_type: &DataType) -> Option<Result<String, FormatError>> {
+        match data_type {
+            DataType::Varchar(None) => {
+                Some(Err(FormatError::new("MySQL requires a length for VARCHAR")))
+            }
+            DataType::Uuid => Some(Ok("char(36)".to_string())),
+            DataType::Clob(_) => Some(Ok("longtext".to_string())),
+            DataType::Bytea => Some(Ok("longblob".to_string())),
+            DataType::Interval | DataType::Regclass | DataType::Array(_) => Some(Err(
+                FormatError::new(format!("MySQL has no {} type", data_type)),
+            )),
+            _ => None,
+        }
+    }
 }
@@ -10,7 +10,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dialect::Dialect;
+use crate::ast::{walk_expr, DataType, Expr, Ident, ObjectName, TableConstraint, Visitor};
+use crate::dialect::{Dialect, PlaceholderStyle};
+use crate::formatter::FormatError;
+use crate::semantic::normalize_ident;
+use std::ops::ControlFlow;
 
 #[derive(Debug)]
 pub struct PostgreSqlDialect {}
@@ -30,4 +34,99 @@ impl Dialect for PostgreSqlDialect {
             || ch == '$'
             || ch == '_'
     }
+
+    fn identifier_quote_style(&self) -> Option<char> {
+        Some('"')
+    }
+
+    fn supports_placeholder(&self, style: PlaceholderStyle) -> bool {
+        style == PlaceholderStyle::Dollar
+    }
+
+    /// Only in `E'...'` strings, as `standard_conforming_strings` is on by
+    /// default
+    fn backslash_escapes(&self) -> Option<bool> {
+        Some(false)
+    }
+
+    fn render_data_type(&self, data_type: &DataType) -> Option<Result<String, FormatError>> {
+        match data_type {
+            DataType::Double => Some(Ok("double precision".to_string())),
+            DataType::Clob(_) => Some(Ok("text".to_string())),
+            DataType::Binary(_) | DataType::Varbinary(_) | DataType::Blob(_) => {
+                Some(Ok("bytea".to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// The name as chosen by `ChooseConstraintName` and its callers in the
+    /// PostgreSQL sources, unless it is too long and would be truncated.
+    /// A name that clashes with another gets a number appended, which is
+    /// not accounted for.
+    fn default_constraint_name(
+        &self,
+        table: &ObjectName,
+        constraint: &TableConstraint,
+    ) -> Option<Ident> {
+        let table = normalize_ident(table.0.last()?);
+        let columns = |columns: &[Ident]| {
+            let columns: Vec<String> = columns.iter().map(normalize_ident).collect();
+            columns.join("_")
+        };
+        let name = match constraint {
+            TableConstraint::Unique {
+                is_primary: true, ..
+            } => format!("{}_pkey", table),
+            TableConstraint::Unique { columns: c, .. } => format!("{}_{}_key", table, columns(c)),
+            TableConstraint::ForeignKey { columns: c, .. } => {
+                format!("{}_{}_fkey", table, columns(c))
+            }
+            // A check that reads a single column is named after it
+            TableConstraint::Check { expr, .. } => {
+                let mut reader = ColumnReader::default();
+                let _ = walk_expr(&mut reader, expr);
+                match reader.columns.as_slice() {
+                    [column] => format!("{}_{}_check", table, column),
+                    _ => format!("{}_check", table),
+                }
+            }
+        };
+        // Names are truncated to NAMEDATALEN - 1 bytes
+        if name.len() > 63 {
+            return None;
+        }
+        let is_plain = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        Some(if is_plain {
+            Ident::new(name)
+        } else {
+            Ident::with_quote('"', name)
+        })
+    }
+}
+
+/// Collects the distinct columns an expression reads
+#[derive(Default)]
+struct ColumnReader {
+    columns: Vec<String>,
+}
+
+impl Visitor for ColumnReader {
+    type Break = ();
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+        let column = match expr {
+            Expr::Identifier(ident) => ident,
+            Expr::CompoundIdentifier(idents) => idents.last().unwrap(),
+            _ => return ControlFlow::Continue(()),
+        };
+        let column = normalize_ident(column);
+        if !self.columns.contains(&column) {
+            self.columns.push(column);
+        }
+        ControlFlow::Continue(())
+    }
 }
@@ -0,0 +1,193 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fingerprints that group statements differing only in their constants.
+//!
+//! ```
+//! use sqlparser::dialect::GenericDialect;
+//! use sqlparser::fingerprint::fingerprint;
+//! use sqlparser::parser::Parser;
+//!
+//! let parse = |sql: &str| Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+//! let a = fingerprint(&parse("SELECT * FROM Users WHERE id IN (1, 2, 3)")[0]);
+//! let b = fingerprint(&parse("select * from users where id in (42)")[0]);
+//! assert_eq!(a.sql, "SELECT * FROM users WHERE id IN (?)");
+//! assert_eq!(a.hash, b.hash);
+//! ```
+
+use crate::ast::*;
+use std::ops::ControlFlow;
+
+/// The normalized text of a statement and its hash
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    /// The statement as printed by [normalize]
+    pub sql: String,
+    /// A hash of `sql` that does not change between runs, platforms or
+    /// versions of this crate
+    pub hash: u64,
+}
+
+/// Return the fingerprint of `statement`. Statements that differ only in
+/// their literal values or placeholders, the number of literals in an `IN`
+/// list or of rows
+/// in `VALUES`, the case of unquoted identifiers and their comments have
+/// the same fingerprint.
+pub fn fingerprint(statement: &Statement) -> Fingerprint {
+    let sql = normalize(statement).to_string();
+    let hash = fnv1a(sql.as_bytes());
+    Fingerprint { sql, hash }
+}
+
+/// Return a copy of `statement` with:
+///
+/// * every literal value, including a negated number, and every placeholder
+///   replaced with the placeholder `?`
+/// * `IN` lists of nothing but placeholders reduced to a single `?`
+/// * repeated rows of `VALUES` reduced to one
+/// * unquoted identifiers folded to lower case
+/// * comments removed
+pub fn normalize(statement: &Statement) -> Statement {
+    let mut statement = statement.clone();
+    strip_comments(&mut statement);
+    let _ = walk_statement_mut(&mut Normalizer, &mut statement);
+    statement
+}
+
+fn placeholder() -> Expr {
+    Expr::Placeholder("?".to_string(), NodeSpan::empty())
+}
+
+fn is_placeholder(expr: &Expr) -> bool {
+    matches!(expr, Expr::Placeholder(_, _))
+}
+
+struct Normalizer;
+
+impl VisitorMut for Normalizer {
+    type Break = ();
+
+    fn post_visit_query(&mut self, query: &mut Query) -> ControlFlow<()> {
+        if let SetExpr::Values(values) = &mut query.body {
+            values.0.dedup();
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<()> {
+        match expr {
+            Expr::Value(_, _) | Expr::Placeholder(_, _) => *expr = placeholder(),
+            Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr: operand,
+                ..
+            }
+            | Expr::UnaryOp {
+                op: UnaryOperator::Plus,
+                expr: operand,
+                ..
+            } if is_placeholder(operand) => *expr = placeholder(),
+            Expr::InList { list, .. } if list.iter().all(is_placeholder) => {
+                *list = vec![placeholder()]
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn visit_ident(&mut self, ident: &mut Ident) -> ControlFlow<()> {
+        if ident.quote_style.is_none() {
+            ident.value = ident.value.to_lowercase();
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// The 64-bit FNV-1a hash of `bytes`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+
+    fn fingerprint_sql(sql: &str) -> Fingerprint {
+        let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        fingerprint(&statements[0])
+    }
+
+    fn normalized(sql: &str) -> String {
+        fingerprint_sql(sql).sql
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalized("SELECT a, 'x' FROM T WHERE b = -1.5 AND c BETWEEN 1 AND 2 LIMIT 10"),
+            "SELECT a, ? FROM t WHERE b = ? AND c BETWEEN ? AND ? LIMIT ?"
+        );
+        assert_eq!(
+            normalized("SELECT \"Mixed\".Col FROM \"Mixed\" WHERE x NOT IN (1, 2, NULL)"),
+            "SELECT \"Mixed\".col FROM \"Mixed\" WHERE x NOT IN (?)"
+        );
+        // Lists with anything but literals are kept
+        assert_eq!(
+            normalized("SELECT * FROM t WHERE x IN (1, y)"),
+            "SELECT * FROM t WHERE x IN (?, y)"
+        );
+        assert_eq!(
+            normalized("INSERT INTO t (a, b) VALUES (1, 'a'), (2, 'b'), (3, c)"),
+            "INSERT INTO t (a, b) VALUES (?, ?), (?, c)"
+        );
+        assert_eq!(
+            normalized("-- leading\nSELECT a -- trailing\n, b FROM t"),
+            "SELECT a, b FROM t"
+        );
+        assert_eq!(
+            normalized(
+                "/* app=foo */ SELECT a FROM t /* from */ WHERE x = 1 /* app=foo */ \
+                 GROUP BY a -- group\nHAVING a > 2 -- having\n"
+            ),
+            "SELECT a FROM t WHERE x = ? GROUP BY a HAVING a > ?"
+        );
+        assert_eq!(
+            normalized("CREATE TABLE t (a INT, -- the a\nb INT /* the b */)"),
+            "CREATE TABLE t (a int, b int)"
+        );
+        assert_eq!(
+            normalized("UPDATE t SET a = DATE '2020-01-01' WHERE id = 7"),
+            "UPDATE t SET a = ? WHERE id = ?"
+        );
+        assert_eq!(
+            normalized("SELECT * FROM t WHERE a = $1 AND b IN (:x, 2) LIMIT ?"),
+            "SELECT * FROM t WHERE a = ? AND b IN (?) LIMIT ?"
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let a = fingerprint_sql("SELECT * FROM t WHERE id IN (1, 2, 3) AND name = 'x'");
+        let b = fingerprint_sql("select * from T where ID in (4) and NAME = 'yy'");
+        let c = fingerprint_sql("SELECT * FROM t WHERE id IN (1, 2, 3) AND name <> 'x'");
+        assert_eq!(a, b);
+        assert_ne!(a.hash, c.hash);
+        // The hash is part of the API, so it must not change
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fingerprint_sql("SELECT 1").hash, fnv1a(b"SELECT ?"));
+    }
+}
@@ -0,0 +1,1946 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Render the AST as formatted SQL text, spread over several lines.
+//!
+//! Unlike the single-line `Display` implementations, the formatter puts each
+//! clause of a query on its own line, and breaks lists and expressions that do
+//! not fit in the configured width, indenting their contents. A formatter
+//! created with [Formatter::for_dialect] renders SQL for that dialect instead,
+//! e.g. quoting identifiers and limiting rows in the dialect's syntax, and
+//! returns an error for constructs the dialect cannot express:
+//!
+//! ```
+//! use sqlparser::dialect::{GenericDialect, MsSqlDialect};
+//! use sqlparser::formatter::{FormatOptions, Formatter, KeywordCase};
+//! use sqlparser::parser::Parser;
+//!
+//! let sql = "select a, count(*) from t where b > 1 group by a";
+//! let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+//! let formatter = Formatter::new(FormatOptions {
+//!     keyword_case: KeywordCase::Lower,
+//!     ..FormatOptions::default()
+//! });
+//! assert_eq!(
+//!     formatter.format_statement(&statements[0]).unwrap(),
+//!     "select a, count(*)\nfrom t\nwhere b > 1\ngroup by a"
+//! );
+//!
+//! let sql = "SELECT a FROM t LIMIT 10";
+//! let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+//! let formatter = Formatter::for_dialect(FormatOptions::default(), &MsSqlDialect {});
+//! assert_eq!(
+//!     formatter.format_statement(&statements[0]).unwrap(),
+//!     "SELECT TOP (10) a\nFROM t"
+//! );
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use crate::ast::*;
+use crate::dialect::{Dialect, GenericDialect, RowLimitSyntax};
+use crate::tokenizer::Word;
+
+/// The case in which keywords are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordCase {
+    Upper,
+    Lower,
+}
+
+/// Where to put the commas of a list that is split over several lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommaStyle {
+    /// At the end of each line but the last
+    Trailing,
+    /// At the start of each line but the first
+    Leading,
+}
+
+/// Options for [Formatter]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// The number of spaces to indent by, for each level of nesting
+    pub indent: usize,
+    /// The width that lines should fit in. Lines may be longer if they cannot
+    /// be broken, e.g. if they contain a long identifier or string.
+    pub max_width: usize,
+    pub keyword_case: KeywordCase,
+    pub comma_style: CommaStyle,
+    /// Start each clause of a statement (e.g. `FROM`, `WHERE`) on a new line,
+    /// even if the statement would fit on one line. Subqueries are only
+    /// broken into lines if they do not fit.
+    pub clause_per_line: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent: 2,
+            max_width: 80,
+            keyword_case: KeywordCase::Upper,
+            comma_style: CommaStyle::Trailing,
+            clause_per_line: true,
+        }
+    }
+}
+
+/// A layout-independent description of the output, which is turned into text
+/// by [Printer]
+#[derive(Debug, Clone)]
+enum Doc {
+    Text(String),
+    /// A line break, or the given text if the enclosing group fits on one line
+    Line(&'static str),
+    /// The given docs, with the line breaks inside indented one more level
+    Nest(Vec<Doc>),
+    /// The given docs, laid out on one line if they fit, and otherwise with
+    /// each of the line breaks directly inside the group (rather than inside
+    /// a nested group) taken
+    Group {
+        docs: Vec<Doc>,
+        always_break: bool,
+    },
+    Concat(Vec<Doc>),
+}
+
+impl Doc {
+    fn group(docs: Vec<Doc>) -> Doc {
+        Doc::Group {
+            docs,
+            always_break: false,
+        }
+    }
+
+    /// The width of this doc laid out on a single line, or `None` if it
+    /// cannot be laid out on one line
+    fn flat_width(&self) -> Option<usize> {
+        match self {
+            Doc::Text(text) if text.contains('\n') => None,
+            Doc::Text(text) => Some(text.chars().count()),
+            Doc::Line(flat) => Some(flat.len()),
+            Doc::Group {
+                always_break: true, ..
+            } => None,
+            Doc::Nest(docs) | Doc::Group { docs, .. } | Doc::Concat(docs) => {
+                docs.iter().map(Doc::flat_width).sum()
+            }
+        }
+    }
+}
+
+impl From<&str> for Doc {
+    fn from(text: &str) -> Self {
+        Doc::Text(text.to_string())
+    }
+}
+
+impl From<String> for Doc {
+    fn from(text: String) -> Self {
+        Doc::Text(text)
+    }
+}
+
+/// A line break that is always taken, e.g. after a single-line comment
+fn hard_line() -> Doc {
+    Doc::Group {
+        docs: vec![Doc::Line("")],
+        always_break: true,
+    }
+}
+
+/// Lays out a [Doc]
+struct Printer<'a> {
+    options: &'a FormatOptions,
+    output: String,
+    column: usize,
+}
+
+impl<'a> Printer<'a> {
+    fn print(&mut self, doc: &Doc, indent: usize, flat: bool) {
+        match doc {
+            Doc::Text(text) => self.write(text),
+            Doc::Line(text) if flat => self.write(text),
+            Doc::Line(_) => {
+                // Don't leave trailing whitespace or blank lines behind
+                let trimmed = self.output.trim_end_matches(' ').len();
+                self.output.truncate(trimmed);
+                if !self.output.is_empty() && !self.output.ends_with('\n') {
+                    self.output.push('\n');
+                }
+                self.output.push_str(&" ".repeat(indent));
+                self.column = indent;
+            }
+            Doc::Nest(docs) => {
+                for doc in docs {
+                    self.print(doc, indent + self.options.indent, flat);
+                }
+            }
+            Doc::Group { docs, .. } => {
+                let flat = flat
+                    || matches!(doc.flat_width(), Some(width) if self.column + width <= self.options.max_width);
+                for doc in docs {
+                    self.print(doc, indent, flat);
+                }
+            }
+            Doc::Concat(docs) => {
+                for doc in docs {
+                    self.print(doc, indent, flat);
+                }
+            }
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        self.output.push_str(text);
+        self.column = match text.rfind('\n') {
+            Some(newline) => text[newline + 1..].chars().count(),
+            None => self.column + text.chars().count(),
+        };
+    }
+}
+
+/// An error returned when a statement cannot be expressed in the dialect that
+/// the formatter targets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatError {
+    pub message: String,
+}
+
+impl FormatError {
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        FormatError {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "sql formatter error: {}", self.message)
+    }
+}
+
+impl Error for FormatError {}
+
+/// Renders statements and expressions as formatted SQL for a target dialect.
+/// The output parses to the same AST as the input, except for the changes
+/// needed to express it in the target dialect.
+#[derive(Debug, Clone)]
+pub struct Formatter<'a> {
+    options: FormatOptions,
+    dialect: &'a dyn Dialect,
+}
+
+impl Default for Formatter<'static> {
+    fn default() -> Self {
+        Formatter::new(FormatOptions::default())
+    }
+}
+
+impl Formatter<'static> {
+    /// A formatter that keeps the syntax of its input, as parsed by the
+    /// generic dialect
+    pub fn new(options: FormatOptions) -> Self {
+        Formatter::for_dialect(options, &GenericDialect {})
+    }
+}
+
+impl<'a> Formatter<'a> {
+    /// A formatter that renders SQL in the syntax of `dialect`, using its
+    /// rendering hooks such as [Dialect::row_limit_syntax]
+    pub fn for_dialect(options: FormatOptions, dialect: &'a dyn Dialect) -> Self {
+        Formatter { options, dialect }
+    }
+
+    /// Format a statement, without a terminating semicolon
+    pub fn format_statement(&self, statement: &Statement) -> Result<String, FormatError> {
+        Ok(self.print(&self.statement(statement)?))
+    }
+
+    /// Format a sequence of statements, each terminated by a semicolon, and
+    /// separated by blank lines. The semicolon goes before the comments
+    /// trailing a statement.
+    pub fn format_statements(&self, statements: &[Statement]) -> Result<String, FormatError> {
+        let statements = statements
+            .iter()
+            .map(|statement| {
+                let doc = match statement {
+                    Statement::Commented {
+                        comments,
+                        statement,
+                    } => {
+                        let mut docs = self.leading_comments(comments);
+                        docs.push(self.statement(statement)?);
+                        docs.push(";".into());
+                        docs.extend(self.trailing_comments(comments));
+                        Doc::Concat(docs)
+                    }
+                    _ => Doc::Concat(vec![self.statement(statement)?, ";".into()]),
+                };
+                Ok(format!("{}\n", self.print(&doc).trim_end_matches('\n')))
+            })
+            .collect::<Result<Vec<_>, FormatError>>()?;
+        Ok(statements.join("\n"))
+    }
+
+    pub fn format_query(&self, query: &Query) -> Result<String, FormatError> {
+        Ok(self.print(&self.query(query, false)?))
+    }
+
+    pub fn format_expr(&self, expr: &Expr) -> Result<String, FormatError> {
+        Ok(self.print(&self.expr(expr)?))
+    }
+
+    fn print(&self, doc: &Doc) -> String {
+        let mut printer = Printer {
+            options: &self.options,
+            output: String::new(),
+            column: 0,
+        };
+        printer.print(doc, 0, false);
+        printer.output
+    }
+
+    /// A keyword, or a sequence of keywords and punctuation
+    fn kw(&self, keyword: &str) -> Doc {
+        Doc::Text(match self.options.keyword_case {
+            KeywordCase::Upper => keyword.to_uppercase(),
+            KeywordCase::Lower => keyword.to_lowercase(),
+        })
+    }
+
+    /// The items of a comma-separated list, separated by line breaks if the
+    /// enclosing group does not fit on one line
+    fn list(&self, items: Vec<Doc>) -> Doc {
+        self.commented_list(items.into_iter().map(|item| (item, None)).collect())
+    }
+
+    /// A list whose items may have comments. The comments trailing an item
+    /// follow its comma, if it has one on the same line.
+    fn commented_list(&self, items: Vec<(Doc, Option<&Comments>)>) -> Doc {
+        let mut docs = vec![];
+        let len = items.len();
+        for (i, (item, comments)) in items.into_iter().enumerate() {
+            let last = i + 1 == len;
+            if i > 0 && self.options.comma_style == CommaStyle::Leading {
+                docs.push(Doc::Line(""));
+                docs.push(", ".into());
+            }
+            if let Some(comments) = comments {
+                docs.extend(self.leading_comments(comments));
+            }
+            docs.push(item);
+            if !last && self.options.comma_style == CommaStyle::Trailing {
+                docs.push(",".into());
+            }
+            if let Some(comments) = comments {
+                docs.extend(self.trailing_comments(comments));
+            }
+            if !last && self.options.comma_style == CommaStyle::Trailing {
+                docs.push(Doc::Line(" "));
+            }
+        }
+        Doc::Concat(docs)
+    }
+
+    /// A parenthesized doc, whose contents are indented on their own lines if
+    /// they do not fit on one line
+    fn parens(&self, doc: Doc) -> Doc {
+        Doc::group(vec![
+            "(".into(),
+            Doc::Nest(vec![Doc::Line(""), doc]),
+            Doc::Line(""),
+            ")".into(),
+        ])
+    }
+
+    fn parenthesized_list(&self, items: Vec<Doc>) -> Doc {
+        self.parens(self.list(items))
+    }
+
+    /// A clause made of a keyword followed by its contents, which are
+    /// indented on the following lines if they do not fit on one line
+    fn clause(&self, keyword: Doc, contents: Doc) -> Doc {
+        Doc::group(vec![keyword, Doc::Nest(vec![Doc::Line(" "), contents])])
+    }
+
+    /// The clauses of a statement, each on its own line if `always_break` is
+    /// set or if they do not fit on one line
+    fn clauses(&self, clauses: Vec<Doc>, always_break: bool) -> Doc {
+        let mut docs = vec![];
+        for (i, clause) in clauses.into_iter().enumerate() {
+            if i > 0 {
+                docs.push(Doc::Line(" "));
+            }
+            docs.push(clause);
+        }
+        Doc::Group {
+            always_break: always_break && docs.len() > 1,
+            docs,
+        }
+    }
+
+    /// A doc with the comments attached to its node. Single-line comments
+    /// end with a line break, which also breaks the enclosing groups.
+    fn commented(&self, comments: &Comments, doc: Doc) -> Doc {
+        let mut docs = self.leading_comments(comments);
+        docs.push(doc);
+        docs.extend(self.trailing_comments(comments));
+        Doc::Concat(docs)
+    }
+
+    fn leading_comments(&self, comments: &Comments) -> Vec<Doc> {
+        let mut docs = vec![];
+        for comment in &comments.leading {
+            docs.push(self.display(comment));
+            docs.push(match comment {
+                Comment::SingleLine(_) => hard_line(),
+                Comment::MultiLine(_) => " ".into(),
+            });
+        }
+        docs
+    }
+
+    fn trailing_comments(&self, comments: &Comments) -> Vec<Doc> {
+        let mut docs = vec![];
+        for comment in &comments.trailing {
+            docs.push(" ".into());
+            docs.push(self.display(comment));
+            if let Comment::SingleLine(_) = comment {
+                docs.push(hard_line());
+            }
+        }
+        docs
+    }
+
+    fn display<T: fmt::Display>(&self, node: &T) -> Doc {
+        Doc::Text(node.to_string())
+    }
+
+    fn exprs(&self, exprs: &[Expr]) -> Result<Vec<Doc>, FormatError> {
+        exprs.iter().map(|expr| self.expr(expr)).collect()
+    }
+
+    /// An identifier, with its quotes (if any) replaced by the ones of the
+    /// target dialect
+    fn ident(&self, ident: &Ident) -> Doc {
+        match (ident.quote_style, self.dialect.identifier_quote_style()) {
+            (Some(_), Some(quote)) => {
+                let end = Word::matching_end_quote(quote);
+                let value = ident.value.replace(end, &format!("{}{}", end, end));
+                Doc::Text(format!("{}{}{}", quote, value, end))
+            }
+            _ => self.display(ident),
+        }
+    }
+
+    fn idents(&self, idents: &[Ident]) -> Vec<Doc> {
+        idents.iter().map(|ident| self.ident(ident)).collect()
+    }
+
+    /// A name made of identifiers separated by dots
+    fn compound_ident(&self, idents: &[Ident]) -> Doc {
+        let mut docs = vec![];
+        for (i, ident) in idents.iter().enumerate() {
+            if i > 0 {
+                docs.push(".".into());
+            }
+            docs.push(self.ident(ident));
+        }
+        Doc::Concat(docs)
+    }
+
+    fn object_name(&self, name: &ObjectName) -> Doc {
+        self.compound_ident(&name.0)
+    }
+
+    fn statement(&self, statement: &Statement) -> Result<Doc, FormatError> {
+        let top_level = self.options.clause_per_line;
+        Ok(match statement {
+            Statement::Query(query) => self.query(query, false)?,
+            Statement::Commented {
+                comments,
+                statement,
+            } => self.commented(comments, self.statement(statement)?),
+            Statement::Insert {
+                table_name,
+                columns,
+                source,
+                ..
+            } => {
+                let mut target = vec![self.kw("INSERT INTO "), self.object_name(table_name)];
+                if !columns.is_empty() {
+                    target.push(" ".into());
+                    target.push(self.parenthesized_list(self.idents(columns)));
+                }
+                self.clauses(
+                    vec![Doc::Concat(target), self.query(source, false)?],
+                    top_level,
+                )
+            }
+            Statement::Copy {
+                table_name,
+                columns,
+                values,
+                ..
+            } => {
+                let mut docs = vec![self.kw("COPY "), self.object_name(table_name)];
+                if !columns.is_empty() {
+                    docs.push(" ".into());
+                    docs.push(self.parenthesized_list(self.idents(columns)));
+                }
+                docs.push(self.kw(" FROM STDIN;"));
+                let mut data = String::new();
+                if !values.is_empty() {
+                    data.push('\n');
+                    let values: Vec<&str> = values
+                        .iter()
+                        .map(|v| v.as_ref().map_or("\\N", String::as_str))
+                        .collect();
+                    data.push_str(&values.join("\t"));
+                }
+                data.push_str("\n\\.");
+                docs.push(data.into());
+                Doc::Concat(docs)
+            }
+            Statement::Update {
+                table_name,
+                assignments,
+                selection,
+                ..
+            } => {
+                let mut clauses = vec![Doc::Concat(vec![
+                    self.kw("UPDATE "),
+                    self.object_name(table_name),
+                ])];
+                if !assignments.is_empty() {
+                    let assignments = assignments
+                        .iter()
+                        .map(|a| {
+                            Ok(Doc::Concat(vec![
+                                self.ident(&a.id),
+                                " = ".into(),
+                                self.expr(&a.value)?,
+                            ]))
+                        })
+                        .collect::<Result<_, FormatError>>()?;
+                    clauses.push(self.clause(self.kw("SET"), self.list(assignments)));
+                }
+                if let Some(selection) = selection {
+                    clauses.push(self.clause(self.kw("WHERE"), self.expr(selection)?));
+                }
+                self.clauses(clauses, top_level)
+            }
+            Statement::Delete {
+                table_name,
+                selection,
+                ..
+            } => {
+                let mut clauses = vec![Doc::Concat(vec![
+                    self.kw("DELETE FROM "),
+                    self.object_name(table_name),
+                ])];
+                if let Some(selection) = selection {
+                    clauses.push(self.clause(self.kw("WHERE"), self.expr(selection)?));
+                }
+                self.clauses(clauses, top_level)
+            }
+            Statement::CreateView {
+                name,
+                columns,
+                query,
+                materialized,
+                with_options,
+                ..
+            } => {
+                let mut docs = vec![self.kw(if *materialized {
+                    "CREATE MATERIALIZED VIEW "
+                } else {
+                    "CREATE VIEW "
+                })];
+                docs.push(self.object_name(name));
+                if !columns.is_empty() {
+                    docs.push(" ".into());
+                    docs.push(self.parenthesized_list(self.idents(columns)));
+                }
+                if !with_options.is_empty() {
+                    docs.push(self.kw(" WITH "));
+                    docs.push(self.sql_options(with_options)?);
+                }
+                docs.push(self.kw(" AS"));
+                self.clauses(
+                    vec![Doc::Concat(docs), self.query(query, false)?],
+                    top_level,
+                )
+            }
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                with_options,
+                external,
+                file_format,
+                location,
+                ..
+            } => {
+                let mut docs = vec![self.kw(if *external {
+                    "CREATE EXTERNAL TABLE "
+                } else {
+                    "CREATE TABLE "
+                })];
+                docs.push(self.object_name(name));
+                docs.push(" ".into());
+                let elements = columns
+                    .iter()
+                    .map(|column| Ok((self.column_def(&column.node)?, Some(&column.comments))))
+                    .chain(
+                        constraints
+                            .iter()
+                            .map(|c| Ok((self.table_constraint(c)?, None))),
+                    )
+                    .collect::<Result<_, FormatError>>()?;
+                docs.push(self.parens(self.commented_list(elements)));
+                if let Some(file_format) = file_format {
+                    docs.push(self.kw(" STORED AS "));
+                    docs.push(self.kw(&file_format.to_string()));
+                }
+                if let Some(location) = location {
+                    docs.push(self.kw(" LOCATION "));
+                    docs.push(self.display(&Value::SingleQuotedString(location.clone())));
+                }
+                if !with_options.is_empty() {
+                    docs.push(self.kw(" WITH "));
+                    docs.push(self.sql_options(with_options)?);
+                }
+                Doc::Concat(docs)
+            }
+            Statement::CreateIndex {
+                name,
+                table_name,
+                columns,
+                unique,
+                if_not_exists,
+                ..
+            } => {
+                let mut docs = vec![self.kw("CREATE ")];
+                if *unique {
+                    docs.push(self.kw("UNIQUE "));
+                }
+                docs.push(self.kw("INDEX "));
+                if *if_not_exists {
+                    docs.push(self.kw("IF NOT EXISTS "));
+                }
+                let columns = columns
+                    .iter()
+                    .map(|column| self.order_by_expr(column))
+                    .collect::<Result<_, _>>()?;
+                docs.extend(vec![
+                    self.object_name(name),
+                    self.kw(" ON "),
+                    self.object_name(table_name),
+                    " ".into(),
+                    self.parenthesized_list(columns),
+                ]);
+                Doc::group(docs)
+            }
+            Statement::AlterTable {
+                name, operation, ..
+            } => {
+                let operation = match operation {
+                    AlterTableOperation::AddConstraint(c) => {
+                        Doc::Concat(vec![self.kw("ADD "), self.table_constraint(c)?])
+                    }
+                    AlterTableOperation::AddColumn { column_def } => {
+                        Doc::Concat(vec![self.kw("ADD COLUMN "), self.column_def(column_def)?])
+                    }
+                    AlterTableOperation::DropConstraint { name } => {
+                        Doc::Concat(vec![self.kw("DROP CONSTRAINT "), self.ident(name)])
+                    }
+                    AlterTableOperation::DropColumn {
+                        column_name,
+                        if_exists,
+                        cascade,
+                    } => {
+                        let mut docs = vec![self.kw("DROP COLUMN ")];
+                        if *if_exists {
+                            docs.push(self.kw("IF EXISTS "));
+                        }
+                        docs.push(self.ident(column_name));
+                        if *cascade {
+                            docs.push(self.kw(" CASCADE"));
+                        }
+                        Doc::Concat(docs)
+                    }
+                    AlterTableOperation::RenameColumn {
+                        old_column_name,
+                        new_column_name,
+                    } => Doc::Concat(vec![
+                        self.kw("RENAME COLUMN "),
+                        self.ident(old_column_name),
+                        self.kw(" TO "),
+                        self.ident(new_column_name),
+                    ]),
+                    AlterTableOperation::RenameTable { table_name } => {
+                        Doc::Concat(vec![self.kw("RENAME TO "), self.object_name(table_name)])
+                    }
+                    AlterTableOperation::AlterColumn { column_name, op } => {
+                        let op = match op {
+                            AlterColumnOperation::SetNotNull => self.kw("SET NOT NULL"),
+                            AlterColumnOperation::DropNotNull => self.kw("DROP NOT NULL"),
+                            AlterColumnOperation::SetDefault { value } => {
+                                Doc::Concat(vec![self.kw("SET DEFAULT "), self.expr(value)?])
+                            }
+                            AlterColumnOperation::DropDefault => self.kw("DROP DEFAULT"),
+                            AlterColumnOperation::SetDataType { data_type } => Doc::Concat(vec![
+                                self.kw("SET DATA TYPE "),
+                                self.data_type(data_type)?,
+                            ]),
+                        };
+                        Doc::Concat(vec![
+                            self.kw("ALTER COLUMN "),
+                            self.ident(column_name),
+                            " ".into(),
+                            op,
+                        ])
+                    }
+                };
+                Doc::Concat(vec![
+                    self.kw("ALTER TABLE "),
+                    self.object_name(name),
+                    " ".into(),
+                    operation,
+                ])
+            }
+            Statement::Drop {
+                object_type,
+                if_exists,
+                names,
+                cascade,
+                ..
+            } => {
+                let mut docs = vec![self.kw(&format!("DROP {} ", object_type))];
+                if *if_exists {
+                    docs.push(self.kw("IF EXISTS "));
+                }
+                docs.push(self.list(names.iter().map(|n| self.object_name(n)).collect()));
+                if *cascade {
+                    docs.push(self.kw(" CASCADE"));
+                }
+                Doc::group(docs)
+            }
+            Statement::SetVariable {
+                local,
+                variable,
+                value,
+                ..
+            } => Doc::Concat(vec![
+                self.kw(if *local { "SET LOCAL " } else { "SET " }),
+                self.ident(variable),
+                " = ".into(),
+                match value {
+                    SetVariableValue::Ident(ident) => self.ident(ident),
+                    SetVariableValue::Literal(value) => self.value(value)?,
+                },
+            ]),
+            Statement::ShowVariable { variable, .. } => {
+                Doc::Concat(vec![self.kw("SHOW "), self.ident(variable)])
+            }
+            Statement::ShowColumns {
+                extended,
+                full,
+                table_name,
+                filter,
+                ..
+            } => {
+                let mut docs = vec![self.kw("SHOW ")];
+                if *extended {
+                    docs.push(self.kw("EXTENDED "));
+                }
+                if *full {
+                    docs.push(self.kw("FULL "));
+                }
+                docs.push(self.kw("COLUMNS FROM "));
+                docs.push(self.object_name(table_name));
+                match filter {
+                    Some(ShowStatementFilter::Like(pattern)) => {
+                        docs.push(self.kw(" LIKE "));
+                        docs.push(self.display(&Value::SingleQuotedString(pattern.clone())));
+                    }
+                    Some(ShowStatementFilter::Where(expr)) => {
+                        docs.push(self.kw(" WHERE "));
+                        docs.push(self.expr(expr)?);
+                    }
+                    None => {}
+                }
+                Doc::Concat(docs)
+            }
+            Statement::StartTransaction { modes, .. } => {
+                self.transaction("START TRANSACTION", modes)
+            }
+            Statement::SetTransaction { modes, .. } => self.transaction("SET TRANSACTION", modes),
+            Statement::Commit { chain, .. } => {
+                self.kw(if *chain { "COMMIT AND CHAIN" } else { "COMMIT" })
+            }
+            Statement::Rollback { chain, .. } => self.kw(if *chain {
+                "ROLLBACK AND CHAIN"
+            } else {
+                "ROLLBACK"
+            }),
+        })
+    }
+
+    fn transaction(&self, keywords: &str, modes: &[TransactionMode]) -> Doc {
+        let mut docs = vec![self.kw(keywords)];
+        if !modes.is_empty() {
+            docs.push(" ".into());
+            docs.push(self.list(modes.iter().map(|m| self.kw(&m.to_string())).collect()));
+        }
+        Doc::Concat(docs)
+    }
+
+    fn sql_options(&self, options: &[SqlOption]) -> Result<Doc, FormatError> {
+        Ok(self.parenthesized_list(
+            options
+                .iter()
+                .map(|o| {
+                    Ok(Doc::Concat(vec![
+                        self.ident(&o.name),
+                        " = ".into(),
+                        self.value(&o.value)?,
+                    ]))
+                })
+                .collect::<Result<_, FormatError>>()?,
+        ))
+    }
+
+    fn column_def(&self, column: &ColumnDef) -> Result<Doc, FormatError> {
+        let mut docs = vec![
+            self.ident(&column.name),
+            " ".into(),
+            self.data_type(&column.data_type)?,
+        ];
+        if let Some(collation) = &column.collation {
+            docs.push(self.kw(" COLLATE "));
+            docs.push(self.object_name(collation));
+        }
+        for option in &column.options {
+            docs.push(" ".into());
+            docs.push(self.constraint_name(&option.name));
+            docs.push(match &option.option {
+                ColumnOption::Null => self.kw("NULL"),
+                ColumnOption::NotNull => self.kw("NOT NULL"),
+                ColumnOption::Default(expr) => {
+                    Doc::Concat(vec![self.kw("DEFAULT "), self.expr(expr)?])
+                }
+                ColumnOption::Unique { is_primary } => {
+                    self.kw(if *is_primary { "PRIMARY KEY" } else { "UNIQUE" })
+                }
+                ColumnOption::ForeignKey {
+                    foreign_table,
+                    referred_columns,
+                } => Doc::Concat(vec![
+                    self.kw("REFERENCES "),
+                    self.object_name(foreign_table),
+                    " ".into(),
+                    self.parenthesized_list(self.idents(referred_columns)),
+                ]),
+                ColumnOption::Check(expr) => {
+                    Doc::Concat(vec![self.kw("CHECK "), self.parens(self.expr(expr)?)])
+                }
+            });
+        }
+        Ok(Doc::Concat(docs))
+    }
+
+    fn constraint_name(&self, name: &Option<Ident>) -> Doc {
+        match name {
+            Some(name) => Doc::Concat(vec![self.kw("CONSTRAINT "), self.ident(name), " ".into()]),
+            None => Doc::Concat(vec![]),
+        }
+    }
+
+    fn table_constraint(&self, constraint: &TableConstraint) -> Result<Doc, FormatError> {
+        Ok(match constraint {
+            TableConstraint::Unique {
+                name,
+                columns,
+                is_primary,
+            } => Doc::Concat(vec![
+                self.constraint_name(name),
+                self.kw(if *is_primary {
+                    "PRIMARY KEY "
+                } else {
+                    "UNIQUE "
+                }),
+                self.parenthesized_list(self.idents(columns)),
+            ]),
+            TableConstraint::ForeignKey {
+                name,
+                columns,
+                foreign_table,
+                referred_columns,
+            } => Doc::Concat(vec![
+                self.constraint_name(name),
+                self.kw("FOREIGN KEY "),
+                self.parenthesized_list(self.idents(columns)),
+                self.kw(" REFERENCES "),
+                self.object_name(foreign_table),
+                self.parenthesized_list(self.idents(referred_columns)),
+            ]),
+            TableConstraint::Check { name, expr } => Doc::Concat(vec![
+                self.constraint_name(name),
+                self.kw("CHECK "),
+                self.parens(self.expr(expr)?),
+            ]),
+        })
+    }
+
+    /// A query. Its clauses are always on separate lines if
+    /// `clause_per_line` is set, unless it is `nested` in another statement.
+    fn query(&self, query: &Query, nested: bool) -> Result<Doc, FormatError> {
+        let mut clauses = vec![];
+        if !query.ctes.is_empty() {
+            let ctes = query
+                .ctes
+                .iter()
+                .map(|cte| {
+                    Ok(Doc::Concat(vec![
+                        self.table_alias(&cte.alias),
+                        self.kw(" AS "),
+                        self.parens(self.query(&cte.query, true)?),
+                    ]))
+                })
+                .collect::<Result<_, FormatError>>()?;
+            clauses.push(self.clause(self.kw("WITH"), self.list(ctes)));
+        }
+        let (top, row_limit) = match self.dialect.row_limit_syntax() {
+            RowLimitSyntax::Any => (None, self.parsed_row_limit(query)?),
+            syntax => self.row_limit(query, syntax)?,
+        };
+        self.set_expr_clauses(&query.body, top, &mut clauses)?;
+        if !query.order_by.is_empty() {
+            let order_by = query
+                .order_by
+                .iter()
+                .map(|e| self.order_by_expr(e))
+                .collect::<Result<_, FormatError>>()?;
+            clauses.push(self.clause(self.kw("ORDER BY"), self.list(order_by)));
+        }
+        clauses.extend(row_limit);
+        Ok(self.clauses(clauses, self.options.clause_per_line && !nested))
+    }
+
+    /// The `LIMIT`, `OFFSET` and `FETCH` clauses of a query, as parsed
+    fn parsed_row_limit(&self, query: &Query) -> Result<Vec<Doc>, FormatError> {
+        let mut clauses = vec![];
+        if let Some(limit) = &query.limit {
+            clauses.push(Doc::Concat(vec![self.kw("LIMIT "), self.expr(limit)?]));
+        }
+        if let Some(offset) = &query.offset {
+            clauses.push(Doc::Concat(vec![
+                self.kw("OFFSET "),
+                self.expr(offset)?,
+                self.kw(" ROWS"),
+            ]));
+        }
+        if let Some(fetch) = &query.fetch {
+            let mut docs = vec![self.kw("FETCH FIRST ")];
+            if let Some(quantity) = &fetch.quantity {
+                docs.push(self.expr(quantity)?);
+                docs.push(self.kw(if fetch.percent {
+                    " PERCENT ROWS"
+                } else {
+                    " ROWS"
+                }));
+            } else {
+                docs.push(self.kw("ROWS"));
+            }
+            docs.push(self.kw(if fetch.with_ties {
+                " WITH TIES"
+            } else {
+                " ONLY"
+            }));
+            clauses.push(Doc::Concat(docs));
+        }
+        Ok(clauses)
+    }
+
+    /// The `TOP` clause, if any, and the clauses following `ORDER BY` that
+    /// limit the rows returned by a query, in the given syntax
+    fn row_limit(
+        &self,
+        query: &Query,
+        syntax: RowLimitSyntax,
+    ) -> Result<(Option<Doc>, Vec<Doc>), FormatError> {
+        let (count, percent, with_ties) =
+            match (&query.limit, &query.fetch) {
+                (Some(_), Some(_)) => return Err(FormatError::new(
+                    "a query with both LIMIT and FETCH cannot be expressed in the target dialect",
+                )),
+                (Some(limit), None) => (Some(self.expr(limit)?), false, false),
+                (None, Some(fetch)) => {
+                    let count = match &fetch.quantity {
+                        Some(quantity) => self.expr(quantity)?,
+                        None => "1".into(),
+                    };
+                    (Some(count), fetch.percent, fetch.with_ties)
+                }
+                (None, None) => (None, false, false),
+            };
+        let offset = match &query.offset {
+            Some(offset) => Some(self.expr(offset)?),
+            None => None,
+        };
+        let fetch = |count: Doc, first: &str| {
+            let mut docs = vec![self.kw(&format!("FETCH {} ", first)), count];
+            if percent {
+                docs.push(self.kw(" PERCENT"));
+            }
+            docs.push(self.kw(if with_ties {
+                " ROWS WITH TIES"
+            } else {
+                " ROWS ONLY"
+            }));
+            Doc::Concat(docs)
+        };
+        let offset_rows =
+            |offset: Doc| Doc::Concat(vec![self.kw("OFFSET "), offset, self.kw(" ROWS")]);
+        let mut top = None;
+        let mut clauses = vec![];
+        match syntax {
+            RowLimitSyntax::Any | RowLimitSyntax::Fetch => {
+                clauses.extend(offset.map(offset_rows));
+                clauses.extend(count.map(|count| fetch(count, "FIRST")));
+            }
+            RowLimitSyntax::Limit => {
+                if percent || with_ties {
+                    return Err(FormatError::new(
+                        "FETCH with PERCENT or WITH TIES cannot be expressed with LIMIT",
+                    ));
+                }
+                match count {
+                    Some(count) => clauses.push(Doc::Concat(vec![self.kw("LIMIT "), count])),
+                    None if offset.is_some() => {
+                        return Err(FormatError::new(
+                            "OFFSET without a row count cannot be expressed with LIMIT",
+                        ))
+                    }
+                    None => {}
+                }
+                if let Some(offset) = offset {
+                    clauses.push(Doc::Concat(vec![self.kw("OFFSET "), offset]));
+                }
+            }
+            RowLimitSyntax::Top => match offset {
+                Some(offset) => {
+                    if query.order_by.is_empty() {
+                        return Err(FormatError::new(
+                            "OFFSET without ORDER BY cannot be expressed in the target dialect",
+                        ));
+                    }
+                    if percent || with_ties {
+                        return Err(FormatError::new(
+                            "FETCH with PERCENT or WITH TIES cannot be combined with OFFSET \
+                             in the target dialect",
+                        ));
+                    }
+                    clauses.push(offset_rows(offset));
+                    clauses.extend(count.map(|count| fetch(count, "NEXT")));
+                }
+                None => {
+                    if let Some(count) = count {
+                        if !matches!(query.body, SetExpr::Select(_)) {
+                            return Err(FormatError::new(
+                                "TOP cannot limit the rows of a set operation or VALUES",
+                            ));
+                        }
+                        let mut docs = vec![self.kw("TOP "), self.parens(count)];
+                        if percent {
+                            docs.push(self.kw(" PERCENT"));
+                        }
+                        if with_ties {
+                            docs.push(self.kw(" WITH TIES"));
+                        }
+                        top = Some(Doc::Concat(docs));
+                    }
+                }
+            },
+        }
+        Ok((top, clauses))
+    }
+
+    /// The clauses of a set expression, the first `SELECT` of which is
+    /// limited by the `top` clause, if any
+    fn set_expr_clauses(
+        &self,
+        set_expr: &SetExpr,
+        top: Option<Doc>,
+        clauses: &mut Vec<Doc>,
+    ) -> Result<(), FormatError> {
+        match set_expr {
+            SetExpr::Select(select) => self.select_clauses(select, top, clauses)?,
+            SetExpr::Query(query) => clauses.push(self.parens(self.query(query, true)?)),
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                self.set_expr_clauses(left, top, clauses)?;
+                let op = format!("{}{}", op, if *all { " ALL" } else { "" });
+                clauses.push(self.kw(&op));
+                self.set_expr_clauses(right, None, clauses)?;
+            }
+            SetExpr::Values(Values(rows)) => {
+                let rows = rows
+                    .iter()
+                    .map(|row| Ok(self.parenthesized_list(self.exprs(row)?)))
+                    .collect::<Result<_, FormatError>>()?;
+                clauses.push(self.clause(self.kw("VALUES"), self.list(rows)));
+            }
+        }
+        Ok(())
+    }
+
+    fn select_clauses(
+        &self,
+        select: &Select,
+        top: Option<Doc>,
+        clauses: &mut Vec<Doc>,
+    ) -> Result<(), FormatError> {
+        let projection = select
+            .projection
+            .iter()
+            .map(|item| match item {
+                SelectItem::Commented { comments, item } => {
+                    Ok((self.select_item(item)?, Some(comments)))
+                }
+                item => Ok((self.select_item(item)?, None)),
+            })
+            .collect::<Result<_, FormatError>>()?;
+        let mut keywords = vec![self.kw(if select.distinct {
+            "SELECT DISTINCT"
+        } else {
+            "SELECT"
+        })];
+        if let Some(top) = top {
+            keywords.push(" ".into());
+            keywords.push(top);
+        }
+        clauses.push(self.clause(Doc::Concat(keywords), self.commented_list(projection)));
+        let comments = &select.comments;
+        if !select.from.is_empty() {
+            let from = select
+                .from
+                .iter()
+                .map(|t| self.table_with_joins(t))
+                .collect::<Result<_, FormatError>>()?;
+            let clause = self.clause(self.kw("FROM"), self.list(from));
+            clauses.push(self.commented(&comments.from, clause));
+        }
+        if let Some(selection) = &select.selection {
+            let clause = self.clause(self.kw("WHERE"), self.expr(selection)?);
+            clauses.push(self.commented(&comments.selection, clause));
+        }
+        if !select.group_by.is_empty() {
+            let group_by = self.exprs(&select.group_by)?;
+            let clause = self.clause(self.kw("GROUP BY"), self.list(group_by));
+            clauses.push(self.commented(&comments.group_by, clause));
+        }
+        if let Some(having) = &select.having {
+            let clause = self.clause(self.kw("HAVING"), self.expr(having)?);
+            clauses.push(self.commented(&comments.having, clause));
+        }
+        Ok(())
+    }
+
+    fn select_item(&self, item: &SelectItem) -> Result<Doc, FormatError> {
+        Ok(match item {
+            SelectItem::UnnamedExpr(expr) => self.expr(expr)?,
+            SelectItem::ExprWithAlias { expr, alias } => {
+                Doc::Concat(vec![self.expr(expr)?, self.kw(" AS "), self.ident(alias)])
+            }
+            SelectItem::QualifiedWildcard(prefix, _) => {
+                Doc::Concat(vec![self.object_name(prefix), ".*".into()])
+            }
+            SelectItem::Wildcard(_) => "*".into(),
+            SelectItem::Commented { comments, item } => {
+                self.commented(comments, self.select_item(item)?)
+            }
+        })
+    }
+
+    fn table_with_joins(&self, table: &TableWithJoins) -> Result<Doc, FormatError> {
+        let mut docs = vec![self.table_factor(&table.relation)?];
+        for join in &table.joins {
+            docs.push(Doc::Line(" "));
+            docs.push(self.join(join)?);
+        }
+        Ok(Doc::Concat(docs))
+    }
+
+    fn join(&self, join: &Join) -> Result<Doc, FormatError> {
+        let (keywords, constraint) = match &join.join_operator {
+            JoinOperator::Inner(constraint) => ("JOIN ", Some(constraint)),
+            JoinOperator::LeftOuter(constraint) => ("LEFT JOIN ", Some(constraint)),
+            JoinOperator::RightOuter(constraint) => ("RIGHT JOIN ", Some(constraint)),
+            JoinOperator::FullOuter(constraint) => ("FULL JOIN ", Some(constraint)),
+            JoinOperator::CrossJoin => ("CROSS JOIN ", None),
+            JoinOperator::CrossApply => ("CROSS APPLY ", None),
+            JoinOperator::OuterApply => ("OUTER APPLY ", None),
+        };
+        let mut docs = vec![];
+        if let Some(JoinConstraint::Natural) = constraint {
+            docs.push(self.kw("NATURAL "));
+        }
+        docs.push(self.kw(keywords));
+        docs.push(self.table_factor(&join.relation)?);
+        match constraint {
+            Some(JoinConstraint::On(expr)) => {
+                docs.push(self.kw(" ON "));
+                docs.push(self.expr(expr)?);
+            }
+            Some(JoinConstraint::Using(columns)) => {
+                docs.push(self.kw(" USING"));
+                docs.push(self.parenthesized_list(self.idents(columns)));
+            }
+            _ => {}
+        }
+        Ok(Doc::Concat(docs))
+    }
+
+    fn table_factor(&self, table_factor: &TableFactor) -> Result<Doc, FormatError> {
+        let mut docs = vec![];
+        match table_factor {
+            TableFactor::Table {
+                name,
+                alias,
+                args,
+                with_hints,
+                ..
+            } => {
+                docs.push(self.object_name(name));
+                if !args.is_empty() {
+                    docs.push(self.parenthesized_list(self.exprs(args)?));
+                }
+                docs.push(self.alias(alias));
+                if !with_hints.is_empty() {
+                    docs.push(self.kw(" WITH "));
+                    docs.push(self.parenthesized_list(self.exprs(with_hints)?));
+                }
+            }
+            TableFactor::Derived {
+                lateral,
+                subquery,
+                alias,
+                ..
+            } => {
+                if *lateral {
+                    docs.push(self.kw("LATERAL "));
+                }
+                docs.push(self.parens(self.query(subquery, true)?));
+                docs.push(self.alias(alias));
+            }
+            TableFactor::NestedJoin(table, _) => {
+                docs.push(self.parens(self.table_with_joins(table)?));
+            }
+        }
+        Ok(Doc::Concat(docs))
+    }
+
+    fn alias(&self, alias: &Option<TableAlias>) -> Doc {
+        match alias {
+            Some(alias) => Doc::Concat(vec![self.kw(" AS "), self.table_alias(alias)]),
+            None => Doc::Concat(vec![]),
+        }
+    }
+
+    fn table_alias(&self, alias: &TableAlias) -> Doc {
+        let mut docs = vec![self.ident(&alias.name)];
+        if !alias.columns.is_empty() {
+            docs.push(" ".into());
+            docs.push(self.parenthesized_list(self.idents(&alias.columns)));
+        }
+        Doc::Concat(docs)
+    }
+
+    fn order_by_expr(&self, order_by: &OrderByExpr) -> Result<Doc, FormatError> {
+        let mut docs = vec![self.expr(&order_by.expr)?];
+        match order_by.asc {
+            Some(true) => docs.push(self.kw(" ASC")),
+            Some(false) => docs.push(self.kw(" DESC")),
+            None => {}
+        }
+        Ok(Doc::Concat(docs))
+    }
+
+    fn expr(&self, expr: &Expr) -> Result<Doc, FormatError> {
+        let not = |negated: &bool| if *negated { "NOT " } else { "" };
+        Ok(match expr {
+            Expr::Identifier(ident) => self.ident(ident),
+            Expr::Wildcard(_) => "*".into(),
+            Expr::QualifiedWildcard(prefix, _) => {
+                Doc::Concat(vec![self.compound_ident(prefix), ".*".into()])
+            }
+            Expr::CompoundIdentifier(idents) => self.compound_ident(idents),
+            Expr::IsNull(expr, _) => Doc::Concat(vec![self.expr(expr)?, self.kw(" IS NULL")]),
+            Expr::IsNotNull(expr, _) => {
+                Doc::Concat(vec![self.expr(expr)?, self.kw(" IS NOT NULL")])
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+                ..
+            } => Doc::Concat(vec![
+                self.expr(expr)?,
+                self.kw(&format!(" {}IN ", not(negated))),
+                self.parenthesized_list(self.exprs(list)?),
+            ]),
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+                ..
+            } => Doc::Concat(vec![
+                self.expr(expr)?,
+                self.kw(&format!(" {}IN ", not(negated))),
+                self.parens(self.query(subquery, true)?),
+            ]),
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => Doc::Concat(vec![
+                self.expr(expr)?,
+                self.kw(&format!(" {}BETWEEN ", not(negated))),
+                self.expr(low)?,
+                self.kw(" AND "),
+                self.expr(high)?,
+            ]),
+            Expr::BinaryOp {
+                op: op @ BinaryOperator::And,
+                ..
+            }
+            | Expr::BinaryOp {
+                op: op @ BinaryOperator::Or,
+                ..
+            } => {
+                // Put each operand of a chain of ANDs or ORs on its own line
+                // if they don't fit on one
+                let mut operands = vec![];
+                collect_operands(expr, op, &mut operands);
+                let mut docs = vec![];
+                for (i, operand) in operands.into_iter().enumerate() {
+                    if i > 0 {
+                        docs.push(Doc::Line(" "));
+                        docs.push(self.kw(&format!("{} ", op)));
+                    }
+                    docs.push(self.expr(operand)?);
+                }
+                Doc::group(docs)
+            }
+            Expr::BinaryOp { left, op, right } => Doc::Concat(vec![
+                self.expr(left)?,
+                " ".into(),
+                self.operator(&op.to_string()),
+                " ".into(),
+                self.expr(right)?,
+            ]),
+            Expr::UnaryOp { op, expr, .. } => Doc::Concat(vec![
+                self.operator(&op.to_string()),
+                " ".into(),
+                self.expr(expr)?,
+            ]),
+            Expr::Cast {
+                expr, data_type, ..
+            } => Doc::Concat(vec![
+                self.kw("CAST("),
+                self.expr(expr)?,
+                self.kw(" AS "),
+                self.data_type(data_type)?,
+                ")".into(),
+            ]),
+            Expr::Extract { field, expr, .. } => Doc::Concat(vec![
+                self.kw(&format!("EXTRACT({} FROM ", field)),
+                self.expr(expr)?,
+                ")".into(),
+            ]),
+            Expr::Collate { expr, collation } => Doc::Concat(vec![
+                self.expr(expr)?,
+                self.kw(" COLLATE "),
+                self.object_name(collation),
+            ]),
+            Expr::Nested(expr, _) => Doc::Concat(vec!["(".into(), self.expr(expr)?, ")".into()]),
+            Expr::Value(value, _) => self.value(value)?,
+            Expr::Placeholder(text, _) => text.as_str().into(),
+            Expr::Function(function) => self.function(function)?,
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+                ..
+            } => {
+                let mut head = vec![self.kw("CASE")];
+                if let Some(operand) = operand {
+                    head.push(" ".into());
+                    head.push(self.expr(operand)?);
+                }
+                let mut branches = vec![];
+                for (condition, result) in conditions.iter().zip(results) {
+                    branches.push(Doc::Line(" "));
+                    branches.push(self.kw("WHEN "));
+                    branches.push(self.expr(condition)?);
+                    branches.push(self.kw(" THEN "));
+                    branches.push(self.expr(result)?);
+                }
+                if let Some(else_result) = else_result {
+                    branches.push(Doc::Line(" "));
+                    branches.push(self.kw("ELSE "));
+                    branches.push(self.expr(else_result)?);
+                }
+                Doc::group(vec![
+                    Doc::Concat(head),
+                    Doc::Nest(branches),
+                    Doc::Line(" "),
+                    self.kw("END"),
+                ])
+            }
+            Expr::Exists(query, _) => Doc::Concat(vec![
+                self.kw("EXISTS "),
+                self.parens(self.query(query, true)?),
+            ]),
+            Expr::Subquery(query, _) => self.parens(self.query(query, true)?),
+        })
+    }
+
+    /// An operator, which may be a keyword like `AND`
+    fn operator(&self, op: &str) -> Doc {
+        if op.chars().all(|ch| ch.is_ascii_alphabetic() || ch == ' ') {
+            self.kw(op)
+        } else {
+            op.into()
+        }
+    }
+
+    fn function(&self, function: &Function) -> Result<Doc, FormatError> {
+        let mut args = self.exprs(&function.args)?;
+        if function.distinct {
+            // The parser accepts DISTINCT without arguments, as in `f(DISTINCT)`
+            let distinct = if args.is_empty() {
+                self.kw("DISTINCT")
+            } else {
+                Doc::Concat(vec![self.kw("DISTINCT "), args.remove(0)])
+            };
+            args.insert(0, distinct);
+        }
+        let mut docs = vec![
+            self.object_name(&function.name),
+            self.parenthesized_list(args),
+        ];
+        if let Some(over) = &function.over {
+            let mut window = vec![];
+            if !over.partition_by.is_empty() {
+                let partition_by = self.exprs(&over.partition_by)?;
+                window.push(self.clause(self.kw("PARTITION BY"), self.list(partition_by)));
+            }
+            if !over.order_by.is_empty() {
+                let order_by = over
+                    .order_by
+                    .iter()
+                    .map(|e| self.order_by_expr(e))
+                    .collect::<Result<_, FormatError>>()?;
+                window.push(self.clause(self.kw("ORDER BY"), self.list(order_by)));
+            }
+            if let Some(frame) = &over.window_frame {
+                let frame = match &frame.end_bound {
+                    Some(end_bound) => format!(
+                        "{} BETWEEN {} AND {}",
+                        frame.units, frame.start_bound, end_bound
+                    ),
+                    None => format!("{} {}", frame.units, frame.start_bound),
+                };
+                window.push(self.kw(&frame));
+            }
+            docs.push(self.kw(" OVER "));
+            docs.push(self.parens(self.clauses(window, false)));
+        }
+        Ok(Doc::Concat(docs))
+    }
+
+    fn value(&self, value: &Value) -> Result<Doc, FormatError> {
+        let quoted = |s: &String| Doc::from(format!("'{}'", escape_single_quote_string(s)));
+        Ok(match value {
+            Value::Boolean(_) if !self.dialect.supports_boolean_literals() => {
+                return Err(FormatError::new(
+                    "boolean literals cannot be expressed in the target dialect",
+                ))
+            }
+            Value::Boolean(true) => self.kw("TRUE"),
+            Value::Boolean(false) => self.kw("FALSE"),
+            Value::Null => self.kw("NULL"),
+            Value::Date(v) => Doc::Concat(vec![self.kw("DATE "), quoted(v)]),
+            Value::Time(v) => Doc::Concat(vec![self.kw("TIME "), quoted(v)]),
+            Value::Timestamp(v) => Doc::Concat(vec![self.kw("TIMESTAMP "), quoted(v)]),
+            Value::Interval { value: v, .. } => {
+                // Everything after the quoted value is keywords and numbers
+                let text = value.to_string();
+                let prefix = format!("INTERVAL '{}'", escape_single_quote_string(v));
+                Doc::Concat(vec![
+                    self.kw("INTERVAL "),
+                    quoted(v),
+                    self.kw(&text[prefix.len()..]),
+                ])
+            }
+            _ => self.display(value),
+        })
+    }
+
+    fn data_type(&self, data_type: &DataType) -> Result<Doc, FormatError> {
+        if let Some(spelling) = self.dialect.render_data_type(data_type) {
+            return Ok(self.kw(&spelling?));
+        }
+        Ok(match data_type {
+            DataType::Custom(name) => self.object_name(name),
+            DataType::Array(inner) => Doc::Concat(vec![self.data_type(inner)?, "[]".into()]),
+            _ => self.kw(&data_type.to_string()),
+        })
+    }
+}
+
+/// Collect the operands of a chain of `op`s, like `a AND b AND c`
+fn collect_operands<'a>(expr: &'a Expr, op: &BinaryOperator, operands: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: expr_op,
+            right,
+        } if expr_op == op => {
+            collect_operands(left, op, operands);
+            collect_operands(right, op, operands);
+        }
+        _ => operands.push(expr),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::{AnsiDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect};
+    use crate::parser::Parser;
+
+    fn parse(sql: &str) -> Statement {
+        let mut statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        assert_eq!(statements.len(), 1);
+        statements.pop().unwrap()
+    }
+
+    fn format(sql: &str, options: FormatOptions) -> String {
+        Formatter::new(options)
+            .format_statement(&parse(sql))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_clause_per_line() {
+        assert_eq!(
+            format(
+                "SELECT a, b FROM t JOIN u ON t.id = u.id WHERE a > 1 ORDER BY b DESC LIMIT 5",
+                FormatOptions::default()
+            ),
+            "SELECT a, b\nFROM t JOIN u ON t.id = u.id\nWHERE a > 1\nORDER BY b DESC\nLIMIT 5"
+        );
+        assert_eq!(
+            format(
+                "SELECT a, b FROM t WHERE a > 1",
+                FormatOptions {
+                    clause_per_line: false,
+                    ..FormatOptions::default()
+                }
+            ),
+            "SELECT a, b FROM t WHERE a > 1"
+        );
+    }
+
+    #[test]
+    fn test_line_width() {
+        let options = FormatOptions {
+            max_width: 30,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format(
+                "SELECT first_column, second_column, third_column FROM t \
+                 WHERE first_column = 1 AND second_column = 2",
+                options
+            ),
+            "SELECT\n  first_column,\n  second_column,\n  third_column\nFROM t\nWHERE\n  \
+             first_column = 1\n  AND second_column = 2"
+        );
+        // Subqueries are broken only if they don't fit
+        assert_eq!(
+            format(
+                "SELECT * FROM (SELECT a FROM t) AS s WHERE x IN (SELECT first_column FROM table_name)",
+                FormatOptions {
+                    max_width: 40,
+                    ..FormatOptions::default()
+                }
+            ),
+            "SELECT *\nFROM (SELECT a FROM t) AS s\nWHERE\n  x IN (\n    SELECT first_column FROM table_name\n  )"
+        );
+    }
+
+    #[test]
+    fn test_indent_and_commas() {
+        let options = FormatOptions {
+            indent: 4,
+            max_width: 20,
+            comma_style: CommaStyle::Leading,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format("SELECT alpha, beta, gamma FROM t", options),
+            "SELECT\n    alpha\n    , beta\n    , gamma\nFROM t"
+        );
+    }
+
+    #[test]
+    fn test_keyword_case() {
+        let options = FormatOptions {
+            keyword_case: KeywordCase::Lower,
+            clause_per_line: false,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format(
+                "SELECT CAST(\"A\" AS INT), NULL, 'It''s' FROM T WHERE a IS NOT NULL",
+                options
+            ),
+            "select cast(\"A\" as int), null, 'It''s' from T where a is not null"
+        );
+    }
+
+    #[test]
+    fn test_create_table() {
+        assert_eq!(
+            format(
+                "CREATE TABLE t (id INT PRIMARY KEY, name TEXT NOT NULL)",
+                FormatOptions::default()
+            ),
+            "CREATE TABLE t (id INT PRIMARY KEY, name TEXT NOT NULL)"
+        );
+        assert_eq!(
+            format(
+                "CREATE TABLE t (id INT PRIMARY KEY, name TEXT NOT NULL)",
+                FormatOptions {
+                    max_width: 40,
+                    ..FormatOptions::default()
+                }
+            ),
+            "CREATE TABLE t (\n  id INT PRIMARY KEY,\n  name TEXT NOT NULL\n)"
+        );
+
+        let mut statement =
+            parse("CREATE EXTERNAL TABLE t (a INT) STORED AS TEXTFILE LOCATION '/t'");
+        if let Statement::CreateTable { location, .. } = &mut statement {
+            *location = None;
+        }
+        assert_eq!(
+            Formatter::default().format_statement(&statement).unwrap(),
+            "CREATE EXTERNAL TABLE t (a INT) STORED AS TEXTFILE"
+        );
+        assert_eq!(
+            statement.to_string(),
+            "CREATE EXTERNAL TABLE t (a int) STORED AS TEXTFILE"
+        );
+    }
+
+    #[test]
+    fn test_format_statements() {
+        let statements =
+            Parser::parse_sql(&GenericDialect {}, "SELECT 1; COMMIT".to_string()).unwrap();
+        assert_eq!(
+            Formatter::default().format_statements(&statements).unwrap(),
+            "SELECT 1;\n\nCOMMIT;\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let queries = [
+            "WITH a AS (SELECT 1 FROM t) SELECT DISTINCT x AS y, count(DISTINCT z) FROM a \
+             LEFT JOIN b USING(id) NATURAL JOIN c GROUP BY x HAVING count(*) > 1 \
+             UNION ALL SELECT * FROM d ORDER BY 1 OFFSET 2 ROWS FETCH FIRST 3 ROWS ONLY",
+            "SELECT CASE WHEN a = 1 THEN 'one' ELSE 'other' END, CASE b WHEN 2 THEN 3 END FROM t",
+            "SELECT f(DISTINCT), g(), count(DISTINCT a, b) FROM t",
+            "SELECT row_number() OVER (PARTITION BY a ORDER BY b ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) FROM t",
+            "SELECT INTERVAL '1' DAY, INTERVAL '1:1' HOUR (5) TO MINUTE, DATE '2020-01-01', TRUE, -a, NOT b FROM t",
+            "SELECT a FROM t WHERE a BETWEEN 1 AND 2 OR (b NOT IN (1, 2) AND EXISTS (SELECT 1)) OR c LIKE 'x'",
+            "SELECT EXTRACT(YEAR FROM d), a COLLATE \"de_DE\", x::BIGINT FROM t AS u (a, b)",
+            "INSERT INTO t (a, b) VALUES (1, 2), (3, 4)",
+            "UPDATE t SET a = 1, b = 2 WHERE c = 3",
+            "DELETE FROM t WHERE a = 1",
+            "CREATE MATERIALIZED VIEW v (a) AS SELECT a FROM t",
+            "CREATE TABLE t (a INT CONSTRAINT pos CHECK (a > 0) DEFAULT 1, b TEXT COLLATE \"C\" REFERENCES u (b), \
+             CONSTRAINT fk FOREIGN KEY (a) REFERENCES u(a), UNIQUE (a, b))",
+            "ALTER TABLE t ADD CONSTRAINT c UNIQUE (a)",
+            "DROP TABLE IF EXISTS a, b CASCADE",
+            "SET LOCAL a = 'b'",
+            "SHOW FULL COLUMNS FROM t LIKE 'a%'",
+            "START TRANSACTION READ ONLY, ISOLATION LEVEL SERIALIZABLE",
+            "ROLLBACK AND CHAIN",
+        ];
+        let options = vec![
+            FormatOptions::default(),
+            FormatOptions {
+                indent: 3,
+                max_width: 10,
+                keyword_case: KeywordCase::Lower,
+                comma_style: CommaStyle::Leading,
+                clause_per_line: false,
+            },
+        ];
+        for sql in queries.iter() {
+            let statement = parse(sql);
+            for options in &options {
+                let formatted = Formatter::new(options.clone())
+                    .format_statement(&statement)
+                    .unwrap();
+                assert_eq!(parse(&formatted), statement, "{}", formatted);
+            }
+        }
+    }
+
+    #[test]
+    fn test_comments() {
+        let sql = "-- the users\n\
+                   SELECT id, -- the key\n\
+                   /* full */ name FROM users -- all of them\n\
+                   WHERE active";
+        let formatted = format(sql, FormatOptions::default());
+        assert_eq!(
+            formatted,
+            "-- the users\n\
+             SELECT\n\
+             \x20 id, -- the key\n\
+             \x20 /* full */ name\n\
+             FROM users -- all of them\n\
+             WHERE active"
+        );
+        assert_eq!(parse(&formatted), parse(sql));
+
+        let sql = "CREATE TABLE t (\n\
+                   -- the key\n\
+                   a INT, b TEXT -- free text\n\
+                   )";
+        let options = FormatOptions {
+            comma_style: CommaStyle::Leading,
+            ..FormatOptions::default()
+        };
+        let formatted = format(sql, options);
+        assert_eq!(
+            formatted,
+            "CREATE TABLE t (\n\
+             \x20 -- the key\n\
+             \x20 a INT\n\
+             \x20 , b TEXT -- free text\n\
+             )"
+        );
+        assert_eq!(parse(&formatted), parse(sql));
+
+        // A statement is terminated before its trailing comments
+        let statements = Parser::parse_sql(
+            &GenericDialect {},
+            "SELECT a FROM t WHERE b; -- done\nCOMMIT".to_string(),
+        )
+        .unwrap();
+        let formatted = Formatter::default().format_statements(&statements).unwrap();
+        assert_eq!(formatted, "SELECT a\nFROM t\nWHERE b; -- done\n\nCOMMIT;\n");
+        assert_eq!(
+            Parser::parse_sql(&GenericDialect {}, formatted).unwrap(),
+            statements
+        );
+    }
+
+    fn format_for(sql: &str, dialect: &dyn Dialect) -> Result<String, FormatError> {
+        let options = FormatOptions {
+            clause_per_line: false,
+            ..FormatOptions::default()
+        };
+        let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        Formatter::for_dialect(options, dialect).format_statement(&statements[0])
+    }
+
+    #[test]
+    fn test_target_dialect_quotes() {
+        let sql = "SELECT \"a b\", c FROM \"t\"";
+        assert_eq!(
+            format_for(sql, &PostgreSqlDialect {}).unwrap(),
+            "SELECT \"a b\", c FROM \"t\""
+        );
+        assert_eq!(
+            format_for(sql, &MsSqlDialect {}).unwrap(),
+            "SELECT [a b], c FROM [t]"
+        );
+        assert_eq!(
+            format_for(sql, &MySqlDialect {}).unwrap(),
+            "SELECT `a b`, c FROM `t`"
+        );
+        assert_eq!(
+            format_for("SELECT \"a]b\"", &MsSqlDialect {}).unwrap(),
+            "SELECT [a]]b]"
+        );
+        // The generic dialect keeps the original quotes
+        assert_eq!(
+            format_for(sql, &GenericDialect {}).unwrap(),
+            "SELECT \"a b\", c FROM \"t\""
+        );
+    }
+
+    #[test]
+    fn test_target_dialect_row_limit() {
+        let sql = "SELECT a FROM t ORDER BY a LIMIT 10 OFFSET 5 ROWS";
+        assert_eq!(
+            format_for(sql, &PostgreSqlDialect {}).unwrap(),
+            "SELECT a FROM t ORDER BY a LIMIT 10 OFFSET 5 ROWS"
+        );
+        assert_eq!(
+            format_for(sql, &AnsiDialect {}).unwrap(),
+            "SELECT a FROM t ORDER BY a OFFSET 5 ROWS FETCH FIRST 10 ROWS ONLY"
+        );
+        assert_eq!(
+            format_for(sql, &MsSqlDialect {}).unwrap(),
+            "SELECT a FROM t ORDER BY a OFFSET 5 ROWS FETCH NEXT 10 ROWS ONLY"
+        );
+        assert_eq!(
+            format_for("SELECT DISTINCT a FROM t LIMIT 10", &MsSqlDialect {}).unwrap(),
+            "SELECT DISTINCT TOP (10) a FROM t"
+        );
+        assert_eq!(
+            format_for(
+                "SELECT a FROM t ORDER BY a FETCH FIRST 10 PERCENT ROWS WITH TIES",
+                &MsSqlDialect {}
+            )
+            .unwrap(),
+            "SELECT TOP (10) PERCENT WITH TIES a FROM t ORDER BY a"
+        );
+        assert_eq!(
+            format_for(
+                "SELECT a FROM t OFFSET 5 ROWS FETCH FIRST 10 ROWS ONLY",
+                &MySqlDialect {}
+            )
+            .unwrap(),
+            "SELECT a FROM t LIMIT 10 OFFSET 5"
+        );
+
+        let error = |sql: &str, dialect: &dyn Dialect| format_for(sql, dialect).unwrap_err();
+        assert_eq!(
+            error("SELECT a FROM t LIMIT 10 OFFSET 5 ROWS", &MsSqlDialect {}),
+            FormatError::new("OFFSET without ORDER BY cannot be expressed in the target dialect")
+        );
+        assert_eq!(
+            error(
+                "SELECT a FROM t UNION SELECT b FROM u LIMIT 1",
+                &MsSqlDialect {}
+            ),
+            FormatError::new("TOP cannot limit the rows of a set operation or VALUES")
+        );
+        assert_eq!(
+            error("SELECT a FROM t OFFSET 5 ROWS", &MySqlDialect {}),
+            FormatError::new("OFFSET without a row count cannot be expressed with LIMIT")
+        );
+        assert_eq!(
+            error(
+                "SELECT a FROM t FETCH FIRST 10 PERCENT ROWS ONLY",
+                &MySqlDialect {}
+            ),
+            FormatError::new("FETCH with PERCENT or WITH TIES cannot be expressed with LIMIT")
+        );
+    }
+
+    #[test]
+    fn test_target_dialect_round_trip() {
+        // Each dialect reads back the SQL rendered for it
+        let cases: Vec<(&dyn Dialect, &str)> = vec![
+            (
+                &GenericDialect {},
+                "SELECT \"a b\", c FROM t ORDER BY c LIMIT 10 OFFSET 5 ROWS",
+            ),
+            (
+                &PostgreSqlDialect {},
+                "SELECT \"a b\", c FROM t ORDER BY c LIMIT 10 OFFSET 5 ROWS",
+            ),
+            (
+                &MySqlDialect {},
+                "SELECT `a b`, c FROM `t` ORDER BY c LIMIT 10 OFFSET 5",
+            ),
+            (
+                &MsSqlDialect {},
+                "SELECT [a b], c FROM t ORDER BY c OFFSET 5 ROWS FETCH NEXT 10 ROWS ONLY",
+            ),
+            (
+                &AnsiDialect {},
+                "SELECT \"a b\", c FROM t ORDER BY c OFFSET 5 ROWS FETCH FIRST 10 ROWS ONLY",
+            ),
+        ];
+        let options = FormatOptions {
+            clause_per_line: false,
+            ..FormatOptions::default()
+        };
+        for (dialect, sql) in cases {
+            let statement = Parser::parse_sql(dialect, sql.to_string())
+                .unwrap()
+                .remove(0);
+            let formatted = Formatter::for_dialect(options.clone(), dialect)
+                .format_statement(&statement)
+                .unwrap();
+            assert_eq!(formatted, sql);
+            let reparsed = Parser::parse_sql(dialect, formatted).unwrap().remove(0);
+            assert_eq!(reparsed, statement, "{:?}", dialect);
+        }
+    }
+
+    #[test]
+    fn test_target_dialect_types_and_literals() {
+        let sql = "CREATE TABLE t (a TEXT, b BOOLEAN DEFAULT NULL, c DOUBLE, d BYTEA, e VARCHAR)";
+        assert_eq!(
+            format_for(sql, &MsSqlDialect {}).unwrap(),
+            "CREATE TABLE t (\n  a NVARCHAR(MAX),\n  b BIT DEFAULT NULL,\n  c FLOAT(53),\n  \
+             d VARBINARY(MAX),\n  e VARCHAR(MAX)\n)"
+        );
+        assert_eq!(
+            format_for(sql, &PostgreSqlDialect {}).unwrap(),
+            "CREATE TABLE t (\n  a TEXT,\n  b BOOLEAN DEFAULT NULL,\n  c DOUBLE PRECISION,\n  \
+             d BYTEA,\n  e CHARACTER VARYING\n)"
+        );
+        assert_eq!(
+            format_for("SELECT a FROM t WHERE TRUE", &MsSqlDialect {}).unwrap_err(),
+            FormatError::new("boolean literals cannot be expressed in the target dialect")
+        );
+        assert_eq!(
+            format_for("SELECT a FROM t WHERE TRUE", &PostgreSqlDialect {}).unwrap(),
+            "SELECT a FROM t WHERE TRUE"
+        );
+        assert_eq!(
+            format_for(sql, &MySqlDialect {}).unwrap_err(),
+            FormatError::new("MySQL requires a length for VARCHAR")
+        );
+        assert_eq!(
+            format_for("SELECT CAST(a AS INTERVAL)", &MsSqlDialect {}).unwrap_err(),
+            FormatError::new("MS SQL has no interval type")
+        );
+    }
+}
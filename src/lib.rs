@@ -34,9 +34,18 @@
 //! ```
 #![warn(clippy::all)]
 
+pub mod access;
 pub mod ast;
+pub mod builder;
 pub mod dialect;
+pub mod fingerprint;
+pub mod formatter;
 pub mod parser;
+pub mod placeholders;
+pub mod policy;
+pub mod semantic;
+pub mod simplify;
+pub mod stream;
 pub mod tokenizer;
 
 #[doc(hidden)]
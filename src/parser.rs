@@ -15,22 +15,100 @@
 use log::debug;
 
 use super::ast::*;
-use super::dialect::keywords;
-use super::dialect::Dialect;
+use super::dialect::keywords::{self, Keyword};
+use super::dialect::{Dialect, RowLimitSyntax};
 use super::tokenizer::*;
+use std::cell::RefCell;
 use std::error::Error;
 use std::fmt;
 
+/// An error encountered while tokenizing or parsing SQL
 #[derive(Debug, Clone, PartialEq)]
-pub enum ParserError {
-    TokenizerError(String),
-    ParserError(String),
+pub struct ParserError {
+    pub kind: ParserErrorKind,
+    /// A human-readable description of the error
+    pub message: String,
+    /// The location of the offending token. At the end of the input, this is
+    /// an empty span just past the last token.
+    pub span: Span,
+    /// The offending token, or `None` at the end of the input
+    pub found: Option<Box<Token<'static>>>,
+    /// The keywords, tokens and constructs that would have been accepted in
+    /// place of `found`
+    pub expected: Vec<Expected>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParserErrorKind {
+    /// The input could not be read
+    IoError,
+    /// The input could not be split into tokens
+    TokenizerError,
+    /// A token that is not valid at this position was found
+    UnexpectedToken,
+    /// The input ended before the statement was complete
+    UnexpectedEof,
+    /// The tokens were recognized, but do not form valid SQL (for example,
+    /// conflicting options or an out-of-range number)
+    InvalidSyntax,
+    /// The input exceeds one of the limits set in [ParserOptions]
+    LimitExceeded,
+}
+
+/// Limits on the input that a [Parser] accepts, to protect against
+/// untrusted input exhausting the stack or memory. Exceeding a limit makes
+/// parsing fail with a [ParserErrorKind::LimitExceeded] error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// The maximum depth to which expressions, queries and table factors
+    /// (e.g. joins and derived tables) may nest, counting each of them
+    /// separately. Parsing nested constructs is recursive, so this bounds
+    /// the stack space used; the default of 256 keeps this under 2 MB in
+    /// optimized builds. A chain of operators like `a AND b AND c` is parsed
+    /// in a loop, so it does not count, however long it is; limit the
+    /// number of tokens to bound it.
+    pub max_nesting_depth: usize,
+    /// The maximum number of tokens, including whitespace and comments
+    pub max_tokens: Option<usize>,
+    /// The maximum length of the input in bytes. The length of tokens
+    /// without locations, as passed to [Parser::new], is the length of
+    /// their text as printed.
+    pub max_input_length: Option<usize>,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            max_nesting_depth: 256,
+            max_tokens: None,
+            max_input_length: None,
+        }
+    }
+}
+
+/// Something that the parser would have accepted at the location of an error
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    Keyword(Keyword),
+    Token(Token<'static>),
+    /// A construct made of several tokens, like "an expression"
+    Description(String),
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expected::Keyword(keyword) => write!(f, "{}", keyword),
+            Expected::Token(token) => write!(f, "{}", token),
+            Expected::Description(description) => f.write_str(description),
+        }
+    }
 }
 
 // Use `Parser::expected` instead, if possible
 macro_rules! parser_err {
-    ($MSG:expr) => {
-        Err(ParserError::ParserError($MSG.to_string()))
+    ($parser:expr, $MSG:expr) => {
+        Err($parser.error_at_previous(ParserErrorKind::InvalidSyntax, $MSG.to_string()))
     };
 }
 
@@ -47,96 +125,465 @@ pub enum IsLateral {
 }
 use IsLateral::*;
 
+impl ParserError {
+    /// Create an error with no location or expected set
+    pub fn new(kind: ParserErrorKind, message: String) -> Self {
+        ParserError {
+            kind,
+            message,
+            span: Span::empty(),
+            found: None,
+            expected: vec![],
+        }
+    }
+}
+
 impl From<TokenizerError> for ParserError {
     fn from(e: TokenizerError) -> Self {
-        ParserError::TokenizerError(format!("{:?}", e))
+        ParserError {
+            kind: ParserErrorKind::TokenizerError,
+            message: e.message,
+            span: Span::new(e.location, e.location),
+            found: None,
+            expected: vec![],
+        }
     }
 }
 
 impl fmt::Display for ParserError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "sql parser error: {}",
-            match self {
-                ParserError::TokenizerError(s) => s,
-                ParserError::ParserError(s) => s,
-            }
-        )
+        write!(f, "sql parser error: {}", self.message)
     }
 }
 
 impl Error for ParserError {}
 
-/// SQL Parser
-pub struct Parser {
-    tokens: Vec<Token>,
+/// The statements parsed by [Parser::parse_statements_recovering], and the
+/// errors in the ones that could not be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoveredStatements<'a> {
+    /// The statements that were parsed successfully, in input order
+    pub statements: Vec<Statement>,
+    /// A diagnostic for each statement that could not be parsed, in input order
+    pub failures: Vec<FailedStatement<'a>>,
+}
+
+/// A statement that could not be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailedStatement<'a> {
+    pub error: ParserError,
+    /// The number of statements that were parsed successfully before this one,
+    /// i.e. its position relative to `RecoveredStatements::statements`
+    pub position: usize,
+    /// The tokens of the statement, without the terminating semicolon or any
+    /// surrounding whitespace. Empty if the failure is a missing semicolon
+    /// after a statement that was parsed.
+    pub tokens: Vec<TokenWithLocation<'a>>,
+}
+
+impl FailedStatement<'_> {
+    /// The span of source text covered by `tokens`
+    pub fn span(&self) -> Span {
+        match (self.tokens.first(), self.tokens.last()) {
+            (Some(first), Some(last)) => first.span.union(&last.span),
+            _ => self.error.span,
+        }
+    }
+}
+
+/// The tokens read so far, and the source of the remaining ones
+struct TokenBuffer<'a> {
+    tokens: Vec<TokenWithLocation<'a>>,
+    source: Box<dyn Iterator<Item = Result<TokenWithLocation<'a>, TokenizerError>> + 'a>,
+    /// The error that ended `source`, if any; the input past the last token
+    /// in `tokens` could not be tokenized, or exceeds a limit.
+    error: Option<ParserError>,
+    max_tokens: Option<usize>,
+    max_input_length: Option<usize>,
+    /// The total length of the tokens in `tokens` that have no location
+    unlocated_length: usize,
+}
+
+impl<'a> TokenBuffer<'a> {
+    /// Read tokens from the source until `tokens[index]` is available, and
+    /// return it, or `None` if the source ends first.
+    fn get(&mut self, index: usize) -> Option<&TokenWithLocation<'a>> {
+        while self.tokens.len() <= index && self.error.is_none() {
+            match self.source.next() {
+                Some(Ok(token)) => {
+                    if let Err(e) = self.check_limits(&token) {
+                        self.error = Some(e);
+                        break;
+                    }
+                    if token.span.is_empty() && self.max_input_length.is_some() {
+                        self.unlocated_length += token.token.to_string().len();
+                    }
+                    self.tokens.push(token)
+                }
+                Some(Err(e)) => self.error = Some(e.into()),
+                None => break,
+            }
+        }
+        self.tokens.get(index)
+    }
+
+    /// Check that `token` can be added to `tokens` without exceeding the
+    /// limits
+    fn check_limits(&self, token: &TokenWithLocation) -> Result<(), ParserError> {
+        let limit_exceeded = |message| ParserError {
+            span: Span::new(token.span.start, token.span.start),
+            ..ParserError::new(ParserErrorKind::LimitExceeded, message)
+        };
+        if let Some(max) = self.max_tokens {
+            if self.tokens.len() >= max {
+                return Err(limit_exceeded(format!(
+                    "Exceeded the maximum of {} tokens",
+                    max
+                )));
+            }
+        }
+        if let Some(max) = self.max_input_length {
+            let length = if token.span.is_empty() {
+                self.unlocated_length + token.token.to_string().len()
+            } else {
+                let start = self.tokens.first().unwrap_or(token).span.start.offset;
+                token.span.end.offset - start
+            };
+            if length > max {
+                return Err(limit_exceeded(format!(
+                    "Exceeded the maximum input length of {} bytes",
+                    max
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// SQL Parser. Tokens are read from the tokenizer as they are needed, so a
+/// statement can be parsed before the rest of the input is tokenized.
+pub struct Parser<'a> {
+    tokens: RefCell<TokenBuffer<'a>>,
     /// The index of the first unprocessed token in `self.tokens`
     index: usize,
+    /// The keywords and tokens that were tried, and did not match, at
+    /// `self.tokens[expected_index]`; used to report errors at that token.
+    expected: Vec<Expected>,
+    expected_index: usize,
+    /// The comments before this index in `self.tokens` have been attached to
+    /// a node, or were passed over
+    comments_claimed: usize,
+    /// The comments passed over inside a node, with their index in
+    /// `self.tokens`, until the node or clause around them claims them
+    skipped_comments: Vec<(usize, Comment)>,
+    options: ParserOptions,
+    /// The number of nested constructs being parsed, see
+    /// [ParserOptions::max_nesting_depth]
+    depth: usize,
+    dialect: &'a dyn Dialect,
 }
 
-impl Parser {
-    /// Parse the specified tokens
-    pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, index: 0 }
+impl<'a> Parser<'a> {
+    /// Parse the specified tokens. The resulting AST will not contain source
+    /// locations; use [Parser::new_with_locations] if they are needed.
+    pub fn new(tokens: Vec<Token<'a>>, dialect: &'a dyn Dialect) -> Self {
+        Parser::new_with_locations(
+            tokens
+                .into_iter()
+                .map(|token| TokenWithLocation::new(token, Span::empty()))
+                .collect(),
+            dialect,
+        )
+    }
+
+    /// Parse the specified tokens, recording their locations in the AST
+    pub fn new_with_locations(
+        tokens: Vec<TokenWithLocation<'a>>,
+        dialect: &'a dyn Dialect,
+    ) -> Self {
+        Parser::from_source(Box::new(tokens.into_iter().map(Ok)), dialect)
+    }
+
+    /// Parse the tokens produced by `tokenizer`, reading them only as they are
+    /// needed. A tokenizer error is reported when the parser reaches it.
+    pub fn from_tokenizer(tokenizer: Tokenizer<'a>) -> Self {
+        let dialect = tokenizer.dialect();
+        Parser::from_source(Box::new(tokenizer), dialect)
+    }
+
+    fn from_source(
+        source: Box<dyn Iterator<Item = Result<TokenWithLocation<'a>, TokenizerError>> + 'a>,
+        dialect: &'a dyn Dialect,
+    ) -> Self {
+        Parser {
+            tokens: RefCell::new(TokenBuffer {
+                tokens: vec![],
+                source,
+                error: None,
+                max_tokens: None,
+                max_input_length: None,
+                unlocated_length: 0,
+            }),
+            index: 0,
+            dialect,
+            expected: vec![],
+            expected_index: 0,
+            comments_claimed: 0,
+            skipped_comments: vec![],
+            options: ParserOptions::default(),
+            depth: 0,
+        }
+    }
+
+    /// Limit the input that the parser accepts
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        let tokens = self.tokens.get_mut();
+        tokens.max_tokens = options.max_tokens;
+        tokens.max_input_length = options.max_input_length;
+        self.options = options;
+        self
     }
 
     /// Parse a SQL statement and produce an Abstract Syntax Tree (AST)
     pub fn parse_sql(dialect: &dyn Dialect, sql: String) -> Result<Vec<Statement>, ParserError> {
-        let mut tokenizer = Tokenizer::new(dialect, &sql);
-        let tokens = tokenizer.tokenize()?;
-        let mut parser = Parser::new(tokens);
+        Parser::parse_sql_with_options(dialect, sql, ParserOptions::default())
+    }
+
+    /// Parse SQL like [Parser::parse_sql], within the limits in `options`
+    pub fn parse_sql_with_options(
+        dialect: &dyn Dialect,
+        sql: String,
+        options: ParserOptions,
+    ) -> Result<Vec<Statement>, ParserError> {
+        debug!("Parsing sql '{}'...", sql);
+        // Don't tokenize input that is too long
+        if let Some(max) = options.max_input_length {
+            if sql.len() > max {
+                return Err(ParserError::new(
+                    ParserErrorKind::LimitExceeded,
+                    format!("Exceeded the maximum input length of {} bytes", max),
+                ));
+            }
+        }
+        Parser::from_tokenizer(Tokenizer::new(dialect, &sql))
+            .with_options(options)
+            .parse_statements()
+    }
+
+    /// Parse the remaining input as a sequence of statements separated by
+    /// semicolons
+    pub fn parse_statements(&mut self) -> Result<Vec<Statement>, ParserError> {
         let mut stmts = Vec::new();
         let mut expecting_statement_delimiter = false;
-        debug!("Parsing sql '{}'...", sql);
         loop {
             // ignore empty statements (between successive statement delimiters)
-            while parser.consume_token(&Token::SemiColon) {
+            while self.consume_token(&Token::SemiColon) {
                 expecting_statement_delimiter = false;
             }
 
-            if parser.peek_token().is_none() {
+            if self.peek_token().is_none() {
                 break;
             } else if expecting_statement_delimiter {
-                return parser.expected("end of statement", parser.peek_token());
+                return self.expected("end of statement", self.peek_token());
             }
 
-            let statement = parser.parse_statement()?;
+            let statement = self.parse_commented_statement()?;
             stmts.push(statement);
             expecting_statement_delimiter = true;
         }
-        Ok(stmts)
+        match self.tokens.get_mut().error.take() {
+            Some(e) => Err(e),
+            None => Ok(stmts),
+        }
+    }
+
+    /// Parse a SQL script, skipping over the statements that cannot be parsed
+    /// instead of stopping at the first error. See
+    /// [Parser::parse_statements_recovering].
+    pub fn parse_sql_recovering(dialect: &'a dyn Dialect, sql: &'a str) -> RecoveredStatements<'a> {
+        debug!("Parsing sql '{}'...", sql);
+        Parser::from_tokenizer(Tokenizer::new(dialect, sql)).parse_statements_recovering()
+    }
+
+    /// Parse the remaining input as a sequence of statements, like
+    /// [Parser::parse_statements], but when a statement fails to parse, record
+    /// the error along with the statement's tokens and carry on with the next
+    /// one.
+    ///
+    /// A failed statement ends at the next semicolon or, to cope with missing
+    /// semicolons, at a keyword that begins a statement (like `CREATE` or
+    /// `INSERT`, but not keywords that can also continue a statement, like
+    /// `SELECT` or `SET`) if it is the first token on its line, outside of any
+    /// parentheses, and after the location of the error.
+    ///
+    /// A statement that parses but is followed by something other than a
+    /// semicolon is kept, and the missing semicolon is reported as a failure
+    /// without any tokens, before parsing carries on with what follows.
+    ///
+    /// If the input cannot be tokenized, parsing stops at that point, and the
+    /// tokenizer error is reported as the last failure.
+    pub fn parse_statements_recovering(&mut self) -> RecoveredStatements<'a> {
+        let mut statements = Vec::new();
+        let mut failures = Vec::new();
+        loop {
+            while self.consume_token(&Token::SemiColon) {}
+            if self.peek_token().is_none() {
+                break;
+            }
+
+            let start = self.next_token_index();
+            match self.parse_commented_statement() {
+                Ok(statement) => {
+                    statements.push(statement);
+                    let next = self.peek_token().filter(|t| *t != Token::SemiColon);
+                    if let Some(Err(error)) =
+                        next.map(|t| self.expected::<()>("end of statement", Some(t)))
+                    {
+                        failures.push(FailedStatement {
+                            error,
+                            position: statements.len(),
+                            tokens: vec![],
+                        });
+                    }
+                }
+                Err(error) => {
+                    let end = self.find_statement_boundary(start, self.index.max(start + 1));
+                    let mut tokens = (start..end)
+                        .filter_map(|i| self.token_at(i))
+                        .collect::<Vec<_>>();
+                    while let Some(Token::Whitespace(_)) = tokens.last().map(|t| &t.token) {
+                        tokens.pop();
+                    }
+                    failures.push(FailedStatement {
+                        error,
+                        position: statements.len(),
+                        tokens,
+                    });
+                    self.index = end;
+                }
+            }
+        }
+        if let Some(e) = self.tokens.get_mut().error.take() {
+            // The error was already reported if a statement failed on it
+            let reported = matches!(
+                failures.last(),
+                Some(FailedStatement { error, .. }) if *error == e
+            );
+            if !reported {
+                failures.push(FailedStatement {
+                    error: e,
+                    position: statements.len(),
+                    tokens: vec![],
+                });
+            }
+        }
+        RecoveredStatements {
+            statements,
+            failures,
+        }
+    }
+
+    /// Return the index of the token that ends the statement starting at
+    /// `self.tokens[start]`, or the number of tokens if the input ends first.
+    /// Keywords are only considered as boundaries from `keywords_from` onward.
+    fn find_statement_boundary(&self, start: usize, keywords_from: usize) -> usize {
+        let mut depth = 0usize;
+        let mut line_start = false;
+        let mut index = start;
+        while let Some(token) = self.token_at(index) {
+            match token.token {
+                Token::SemiColon if index > start => return index,
+                Token::LParen => depth += 1,
+                Token::RParen => depth = depth.saturating_sub(1),
+                Token::Word(ref w) if index >= keywords_from && line_start && depth == 0 => {
+                    match w.keyword {
+                        Keyword::CREATE
+                        | Keyword::DROP
+                        | Keyword::DELETE
+                        | Keyword::INSERT
+                        | Keyword::UPDATE
+                        | Keyword::ALTER
+                        | Keyword::COPY
+                        | Keyword::SHOW
+                        | Keyword::START
+                        | Keyword::BEGIN
+                        | Keyword::COMMIT
+                        | Keyword::ROLLBACK => return index,
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+            match token.token {
+                Token::Whitespace(Whitespace::Newline)
+                | Token::Whitespace(Whitespace::SingleLineComment(_)) => line_start = true,
+                Token::Whitespace(_) => {}
+                _ => line_start = false,
+            }
+            index += 1;
+        }
+        index
+    }
+
+    /// Parse a single top-level statement, with the comments before it and
+    /// the ones following it on the same line, possibly after its semicolon
+    fn parse_commented_statement(&mut self) -> Result<Statement, ParserError> {
+        self.skipped_comments.clear();
+        let leading = self.leading_comments();
+        let start = self.next_token_index();
+        let statement = self.parse_statement()?;
+        let mut trailing = self.trailing_comments();
+        if let Some(Token::SemiColon) = self.peek_token() {
+            let semicolon = self.next_token_index();
+            trailing.extend(self.trailing_comments_from(semicolon + 1));
+        }
+        let trailing = self.with_enclosed_comments(start, trailing);
+        Ok(statement.with_comments(Comments { leading, trailing }))
     }
 
     /// Parse a single top-level statement (such as SELECT, INSERT, CREATE, etc.),
     /// stopping before the statement separator, if any.
     pub fn parse_statement(&mut self) -> Result<Statement, ParserError> {
+        // The statements other than queries are parsed after the keywords
+        // that introduce them, so their span is recorded here
+        let start = self.next_token_index();
+        let statement = self.parse_statement_inner()?;
+        Ok(statement.with_span(self.span_from(start)))
+    }
+
+    fn parse_statement_inner(&mut self) -> Result<Statement, ParserError> {
+        if let Some(statement) = self.dialect.parse_statement(self) {
+            return statement;
+        }
         match self.next_token() {
             Some(t) => match t {
-                Token::Word(ref w) if w.keyword != "" => match w.keyword.as_ref() {
-                    "SELECT" | "WITH" | "VALUES" => {
+                Token::Word(ref w) if w.keyword != Keyword::NoKeyword => match w.keyword {
+                    Keyword::SELECT | Keyword::WITH | Keyword::VALUES => {
                         self.prev_token();
                         Ok(Statement::Query(Box::new(self.parse_query()?)))
                     }
-                    "CREATE" => Ok(self.parse_create()?),
-                    "DROP" => Ok(self.parse_drop()?),
-                    "DELETE" => Ok(self.parse_delete()?),
-                    "INSERT" => Ok(self.parse_insert()?),
-                    "UPDATE" => Ok(self.parse_update()?),
-                    "ALTER" => Ok(self.parse_alter()?),
-                    "COPY" => Ok(self.parse_copy()?),
-                    "SET" => Ok(self.parse_set()?),
-                    "SHOW" => Ok(self.parse_show()?),
-                    "START" => Ok(self.parse_start_transaction()?),
+                    Keyword::CREATE => Ok(self.parse_create()?),
+                    Keyword::DROP => Ok(self.parse_drop()?),
+                    Keyword::DELETE => Ok(self.parse_delete()?),
+                    Keyword::INSERT => Ok(self.parse_insert()?),
+                    Keyword::UPDATE => Ok(self.parse_update()?),
+                    Keyword::ALTER => Ok(self.parse_alter()?),
+                    Keyword::COPY => Ok(self.parse_copy()?),
+                    Keyword::SET => Ok(self.parse_set()?),
+                    Keyword::SHOW => Ok(self.parse_show()?),
+                    Keyword::START => Ok(self.parse_start_transaction()?),
                     // `BEGIN` is a nonstandard but common alias for the
                     // standard `START TRANSACTION` statement. It is supported
                     // by at least PostgreSQL and MySQL.
-                    "BEGIN" => Ok(self.parse_begin()?),
-                    "COMMIT" => Ok(self.parse_commit()?),
-                    "ROLLBACK" => Ok(self.parse_rollback()?),
-                    _ => parser_err!(format!(
-                        "Unexpected keyword {:?} at the beginning of a statement",
-                        w.to_string()
+                    Keyword::BEGIN => Ok(self.parse_begin()?),
+                    Keyword::COMMIT => Ok(self.parse_commit()?),
+                    Keyword::ROLLBACK => Ok(self.parse_rollback()?),
+                    _ => Err(self.unexpected_token(
+                        format!(
+                            "Unexpected keyword {:?} at the beginning of a statement",
+                            w.to_string()
+                        ),
+                        Some(t.clone()),
                     )),
                 },
                 Token::LParen => {
@@ -159,6 +606,10 @@ impl Parser {
 
     /// Parse tokens until the precedence changes
     pub fn parse_subexpr(&mut self, precedence: u8) -> Result<Expr, ParserError> {
+        self.nested(|parser| parser.parse_subexpr_inner(precedence))
+    }
+
+    fn parse_subexpr_inner(&mut self, precedence: u8) -> Result<Expr, ParserError> {
         debug!("parsing expr");
         let mut expr = self.parse_prefix()?;
         debug!("prefix: {:?}", expr);
@@ -176,36 +627,53 @@ impl Parser {
 
     /// Parse an expression prefix
     pub fn parse_prefix(&mut self) -> Result<Expr, ParserError> {
-        let tok = self
-            .next_token()
-            .ok_or_else(|| ParserError::ParserError("Unexpected EOF".to_string()))?;
+        if let Some(prefix) = self.dialect.parse_prefix(self) {
+            return prefix;
+        }
+        let start = self.next_token_index();
+        let tok = match self.next_token() {
+            Some(tok) => tok,
+            None => return Err(self.unexpected_token("Unexpected EOF".to_string(), None)),
+        };
         let expr = match tok {
-            Token::Word(w) => match w.keyword.as_ref() {
-                "TRUE" | "FALSE" | "NULL" => {
+            Token::Word(w) => match w.keyword {
+                Keyword::TRUE | Keyword::FALSE | Keyword::NULL => {
                     self.prev_token();
-                    Ok(Expr::Value(self.parse_value()?))
+                    Ok(Expr::Value(self.parse_value()?, self.span_from(start)))
                 }
-                "CASE" => self.parse_case_expr(),
-                "CAST" => self.parse_cast_expr(),
-                "DATE" => Ok(Expr::Value(Value::Date(self.parse_literal_string()?))),
-                "EXISTS" => self.parse_exists_expr(),
-                "EXTRACT" => self.parse_extract_expr(),
-                "INTERVAL" => self.parse_literal_interval(),
-                "NOT" => Ok(Expr::UnaryOp {
+                Keyword::CASE => self.parse_case_expr(),
+                Keyword::CAST => self.parse_cast_expr(),
+                Keyword::DATE => Ok(Expr::Value(
+                    Value::Date(self.parse_literal_string()?),
+                    self.span_from(start),
+                )),
+                Keyword::EXISTS => self.parse_exists_expr(),
+                Keyword::EXTRACT => self.parse_extract_expr(),
+                Keyword::INTERVAL => self.parse_literal_interval(),
+                Keyword::NOT => Ok(Expr::UnaryOp {
                     op: UnaryOperator::Not,
-                    expr: Box::new(self.parse_subexpr(Self::UNARY_NOT_PREC)?),
+                    expr: Box::new(self.parse_subexpr(precedence::UNARY_NOT)?),
+                    span: self.span_from(start),
                 }),
-                "TIME" => Ok(Expr::Value(Value::Time(self.parse_literal_string()?))),
-                "TIMESTAMP" => Ok(Expr::Value(Value::Timestamp(self.parse_literal_string()?))),
+                Keyword::TIME => Ok(Expr::Value(
+                    Value::Time(self.parse_literal_string()?),
+                    self.span_from(start),
+                )),
+                Keyword::TIMESTAMP => Ok(Expr::Value(
+                    Value::Timestamp(self.parse_literal_string()?),
+                    self.span_from(start),
+                )),
                 // Here `w` is a word, check if it's a part of a multi-part
                 // identifier, a function call, or a simple identifier:
                 _ => match self.peek_token() {
                     Some(Token::LParen) | Some(Token::Period) => {
-                        let mut id_parts: Vec<Ident> = vec![w.to_ident()];
+                        let mut id_parts: Vec<Ident> = vec![w.to_ident(self.last_token_span())];
                         let mut ends_with_wildcard = false;
                         while self.consume_token(&Token::Period) {
                             match self.next_token() {
-                                Some(Token::Word(w)) => id_parts.push(w.to_ident()),
+                                Some(Token::Word(w)) => {
+                                    id_parts.push(w.to_ident(self.last_token_span()))
+                                }
                                 Some(Token::Mult) => {
                                     ends_with_wildcard = true;
                                     break;
@@ -217,7 +685,7 @@ impl Parser {
                             }
                         }
                         if ends_with_wildcard {
-                            Ok(Expr::QualifiedWildcard(id_parts))
+                            Ok(Expr::QualifiedWildcard(id_parts, self.span_from(start)))
                         } else if self.consume_token(&Token::LParen) {
                             self.prev_token();
                             self.parse_function(ObjectName(id_parts))
@@ -225,10 +693,10 @@ impl Parser {
                             Ok(Expr::CompoundIdentifier(id_parts))
                         }
                     }
-                    _ => Ok(Expr::Identifier(w.to_ident())),
+                    _ => Ok(Expr::Identifier(w.to_ident(self.last_token_span()))),
                 },
             }, // End of Token::Word
-            Token::Mult => Ok(Expr::Wildcard),
+            Token::Mult => Ok(Expr::Wildcard(self.span_from(start))),
             tok @ Token::Minus | tok @ Token::Plus => {
                 let op = if tok == Token::Plus {
                     UnaryOperator::Plus
@@ -237,7 +705,8 @@ impl Parser {
                 };
                 Ok(Expr::UnaryOp {
                     op,
-                    expr: Box::new(self.parse_subexpr(Self::PLUS_MINUS_PREC)?),
+                    expr: Box::new(self.parse_subexpr(precedence::PLUS_MINUS)?),
+                    span: self.span_from(start),
                 })
             }
             Token::Number(_)
@@ -245,22 +714,27 @@ impl Parser {
             | Token::NationalStringLiteral(_)
             | Token::HexStringLiteral(_) => {
                 self.prev_token();
-                Ok(Expr::Value(self.parse_value()?))
+                Ok(Expr::Value(self.parse_value()?, self.span_from(start)))
+            }
+            Token::Placeholder(text) => {
+                Ok(Expr::Placeholder(text.into_owned(), self.span_from(start)))
             }
             Token::LParen => {
-                let expr = if self.parse_keyword("SELECT") || self.parse_keyword("WITH") {
+                if self.parse_keyword(Keyword::SELECT) || self.parse_keyword(Keyword::WITH) {
                     self.prev_token();
-                    Expr::Subquery(Box::new(self.parse_query()?))
+                    let subquery = Box::new(self.parse_query()?);
+                    self.expect_token(&Token::RParen)?;
+                    Ok(Expr::Subquery(subquery, self.span_from(start)))
                 } else {
-                    Expr::Nested(Box::new(self.parse_expr()?))
-                };
-                self.expect_token(&Token::RParen)?;
-                Ok(expr)
+                    let expr = Box::new(self.parse_expr()?);
+                    self.expect_token(&Token::RParen)?;
+                    Ok(Expr::Nested(expr, self.span_from(start)))
+                }
             }
             unexpected => self.expected("an expression", Some(unexpected)),
         }?;
 
-        if self.parse_keyword("COLLATE") {
+        if self.parse_keyword(Keyword::COLLATE) {
             Ok(Expr::Collate {
                 expr: Box::new(expr),
                 collation: self.parse_object_name()?,
@@ -271,26 +745,30 @@ impl Parser {
     }
 
     pub fn parse_function(&mut self, name: ObjectName) -> Result<Expr, ParserError> {
+        let start = name.span();
         self.expect_token(&Token::LParen)?;
-        let all = self.parse_keyword("ALL");
-        let distinct = self.parse_keyword("DISTINCT");
+        let all = self.parse_keyword(Keyword::ALL);
+        let distinct = self.parse_keyword(Keyword::DISTINCT);
         if all && distinct {
-            return parser_err!(format!(
-                "Cannot specify both ALL and DISTINCT in function: {}",
-                name.to_string(),
-            ));
+            return parser_err!(
+                self,
+                format!(
+                    "Cannot specify both ALL and DISTINCT in function: {}",
+                    name.to_string(),
+                )
+            );
         }
         let args = self.parse_optional_args()?;
-        let over = if self.parse_keyword("OVER") {
+        let over = if self.parse_keyword(Keyword::OVER) {
             // TBD: support window names (`OVER mywin`) in place of inline specification
             self.expect_token(&Token::LParen)?;
-            let partition_by = if self.parse_keywords(vec!["PARTITION", "BY"]) {
+            let partition_by = if self.parse_keywords(&[Keyword::PARTITION, Keyword::BY]) {
                 // a list of possibly-qualified column names
                 self.parse_comma_separated(Parser::parse_expr)?
             } else {
                 vec![]
             };
-            let order_by = if self.parse_keywords(vec!["ORDER", "BY"]) {
+            let order_by = if self.parse_keywords(&[Keyword::ORDER, Keyword::BY]) {
                 self.parse_comma_separated(Parser::parse_order_by_expr)?
             } else {
                 vec![]
@@ -317,17 +795,23 @@ impl Parser {
             args,
             over,
             distinct,
+            span: self.span_since(start),
         }))
     }
 
     pub fn parse_window_frame(&mut self) -> Result<WindowFrame, ParserError> {
         let units = match self.next_token() {
-            Some(Token::Word(w)) => w.keyword.parse::<WindowFrameUnits>()?,
+            Some(Token::Word(w)) => match w.keyword {
+                Keyword::ROWS => WindowFrameUnits::Rows,
+                Keyword::RANGE => WindowFrameUnits::Range,
+                Keyword::GROUPS => WindowFrameUnits::Groups,
+                _ => return self.expected("ROWS, RANGE, GROUPS", Some(Token::Word(w))),
+            },
             unexpected => return self.expected("ROWS, RANGE, GROUPS", unexpected),
         };
-        let (start_bound, end_bound) = if self.parse_keyword("BETWEEN") {
+        let (start_bound, end_bound) = if self.parse_keyword(Keyword::BETWEEN) {
             let start_bound = self.parse_window_frame_bound()?;
-            self.expect_keyword("AND")?;
+            self.expect_keyword(Keyword::AND)?;
             let end_bound = Some(self.parse_window_frame_bound()?);
             (start_bound, end_bound)
         } else {
@@ -342,17 +826,17 @@ impl Parser {
 
     /// Parse `CURRENT ROW` or `{ <positive number> | UNBOUNDED } { PRECEDING | FOLLOWING }`
     pub fn parse_window_frame_bound(&mut self) -> Result<WindowFrameBound, ParserError> {
-        if self.parse_keywords(vec!["CURRENT", "ROW"]) {
+        if self.parse_keywords(&[Keyword::CURRENT, Keyword::ROW]) {
             Ok(WindowFrameBound::CurrentRow)
         } else {
-            let rows = if self.parse_keyword("UNBOUNDED") {
+            let rows = if self.parse_keyword(Keyword::UNBOUNDED) {
                 None
             } else {
                 Some(self.parse_literal_uint()?)
             };
-            if self.parse_keyword("PRECEDING") {
+            if self.parse_keyword(Keyword::PRECEDING) {
                 Ok(WindowFrameBound::Preceding(rows))
-            } else if self.parse_keyword("FOLLOWING") {
+            } else if self.parse_keyword(Keyword::FOLLOWING) {
                 Ok(WindowFrameBound::Following(rows))
             } else {
                 self.expected("PRECEDING or FOLLOWING", self.peek_token())
@@ -361,65 +845,72 @@ impl Parser {
     }
 
     pub fn parse_case_expr(&mut self) -> Result<Expr, ParserError> {
+        let start = self.current_token_index();
         let mut operand = None;
-        if !self.parse_keyword("WHEN") {
+        if !self.parse_keyword(Keyword::WHEN) {
             operand = Some(Box::new(self.parse_expr()?));
-            self.expect_keyword("WHEN")?;
+            self.expect_keyword(Keyword::WHEN)?;
         }
         let mut conditions = vec![];
         let mut results = vec![];
         loop {
             conditions.push(self.parse_expr()?);
-            self.expect_keyword("THEN")?;
+            self.expect_keyword(Keyword::THEN)?;
             results.push(self.parse_expr()?);
-            if !self.parse_keyword("WHEN") {
+            if !self.parse_keyword(Keyword::WHEN) {
                 break;
             }
         }
-        let else_result = if self.parse_keyword("ELSE") {
+        let else_result = if self.parse_keyword(Keyword::ELSE) {
             Some(Box::new(self.parse_expr()?))
         } else {
             None
         };
-        self.expect_keyword("END")?;
+        self.expect_keyword(Keyword::END)?;
         Ok(Expr::Case {
             operand,
             conditions,
             results,
             else_result,
+            span: self.span_from(start),
         })
     }
 
     /// Parse a SQL CAST function e.g. `CAST(expr AS FLOAT)`
     pub fn parse_cast_expr(&mut self) -> Result<Expr, ParserError> {
+        let start = self.current_token_index();
         self.expect_token(&Token::LParen)?;
         let expr = self.parse_expr()?;
-        self.expect_keyword("AS")?;
+        self.expect_keyword(Keyword::AS)?;
         let data_type = self.parse_data_type()?;
         self.expect_token(&Token::RParen)?;
         Ok(Expr::Cast {
             expr: Box::new(expr),
             data_type,
+            span: self.span_from(start),
         })
     }
 
     /// Parse a SQL EXISTS expression e.g. `WHERE EXISTS(SELECT ...)`.
     pub fn parse_exists_expr(&mut self) -> Result<Expr, ParserError> {
+        let start = self.current_token_index();
         self.expect_token(&Token::LParen)?;
-        let exists_node = Expr::Exists(Box::new(self.parse_query()?));
+        let query = Box::new(self.parse_query()?);
         self.expect_token(&Token::RParen)?;
-        Ok(exists_node)
+        Ok(Expr::Exists(query, self.span_from(start)))
     }
 
     pub fn parse_extract_expr(&mut self) -> Result<Expr, ParserError> {
+        let start = self.current_token_index();
         self.expect_token(&Token::LParen)?;
         let field = self.parse_date_time_field()?;
-        self.expect_keyword("FROM")?;
+        self.expect_keyword(Keyword::FROM)?;
         let expr = self.parse_expr()?;
         self.expect_token(&Token::RParen)?;
         Ok(Expr::Extract {
             field,
             expr: Box::new(expr),
+            span: self.span_from(start),
         })
     }
 
@@ -430,13 +921,13 @@ impl Parser {
     pub fn parse_date_time_field(&mut self) -> Result<DateTimeField, ParserError> {
         let tok = self.next_token();
         if let Some(Token::Word(ref k)) = tok {
-            match k.keyword.as_ref() {
-                "YEAR" => Ok(DateTimeField::Year),
-                "MONTH" => Ok(DateTimeField::Month),
-                "DAY" => Ok(DateTimeField::Day),
-                "HOUR" => Ok(DateTimeField::Hour),
-                "MINUTE" => Ok(DateTimeField::Minute),
-                "SECOND" => Ok(DateTimeField::Second),
+            match k.keyword {
+                Keyword::YEAR => Ok(DateTimeField::Year),
+                Keyword::MONTH => Ok(DateTimeField::Month),
+                Keyword::DAY => Ok(DateTimeField::Day),
+                Keyword::HOUR => Ok(DateTimeField::Hour),
+                Keyword::MINUTE => Ok(DateTimeField::Minute),
+                Keyword::SECOND => Ok(DateTimeField::Second),
                 _ => self.expected("date/time field", tok)?,
             }
         } else {
@@ -457,6 +948,7 @@ impl Parser {
     ///
     /// Note that we do not currently attempt to parse the quoted value.
     pub fn parse_literal_interval(&mut self) -> Result<Expr, ParserError> {
+        let start = self.current_token_index();
         // The SQL standard allows an optional sign before the value string, but
         // it is not clear if any implementations support that syntax, so we
         // don't currently try to parse it. (The sign can instead be included
@@ -485,7 +977,7 @@ impl Parser {
                 (leading_precision, last_field, fsec_precision)
             } else {
                 let leading_precision = self.parse_optional_precision()?;
-                if self.parse_keyword("TO") {
+                if self.parse_keyword(Keyword::TO) {
                     let last_field = Some(self.parse_date_time_field()?);
                     let fsec_precision = if last_field == Some(DateTimeField::Second) {
                         self.parse_optional_precision()?
@@ -498,18 +990,25 @@ impl Parser {
                 }
             };
 
-        Ok(Expr::Value(Value::Interval {
-            value,
-            leading_field,
-            leading_precision,
-            last_field,
-            fractional_seconds_precision: fsec_precision,
-        }))
+        Ok(Expr::Value(
+            Value::Interval {
+                value,
+                leading_field,
+                leading_precision,
+                last_field,
+                fractional_seconds_precision: fsec_precision,
+            },
+            self.span_from(start),
+        ))
     }
 
     /// Parse an operator following an expression
     pub fn parse_infix(&mut self, expr: Expr, precedence: u8) -> Result<Expr, ParserError> {
+        if let Some(infix) = self.dialect.parse_infix(self, &expr, precedence) {
+            return infix;
+        }
         debug!("parsing infix");
+        let start = expr.span();
         let tok = self.next_token().unwrap(); // safe as EOF's precedence is the lowest
 
         let regular_binary_operator = match tok {
@@ -524,12 +1023,12 @@ impl Parser {
             Token::Mult => Some(BinaryOperator::Multiply),
             Token::Mod => Some(BinaryOperator::Modulus),
             Token::Div => Some(BinaryOperator::Divide),
-            Token::Word(ref k) => match k.keyword.as_ref() {
-                "AND" => Some(BinaryOperator::And),
-                "OR" => Some(BinaryOperator::Or),
-                "LIKE" => Some(BinaryOperator::Like),
-                "NOT" => {
-                    if self.parse_keyword("LIKE") {
+            Token::Word(ref k) => match k.keyword {
+                Keyword::AND => Some(BinaryOperator::And),
+                Keyword::OR => Some(BinaryOperator::Or),
+                Keyword::LIKE => Some(BinaryOperator::Like),
+                Keyword::NOT => {
+                    if self.parse_keyword(Keyword::LIKE) {
                         Some(BinaryOperator::NotLike)
                     } else {
                         None
@@ -547,22 +1046,22 @@ impl Parser {
                 right: Box::new(self.parse_subexpr(precedence)?),
             })
         } else if let Token::Word(ref k) = tok {
-            match k.keyword.as_ref() {
-                "IS" => {
-                    if self.parse_keyword("NULL") {
-                        Ok(Expr::IsNull(Box::new(expr)))
-                    } else if self.parse_keywords(vec!["NOT", "NULL"]) {
-                        Ok(Expr::IsNotNull(Box::new(expr)))
+            match k.keyword {
+                Keyword::IS => {
+                    if self.parse_keyword(Keyword::NULL) {
+                        Ok(Expr::IsNull(Box::new(expr), self.span_since(start)))
+                    } else if self.parse_keywords(&[Keyword::NOT, Keyword::NULL]) {
+                        Ok(Expr::IsNotNull(Box::new(expr), self.span_since(start)))
                     } else {
                         self.expected("NULL or NOT NULL after IS", self.peek_token())
                     }
                 }
-                "NOT" | "IN" | "BETWEEN" => {
+                Keyword::NOT | Keyword::IN | Keyword::BETWEEN => {
                     self.prev_token();
-                    let negated = self.parse_keyword("NOT");
-                    if self.parse_keyword("IN") {
+                    let negated = self.parse_keyword(Keyword::NOT);
+                    if self.parse_keyword(Keyword::IN) {
                         self.parse_in(expr, negated)
-                    } else if self.parse_keyword("BETWEEN") {
+                    } else if self.parse_keyword(Keyword::BETWEEN) {
                         self.parse_between(expr, negated)
                     } else {
                         self.expected("IN or BETWEEN after NOT", self.peek_token())
@@ -581,32 +1080,37 @@ impl Parser {
 
     /// Parses the parens following the `[ NOT ] IN` operator
     pub fn parse_in(&mut self, expr: Expr, negated: bool) -> Result<Expr, ParserError> {
+        let start = expr.span();
         self.expect_token(&Token::LParen)?;
-        let in_op = if self.parse_keyword("SELECT") || self.parse_keyword("WITH") {
+        if self.parse_keyword(Keyword::SELECT) || self.parse_keyword(Keyword::WITH) {
             self.prev_token();
-            Expr::InSubquery {
+            let subquery = Box::new(self.parse_query()?);
+            self.expect_token(&Token::RParen)?;
+            Ok(Expr::InSubquery {
                 expr: Box::new(expr),
-                subquery: Box::new(self.parse_query()?),
+                subquery,
                 negated,
-            }
+                span: self.span_since(start),
+            })
         } else {
-            Expr::InList {
+            let list = self.parse_comma_separated(Parser::parse_expr)?;
+            self.expect_token(&Token::RParen)?;
+            Ok(Expr::InList {
                 expr: Box::new(expr),
-                list: self.parse_comma_separated(Parser::parse_expr)?,
+                list,
                 negated,
-            }
-        };
-        self.expect_token(&Token::RParen)?;
-        Ok(in_op)
+                span: self.span_since(start),
+            })
+        }
     }
 
     /// Parses `BETWEEN <low> AND <high>`, assuming the `BETWEEN` keyword was already consumed
     pub fn parse_between(&mut self, expr: Expr, negated: bool) -> Result<Expr, ParserError> {
         // Stop parsing subexpressions for <low> and <high> on tokens with
         // precedence lower than that of `BETWEEN`, such as `AND`, `IS`, etc.
-        let low = self.parse_subexpr(Self::BETWEEN_PREC)?;
-        self.expect_keyword("AND")?;
-        let high = self.parse_subexpr(Self::BETWEEN_PREC)?;
+        let low = self.parse_subexpr(precedence::COMPARISON)?;
+        self.expect_keyword(Keyword::AND)?;
+        let high = self.parse_subexpr(precedence::COMPARISON)?;
         Ok(Expr::Between {
             expr: Box::new(expr),
             negated,
@@ -617,45 +1121,50 @@ impl Parser {
 
     /// Parse a postgresql casting style which is in the form of `expr::datatype`
     pub fn parse_pg_cast(&mut self, expr: Expr) -> Result<Expr, ParserError> {
+        let start = expr.span();
         Ok(Expr::Cast {
             expr: Box::new(expr),
             data_type: self.parse_data_type()?,
+            span: self.span_since(start),
         })
     }
 
-    const UNARY_NOT_PREC: u8 = 15;
-    const BETWEEN_PREC: u8 = 20;
-    const PLUS_MINUS_PREC: u8 = 30;
-
     /// Get the precedence of the next token
     pub fn get_next_precedence(&self) -> Result<u8, ParserError> {
+        if let Some(precedence) = self.dialect.get_next_precedence(self) {
+            return precedence;
+        }
         if let Some(token) = self.peek_token() {
             debug!("get_next_precedence() {:?}", token);
 
             match &token {
-                Token::Word(k) if k.keyword == "OR" => Ok(5),
-                Token::Word(k) if k.keyword == "AND" => Ok(10),
-                Token::Word(k) if k.keyword == "NOT" => match &self.peek_nth_token(1) {
+                Token::Word(k) if k.keyword == Keyword::OR => Ok(precedence::OR),
+                Token::Word(k) if k.keyword == Keyword::AND => Ok(precedence::AND),
+                Token::Word(k) if k.keyword == Keyword::NOT => match &self.peek_nth_token(1) {
                     // The precedence of NOT varies depending on keyword that
                     // follows it. If it is followed by IN, BETWEEN, or LIKE,
                     // it takes on the precedence of those tokens. Otherwise it
                     // is not an infix operator, and therefore has zero
                     // precedence.
-                    Some(Token::Word(k)) if k.keyword == "IN" => Ok(Self::BETWEEN_PREC),
-                    Some(Token::Word(k)) if k.keyword == "BETWEEN" => Ok(Self::BETWEEN_PREC),
-                    Some(Token::Word(k)) if k.keyword == "LIKE" => Ok(Self::BETWEEN_PREC),
+                    Some(Token::Word(k)) if k.keyword == Keyword::IN => Ok(precedence::COMPARISON),
+                    Some(Token::Word(k)) if k.keyword == Keyword::BETWEEN => {
+                        Ok(precedence::COMPARISON)
+                    }
+                    Some(Token::Word(k)) if k.keyword == Keyword::LIKE => {
+                        Ok(precedence::COMPARISON)
+                    }
                     _ => Ok(0),
                 },
-                Token::Word(k) if k.keyword == "IS" => Ok(17),
-                Token::Word(k) if k.keyword == "IN" => Ok(Self::BETWEEN_PREC),
-                Token::Word(k) if k.keyword == "BETWEEN" => Ok(Self::BETWEEN_PREC),
-                Token::Word(k) if k.keyword == "LIKE" => Ok(Self::BETWEEN_PREC),
+                Token::Word(k) if k.keyword == Keyword::IS => Ok(precedence::IS),
+                Token::Word(k) if k.keyword == Keyword::IN => Ok(precedence::COMPARISON),
+                Token::Word(k) if k.keyword == Keyword::BETWEEN => Ok(precedence::COMPARISON),
+                Token::Word(k) if k.keyword == Keyword::LIKE => Ok(precedence::COMPARISON),
                 Token::Eq | Token::Lt | Token::LtEq | Token::Neq | Token::Gt | Token::GtEq => {
-                    Ok(20)
+                    Ok(precedence::COMPARISON)
                 }
-                Token::Plus | Token::Minus => Ok(Self::PLUS_MINUS_PREC),
-                Token::Mult | Token::Div | Token::Mod => Ok(40),
-                Token::DoubleColon => Ok(50),
+                Token::Plus | Token::Minus => Ok(precedence::PLUS_MINUS),
+                Token::Mult | Token::Div | Token::Mod => Ok(precedence::MULTIPLY_DIVIDE),
+                Token::DoubleColon => Ok(precedence::DOUBLE_COLON),
                 _ => Ok(0),
             }
         } else {
@@ -663,22 +1172,38 @@ impl Parser {
         }
     }
 
+    /// Return `self.tokens[index]`, reading it from the tokenizer if needed,
+    /// or `None` if the input ends first.
+    fn token_at(&self, index: usize) -> Option<TokenWithLocation<'a>> {
+        self.tokens.borrow_mut().get(index).cloned()
+    }
+
+    fn is_whitespace_at(&self, index: usize) -> bool {
+        matches!(
+            self.tokens.borrow_mut().get(index),
+            Some(TokenWithLocation {
+                token: Token::Whitespace(_),
+                ..
+            })
+        )
+    }
+
     /// Return the first non-whitespace token that has not yet been processed
     /// (or None if reached end-of-file)
-    pub fn peek_token(&self) -> Option<Token> {
+    pub fn peek_token(&self) -> Option<Token<'a>> {
         self.peek_nth_token(0)
     }
 
     /// Return nth non-whitespace token that has not yet been processed
-    pub fn peek_nth_token(&self, mut n: usize) -> Option<Token> {
+    pub fn peek_nth_token(&self, mut n: usize) -> Option<Token<'a>> {
         let mut index = self.index;
         loop {
             index += 1;
-            match self.tokens.get(index - 1) {
+            match self.token_at(index - 1).map(|t| t.token) {
                 Some(Token::Whitespace(_)) => continue,
                 non_whitespace => {
                     if n == 0 {
-                        return non_whitespace.cloned();
+                        return non_whitespace;
                     }
                     n -= 1;
                 }
@@ -689,20 +1214,29 @@ impl Parser {
     /// Return the first non-whitespace token that has not yet been processed
     /// (or None if reached end-of-file) and mark it as processed. OK to call
     /// repeatedly after reaching EOF.
-    pub fn next_token(&mut self) -> Option<Token> {
+    pub fn next_token(&mut self) -> Option<Token<'a>> {
         loop {
             self.index += 1;
-            match self.tokens.get(self.index - 1) {
+            match self.token_at(self.index - 1).map(|t| t.token) {
                 Some(Token::Whitespace(_)) => continue,
-                token => return token.cloned(),
+                token => return token,
             }
         }
     }
 
     /// Return the first unprocessed token, possibly whitespace.
-    pub fn next_token_no_skip(&mut self) -> Option<&Token> {
+    pub fn next_token_no_skip(&mut self) -> Option<Token<'a>> {
         self.index += 1;
-        self.tokens.get(self.index - 1)
+        self.token_at(self.index - 1).map(|t| t.token)
+    }
+
+    /// Return the span of the token most recently returned by `next_token()`
+    /// (or by `next_token_no_skip()`), or an empty span at EOF.
+    pub fn last_token_span(&self) -> Span {
+        self.index
+            .checked_sub(1)
+            .and_then(|index| self.token_at(index))
+            .map_or_else(Span::empty, |t| t.span)
     }
 
     /// Push back the last one non-whitespace token. Must be called after
@@ -712,45 +1246,313 @@ impl Parser {
         loop {
             assert!(self.index > 0);
             self.index -= 1;
-            if let Some(Token::Whitespace(_)) = self.tokens.get(self.index) {
+            if self.is_whitespace_at(self.index) {
                 continue;
             }
             return;
         }
     }
 
-    /// Report unexpected token
-    fn expected<T>(&self, expected: &str, found: Option<Token>) -> Result<T, ParserError> {
-        parser_err!(format!(
+    /// Return the index of the first non-whitespace token that has not yet
+    /// been processed, or the number of tokens at EOF.
+    fn next_token_index(&self) -> usize {
+        let mut index = self.index;
+        while self.is_whitespace_at(index) {
+            index += 1;
+        }
+        index.min(self.tokens.borrow().tokens.len())
+    }
+
+    /// Return the index of the last non-whitespace token that was processed,
+    /// if any.
+    fn prev_token_index(&self) -> Option<usize> {
+        let len = self.tokens.borrow().tokens.len();
+        (0..self.index.min(len))
+            .rev()
+            .find(|&i| !self.is_whitespace_at(i))
+    }
+
+    /// Return the index of the token that was just processed, where a node
+    /// whose first token has already been consumed starts.
+    fn current_token_index(&self) -> usize {
+        self.prev_token_index().unwrap_or(0)
+    }
+
+    /// Return the span of a node that starts with the token at `start` and
+    /// ends with the last token processed.
+    fn span_from(&self, start: usize) -> NodeSpan {
+        let first = self.token_at(start).map_or_else(Span::empty, |t| t.span);
+        self.span_since(first)
+    }
+
+    /// Return the span of a node that starts with `start`, such as the span
+    /// of its left operand, and ends with the last token processed.
+    fn span_since(&self, start: Span) -> NodeSpan {
+        let last = self
+            .prev_token_index()
+            .and_then(|index| self.token_at(index))
+            .map_or_else(Span::empty, |t| t.span);
+        NodeSpan(start.union(&last))
+    }
+
+    /// Claim the comments before the next non-whitespace token, to attach
+    /// them to the node that starts there.
+    fn leading_comments(&mut self) -> Vec<Comment> {
+        self.comments_before(self.next_token_index())
+    }
+
+    /// Claim the comments that have not been claimed yet in the whitespace
+    /// directly before `self.tokens[index]`.
+    fn comments_before(&mut self, index: usize) -> Vec<Comment> {
+        let mut start = index;
+        while start > self.comments_claimed && self.is_whitespace_at(start - 1) {
+            start -= 1;
+        }
+        self.skip_comments(start);
+        self.comments_claimed = self.comments_claimed.max(index);
+        (start..index).filter_map(|i| self.comment_at(i)).collect()
+    }
+
+    /// Claim the comments following the last processed token on the same
+    /// line, to attach them to the node that ends there.
+    fn trailing_comments(&mut self) -> Vec<Comment> {
+        match self.prev_token_index() {
+            Some(index) => self.trailing_comments_from(index + 1),
+            None => vec![],
+        }
+    }
+
+    /// Claim the comments on the same line in the whitespace starting at
+    /// `self.tokens[index]`.
+    fn trailing_comments_from(&mut self, index: usize) -> Vec<Comment> {
+        let mut comments = vec![];
+        let mut index = index.max(self.comments_claimed);
+        self.skip_comments(index);
+        loop {
+            match self.token_at(index).map(|t| t.token) {
+                Some(Token::Whitespace(Whitespace::Space))
+                | Some(Token::Whitespace(Whitespace::Tab)) => index += 1,
+                Some(Token::Whitespace(Whitespace::MultiLineComment(_))) => {
+                    comments.extend(self.comment_at(index));
+                    index += 1;
+                    self.comments_claimed = index;
+                }
+                Some(Token::Whitespace(Whitespace::SingleLineComment(_))) => {
+                    // The comment runs to the end of the line
+                    comments.extend(self.comment_at(index));
+                    self.comments_claimed = index + 1;
+                    break;
+                }
+                _ => break,
+            }
+        }
+        comments
+    }
+
+    /// Pass over the comments that have not been claimed before `index`,
+    /// which are inside the node being parsed, keeping them for
+    /// [Parser::with_enclosed_comments].
+    fn skip_comments(&mut self, index: usize) {
+        for i in self.comments_claimed..index {
+            if let Some(comment) = self.comment_at(i) {
+                self.skipped_comments.push((i, comment));
+            }
+        }
+        self.comments_claimed = self.comments_claimed.max(index);
+    }
+
+    /// Return the comments passed over since `self.tokens[start]`, where the
+    /// node or clause being parsed starts, followed by its `trailing` ones.
+    /// This is called after claiming the trailing comments, so that all the
+    /// comments inside the node have been passed over.
+    fn with_enclosed_comments(&mut self, start: usize, trailing: Vec<Comment>) -> Vec<Comment> {
+        let from = self
+            .skipped_comments
+            .iter()
+            .position(|(index, _)| *index >= start)
+            .unwrap_or(self.skipped_comments.len());
+        let mut comments: Vec<Comment> = self
+            .skipped_comments
+            .drain(from..)
+            .map(|(_, comment)| comment)
+            .collect();
+        comments.extend(trailing);
+        comments
+    }
+
+    /// Consume the comma following an item of a list, if there is one, and
+    /// return the comments that trail it. These belong to the item, unless
+    /// the comma starts a new line.
+    fn consume_list_comma(&mut self) -> Option<Vec<Comment>> {
+        let item_end = self.prev_token_index().map_or(0, |index| index + 1);
+        let comma = self.next_token_index();
+        if !self.consume_token(&Token::Comma) {
+            return None;
+        }
+        let same_line = (item_end..comma).all(|index| {
+            !matches!(
+                self.token_at(index).map(|t| t.token),
+                Some(Token::Whitespace(Whitespace::Newline))
+                    | Some(Token::Whitespace(Whitespace::SingleLineComment(_)))
+            )
+        });
+        Some(if same_line {
+            self.trailing_comments()
+        } else {
+            vec![]
+        })
+    }
+
+    fn comment_at(&self, index: usize) -> Option<Comment> {
+        match self.token_at(index)?.token {
+            Token::Whitespace(Whitespace::SingleLineComment(text)) => {
+                let text = text.strip_suffix('\n').unwrap_or(&text);
+                let text = text.strip_suffix('\r').unwrap_or(text);
+                Some(Comment::SingleLine(text.to_string()))
+            }
+            Token::Whitespace(Whitespace::MultiLineComment(text)) => {
+                Some(Comment::MultiLine(text.into_owned()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Remember that `expected` would have been accepted at the next token,
+    /// so that it can be listed if parsing fails there.
+    fn record_expected(&mut self, expected: Expected) {
+        let index = self.next_token_index();
+        if index != self.expected_index {
+            self.expected_index = index;
+            self.expected.clear();
+        }
+        if !self.expected.contains(&expected) {
+            self.expected.push(expected);
+        }
+    }
+
+    /// Run `f` to parse a construct that may contain other constructs, and
+    /// fail instead if this would exceed the maximum nesting depth
+    fn nested<T, F>(&mut self, f: F) -> Result<T, ParserError>
+    where
+        F: FnOnce(&mut Parser<'a>) -> Result<T, ParserError>,
+    {
+        if self.depth >= self.options.max_nesting_depth {
+            return Err(self.error_at(
+                ParserErrorKind::LimitExceeded,
+                format!(
+                    "Exceeded the maximum nesting depth of {}",
+                    self.options.max_nesting_depth
+                ),
+                self.next_token_index(),
+            ));
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Build an error located at `self.tokens[index]` (or at EOF if `index`
+    /// is past the last token). If the input past the last token could not be
+    /// tokenized, report that instead of an unexpected EOF.
+    fn error_at(&self, kind: ParserErrorKind, message: String, index: usize) -> ParserError {
+        let found = self.token_at(index);
+        let span = match &found {
+            Some(token) => token.span,
+            None => {
+                let buffer = self.tokens.borrow();
+                if let Some(e) = &buffer.error {
+                    return e.clone();
+                }
+                let end = buffer
+                    .tokens
+                    .last()
+                    .map_or_else(Location::start, |t| t.span.end);
+                Span::new(end, end)
+            }
+        };
+        ParserError {
+            kind,
+            message,
+            span,
+            found: found.map(|t| Box::new(t.token.into_owned())),
+            expected: if index == self.expected_index {
+                self.expected.clone()
+            } else {
+                vec![]
+            },
+        }
+    }
+
+    /// Build an error located at the last token that was processed
+    fn error_at_previous(&self, kind: ParserErrorKind, message: String) -> ParserError {
+        let index = self
+            .prev_token_index()
+            .unwrap_or_else(|| self.next_token_index());
+        self.error_at(kind, message, index)
+    }
+
+    /// Build an error for an unexpected token. `found` must be either the next
+    /// token or the one that was just processed.
+    fn unexpected_token(&self, message: String, found: Option<Token>) -> ParserError {
+        let next = self.next_token_index();
+        let (kind, index) = match found {
+            None => (ParserErrorKind::UnexpectedEof, next),
+            Some(found) => {
+                let index = if self.token_at(next).map(|t| t.token) == Some(found) {
+                    next
+                } else {
+                    self.prev_token_index().unwrap_or(next)
+                };
+                (ParserErrorKind::UnexpectedToken, index)
+            }
+        };
+        self.error_at(kind, message, index)
+    }
+
+    /// Report unexpected token, listing `expected` along with any keywords or
+    /// tokens that were tried at its location.
+    pub fn expected<T>(&self, expected: &str, found: Option<Token>) -> Result<T, ParserError> {
+        let mut error = self.expected_error(expected, found);
+        let description = Expected::Description(expected.to_string());
+        if !error.expected.contains(&description) {
+            error.expected.push(description);
+        }
+        Err(error)
+    }
+
+    fn expected_error(&self, expected: &str, found: Option<Token>) -> ParserError {
+        let message = format!(
             "Expected {}, found: {}",
             expected,
-            found.map_or_else(|| "EOF".to_string(), |t| format!("{}", t))
-        ))
+            found
+                .as_ref()
+                .map_or_else(|| "EOF".to_string(), |t| format!("{}", t))
+        );
+        self.unexpected_token(message, found)
     }
 
     /// Look for an expected keyword and consume it if it exists
     #[must_use]
-    pub fn parse_keyword(&mut self, expected: &'static str) -> bool {
-        // Ideally, we'd accept a enum variant, not a string, but since
-        // it's not trivial to maintain the enum without duplicating all
-        // the keywords three times, we'll settle for a run-time check that
-        // the string actually represents a known keyword...
-        assert!(keywords::ALL_KEYWORDS.contains(&expected));
+    pub fn parse_keyword(&mut self, expected: Keyword) -> bool {
         match self.peek_token() {
-            Some(Token::Word(ref k)) if expected.eq_ignore_ascii_case(&k.keyword) => {
+            Some(Token::Word(ref k)) if expected == k.keyword => {
                 self.next_token();
                 true
             }
-            _ => false,
+            _ => {
+                self.record_expected(Expected::Keyword(expected));
+                false
+            }
         }
     }
 
     /// Look for an expected sequence of keywords and consume them if they exist
     #[must_use]
-    pub fn parse_keywords(&mut self, keywords: Vec<&'static str>) -> bool {
+    pub fn parse_keywords(&mut self, keywords: &[Keyword]) -> bool {
         let index = self.index;
-        for keyword in keywords {
-            if !self.parse_keyword(&keyword) {
+        for &keyword in keywords {
+            if !self.parse_keyword(keyword) {
                 //println!("parse_keywords aborting .. did not find {}", keyword);
                 // reset index and return immediately
                 self.index = index;
@@ -762,55 +1564,51 @@ impl Parser {
 
     /// Look for one of the given keywords and return the one that matches.
     #[must_use]
-    pub fn parse_one_of_keywords(&mut self, keywords: &[&'static str]) -> Option<&'static str> {
-        for keyword in keywords {
-            assert!(
-                keywords::ALL_KEYWORDS.contains(keyword),
-                "{} is not contained in keyword list",
-                keyword
-            );
-        }
-        match self.peek_token() {
-            Some(Token::Word(ref k)) => keywords
-                .iter()
-                .find(|keyword| keyword.eq_ignore_ascii_case(&k.keyword))
-                .map(|keyword| {
-                    self.next_token();
-                    *keyword
-                }),
+    pub fn parse_one_of_keywords(&mut self, keywords: &[Keyword]) -> Option<Keyword> {
+        let found = match self.peek_token() {
+            Some(Token::Word(ref k)) => keywords.iter().find(|&&keyword| keyword == k.keyword),
             _ => None,
+        };
+        match found {
+            Some(_) => {
+                self.next_token();
+            }
+            None => {
+                for &keyword in keywords {
+                    self.record_expected(Expected::Keyword(keyword));
+                }
+            }
         }
+        found.copied()
     }
 
     /// Bail out if the current token is not one of the expected keywords, or consume it if it is
     #[must_use]
-    pub fn expect_one_of_keywords(
-        &mut self,
-        keywords: &[&'static str],
-    ) -> Result<&'static str, ParserError> {
+    pub fn expect_one_of_keywords(&mut self, keywords: &[Keyword]) -> Result<Keyword, ParserError> {
         if let Some(keyword) = self.parse_one_of_keywords(keywords) {
             Ok(keyword)
         } else {
-            self.expected(
+            let keywords: Vec<&str> = keywords.iter().map(|keyword| keyword.as_str()).collect();
+            Err(self.expected_error(
                 &format!("one of {}", keywords.join(" or ")),
                 self.peek_token(),
-            )
+            ))
         }
     }
 
     /// Bail out if the current token is not an expected keyword, or consume it if it is
-    pub fn expect_keyword(&mut self, expected: &'static str) -> Result<(), ParserError> {
+    pub fn expect_keyword(&mut self, expected: Keyword) -> Result<(), ParserError> {
         if self.parse_keyword(expected) {
             Ok(())
         } else {
-            self.expected(expected, self.peek_token())
+            Err(self.expected_error(expected.as_str(), self.peek_token()))
         }
     }
 
     /// Bail out if the following tokens are not the expected sequence of
     /// keywords, or consume them if they are.
-    pub fn expect_keywords(&mut self, expected: &[&'static str]) -> Result<(), ParserError> {
-        for kw in expected {
+    pub fn expect_keywords(&mut self, expected: &[Keyword]) -> Result<(), ParserError> {
+        for &kw in expected {
             self.expect_keyword(kw)?;
         }
         Ok(())
@@ -824,7 +1622,10 @@ impl Parser {
                 self.next_token();
                 true
             }
-            _ => false,
+            _ => {
+                self.record_expected(Expected::Token(expected.clone().into_owned()));
+                false
+            }
         }
     }
 
@@ -833,14 +1634,14 @@ impl Parser {
         if self.consume_token(expected) {
             Ok(())
         } else {
-            self.expected(&expected.to_string(), self.peek_token())
+            Err(self.expected_error(&expected.to_string(), self.peek_token()))
         }
     }
 
     /// Parse a comma-separated list of 1+ items accepted by `F`
     pub fn parse_comma_separated<T, F>(&mut self, mut f: F) -> Result<Vec<T>, ParserError>
     where
-        F: FnMut(&mut Parser) -> Result<T, ParserError>,
+        F: FnMut(&mut Parser<'a>) -> Result<T, ParserError>,
     {
         let mut values = vec![];
         loop {
@@ -854,26 +1655,53 @@ impl Parser {
 
     /// Parse a SQL CREATE statement
     pub fn parse_create(&mut self) -> Result<Statement, ParserError> {
-        if self.parse_keyword("TABLE") {
+        if self.parse_keyword(Keyword::TABLE) {
             self.parse_create_table()
-        } else if self.parse_keyword("MATERIALIZED") || self.parse_keyword("VIEW") {
+        } else if self.parse_keyword(Keyword::MATERIALIZED) || self.parse_keyword(Keyword::VIEW) {
             self.prev_token();
             self.parse_create_view()
-        } else if self.parse_keyword("EXTERNAL") {
+        } else if self.parse_keyword(Keyword::EXTERNAL) {
             self.parse_create_external_table()
+        } else if self.parse_keyword(Keyword::UNIQUE) || self.parse_keyword(Keyword::INDEX) {
+            self.prev_token();
+            self.parse_create_index()
         } else {
-            self.expected("TABLE or VIEW after CREATE", self.peek_token())
+            self.expected("TABLE, VIEW or INDEX after CREATE", self.peek_token())
         }
     }
 
+    pub fn parse_create_index(&mut self) -> Result<Statement, ParserError> {
+        let unique = self.parse_keyword(Keyword::UNIQUE);
+        self.expect_keyword(Keyword::INDEX)?;
+        let if_not_exists = self.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parse_object_name()?;
+        self.expect_keyword(Keyword::ON)?;
+        let table_name = self.parse_object_name()?;
+        self.expect_token(&Token::LParen)?;
+        let columns = self.parse_comma_separated(Parser::parse_order_by_expr)?;
+        self.expect_token(&Token::RParen)?;
+        Ok(Statement::CreateIndex {
+            name,
+            table_name,
+            columns,
+            unique,
+            if_not_exists,
+            span: NodeSpan::empty(),
+        })
+    }
+
     pub fn parse_create_external_table(&mut self) -> Result<Statement, ParserError> {
-        self.expect_keyword("TABLE")?;
+        self.expect_keyword(Keyword::TABLE)?;
         let table_name = self.parse_object_name()?;
         let (columns, constraints) = self.parse_columns()?;
-        self.expect_keywords(&["STORED", "AS"])?;
-        let file_format = self.parse_identifier()?.value.parse::<FileFormat>()?;
-
-        self.expect_keyword("LOCATION")?;
+        self.expect_keywords(&[Keyword::STORED, Keyword::AS])?;
+        let file_format = self
+            .parse_identifier()?
+            .value
+            .parse::<FileFormat>()
+            .map_err(|e| self.error_at_previous(e.kind, e.message))?;
+
+        self.expect_keyword(Keyword::LOCATION)?;
         let location = self.parse_literal_string()?;
 
         Ok(Statement::CreateTable {
@@ -884,18 +1712,19 @@ impl Parser {
             external: true,
             file_format: Some(file_format),
             location: Some(location),
+            span: NodeSpan::empty(),
         })
     }
 
     pub fn parse_create_view(&mut self) -> Result<Statement, ParserError> {
-        let materialized = self.parse_keyword("MATERIALIZED");
-        self.expect_keyword("VIEW")?;
+        let materialized = self.parse_keyword(Keyword::MATERIALIZED);
+        self.expect_keyword(Keyword::VIEW)?;
         // Many dialects support `OR REPLACE` | `OR ALTER` right after `CREATE`, but we don't (yet).
         // ANSI SQL and Postgres support RECURSIVE here, but we don't support it either.
         let name = self.parse_object_name()?;
         let columns = self.parse_parenthesized_column_list(Optional)?;
         let with_options = self.parse_with_options()?;
-        self.expect_keyword("AS")?;
+        self.expect_keyword(Keyword::AS)?;
         let query = Box::new(self.parse_query()?);
         // Optional `WITH [ CASCADED | LOCAL ] CHECK OPTION` is widely supported here.
         Ok(Statement::CreateView {
@@ -904,31 +1733,35 @@ impl Parser {
             query,
             materialized,
             with_options,
+            span: NodeSpan::empty(),
         })
     }
 
     pub fn parse_drop(&mut self) -> Result<Statement, ParserError> {
-        let object_type = if self.parse_keyword("TABLE") {
+        let object_type = if self.parse_keyword(Keyword::TABLE) {
             ObjectType::Table
-        } else if self.parse_keyword("VIEW") {
+        } else if self.parse_keyword(Keyword::VIEW) {
             ObjectType::View
+        } else if self.parse_keyword(Keyword::INDEX) {
+            ObjectType::Index
         } else {
-            return self.expected("TABLE or VIEW after DROP", self.peek_token());
+            return self.expected("TABLE, VIEW or INDEX after DROP", self.peek_token());
         };
         // Many dialects support the non standard `IF EXISTS` clause and allow
         // specifying multiple objects to delete in a single statement
-        let if_exists = self.parse_keywords(vec!["IF", "EXISTS"]);
+        let if_exists = self.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
         let names = self.parse_comma_separated(Parser::parse_object_name)?;
-        let cascade = self.parse_keyword("CASCADE");
-        let restrict = self.parse_keyword("RESTRICT");
+        let cascade = self.parse_keyword(Keyword::CASCADE);
+        let restrict = self.parse_keyword(Keyword::RESTRICT);
         if cascade && restrict {
-            return parser_err!("Cannot specify both CASCADE and RESTRICT in DROP");
+            return parser_err!(self, "Cannot specify both CASCADE and RESTRICT in DROP");
         }
         Ok(Statement::Drop {
             object_type,
             if_exists,
             names,
             cascade,
+            span: NodeSpan::empty(),
         })
     }
 
@@ -946,10 +1779,13 @@ impl Parser {
             external: false,
             file_format: None,
             location: None,
+            span: NodeSpan::empty(),
         })
     }
 
-    fn parse_columns(&mut self) -> Result<(Vec<ColumnDef>, Vec<TableConstraint>), ParserError> {
+    fn parse_columns(
+        &mut self,
+    ) -> Result<(Vec<Commented<ColumnDef>>, Vec<TableConstraint>), ParserError> {
         let mut columns = vec![];
         let mut constraints = vec![];
         if !self.consume_token(&Token::LParen) || self.consume_token(&Token::RParen) {
@@ -957,34 +1793,33 @@ impl Parser {
         }
 
         loop {
-            if let Some(constraint) = self.parse_optional_table_constraint()? {
+            let is_column = if let Some(constraint) = self.parse_optional_table_constraint()? {
                 constraints.push(constraint);
-            } else if let Some(Token::Word(column_name)) = self.peek_token() {
-                self.next_token();
-                let data_type = self.parse_data_type()?;
-                let collation = if self.parse_keyword("COLLATE") {
-                    Some(self.parse_object_name()?)
-                } else {
-                    None
-                };
-                let mut options = vec![];
-                loop {
-                    match self.peek_token() {
-                        None | Some(Token::Comma) | Some(Token::RParen) => break,
-                        _ => options.push(self.parse_column_option_def()?),
-                    }
-                }
-
-                columns.push(ColumnDef {
-                    name: column_name.to_ident(),
-                    data_type,
-                    collation,
-                    options,
+                false
+            } else if let Some(Token::Word(_)) = self.peek_token() {
+                let leading = self.leading_comments();
+                let start = self.next_token_index();
+                let node = self.parse_column_def()?;
+                let trailing = self.trailing_comments();
+                let trailing = self.with_enclosed_comments(start, trailing);
+                columns.push(Commented {
+                    comments: Comments { leading, trailing },
+                    node,
                 });
+                true
             } else {
                 return self.expected("column name or constraint definition", self.peek_token());
-            }
-            let comma = self.consume_token(&Token::Comma);
+            };
+            let comma = match self.consume_list_comma() {
+                Some(trailing) => {
+                    if is_column {
+                        let column = columns.last_mut().unwrap();
+                        column.comments.trailing.extend(trailing);
+                    }
+                    true
+                }
+                None => false,
+            };
             if self.consume_token(&Token::RParen) {
                 // allow a trailing comma, even though it's not in standard
                 break;
@@ -996,31 +1831,55 @@ impl Parser {
         Ok((columns, constraints))
     }
 
+    pub fn parse_column_def(&mut self) -> Result<ColumnDef, ParserError> {
+        let name = self.parse_identifier()?;
+        let data_type = self.parse_data_type()?;
+        let collation = if self.parse_keyword(Keyword::COLLATE) {
+            Some(self.parse_object_name()?)
+        } else {
+            None
+        };
+        let mut options = vec![];
+        loop {
+            match self.peek_token() {
+                None | Some(Token::Comma) | Some(Token::RParen) | Some(Token::SemiColon) => break,
+                _ => options.push(self.parse_column_option_def()?),
+            }
+        }
+
+        Ok(ColumnDef {
+            name,
+            data_type,
+            collation,
+            options,
+        })
+    }
+
     pub fn parse_column_option_def(&mut self) -> Result<ColumnOptionDef, ParserError> {
-        let name = if self.parse_keyword("CONSTRAINT") {
+        let name = if self.parse_keyword(Keyword::CONSTRAINT) {
             Some(self.parse_identifier()?)
         } else {
             None
         };
 
-        let option = if self.parse_keywords(vec!["NOT", "NULL"]) {
+        let option = if self.parse_keywords(&[Keyword::NOT, Keyword::NULL]) {
             ColumnOption::NotNull
-        } else if self.parse_keyword("NULL") {
+        } else if self.parse_keyword(Keyword::NULL) {
             ColumnOption::Null
-        } else if self.parse_keyword("DEFAULT") {
+        } else if self.parse_keyword(Keyword::DEFAULT) {
             ColumnOption::Default(self.parse_expr()?)
-        } else if self.parse_keywords(vec!["PRIMARY", "KEY"]) {
+        } else if self.parse_keywords(&[Keyword::PRIMARY, Keyword::KEY]) {
             ColumnOption::Unique { is_primary: true }
-        } else if self.parse_keyword("UNIQUE") {
+        } else if self.parse_keyword(Keyword::UNIQUE) {
             ColumnOption::Unique { is_primary: false }
-        } else if self.parse_keyword("REFERENCES") {
+        } else if self.parse_keyword(Keyword::REFERENCES) {
             let foreign_table = self.parse_object_name()?;
             let referred_columns = self.parse_parenthesized_column_list(Mandatory)?;
             ColumnOption::ForeignKey {
                 foreign_table,
                 referred_columns,
             }
-        } else if self.parse_keyword("CHECK") {
+        } else if self.parse_keyword(Keyword::CHECK) {
             self.expect_token(&Token::LParen)?;
             let expr = self.parse_expr()?;
             self.expect_token(&Token::RParen)?;
@@ -1035,16 +1894,18 @@ impl Parser {
     pub fn parse_optional_table_constraint(
         &mut self,
     ) -> Result<Option<TableConstraint>, ParserError> {
-        let name = if self.parse_keyword("CONSTRAINT") {
+        let name = if self.parse_keyword(Keyword::CONSTRAINT) {
             Some(self.parse_identifier()?)
         } else {
             None
         };
         match self.next_token() {
-            Some(Token::Word(ref k)) if k.keyword == "PRIMARY" || k.keyword == "UNIQUE" => {
-                let is_primary = k.keyword == "PRIMARY";
+            Some(Token::Word(ref k))
+                if k.keyword == Keyword::PRIMARY || k.keyword == Keyword::UNIQUE =>
+            {
+                let is_primary = k.keyword == Keyword::PRIMARY;
                 if is_primary {
-                    self.expect_keyword("KEY")?;
+                    self.expect_keyword(Keyword::KEY)?;
                 }
                 let columns = self.parse_parenthesized_column_list(Mandatory)?;
                 Ok(Some(TableConstraint::Unique {
@@ -1053,10 +1914,10 @@ impl Parser {
                     is_primary,
                 }))
             }
-            Some(Token::Word(ref k)) if k.keyword == "FOREIGN" => {
-                self.expect_keyword("KEY")?;
+            Some(Token::Word(ref k)) if k.keyword == Keyword::FOREIGN => {
+                self.expect_keyword(Keyword::KEY)?;
                 let columns = self.parse_parenthesized_column_list(Mandatory)?;
-                self.expect_keyword("REFERENCES")?;
+                self.expect_keyword(Keyword::REFERENCES)?;
                 let foreign_table = self.parse_object_name()?;
                 let referred_columns = self.parse_parenthesized_column_list(Mandatory)?;
                 Ok(Some(TableConstraint::ForeignKey {
@@ -1066,7 +1927,7 @@ impl Parser {
                     referred_columns,
                 }))
             }
-            Some(Token::Word(ref k)) if k.keyword == "CHECK" => {
+            Some(Token::Word(ref k)) if k.keyword == Keyword::CHECK => {
                 self.expect_token(&Token::LParen)?;
                 let expr = Box::new(self.parse_expr()?);
                 self.expect_token(&Token::RParen)?;
@@ -1084,7 +1945,7 @@ impl Parser {
     }
 
     pub fn parse_with_options(&mut self) -> Result<Vec<SqlOption>, ParserError> {
-        if self.parse_keyword("WITH") {
+        if self.parse_keyword(Keyword::WITH) {
             self.expect_token(&Token::LParen)?;
             let options = self.parse_comma_separated(Parser::parse_sql_option)?;
             self.expect_token(&Token::RParen)?;
@@ -1102,21 +1963,89 @@ impl Parser {
     }
 
     pub fn parse_alter(&mut self) -> Result<Statement, ParserError> {
-        self.expect_keyword("TABLE")?;
-        let _ = self.parse_keyword("ONLY");
+        self.expect_keyword(Keyword::TABLE)?;
+        let _ = self.parse_keyword(Keyword::ONLY);
         let table_name = self.parse_object_name()?;
-        let operation = if self.parse_keyword("ADD") {
+        let operation = if self.parse_keyword(Keyword::ADD) {
             if let Some(constraint) = self.parse_optional_table_constraint()? {
                 AlterTableOperation::AddConstraint(constraint)
             } else {
-                return self.expected("a constraint in ALTER TABLE .. ADD", self.peek_token());
+                let _ = self.parse_keyword(Keyword::COLUMN);
+                if let Some(Token::Word(_)) = self.peek_token() {
+                    let column_def = self.parse_column_def()?;
+                    AlterTableOperation::AddColumn { column_def }
+                } else {
+                    return self.expected(
+                        "a constraint or column definition in ALTER TABLE .. ADD",
+                        self.peek_token(),
+                    );
+                }
+            }
+        } else if self.parse_keyword(Keyword::DROP) {
+            if self.parse_keyword(Keyword::CONSTRAINT) {
+                let name = self.parse_identifier()?;
+                AlterTableOperation::DropConstraint { name }
+            } else {
+                let _ = self.parse_keyword(Keyword::COLUMN);
+                let if_exists = self.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+                let column_name = self.parse_identifier()?;
+                let cascade = self.parse_keyword(Keyword::CASCADE);
+                AlterTableOperation::DropColumn {
+                    column_name,
+                    if_exists,
+                    cascade,
+                }
             }
+        } else if self.parse_keyword(Keyword::RENAME) {
+            if self.parse_keyword(Keyword::TO) {
+                let table_name = self.parse_object_name()?;
+                AlterTableOperation::RenameTable { table_name }
+            } else {
+                let _ = self.parse_keyword(Keyword::COLUMN);
+                let old_column_name = self.parse_identifier()?;
+                self.expect_keyword(Keyword::TO)?;
+                let new_column_name = self.parse_identifier()?;
+                AlterTableOperation::RenameColumn {
+                    old_column_name,
+                    new_column_name,
+                }
+            }
+        } else if self.parse_keyword(Keyword::ALTER) {
+            let _ = self.parse_keyword(Keyword::COLUMN);
+            let column_name = self.parse_identifier()?;
+            let op = if self.parse_keywords(&[Keyword::SET, Keyword::NOT, Keyword::NULL]) {
+                AlterColumnOperation::SetNotNull
+            } else if self.parse_keywords(&[Keyword::DROP, Keyword::NOT, Keyword::NULL]) {
+                AlterColumnOperation::DropNotNull
+            } else if self.parse_keywords(&[Keyword::SET, Keyword::DEFAULT]) {
+                AlterColumnOperation::SetDefault {
+                    value: self.parse_expr()?,
+                }
+            } else if self.parse_keywords(&[Keyword::DROP, Keyword::DEFAULT]) {
+                AlterColumnOperation::DropDefault
+            } else if self.parse_keywords(&[Keyword::SET, Keyword::DATA, Keyword::TYPE])
+                || self.parse_keyword(Keyword::TYPE)
+            {
+                AlterColumnOperation::SetDataType {
+                    data_type: self.parse_data_type()?,
+                }
+            } else {
+                return self.expected(
+                    "SET/DROP NOT NULL, SET/DROP DEFAULT or SET DATA TYPE after ALTER COLUMN",
+                    self.peek_token(),
+                );
+            };
+            AlterTableOperation::AlterColumn { column_name, op }
         } else {
-            return self.expected("ADD after ALTER TABLE", self.peek_token());
+            return self.expected(
+                "ADD, DROP, RENAME or ALTER after ALTER TABLE",
+                self.peek_token(),
+            );
         };
         Ok(Statement::AlterTable {
             name: table_name,
             operation,
+            span: NodeSpan::empty(),
         })
     }
 
@@ -1124,13 +2053,14 @@ impl Parser {
     pub fn parse_copy(&mut self) -> Result<Statement, ParserError> {
         let table_name = self.parse_object_name()?;
         let columns = self.parse_parenthesized_column_list(Optional)?;
-        self.expect_keywords(&["FROM", "STDIN"])?;
+        self.expect_keywords(&[Keyword::FROM, Keyword::STDIN])?;
         self.expect_token(&Token::SemiColon)?;
         let values = self.parse_tsv()?;
         Ok(Statement::Copy {
             table_name,
             columns,
             values,
+            span: NodeSpan::empty(),
         })
     }
 
@@ -1180,29 +2110,34 @@ impl Parser {
     fn parse_value(&mut self) -> Result<Value, ParserError> {
         match self.next_token() {
             Some(t) => match t {
-                Token::Word(k) => match k.keyword.as_ref() {
-                    "TRUE" => Ok(Value::Boolean(true)),
-                    "FALSE" => Ok(Value::Boolean(false)),
-                    "NULL" => Ok(Value::Null),
-                    _ => {
-                        return parser_err!(format!("No value parser for keyword {}", k.keyword));
-                    }
+                Token::Word(k) => match k.keyword {
+                    Keyword::TRUE => Ok(Value::Boolean(true)),
+                    Keyword::FALSE => Ok(Value::Boolean(false)),
+                    Keyword::NULL => Ok(Value::Null),
+                    _ => Err(self.unexpected_token(
+                        format!("No value parser for keyword {}", k.keyword),
+                        Some(Token::Word(k)),
+                    )),
                 },
                 // The call to n.parse() returns a bigdecimal when the
                 // bigdecimal feature is enabled, and is otherwise a no-op
                 // (i.e., it returns the input string).
                 Token::Number(ref n) => match n.parse() {
                     Ok(n) => Ok(Value::Number(n)),
-                    Err(e) => parser_err!(format!("Could not parse '{}' as number: {}", n, e)),
+                    Err(e) => {
+                        parser_err!(self, format!("Could not parse '{}' as number: {}", n, e))
+                    }
                 },
                 Token::SingleQuotedString(ref s) => Ok(Value::SingleQuotedString(s.to_string())),
                 Token::NationalStringLiteral(ref s) => {
                     Ok(Value::NationalStringLiteral(s.to_string()))
                 }
                 Token::HexStringLiteral(ref s) => Ok(Value::HexStringLiteral(s.to_string())),
-                _ => parser_err!(format!("Unsupported value: {:?}", t)),
+                _ => Err(self.unexpected_token(format!("Unsupported value: {:?}", t), Some(t))),
             },
-            None => parser_err!("Expecting a value, but found EOF"),
+            None => {
+                Err(self.unexpected_token("Expecting a value, but found EOF".to_string(), None))
+            }
         }
     }
 
@@ -1220,7 +2155,10 @@ impl Parser {
     pub fn parse_literal_uint(&mut self) -> Result<u64, ParserError> {
         match self.next_token() {
             Some(Token::Number(s)) => s.parse::<u64>().map_err(|e| {
-                ParserError::ParserError(format!("Could not parse '{}' as u64: {}", s, e))
+                self.error_at_previous(
+                    ParserErrorKind::InvalidSyntax,
+                    format!("Could not parse '{}' as u64: {}", s, e),
+                )
             }),
             other => self.expected("literal int", other),
         }
@@ -1229,7 +2167,7 @@ impl Parser {
     /// Parse a literal string
     pub fn parse_literal_string(&mut self) -> Result<String, ParserError> {
         match self.next_token() {
-            Some(Token::SingleQuotedString(ref s)) => Ok(s.clone()),
+            Some(Token::SingleQuotedString(s)) => Ok(s.into_owned()),
             other => self.expected("literal string", other),
         }
     }
@@ -1237,47 +2175,47 @@ impl Parser {
     /// Parse a SQL datatype (in the context of a CREATE TABLE statement for example)
     pub fn parse_data_type(&mut self) -> Result<DataType, ParserError> {
         match self.next_token() {
-            Some(Token::Word(k)) => match k.keyword.as_ref() {
-                "BOOLEAN" => Ok(DataType::Boolean),
-                "FLOAT" => Ok(DataType::Float(self.parse_optional_precision()?)),
-                "REAL" => Ok(DataType::Real),
-                "DOUBLE" => {
-                    let _ = self.parse_keyword("PRECISION");
+            Some(Token::Word(k)) => match k.keyword {
+                Keyword::BOOLEAN => Ok(DataType::Boolean),
+                Keyword::FLOAT => Ok(DataType::Float(self.parse_optional_precision()?)),
+                Keyword::REAL => Ok(DataType::Real),
+                Keyword::DOUBLE => {
+                    let _ = self.parse_keyword(Keyword::PRECISION);
                     Ok(DataType::Double)
                 }
-                "SMALLINT" => Ok(DataType::SmallInt),
-                "INT" | "INTEGER" => Ok(DataType::Int),
-                "BIGINT" => Ok(DataType::BigInt),
-                "VARCHAR" => Ok(DataType::Varchar(self.parse_optional_precision()?)),
-                "CHAR" | "CHARACTER" => {
-                    if self.parse_keyword("VARYING") {
+                Keyword::SMALLINT => Ok(DataType::SmallInt),
+                Keyword::INT | Keyword::INTEGER => Ok(DataType::Int),
+                Keyword::BIGINT => Ok(DataType::BigInt),
+                Keyword::VARCHAR => Ok(DataType::Varchar(self.parse_optional_precision()?)),
+                Keyword::CHAR | Keyword::CHARACTER => {
+                    if self.parse_keyword(Keyword::VARYING) {
                         Ok(DataType::Varchar(self.parse_optional_precision()?))
                     } else {
                         Ok(DataType::Char(self.parse_optional_precision()?))
                     }
                 }
-                "UUID" => Ok(DataType::Uuid),
-                "DATE" => Ok(DataType::Date),
-                "TIMESTAMP" => {
+                Keyword::UUID => Ok(DataType::Uuid),
+                Keyword::DATE => Ok(DataType::Date),
+                Keyword::TIMESTAMP => {
                     // TBD: we throw away "with/without timezone" information
-                    if self.parse_keyword("WITH") || self.parse_keyword("WITHOUT") {
-                        self.expect_keywords(&["TIME", "ZONE"])?;
+                    if self.parse_keyword(Keyword::WITH) || self.parse_keyword(Keyword::WITHOUT) {
+                        self.expect_keywords(&[Keyword::TIME, Keyword::ZONE])?;
                     }
                     Ok(DataType::Timestamp)
                 }
-                "TIME" => {
+                Keyword::TIME => {
                     // TBD: we throw away "with/without timezone" information
-                    if self.parse_keyword("WITH") || self.parse_keyword("WITHOUT") {
-                        self.expect_keywords(&["TIME", "ZONE"])?;
+                    if self.parse_keyword(Keyword::WITH) || self.parse_keyword(Keyword::WITHOUT) {
+                        self.expect_keywords(&[Keyword::TIME, Keyword::ZONE])?;
                     }
                     Ok(DataType::Time)
                 }
                 // Interval types can be followed by a complicated interval
                 // qualifier that we don't currently support. See
                 // parse_interval_literal for a taste.
-                "INTERVAL" => Ok(DataType::Interval),
-                "REGCLASS" => Ok(DataType::Regclass),
-                "TEXT" => {
+                Keyword::INTERVAL => Ok(DataType::Interval),
+                Keyword::REGCLASS => Ok(DataType::Regclass),
+                Keyword::TEXT => {
                     if self.consume_token(&Token::LBracket) {
                         // Note: this is postgresql-specific
                         self.expect_token(&Token::RBracket)?;
@@ -1286,8 +2224,8 @@ impl Parser {
                         Ok(DataType::Text)
                     }
                 }
-                "BYTEA" => Ok(DataType::Bytea),
-                "NUMERIC" | "DECIMAL" | "DEC" => {
+                Keyword::BYTEA => Ok(DataType::Bytea),
+                Keyword::NUMERIC | Keyword::DECIMAL | Keyword::DEC => {
                     let (precision, scale) = self.parse_optional_precision_scale()?;
                     Ok(DataType::Decimal(precision, scale))
                 }
@@ -1306,23 +2244,23 @@ impl Parser {
     /// `SELECT ... FROM t1 foo, t2 bar`, `SELECT ... FROM (...) AS bar`
     pub fn parse_optional_alias(
         &mut self,
-        reserved_kwds: &[&str],
+        reserved_kwds: &[Keyword],
     ) -> Result<Option<Ident>, ParserError> {
-        let after_as = self.parse_keyword("AS");
+        let after_as = self.parse_keyword(Keyword::AS);
         match self.next_token() {
             // Accept any identifier after `AS` (though many dialects have restrictions on
             // keywords that may appear here). If there's no `AS`: don't parse keywords,
             // which may start a construct allowed in this position, to be parsed as aliases.
             // (For example, in `FROM t1 JOIN` the `JOIN` will always be parsed as a keyword,
             // not an alias.)
-            Some(Token::Word(ref w))
-                if after_as || !reserved_kwds.contains(&w.keyword.as_str()) =>
-            {
-                Ok(Some(w.to_ident()))
+            Some(Token::Word(ref w)) if after_as || !reserved_kwds.contains(&w.keyword) => {
+                Ok(Some(w.to_ident(self.last_token_span())))
             }
             // MSSQL supports single-quoted strings as aliases for columns
             // We accept them as table aliases too, although MSSQL does not.
-            Some(Token::SingleQuotedString(ref s)) => Ok(Some(Ident::with_quote('\'', s.clone()))),
+            Some(Token::SingleQuotedString(ref s)) => Ok(Some(
+                Ident::with_quote('\'', s.clone()).with_span(self.last_token_span()),
+            )),
             not_an_ident => {
                 if after_as {
                     return self.expected("an identifier after AS", not_an_ident);
@@ -1339,7 +2277,7 @@ impl Parser {
     /// addition to the table itself.
     pub fn parse_optional_table_alias(
         &mut self,
-        reserved_kwds: &[&str],
+        reserved_kwds: &[Keyword],
     ) -> Result<Option<TableAlias>, ParserError> {
         match self.parse_optional_alias(reserved_kwds)? {
             Some(name) => {
@@ -1366,7 +2304,7 @@ impl Parser {
     /// Parse a simple one-word identifier (possibly quoted, possibly a keyword)
     pub fn parse_identifier(&mut self) -> Result<Ident, ParserError> {
         match self.next_token() {
-            Some(Token::Word(w)) => Ok(w.to_ident()),
+            Some(Token::Word(w)) => Ok(w.to_ident(self.last_token_span())),
             unexpected => self.expected("identifier", unexpected),
         }
     }
@@ -1415,9 +2353,9 @@ impl Parser {
     }
 
     pub fn parse_delete(&mut self) -> Result<Statement, ParserError> {
-        self.expect_keyword("FROM")?;
+        self.expect_keyword(Keyword::FROM)?;
         let table_name = self.parse_object_name()?;
-        let selection = if self.parse_keyword("WHERE") {
+        let selection = if self.parse_keyword(Keyword::WHERE) {
             Some(self.parse_expr()?)
         } else {
             None
@@ -1426,6 +2364,7 @@ impl Parser {
         Ok(Statement::Delete {
             table_name,
             selection,
+            span: NodeSpan::empty(),
         })
     }
 
@@ -1434,7 +2373,12 @@ impl Parser {
     /// by `ORDER BY`. Unlike some other parse_... methods, this one doesn't
     /// expect the initial keyword to be already consumed
     pub fn parse_query(&mut self) -> Result<Query, ParserError> {
-        let ctes = if self.parse_keyword("WITH") {
+        self.nested(Parser::parse_query_inner)
+    }
+
+    fn parse_query_inner(&mut self) -> Result<Query, ParserError> {
+        let start = self.next_token_index();
+        let ctes = if self.parse_keyword(Keyword::WITH) {
             // TODO: optional RECURSIVE
             self.parse_comma_separated(Parser::parse_cte)?
         } else {
@@ -1443,25 +2387,25 @@ impl Parser {
 
         let body = self.parse_query_body(0)?;
 
-        let order_by = if self.parse_keywords(vec!["ORDER", "BY"]) {
+        let order_by = if self.parse_keywords(&[Keyword::ORDER, Keyword::BY]) {
             self.parse_comma_separated(Parser::parse_order_by_expr)?
         } else {
             vec![]
         };
 
-        let limit = if self.parse_keyword("LIMIT") {
+        let limit = if self.parse_keyword(Keyword::LIMIT) {
             self.parse_limit()?
         } else {
             None
         };
 
-        let offset = if self.parse_keyword("OFFSET") {
+        let offset = if self.parse_keyword(Keyword::OFFSET) {
             Some(self.parse_offset()?)
         } else {
             None
         };
 
-        let fetch = if self.parse_keyword("FETCH") {
+        let fetch = if self.parse_keyword(Keyword::FETCH) {
             Some(self.parse_fetch()?)
         } else {
             None
@@ -1474,6 +2418,7 @@ impl Parser {
             order_by,
             offset,
             fetch,
+            span: self.span_from(start),
         })
     }
 
@@ -1483,7 +2428,7 @@ impl Parser {
             name: self.parse_identifier()?,
             columns: self.parse_parenthesized_column_list(Optional)?,
         };
-        self.expect_keyword("AS")?;
+        self.expect_keyword(Keyword::AS)?;
         self.expect_token(&Token::LParen)?;
         let query = self.parse_query()?;
         self.expect_token(&Token::RParen)?;
@@ -1501,14 +2446,14 @@ impl Parser {
     fn parse_query_body(&mut self, precedence: u8) -> Result<SetExpr, ParserError> {
         // We parse the expression using a Pratt parser, as in `parse_expr()`.
         // Start by parsing a restricted SELECT or a `(subquery)`:
-        let mut expr = if self.parse_keyword("SELECT") {
+        let mut expr = if self.parse_keyword(Keyword::SELECT) {
             SetExpr::Select(Box::new(self.parse_select()?))
         } else if self.consume_token(&Token::LParen) {
             // CTEs are not allowed here, but the parser currently accepts them
             let subquery = self.parse_query()?;
             self.expect_token(&Token::RParen)?;
             SetExpr::Query(Box::new(subquery))
-        } else if self.parse_keyword("VALUES") {
+        } else if self.parse_keyword(Keyword::VALUES) {
             SetExpr::Values(self.parse_values()?)
         } else {
             return self.expected(
@@ -1536,7 +2481,7 @@ impl Parser {
             expr = SetExpr::SetOperation {
                 left: Box::new(expr),
                 op: op.unwrap(),
-                all: self.parse_keyword("ALL"),
+                all: self.parse_keyword(Keyword::ALL),
                 right: Box::new(self.parse_query_body(next_precedence)?),
             };
         }
@@ -1546,9 +2491,9 @@ impl Parser {
 
     fn parse_set_operator(&mut self, token: &Option<Token>) -> Option<SetOperator> {
         match token {
-            Some(Token::Word(w)) if w.keyword == "UNION" => Some(SetOperator::Union),
-            Some(Token::Word(w)) if w.keyword == "EXCEPT" => Some(SetOperator::Except),
-            Some(Token::Word(w)) if w.keyword == "INTERSECT" => Some(SetOperator::Intersect),
+            Some(Token::Word(w)) if w.keyword == Keyword::UNION => Some(SetOperator::Union),
+            Some(Token::Word(w)) if w.keyword == Keyword::EXCEPT => Some(SetOperator::Except),
+            Some(Token::Word(w)) if w.keyword == Keyword::INTERSECT => Some(SetOperator::Intersect),
             _ => None,
         }
     }
@@ -1556,41 +2501,39 @@ impl Parser {
     /// Parse a restricted `SELECT` statement (no CTEs / `UNION` / `ORDER BY`),
     /// assuming the initial `SELECT` was already consumed
     pub fn parse_select(&mut self) -> Result<Select, ParserError> {
-        let all = self.parse_keyword("ALL");
-        let distinct = self.parse_keyword("DISTINCT");
+        let start = self.current_token_index();
+        let all = self.parse_keyword(Keyword::ALL);
+        let distinct = self.parse_keyword(Keyword::DISTINCT);
         if all && distinct {
-            return parser_err!("Cannot specify both ALL and DISTINCT in SELECT");
+            return parser_err!(self, "Cannot specify both ALL and DISTINCT in SELECT");
         }
-        let projection = self.parse_comma_separated(Parser::parse_select_item)?;
+        let projection = self.parse_projection()?;
 
         // Note that for keywords to be properly handled here, they need to be
         // added to `RESERVED_FOR_COLUMN_ALIAS` / `RESERVED_FOR_TABLE_ALIAS`,
         // otherwise they may be parsed as an alias as part of the `projection`
         // or `from`.
 
-        let from = if self.parse_keyword("FROM") {
-            self.parse_comma_separated(Parser::parse_table_and_joins)?
-        } else {
-            vec![]
-        };
-
-        let selection = if self.parse_keyword("WHERE") {
-            Some(self.parse_expr()?)
-        } else {
-            None
-        };
-
-        let group_by = if self.parse_keywords(vec!["GROUP", "BY"]) {
-            self.parse_comma_separated(Parser::parse_expr)?
-        } else {
-            vec![]
-        };
-
-        let having = if self.parse_keyword("HAVING") {
-            Some(self.parse_expr()?)
-        } else {
-            None
-        };
+        let mut comments = ClauseComments::default();
+        let from = self
+            .parse_clause(&[Keyword::FROM], &mut comments.from, |parser| {
+                parser.parse_comma_separated(Parser::parse_table_and_joins)
+            })?
+            .unwrap_or_default();
+        let selection = self.parse_clause(
+            &[Keyword::WHERE],
+            &mut comments.selection,
+            Parser::parse_expr,
+        )?;
+        let group_by = self
+            .parse_clause(
+                &[Keyword::GROUP, Keyword::BY],
+                &mut comments.group_by,
+                |parser| parser.parse_comma_separated(Parser::parse_expr),
+            )?
+            .unwrap_or_default();
+        let having =
+            self.parse_clause(&[Keyword::HAVING], &mut comments.having, Parser::parse_expr)?;
 
         Ok(Select {
             distinct,
@@ -1599,27 +2542,88 @@ impl Parser {
             selection,
             group_by,
             having,
+            comments,
+            span: self.span_from(start),
         })
     }
 
+    /// Parse the comma-separated items of a projection, with the comments
+    /// around each one. A comment following a comma belongs to the item
+    /// before it.
+    fn parse_projection(&mut self) -> Result<Vec<SelectItem>, ParserError> {
+        let mut projection = vec![];
+        loop {
+            let leading = self.leading_comments();
+            let start = self.next_token_index();
+            let item = self.parse_select_item()?;
+            let mut trailing = self.trailing_comments();
+            let comma = self.consume_list_comma();
+            let more = comma.is_some();
+            trailing.extend(comma.unwrap_or_default());
+            let trailing = self.with_enclosed_comments(start, trailing);
+            projection.push(item.with_comments(Comments { leading, trailing }));
+            if !more {
+                break;
+            }
+        }
+        Ok(projection)
+    }
+
+    /// If the next tokens are `keywords`, parse the rest of the clause they
+    /// introduce with `f`, and store the comments around the clause in
+    /// `comments`.
+    fn parse_clause<T, F>(
+        &mut self,
+        keywords: &[Keyword],
+        comments: &mut Comments,
+        f: F,
+    ) -> Result<Option<T>, ParserError>
+    where
+        F: FnOnce(&mut Parser<'a>) -> Result<T, ParserError>,
+    {
+        let start = self.next_token_index();
+        if !self.parse_keywords(keywords) {
+            return Ok(None);
+        }
+        comments.leading = self.comments_before(start);
+        let clause = f(self)?;
+        let trailing = self.trailing_comments();
+        comments.trailing = self.with_enclosed_comments(start, trailing);
+        Ok(Some(clause))
+    }
+
     pub fn parse_set(&mut self) -> Result<Statement, ParserError> {
-        let modifier = self.parse_one_of_keywords(&["SESSION", "LOCAL"]);
-        let variable = self.parse_identifier()?;
-        if self.consume_token(&Token::Eq) || self.parse_keyword("TO") {
+        let modifier = self.parse_one_of_keywords(&[Keyword::SESSION, Keyword::LOCAL]);
+        let variable = match self.peek_token() {
+            // MS SQL variables are spelled like placeholders
+            Some(Token::Placeholder(text)) if text.starts_with('@') => {
+                self.next_token();
+                Ident {
+                    span: self.last_token_span(),
+                    ..Ident::new(text)
+                }
+            }
+            _ => self.parse_identifier()?,
+        };
+        if self.consume_token(&Token::Eq) || self.parse_keyword(Keyword::TO) {
             let token = self.peek_token();
             let value = match (self.parse_value(), token) {
                 (Ok(value), _) => SetVariableValue::Literal(value),
-                (Err(_), Some(Token::Word(ident))) => SetVariableValue::Ident(ident.to_ident()),
+                (Err(_), Some(Token::Word(ident))) => {
+                    SetVariableValue::Ident(ident.to_ident(self.last_token_span()))
+                }
                 (Err(_), other) => self.expected("variable value", other)?,
             };
             Ok(Statement::SetVariable {
-                local: modifier == Some("LOCAL"),
+                local: modifier == Some(Keyword::LOCAL),
                 variable,
                 value,
+                span: NodeSpan::empty(),
             })
         } else if variable.value == "TRANSACTION" && modifier.is_none() {
             Ok(Statement::SetTransaction {
                 modes: self.parse_transaction_modes()?,
+                span: NodeSpan::empty(),
             })
         } else {
             self.expected("equals sign or TO", self.peek_token())
@@ -1628,7 +2632,12 @@ impl Parser {
 
     pub fn parse_show(&mut self) -> Result<Statement, ParserError> {
         if self
-            .parse_one_of_keywords(&["EXTENDED", "FULL", "COLUMNS", "FIELDS"])
+            .parse_one_of_keywords(&[
+                Keyword::EXTENDED,
+                Keyword::FULL,
+                Keyword::COLUMNS,
+                Keyword::FIELDS,
+            ])
             .is_some()
         {
             self.prev_token();
@@ -1636,15 +2645,16 @@ impl Parser {
         } else {
             Ok(Statement::ShowVariable {
                 variable: self.parse_identifier()?,
+                span: NodeSpan::empty(),
             })
         }
     }
 
     fn parse_show_columns(&mut self) -> Result<Statement, ParserError> {
-        let extended = self.parse_keyword("EXTENDED");
-        let full = self.parse_keyword("FULL");
-        self.expect_one_of_keywords(&["COLUMNS", "FIELDS"])?;
-        self.expect_one_of_keywords(&["FROM", "IN"])?;
+        let extended = self.parse_keyword(Keyword::EXTENDED);
+        let full = self.parse_keyword(Keyword::FULL);
+        self.expect_one_of_keywords(&[Keyword::COLUMNS, Keyword::FIELDS])?;
+        self.expect_one_of_keywords(&[Keyword::FROM, Keyword::IN])?;
         let table_name = self.parse_object_name()?;
         // MySQL also supports FROM <database> here. In other words, MySQL
         // allows both FROM <table> FROM <database> and FROM <database>.<table>,
@@ -1655,15 +2665,16 @@ impl Parser {
             full,
             table_name,
             filter,
+            span: NodeSpan::empty(),
         })
     }
 
     fn parse_show_statement_filter(&mut self) -> Result<Option<ShowStatementFilter>, ParserError> {
-        if self.parse_keyword("LIKE") {
+        if self.parse_keyword(Keyword::LIKE) {
             Ok(Some(ShowStatementFilter::Like(
                 self.parse_literal_string()?,
             )))
-        } else if self.parse_keyword("WHERE") {
+        } else if self.parse_keyword(Keyword::WHERE) {
             Ok(Some(ShowStatementFilter::Where(self.parse_expr()?)))
         } else {
             Ok(None)
@@ -1678,10 +2689,10 @@ impl Parser {
         // a table alias.
         let mut joins = vec![];
         loop {
-            let join = if self.parse_keyword("CROSS") {
-                let join_operator = if self.parse_keyword("JOIN") {
+            let join = if self.parse_keyword(Keyword::CROSS) {
+                let join_operator = if self.parse_keyword(Keyword::JOIN) {
                     JoinOperator::CrossJoin
-                } else if self.parse_keyword("APPLY") {
+                } else if self.parse_keyword(Keyword::APPLY) {
                     // MSSQL extension, similar to CROSS JOIN LATERAL
                     JoinOperator::CrossApply
                 } else {
@@ -1691,39 +2702,41 @@ impl Parser {
                     relation: self.parse_table_factor()?,
                     join_operator,
                 }
-            } else if self.parse_keyword("OUTER") {
+            } else if self.parse_keyword(Keyword::OUTER) {
                 // MSSQL extension, similar to LEFT JOIN LATERAL .. ON 1=1
-                self.expect_keyword("APPLY")?;
+                self.expect_keyword(Keyword::APPLY)?;
                 Join {
                     relation: self.parse_table_factor()?,
                     join_operator: JoinOperator::OuterApply,
                 }
             } else {
-                let natural = self.parse_keyword("NATURAL");
+                let natural = self.parse_keyword(Keyword::NATURAL);
                 let peek_keyword = if let Some(Token::Word(kw)) = self.peek_token() {
                     kw.keyword
                 } else {
-                    String::default()
+                    Keyword::NoKeyword
                 };
 
-                let join_operator_type = match peek_keyword.as_ref() {
-                    "INNER" | "JOIN" => {
-                        let _ = self.parse_keyword("INNER");
-                        self.expect_keyword("JOIN")?;
+                let join_operator_type = match peek_keyword {
+                    Keyword::INNER | Keyword::JOIN => {
+                        let _ = self.parse_keyword(Keyword::INNER);
+                        self.expect_keyword(Keyword::JOIN)?;
                         JoinOperator::Inner
                     }
-                    kw @ "LEFT" | kw @ "RIGHT" | kw @ "FULL" => {
+                    kw @ Keyword::LEFT | kw @ Keyword::RIGHT | kw @ Keyword::FULL => {
                         let _ = self.next_token();
-                        let _ = self.parse_keyword("OUTER");
-                        self.expect_keyword("JOIN")?;
+                        let _ = self.parse_keyword(Keyword::OUTER);
+                        self.expect_keyword(Keyword::JOIN)?;
                         match kw {
-                            "LEFT" => JoinOperator::LeftOuter,
-                            "RIGHT" => JoinOperator::RightOuter,
-                            "FULL" => JoinOperator::FullOuter,
+                            Keyword::LEFT => JoinOperator::LeftOuter,
+                            Keyword::RIGHT => JoinOperator::RightOuter,
+                            Keyword::FULL => JoinOperator::FullOuter,
                             _ => unreachable!(),
                         }
                     }
-                    "OUTER" => return self.expected("LEFT, RIGHT, or FULL", self.peek_token()),
+                    Keyword::OUTER => {
+                        return self.expected("LEFT, RIGHT, or FULL", self.peek_token())
+                    }
                     _ if natural => {
                         return self.expected("a join type after NATURAL", self.peek_token());
                     }
@@ -1743,16 +2756,22 @@ impl Parser {
 
     /// A table name or a parenthesized subquery, followed by optional `[AS] alias`
     pub fn parse_table_factor(&mut self) -> Result<TableFactor, ParserError> {
-        if self.parse_keyword("LATERAL") {
+        self.nested(Parser::parse_table_factor_inner)
+    }
+
+    fn parse_table_factor_inner(&mut self) -> Result<TableFactor, ParserError> {
+        let start = self.next_token_index();
+        if self.parse_keyword(Keyword::LATERAL) {
             // LATERAL must always be followed by a subquery.
             if !self.consume_token(&Token::LParen) {
                 self.expected("subquery after LATERAL", self.peek_token())?;
             }
-            return self.parse_derived_table_factor(Lateral);
+            return self.parse_derived_table_factor_from(start, Lateral);
         }
 
         if self.consume_token(&Token::LParen) {
             let index = self.index;
+            let comments_claimed = self.comments_claimed;
             // A left paren introduces either a derived table (i.e., a subquery)
             // or a nested join. It's nearly impossible to determine ahead of
             // time which it is... so we just try to parse both.
@@ -1769,12 +2788,15 @@ impl Parser {
             //                   | (2) starts a nested join
             //                   (1) an additional set of parens around a nested join
             //
-            match self.parse_derived_table_factor(NotLateral) {
+            match self.parse_derived_table_factor_from(start, NotLateral) {
                 // The recently consumed '(' started a derived table, and we've
                 // parsed the subquery, followed by the closing ')', and the
                 // alias of the derived table. In the example above this is
                 // case (3), and the next token would be `NATURAL`.
                 Ok(table_factor) => Ok(table_factor),
+                // Backing up would only hit the same limit again, after
+                // reparsing the same tokens
+                Err(err) if err.kind == ParserErrorKind::LimitExceeded => Err(err),
                 Err(_) => {
                     // The '(' we've recently consumed does not start a derived
                     // table. For valid input this can happen either when the
@@ -1787,9 +2809,12 @@ impl Parser {
                     // Either we'll be able to parse a valid nested join, or
                     // we won't, and we'll return that error instead.
                     self.index = index;
+                    self.comments_claimed = comments_claimed;
+                    self.skipped_comments
+                        .retain(|(index, _)| *index < comments_claimed);
                     let table_and_joins = self.parse_table_and_joins()?;
                     match table_and_joins.relation {
-                        TableFactor::NestedJoin { .. } => (),
+                        TableFactor::NestedJoin(..) => (),
                         _ => {
                             if table_and_joins.joins.is_empty() {
                                 // The SQL spec prohibits derived tables and bare
@@ -1799,7 +2824,10 @@ impl Parser {
                         }
                     }
                     self.expect_token(&Token::RParen)?;
-                    Ok(TableFactor::NestedJoin(Box::new(table_and_joins)))
+                    Ok(TableFactor::NestedJoin(
+                        Box::new(table_and_joins),
+                        self.span_from(start),
+                    ))
                 }
             }
         } else {
@@ -1813,7 +2841,7 @@ impl Parser {
             let alias = self.parse_optional_table_alias(keywords::RESERVED_FOR_TABLE_ALIAS)?;
             // MSSQL-specific table hints:
             let mut with_hints = vec![];
-            if self.parse_keyword("WITH") {
+            if self.parse_keyword(Keyword::WITH) {
                 if self.consume_token(&Token::LParen) {
                     with_hints = self.parse_comma_separated(Parser::parse_expr)?;
                     self.expect_token(&Token::RParen)?;
@@ -1827,13 +2855,26 @@ impl Parser {
                 alias,
                 args,
                 with_hints,
+                span: self.span_from(start),
             })
         }
     }
 
+    /// Parse a subquery and its alias, assuming the `(` before it was
+    /// already consumed
     pub fn parse_derived_table_factor(
         &mut self,
         lateral: IsLateral,
+    ) -> Result<TableFactor, ParserError> {
+        let start = self.current_token_index();
+        self.parse_derived_table_factor_from(start, lateral)
+    }
+
+    /// Parse a derived table that starts with the token at `start`
+    fn parse_derived_table_factor_from(
+        &mut self,
+        start: usize,
+        lateral: IsLateral,
     ) -> Result<TableFactor, ParserError> {
         let subquery = Box::new(self.parse_query()?);
         self.expect_token(&Token::RParen)?;
@@ -1845,16 +2886,17 @@ impl Parser {
             },
             subquery,
             alias,
+            span: self.span_from(start),
         })
     }
 
     fn parse_join_constraint(&mut self, natural: bool) -> Result<JoinConstraint, ParserError> {
         if natural {
             Ok(JoinConstraint::Natural)
-        } else if self.parse_keyword("ON") {
+        } else if self.parse_keyword(Keyword::ON) {
             let constraint = self.parse_expr()?;
             Ok(JoinConstraint::On(constraint))
-        } else if self.parse_keyword("USING") {
+        } else if self.parse_keyword(Keyword::USING) {
             let columns = self.parse_parenthesized_column_list(Mandatory)?;
             Ok(JoinConstraint::Using(columns))
         } else {
@@ -1864,7 +2906,7 @@ impl Parser {
 
     /// Parse an INSERT statement
     pub fn parse_insert(&mut self) -> Result<Statement, ParserError> {
-        self.expect_keyword("INTO")?;
+        self.expect_keyword(Keyword::INTO)?;
         let table_name = self.parse_object_name()?;
         let columns = self.parse_parenthesized_column_list(Optional)?;
         let source = Box::new(self.parse_query()?);
@@ -1872,14 +2914,15 @@ impl Parser {
             table_name,
             columns,
             source,
+            span: NodeSpan::empty(),
         })
     }
 
     pub fn parse_update(&mut self) -> Result<Statement, ParserError> {
         let table_name = self.parse_object_name()?;
-        self.expect_keyword("SET")?;
+        self.expect_keyword(Keyword::SET)?;
         let assignments = self.parse_comma_separated(Parser::parse_assignment)?;
-        let selection = if self.parse_keyword("WHERE") {
+        let selection = if self.parse_keyword(Keyword::WHERE) {
             Some(self.parse_expr()?)
         } else {
             None
@@ -1888,6 +2931,7 @@ impl Parser {
             table_name,
             assignments,
             selection,
+            span: NodeSpan::empty(),
         })
     }
 
@@ -1912,10 +2956,10 @@ impl Parser {
     /// Parse a comma-delimited list of projections after SELECT
     pub fn parse_select_item(&mut self) -> Result<SelectItem, ParserError> {
         let expr = self.parse_expr()?;
-        if let Expr::Wildcard = expr {
-            Ok(SelectItem::Wildcard)
-        } else if let Expr::QualifiedWildcard(prefix) = expr {
-            Ok(SelectItem::QualifiedWildcard(ObjectName(prefix)))
+        if let Expr::Wildcard(span) = expr {
+            Ok(SelectItem::Wildcard(span))
+        } else if let Expr::QualifiedWildcard(prefix, span) = expr {
+            Ok(SelectItem::QualifiedWildcard(ObjectName(prefix), span))
         } else {
             // `expr` is a regular SQL expression and can be followed by an alias
             if let Some(alias) = self.parse_optional_alias(keywords::RESERVED_FOR_COLUMN_ALIAS)? {
@@ -1930,9 +2974,9 @@ impl Parser {
     pub fn parse_order_by_expr(&mut self) -> Result<OrderByExpr, ParserError> {
         let expr = self.parse_expr()?;
 
-        let asc = if self.parse_keyword("ASC") {
+        let asc = if self.parse_keyword(Keyword::ASC) {
             Some(true)
-        } else if self.parse_keyword("DESC") {
+        } else if self.parse_keyword(Keyword::DESC) {
             Some(false)
         } else {
             None
@@ -1942,34 +2986,57 @@ impl Parser {
 
     /// Parse a LIMIT clause
     pub fn parse_limit(&mut self) -> Result<Option<Expr>, ParserError> {
-        if self.parse_keyword("ALL") {
+        if self.parse_keyword(Keyword::ALL) {
             Ok(None)
         } else {
-            Ok(Some(Expr::Value(self.parse_number_value()?)))
+            Ok(Some(self.parse_row_count()?))
+        }
+    }
+
+    /// Parse a number of rows: a number, or a placeholder for one
+    fn parse_row_count(&mut self) -> Result<Expr, ParserError> {
+        let start = self.next_token_index();
+        match self.peek_token() {
+            Some(Token::Placeholder(text)) => {
+                self.next_token();
+                Ok(Expr::Placeholder(text.into_owned(), self.span_from(start)))
+            }
+            _ => Ok(Expr::Value(
+                self.parse_number_value()?,
+                self.span_from(start),
+            )),
         }
     }
 
-    /// Parse an OFFSET clause
+    /// Parse an OFFSET clause. `ROW` or `ROWS` is required, except in
+    /// dialects that write `LIMIT n OFFSET m`.
     pub fn parse_offset(&mut self) -> Result<Expr, ParserError> {
-        let value = Expr::Value(self.parse_number_value()?);
-        self.expect_one_of_keywords(&["ROW", "ROWS"])?;
+        let value = self.parse_row_count()?;
+        if self.dialect.row_limit_syntax() == RowLimitSyntax::Limit {
+            let _ = self.parse_one_of_keywords(&[Keyword::ROW, Keyword::ROWS]);
+        } else {
+            self.expect_one_of_keywords(&[Keyword::ROW, Keyword::ROWS])?;
+        }
         Ok(value)
     }
 
     /// Parse a FETCH clause
     pub fn parse_fetch(&mut self) -> Result<Fetch, ParserError> {
-        self.expect_one_of_keywords(&["FIRST", "NEXT"])?;
-        let (quantity, percent) = if self.parse_one_of_keywords(&["ROW", "ROWS"]).is_some() {
+        self.expect_one_of_keywords(&[Keyword::FIRST, Keyword::NEXT])?;
+        let (quantity, percent) = if self
+            .parse_one_of_keywords(&[Keyword::ROW, Keyword::ROWS])
+            .is_some()
+        {
             (None, false)
         } else {
-            let quantity = Expr::Value(self.parse_value()?);
-            let percent = self.parse_keyword("PERCENT");
-            self.expect_one_of_keywords(&["ROW", "ROWS"])?;
+            let quantity = self.parse_row_count()?;
+            let percent = self.parse_keyword(Keyword::PERCENT);
+            self.expect_one_of_keywords(&[Keyword::ROW, Keyword::ROWS])?;
             (Some(quantity), percent)
         };
-        let with_ties = if self.parse_keyword("ONLY") {
+        let with_ties = if self.parse_keyword(Keyword::ONLY) {
             false
-        } else if self.parse_keywords(vec!["WITH", "TIES"]) {
+        } else if self.parse_keywords(&[Keyword::WITH, Keyword::TIES]) {
             true
         } else {
             return self.expected("one of ONLY or WITH TIES", self.peek_token());
@@ -1992,16 +3059,18 @@ impl Parser {
     }
 
     pub fn parse_start_transaction(&mut self) -> Result<Statement, ParserError> {
-        self.expect_keyword("TRANSACTION")?;
+        self.expect_keyword(Keyword::TRANSACTION)?;
         Ok(Statement::StartTransaction {
             modes: self.parse_transaction_modes()?,
+            span: NodeSpan::empty(),
         })
     }
 
     pub fn parse_begin(&mut self) -> Result<Statement, ParserError> {
-        let _ = self.parse_one_of_keywords(&["TRANSACTION", "WORK"]);
+        let _ = self.parse_one_of_keywords(&[Keyword::TRANSACTION, Keyword::WORK]);
         Ok(Statement::StartTransaction {
             modes: self.parse_transaction_modes()?,
+            span: NodeSpan::empty(),
         })
     }
 
@@ -2009,22 +3078,22 @@ impl Parser {
         let mut modes = vec![];
         let mut required = false;
         loop {
-            let mode = if self.parse_keywords(vec!["ISOLATION", "LEVEL"]) {
-                let iso_level = if self.parse_keywords(vec!["READ", "UNCOMMITTED"]) {
+            let mode = if self.parse_keywords(&[Keyword::ISOLATION, Keyword::LEVEL]) {
+                let iso_level = if self.parse_keywords(&[Keyword::READ, Keyword::UNCOMMITTED]) {
                     TransactionIsolationLevel::ReadUncommitted
-                } else if self.parse_keywords(vec!["READ", "COMMITTED"]) {
+                } else if self.parse_keywords(&[Keyword::READ, Keyword::COMMITTED]) {
                     TransactionIsolationLevel::ReadCommitted
-                } else if self.parse_keywords(vec!["REPEATABLE", "READ"]) {
+                } else if self.parse_keywords(&[Keyword::REPEATABLE, Keyword::READ]) {
                     TransactionIsolationLevel::RepeatableRead
-                } else if self.parse_keyword("SERIALIZABLE") {
+                } else if self.parse_keyword(Keyword::SERIALIZABLE) {
                     TransactionIsolationLevel::Serializable
                 } else {
                     self.expected("isolation level", self.peek_token())?
                 };
                 TransactionMode::IsolationLevel(iso_level)
-            } else if self.parse_keywords(vec!["READ", "ONLY"]) {
+            } else if self.parse_keywords(&[Keyword::READ, Keyword::ONLY]) {
                 TransactionMode::AccessMode(TransactionAccessMode::ReadOnly)
-            } else if self.parse_keywords(vec!["READ", "WRITE"]) {
+            } else if self.parse_keywords(&[Keyword::READ, Keyword::WRITE]) {
                 TransactionMode::AccessMode(TransactionAccessMode::ReadWrite)
             } else if required {
                 self.expected("transaction mode", self.peek_token())?
@@ -2044,20 +3113,22 @@ impl Parser {
     pub fn parse_commit(&mut self) -> Result<Statement, ParserError> {
         Ok(Statement::Commit {
             chain: self.parse_commit_rollback_chain()?,
+            span: NodeSpan::empty(),
         })
     }
 
     pub fn parse_rollback(&mut self) -> Result<Statement, ParserError> {
         Ok(Statement::Rollback {
             chain: self.parse_commit_rollback_chain()?,
+            span: NodeSpan::empty(),
         })
     }
 
     pub fn parse_commit_rollback_chain(&mut self) -> Result<bool, ParserError> {
-        let _ = self.parse_one_of_keywords(&["TRANSACTION", "WORK"]);
-        if self.parse_keyword("AND") {
-            let chain = !self.parse_keyword("NO");
-            self.expect_keyword("CHAIN")?;
+        let _ = self.parse_one_of_keywords(&[Keyword::TRANSACTION, Keyword::WORK]);
+        if self.parse_keyword(Keyword::AND) {
+            let chain = !self.parse_keyword(Keyword::NO);
+            self.expect_keyword(Keyword::CHAIN)?;
             Ok(chain)
         } else {
             Ok(false)
@@ -2065,11 +3136,12 @@ impl Parser {
     }
 }
 
-impl Word {
-    pub fn to_ident(&self) -> Ident {
+impl Word<'_> {
+    pub fn to_ident(&self, span: Span) -> Ident {
         Ident {
-            value: self.value.clone(),
+            value: self.value.to_string(),
             quote_style: self.quote_style,
+            span,
         }
     }
 }
@@ -2077,6 +3149,7 @@ impl Word {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dialect::GenericDialect;
     use crate::test_utils::all_dialects;
 
     #[test]
@@ -2099,4 +3172,26 @@ mod tests {
             parser.prev_token();
         });
     }
+
+    #[test]
+    fn test_parse_lazily() {
+        let sql = "SELECT 1; SELECT 2 !";
+        let dialect = GenericDialect {};
+        let mut parser = Parser::from_tokenizer(Tokenizer::new(&dialect, sql));
+        // The first statement is parsed without reading the invalid input
+        parser.parse_statement().unwrap();
+        assert!(parser.consume_token(&Token::SemiColon));
+        // ...and the second one is complete before the tokenizer error is
+        // reached, which is reported at the end of the input
+        parser.parse_statement().unwrap();
+        let err = parser.parse_statements().unwrap_err();
+        assert_eq!(err.kind, ParserErrorKind::TokenizerError);
+        assert_eq!(err.span.start.offset, 20);
+
+        // An error that needs to look past the end of the valid input reports
+        // the tokenizer error instead of an unexpected EOF
+        let err = Parser::parse_sql(&dialect, "SELECT a FROM !".to_string()).unwrap_err();
+        assert_eq!(err.kind, ParserErrorKind::TokenizerError);
+        assert_eq!(err.message, "Expected '=' after '!'");
+    }
 }
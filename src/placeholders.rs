@@ -0,0 +1,508 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bind parameter placeholders: listing them, and substituting values for
+//! them.
+//!
+//! The dialect decides which placeholders the parser accepts (see
+//! [crate::dialect::Dialect::supports_placeholder]). Values are substituted
+//! into the AST, not the SQL text, and a value is rejected if it would not
+//! print as a literal that the dialect reads back as the same value.
+//!
+//! ```
+//! use sqlparser::ast::Value;
+//! use sqlparser::dialect::PostgreSqlDialect;
+//! use sqlparser::parser::Parser;
+//! use sqlparser::placeholders::{bind, placeholders, Parameters};
+//!
+//! let sql = "SELECT * FROM t WHERE (name = $1 OR nickname = $1) AND active = $2";
+//! let statement = &Parser::parse_sql(&PostgreSqlDialect {}, sql.to_string()).unwrap()[0];
+//! assert_eq!(placeholders(statement), vec!["$1", "$1", "$2"]);
+//!
+//! let values = [Value::SingleQuotedString("O'Brien".into()), Value::Boolean(true)];
+//! let bound = bind(&PostgreSqlDialect {}, statement, &Parameters::Positional(&values)).unwrap();
+//! assert_eq!(
+//!     bound.to_string(),
+//!     "SELECT * FROM t WHERE (name = 'O''Brien' OR nickname = 'O''Brien') AND active = true"
+//! );
+//! ```
+
+use crate::ast::*;
+use crate::dialect::Dialect;
+#[cfg(feature = "bigdecimal")]
+use bigdecimal::BigDecimal;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::ops::ControlFlow;
+
+/// Return every placeholder in `statement`, spelled as in the source, in
+/// the order they appear. A placeholder used more than once is listed each
+/// time.
+pub fn placeholders(statement: &Statement) -> Vec<String> {
+    let mut collector = Collector::default();
+    let _ = walk_statement(&mut collector, statement);
+    collector.0
+}
+
+#[derive(Default)]
+struct Collector(Vec<String>);
+
+impl Visitor for Collector {
+    type Break = ();
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+        if let Expr::Placeholder(text, _) = expr {
+            self.0.push(text.clone());
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// The values to substitute for the placeholders of a statement
+#[derive(Debug, Clone, Copy)]
+pub enum Parameters<'a> {
+    /// Values for `?` and `$n` placeholders. Each `?` takes the value after
+    /// the one taken by the previous `?`, and `$n` takes the `n`th value.
+    /// Every value must be used.
+    Positional(&'a [Value]),
+    /// Values for `:name` and `@name` placeholders, keyed by the name
+    /// without the `:` or `@`. Names are case sensitive.
+    Named(&'a HashMap<String, Value>),
+}
+
+/// Return a copy of `statement`, parsed with `dialect`, with the
+/// placeholders replaced by `parameters`. Backslashes in string values are
+/// escaped if the dialect reads them as escapes, see
+/// [Dialect::backslash_escapes].
+pub fn bind(
+    dialect: &dyn Dialect,
+    statement: &Statement,
+    parameters: &Parameters,
+) -> Result<Statement, BindError> {
+    let mut binder = Binder {
+        backslash_escapes: dialect.backslash_escapes(),
+        parameters: *parameters,
+        next: 0,
+        used: vec![],
+    };
+    let mut statement = statement.clone();
+    if let ControlFlow::Break(err) = walk_statement_mut(&mut binder, &mut statement) {
+        return Err(err);
+    }
+    if let Parameters::Positional(values) = parameters {
+        if let Some(unused) = (0..values.len()).find(|i| !binder.used.contains(i)) {
+            let message = format!(
+                "{} values were given, but value {} is not used",
+                values.len(),
+                unused + 1
+            );
+            return Err(error(BindErrorKind::UnusedValue, message));
+        }
+    }
+    Ok(statement)
+}
+
+/// An error substituting values for placeholders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindError {
+    pub kind: BindErrorKind,
+    /// A human-readable description of the error
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BindErrorKind {
+    /// There is no value for a placeholder
+    MissingValue,
+    /// A positional value is not used by any placeholder
+    UnusedValue,
+    /// A placeholder cannot take the kind of parameters given, e.g. `:name`
+    /// with positional values
+    WrongParameters,
+    /// A value could not be printed as valid SQL, e.g. a number that is
+    /// not a number, a malformed interval, or a string with a backslash in
+    /// a dialect that may read it as an escape
+    InvalidValue,
+}
+
+impl fmt::Display for BindError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bind error: {}", self.message)
+    }
+}
+
+impl Error for BindError {}
+
+fn error(kind: BindErrorKind, message: String) -> BindError {
+    BindError { kind, message }
+}
+
+struct Binder<'a> {
+    backslash_escapes: Option<bool>,
+    parameters: Parameters<'a>,
+    /// The index of the value for the next `?`
+    next: usize,
+    /// The indexes of the positional values used so far
+    used: Vec<usize>,
+}
+
+impl Binder<'_> {
+    fn value(&mut self, placeholder: &str) -> Result<Expr, BindError> {
+        let value = match self.parameters {
+            Parameters::Positional(values) => {
+                let index = match placeholder_index(placeholder) {
+                    Some(Some(index)) => index,
+                    Some(None) => {
+                        self.next += 1;
+                        self.next - 1
+                    }
+                    None => {
+                        let message = format!("{} needs a named parameter", placeholder);
+                        return Err(error(BindErrorKind::WrongParameters, message));
+                    }
+                };
+                self.used.push(index);
+                values.get(index)
+            }
+            Parameters::Named(values) => {
+                if placeholder_index(placeholder).is_some() {
+                    let message = format!("{} needs a positional parameter", placeholder);
+                    return Err(error(BindErrorKind::WrongParameters, message));
+                }
+                values.get(&placeholder[1..])
+            }
+        };
+        match value {
+            Some(value) => value_expr(value, self.backslash_escapes),
+            None => {
+                let message = format!("no value for {}", placeholder);
+                Err(error(BindErrorKind::MissingValue, message))
+            }
+        }
+    }
+}
+
+impl VisitorMut for Binder<'_> {
+    type Break = BindError;
+
+    fn post_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<BindError> {
+        if let Expr::Placeholder(placeholder, _) = expr {
+            match self.value(placeholder) {
+                Ok(value) => *expr = value,
+                Err(err) => return ControlFlow::Break(err),
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// For a positional placeholder, return `Some` of the index of its value if
+/// it has one (`$n`), or `Some(None)` if it takes the next value (`?`).
+/// Return `None` for a named placeholder.
+fn placeholder_index(placeholder: &str) -> Option<Option<usize>> {
+    if placeholder == "?" {
+        Some(None)
+    } else if let Some(digits) = placeholder.strip_prefix('$') {
+        // `$0` has no value, so it is given an index past the end
+        let n: usize = digits.parse().unwrap_or(0);
+        Some(Some(n.checked_sub(1).unwrap_or(usize::MAX)))
+    } else {
+        None
+    }
+}
+
+/// The expression for `value`, checking that it prints as a literal, and
+/// escaping its backslashes if `backslash_escapes`
+fn value_expr(value: &Value, backslash_escapes: Option<bool>) -> Result<Expr, BindError> {
+    let mut value = match value {
+        Value::Number(n) => return number_expr(n),
+        Value::HexStringLiteral(s) if !s.chars().all(|ch| ch.is_ascii_hexdigit()) => {
+            let message = format!("{:?} is not a hexadecimal string", s);
+            return Err(error(BindErrorKind::InvalidValue, message));
+        }
+        Value::Interval {
+            leading_field,
+            leading_precision,
+            last_field,
+            fractional_seconds_precision,
+            ..
+        } if !is_valid_interval(
+            leading_field,
+            leading_precision,
+            last_field,
+            fractional_seconds_precision,
+        ) =>
+        {
+            let message = format!("{:?} is not an interval the parser can read", value);
+            return Err(error(BindErrorKind::InvalidValue, message));
+        }
+        value => value.clone(),
+    };
+    if let Some(text) = text_mut(&mut value) {
+        if text.contains('\\') {
+            match backslash_escapes {
+                Some(true) => *text = text.replace('\\', "\\\\"),
+                Some(false) => {}
+                None => {
+                    let message = format!(
+                        "{:?} has a backslash, which the dialect may read as an escape",
+                        text
+                    );
+                    return Err(error(BindErrorKind::InvalidValue, message));
+                }
+            }
+        }
+    }
+    Ok(Expr::Value(value, NodeSpan::empty()))
+}
+
+/// Whether the fields of an interval are arranged as the parser reads
+/// them: `SECOND` can only be the leading field on its own, with the
+/// fractional seconds precision after its leading precision, and otherwise
+/// the fractional seconds precision goes with a last field of `SECOND`
+fn is_valid_interval(
+    leading_field: &DateTimeField,
+    leading_precision: &Option<u64>,
+    last_field: &Option<DateTimeField>,
+    fractional_seconds_precision: &Option<u64>,
+) -> bool {
+    match leading_field {
+        DateTimeField::Second => {
+            last_field.is_none()
+                && (fractional_seconds_precision.is_none() || leading_precision.is_some())
+        }
+        _ => fractional_seconds_precision.is_none() || *last_field == Some(DateTimeField::Second),
+    }
+}
+
+/// The text of a value that is quoted as a string
+fn text_mut(value: &mut Value) -> Option<&mut String> {
+    match value {
+        Value::SingleQuotedString(text)
+        | Value::NationalStringLiteral(text)
+        | Value::Date(text)
+        | Value::Time(text)
+        | Value::Timestamp(text)
+        | Value::Interval { value: text, .. } => Some(text),
+        _ => None,
+    }
+}
+
+/// The expression for the number `n`, with the sign as a unary operator as
+/// the parser would produce it
+#[cfg(not(feature = "bigdecimal"))]
+fn number_expr(n: &str) -> Result<Expr, BindError> {
+    let (negative, digits) = match n.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, n),
+    };
+    let valid = digits.chars().any(|ch| ch.is_ascii_digit())
+        && digits.chars().all(|ch| ch.is_ascii_digit() || ch == '.')
+        && digits.matches('.').count() <= 1;
+    if !valid {
+        let message = format!("{:?} is not a number", n);
+        return Err(error(BindErrorKind::InvalidValue, message));
+    }
+    let expr = Expr::Value(Value::Number(digits.to_string()), NodeSpan::empty());
+    Ok(if negative { negate(expr) } else { expr })
+}
+
+#[cfg(feature = "bigdecimal")]
+fn number_expr(n: &BigDecimal) -> Result<Expr, BindError> {
+    if *n < BigDecimal::from(0) {
+        Ok(negate(Expr::Value(
+            Value::Number(-n.clone()),
+            NodeSpan::empty(),
+        )))
+    } else {
+        Ok(Expr::Value(Value::Number(n.clone()), NodeSpan::empty()))
+    }
+}
+
+fn negate(expr: Expr) -> Expr {
+    Expr::UnaryOp {
+        op: UnaryOperator::Minus,
+        expr: Box::new(expr),
+        span: NodeSpan::empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::{
+        AnsiDialect, Dialect, GenericDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect,
+    };
+    use crate::parser::{Parser, ParserErrorKind};
+
+    fn parse(dialect: &dyn Dialect, sql: &str) -> Statement {
+        let mut statements = Parser::parse_sql(dialect, sql.to_string()).unwrap();
+        statements.remove(0)
+    }
+
+    fn number(n: &str) -> Value {
+        Value::Number(n.parse().unwrap())
+    }
+
+    fn bind_positional(sql: &str, values: &[Value]) -> Result<String, BindError> {
+        let statement = parse(&GenericDialect {}, sql);
+        bind(
+            &GenericDialect {},
+            &statement,
+            &Parameters::Positional(values),
+        )
+        .map(|s| s.to_string())
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let sql = "INSERT INTO t (a, b) VALUES (?, ?), (?, 1)";
+        assert_eq!(
+            placeholders(&parse(&MySqlDialect {}, sql)),
+            vec!["?", "?", "?"]
+        );
+        let sql = "SELECT @a, x FROM t WHERE y BETWEEN @lo AND @hi ORDER BY @a";
+        assert_eq!(
+            placeholders(&parse(&MsSqlDialect {}, sql)),
+            vec!["@a", "@lo", "@hi", "@a"]
+        );
+        let sql = "UPDATE t SET a = :a WHERE b IN (SELECT c FROM u WHERE d = :d)";
+        assert_eq!(placeholders(&parse(&AnsiDialect {}, sql)), vec![":a", ":d"]);
+    }
+
+    #[test]
+    fn test_bind_positional() {
+        assert_eq!(
+            bind_positional(
+                "SELECT * FROM t WHERE a = ? AND b = ? LIMIT ?",
+                &[
+                    number("-1.5"),
+                    Value::SingleQuotedString("x' OR '1' = '1".to_string()),
+                    number("5"),
+                ],
+            )
+            .unwrap(),
+            "SELECT * FROM t WHERE a = - 1.5 AND b = 'x'' OR ''1'' = ''1' LIMIT 5"
+        );
+        assert_eq!(
+            bind_positional("SELECT $2, $1", &[Value::Null, Value::Boolean(true)]).unwrap(),
+            "SELECT true, NULL"
+        );
+
+        let err = bind_positional("SELECT ?, ?", &[Value::Null]).unwrap_err();
+        assert_eq!(err.kind, BindErrorKind::MissingValue);
+        assert_eq!(err.to_string(), "bind error: no value for ?");
+        let err = bind_positional("SELECT $1, $3", &[Value::Null, Value::Null, Value::Null]);
+        assert_eq!(err.unwrap_err().kind, BindErrorKind::UnusedValue);
+        // Parameters are numbered from `$1`, so `$0` and `$01` are not
+        // placeholders, though a `$0` built into the AST has no value
+        for sql in &["SELECT $0", "SELECT $1, $01"] {
+            let err = Parser::parse_sql(&PostgreSqlDialect {}, sql.to_string()).unwrap_err();
+            assert_eq!(err.kind, ParserErrorKind::TokenizerError);
+        }
+        let statement = Statement::Query(Box::new(
+            crate::builder::select(vec![Expr::Placeholder("$0".to_string(), NodeSpan::empty())])
+                .build(),
+        ));
+        let err = bind(&GenericDialect {}, &statement, &Parameters::Positional(&[]));
+        assert_eq!(err.unwrap_err().kind, BindErrorKind::MissingValue);
+        let err = bind_positional("SELECT :a", &[]).unwrap_err();
+        assert_eq!(err.kind, BindErrorKind::WrongParameters);
+        let err = bind_positional("SELECT ?", &[Value::HexStringLiteral("'1".to_string())]);
+        assert_eq!(err.unwrap_err().kind, BindErrorKind::InvalidValue);
+    }
+
+    #[cfg(not(feature = "bigdecimal"))]
+    #[test]
+    fn test_bind_invalid_number() {
+        let value = Value::Number("1; DROP TABLE t".to_string());
+        let err = bind_positional("SELECT ?", &[value]).unwrap_err();
+        assert_eq!(err.kind, BindErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn test_bind_backslashes() {
+        let values = [
+            Value::SingleQuotedString("\\".to_string()),
+            Value::SingleQuotedString(" OR 1 = 1 -- ".to_string()),
+        ];
+        let bind_for = |dialect: &dyn Dialect| {
+            let statement = parse(dialect, "SELECT * FROM t WHERE a = ? AND b = ?");
+            bind(dialect, &statement, &Parameters::Positional(&values)).map(|s| s.to_string())
+        };
+        assert_eq!(
+            bind_for(&MySqlDialect {}).unwrap(),
+            "SELECT * FROM t WHERE a = '\\\\' AND b = ' OR 1 = 1 -- '"
+        );
+        assert_eq!(
+            bind_for(&AnsiDialect {}).unwrap(),
+            "SELECT * FROM t WHERE a = '\\' AND b = ' OR 1 = 1 -- '"
+        );
+        let err = bind_for(&GenericDialect {}).unwrap_err();
+        assert_eq!(err.kind, BindErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn test_bind_interval() {
+        let interval = |leading_field, last_field| Value::Interval {
+            value: "1".to_string(),
+            leading_field,
+            leading_precision: Some(2),
+            last_field,
+            fractional_seconds_precision: Some(3),
+        };
+        assert_eq!(
+            bind_positional(
+                "SELECT ?",
+                &[interval(DateTimeField::Day, Some(DateTimeField::Second))]
+            )
+            .unwrap(),
+            "SELECT INTERVAL '1' DAY (2) TO SECOND (3)"
+        );
+        let invalid = [
+            interval(DateTimeField::Second, Some(DateTimeField::Second)),
+            interval(DateTimeField::Day, Some(DateTimeField::Hour)),
+            interval(DateTimeField::Day, None),
+        ];
+        for value in invalid.iter().cloned() {
+            let err = bind_positional("SELECT ?", &[value]).unwrap_err();
+            assert_eq!(err.kind, BindErrorKind::InvalidValue);
+        }
+    }
+
+    #[test]
+    fn test_bind_named() {
+        let statement = parse(
+            &MsSqlDialect {},
+            "SELECT * FROM t WHERE a = @a OR b = @b OR c = @a",
+        );
+        let mut values = HashMap::new();
+        values.insert("a".to_string(), number("1"));
+        values.insert("b".to_string(), Value::SingleQuotedString("b".to_string()));
+        values.insert("unused".to_string(), Value::Null);
+        let bound = bind(&MsSqlDialect {}, &statement, &Parameters::Named(&values)).unwrap();
+        assert_eq!(
+            bound.to_string(),
+            "SELECT * FROM t WHERE a = 1 OR b = 'b' OR c = 1"
+        );
+        assert!(placeholders(&bound).is_empty());
+
+        values.remove("b");
+        let err = bind(&MsSqlDialect {}, &statement, &Parameters::Named(&values)).unwrap_err();
+        assert_eq!(err.to_string(), "bind error: no value for @b");
+
+        let statement = parse(&GenericDialect {}, "SELECT ?");
+        let err = bind(&GenericDialect {}, &statement, &Parameters::Named(&values)).unwrap_err();
+        assert_eq!(err.kind, BindErrorKind::WrongParameters);
+    }
+}
@@ -0,0 +1,479 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Row-level security by rewriting statements, so that they only see the
+//! rows of each table that a policy allows.
+//!
+//! ```
+//! use sqlparser::ast::{Expr, NodeSpan};
+//! use sqlparser::builder::col;
+//! use sqlparser::dialect::GenericDialect;
+//! use sqlparser::parser::Parser;
+//! use sqlparser::policy::apply_row_policies;
+//!
+//! let sql = "SELECT * FROM orders AS o LEFT JOIN customers ON o.customer_id = customers.id \
+//!            WHERE o.total > 10";
+//! let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+//! let placeholder = Expr::Placeholder("?".into(), NodeSpan::empty());
+//! let tenant = |_: &_| Some(col("tenant_id").equal(placeholder.clone()));
+//! assert_eq!(
+//!     apply_row_policies(&statements[0], tenant).to_string(),
+//!     "SELECT * FROM orders AS o LEFT JOIN customers \
+//!      ON o.customer_id = customers.id AND customers.tenant_id = ? \
+//!      WHERE o.total > 10 AND o.tenant_id = ?"
+//! );
+//! ```
+
+use crate::access::Ctes;
+use crate::ast::*;
+use crate::builder::{select, wildcard};
+use crate::dialect::keywords::Keyword;
+use std::ops::ControlFlow;
+
+/// Return a copy of `statement` in which every reference to a table reads
+/// only the rows for which the predicate that `policy` returns for the
+/// table is true. `policy` is called with the name of each table as it is
+/// written in the statement, including in subqueries and CTEs, and returns
+/// `None` for tables that every row of may be read. References to CTEs are
+/// not tables, so they are left alone.
+///
+/// Column references in a predicate are qualified with the alias of the
+/// table, or with its name if it has none, except where they are already
+/// qualified or are in a subquery of the predicate. The predicate is then
+/// added with `AND` to:
+///
+/// - the `WHERE` clause of the `SELECT`, `UPDATE` or `DELETE` that reads
+///   the table, unless an outer join may extend the table with `NULL`s;
+/// - the `ON` condition of the `LEFT JOIN` of the table, or of the
+///   `RIGHT JOIN` after it, if there is one of those;
+/// - otherwise, e.g. for a table in a `FULL JOIN` or joined with `USING`,
+///   a derived table that replaces the table reference:
+///   `(SELECT * FROM t WHERE ...) AS t`. This is also done for tables whose
+///   alias renames their columns, so that the predicate sees the original
+///   names. The derived table can only be referred to by its alias, or the
+///   last part of the name of the table.
+///
+/// The targets of `INSERT` and of other statements that change tables are
+/// not restricted, and neither are the rows that `UPDATE` writes.
+pub fn apply_row_policies<F>(statement: &Statement, policy: F) -> Statement
+where
+    F: FnMut(&ObjectName) -> Option<Expr>,
+{
+    let mut statement = statement.clone();
+    let mut enforcer = Enforcer {
+        policy,
+        ctes: Ctes::default(),
+    };
+    let _ = walk_statement_mut(&mut enforcer, &mut statement);
+    statement
+}
+
+struct Enforcer<F> {
+    policy: F,
+    ctes: Ctes,
+}
+
+/// Where the predicate for a relation in a join goes
+enum Placement {
+    /// In the `WHERE` clause of the `SELECT`, or of the query that the join
+    /// is nested in
+    Filter,
+    /// In the `ON` condition of the join with this index
+    On(usize),
+    /// In a derived table that replaces the relation
+    Wrap,
+}
+
+impl<F> VisitorMut for Enforcer<F>
+where
+    F: FnMut(&ObjectName) -> Option<Expr>,
+{
+    type Break = ();
+
+    fn post_visit_statement(&mut self, statement: &mut Statement) -> ControlFlow<()> {
+        match statement {
+            Statement::Update {
+                table_name,
+                selection,
+                ..
+            }
+            | Statement::Delete {
+                table_name,
+                selection,
+                ..
+            } => {
+                if let Some(predicate) = (self.policy)(table_name) {
+                    add_filter(selection, qualify(predicate, &table_name.0));
+                }
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_query(&mut self, query: &mut Query) -> ControlFlow<()> {
+        self.ctes.enter(query);
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &mut Query) -> ControlFlow<()> {
+        self.ctes.exit();
+        ControlFlow::Continue(())
+    }
+
+    /// Restrict the tables in the `FROM` clause, after the walk has
+    /// restricted the ones in subqueries, so that the derived tables added
+    /// here are not walked
+    fn post_visit_select(&mut self, select: &mut Select) -> ControlFlow<()> {
+        for table in &mut select.from {
+            for predicate in self.table_with_joins(table, false) {
+                add_filter(&mut select.selection, predicate);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+impl<F> Enforcer<F>
+where
+    F: FnMut(&ObjectName) -> Option<Expr>,
+{
+    /// Restrict the relations of `table`, returning the predicates that
+    /// belong in the `WHERE` clause. If `wrap` is set, no predicates may be
+    /// returned, as the whole of `table` may be extended with `NULL`s.
+    fn table_with_joins(&mut self, table: &mut TableWithJoins, wrap: bool) -> Vec<Expr> {
+        let mut filters = vec![];
+        for i in 0..=table.joins.len() {
+            let placement = if wrap {
+                Placement::Wrap
+            } else {
+                placement(&table.joins, i)
+            };
+            let relation = match i {
+                0 => &mut table.relation,
+                i => &mut table.joins[i - 1].relation,
+            };
+            let wrap = matches!(placement, Placement::Wrap);
+            let predicates = self.table_factor(relation, wrap);
+            match placement {
+                Placement::Filter => filters.extend(predicates),
+                Placement::On(j) => {
+                    if let JoinOperator::LeftOuter(JoinConstraint::On(on))
+                    | JoinOperator::RightOuter(JoinConstraint::On(on)) =
+                        &mut table.joins[j].join_operator
+                    {
+                        for predicate in predicates {
+                            let condition =
+                                std::mem::replace(on, Expr::Wildcard(NodeSpan::empty()));
+                            *on = condition.and(predicate);
+                        }
+                    }
+                }
+                Placement::Wrap => {}
+            }
+        }
+        filters
+    }
+
+    /// Restrict `factor`, returning the predicates to place, unless `wrap`
+    /// is set or it cannot be restricted by one
+    fn table_factor(&mut self, factor: &mut TableFactor, wrap: bool) -> Vec<Expr> {
+        match factor {
+            TableFactor::Table { name, alias, .. } if !self.ctes.contains(name) => {
+                let predicate = match (self.policy)(name) {
+                    Some(predicate) => predicate,
+                    None => return vec![],
+                };
+                match alias {
+                    _ if wrap => {}
+                    Some(alias) if alias.columns.is_empty() => {
+                        return vec![qualify(predicate, std::slice::from_ref(&alias.name))]
+                    }
+                    Some(_) => {}
+                    None => return vec![qualify(predicate, &name.0)],
+                }
+                *factor = restricted_table(factor.clone(), predicate);
+                vec![]
+            }
+            TableFactor::NestedJoin(table, _) => self.table_with_joins(table, wrap),
+            // The walk has restricted the tables of derived tables
+            TableFactor::Table { .. } | TableFactor::Derived { .. } => vec![],
+        }
+    }
+}
+
+/// Where the predicate for relation `i` of a join goes, where relation 0 is
+/// the first one and the others are the relations of `joins`
+fn placement(joins: &[Join], i: usize) -> Placement {
+    let later = &joins[i..];
+    let is_full = |join: &Join| matches!(join.join_operator, JoinOperator::FullOuter(_));
+    if later.iter().any(is_full) {
+        return Placement::Wrap;
+    }
+    if i > 0 {
+        match &joins[i - 1].join_operator {
+            JoinOperator::LeftOuter(JoinConstraint::On(_)) => return Placement::On(i - 1),
+            JoinOperator::LeftOuter(_) | JoinOperator::FullOuter(_) | JoinOperator::OuterApply => {
+                return Placement::Wrap
+            }
+            _ => {}
+        }
+    }
+    let is_right = |join: &Join| matches!(join.join_operator, JoinOperator::RightOuter(_));
+    match later.iter().position(is_right) {
+        Some(j) => match &later[j].join_operator {
+            JoinOperator::RightOuter(JoinConstraint::On(_)) => Placement::On(i + j),
+            _ => Placement::Wrap,
+        },
+        None => Placement::Filter,
+    }
+}
+
+/// Replace `table`, a table reference, with a derived table of its rows for
+/// which `predicate` holds
+fn restricted_table(table: TableFactor, predicate: Expr) -> TableFactor {
+    match table {
+        TableFactor::Table {
+            name,
+            alias,
+            args,
+            with_hints,
+            ..
+        } => {
+            let alias = alias.unwrap_or_else(|| TableAlias {
+                name: name.0.last().cloned().unwrap_or_else(|| Ident::new("")),
+                columns: vec![],
+            });
+            let predicate = qualify(predicate, &name.0);
+            let table = TableFactor::Table {
+                name,
+                alias: None,
+                args,
+                with_hints,
+                span: NodeSpan::empty(),
+            };
+            TableFactor::Derived {
+                lateral: false,
+                subquery: Box::new(
+                    select(vec![wildcard()])
+                        .from(table)
+                        .filter(predicate)
+                        .build(),
+                ),
+                alias: Some(alias),
+                span: NodeSpan::empty(),
+            }
+        }
+        table => table,
+    }
+}
+
+/// Set `selection` to `predicate`, or add it with `AND`
+fn add_filter(selection: &mut Option<Expr>, predicate: Expr) {
+    *selection = Some(match selection.take() {
+        Some(selection) => selection.and(predicate),
+        None => predicate,
+    });
+}
+
+/// Qualify the unqualified column references in `predicate`, outside its
+/// subqueries, with `qualifier`. Keywords such as `CURRENT_USER` that the
+/// parser reads as identifiers are functions, so they are left as they are.
+fn qualify(mut predicate: Expr, qualifier: &[Ident]) -> Expr {
+    let mut qualifier = Qualifier {
+        qualifier,
+        depth: 0,
+    };
+    let _ = walk_expr_mut(&mut qualifier, &mut predicate);
+    predicate
+}
+
+struct Qualifier<'a> {
+    qualifier: &'a [Ident],
+    /// How many subqueries deep the walk is
+    depth: usize,
+}
+
+impl<'a> VisitorMut for Qualifier<'a> {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, _query: &mut Query) -> ControlFlow<()> {
+        self.depth += 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &mut Query) -> ControlFlow<()> {
+        self.depth -= 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<()> {
+        if let (Expr::Identifier(ident), 0) = (&*expr, self.depth) {
+            if is_niladic_function(ident) {
+                return ControlFlow::Continue(());
+            }
+            let mut idents = self.qualifier.to_vec();
+            idents.push(ident.clone());
+            *expr = Expr::CompoundIdentifier(idents);
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Whether `ident` is a function that is called without parentheses
+fn is_niladic_function(ident: &Ident) -> bool {
+    use Keyword::*;
+    ident.quote_style.is_none()
+        && matches!(
+            Keyword::lookup(&ident.value),
+            CURRENT_CATALOG
+                | CURRENT_DATE
+                | CURRENT_PATH
+                | CURRENT_ROLE
+                | CURRENT_SCHEMA
+                | CURRENT_TIME
+                | CURRENT_TIMESTAMP
+                | CURRENT_USER
+                | LOCALTIME
+                | LOCALTIMESTAMP
+                | SESSION_USER
+                | SYSTEM_USER
+                | USER
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::col;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+
+    /// Apply a policy of `tenant_id = ?` to every table but `lookup`
+    fn restrict(sql: &str) -> String {
+        let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        let policy = |name: &ObjectName| match name.to_string().as_str() {
+            "lookup" => None,
+            _ => Some(col("tenant_id").equal(Expr::Placeholder("?".into(), NodeSpan::empty()))),
+        };
+        let restricted = apply_row_policies(&statements[0], policy).to_string();
+        // The result must parse back to itself
+        let reparsed = Parser::parse_sql(&GenericDialect {}, restricted.clone()).unwrap();
+        assert_eq!(reparsed[0].to_string(), restricted);
+        restricted
+    }
+
+    #[test]
+    fn test_filters() {
+        assert_eq!(
+            restrict("SELECT * FROM a, s.b AS x JOIN c ON x.id = c.id, lookup WHERE a.n = 1 OR a.n = 2"),
+            "SELECT * FROM a, s.b AS x JOIN c ON x.id = c.id, lookup \
+             WHERE (a.n = 1 OR a.n = 2) AND a.tenant_id = ? AND x.tenant_id = ? AND c.tenant_id = ?"
+        );
+        assert_eq!(
+            restrict("SELECT * FROM s.t"),
+            "SELECT * FROM s.t WHERE s.t.tenant_id = ?"
+        );
+        assert_eq!(
+            restrict("UPDATE t SET n = (SELECT MAX(n) FROM u) WHERE n > 1"),
+            "UPDATE t SET n = (SELECT MAX(n) FROM u WHERE u.tenant_id = ?) \
+             WHERE n > 1 AND t.tenant_id = ?"
+        );
+        assert_eq!(
+            restrict("DELETE FROM t"),
+            "DELETE FROM t WHERE t.tenant_id = ?"
+        );
+        assert_eq!(
+            restrict("INSERT INTO t SELECT * FROM u"),
+            "INSERT INTO t SELECT * FROM u WHERE u.tenant_id = ?"
+        );
+    }
+
+    #[test]
+    fn test_niladic_functions_are_not_qualified() {
+        let statements =
+            Parser::parse_sql(&GenericDialect {}, "SELECT * FROM t".to_string()).unwrap();
+        let policy = |_: &ObjectName| {
+            let function = |name: &str| Expr::Identifier(Ident::new(name));
+            Some(
+                col("owner")
+                    .equal(function("CURRENT_USER"))
+                    .and(col("expires").gt(function("current_date"))),
+            )
+        };
+        assert_eq!(
+            apply_row_policies(&statements[0], policy).to_string(),
+            "SELECT * FROM t WHERE t.owner = CURRENT_USER AND t.expires > current_date"
+        );
+    }
+
+    #[test]
+    fn test_outer_joins() {
+        assert_eq!(
+            restrict("SELECT * FROM a LEFT JOIN b ON a.id = b.id RIGHT JOIN c ON b.id = c.id"),
+            "SELECT * FROM a LEFT JOIN b ON a.id = b.id AND b.tenant_id = ? \
+             RIGHT JOIN c ON b.id = c.id AND a.tenant_id = ? WHERE c.tenant_id = ?"
+        );
+        assert_eq!(
+            restrict("SELECT * FROM a FULL JOIN b AS x ON a.id = x.id"),
+            "SELECT * FROM (SELECT * FROM a WHERE a.tenant_id = ?) AS a \
+             FULL JOIN (SELECT * FROM b WHERE b.tenant_id = ?) AS x ON a.id = x.id"
+        );
+        assert_eq!(
+            restrict("SELECT * FROM a LEFT JOIN b USING (id) JOIN c AS y (k) ON y.k = a.id"),
+            "SELECT * FROM a LEFT JOIN (SELECT * FROM b WHERE b.tenant_id = ?) AS b USING(id) \
+             JOIN (SELECT * FROM c WHERE c.tenant_id = ?) AS y (k) ON y.k = a.id \
+             WHERE a.tenant_id = ?"
+        );
+        // The tables of a nested join go where the nested join's would
+        assert_eq!(
+            restrict("SELECT * FROM a LEFT JOIN (b JOIN c ON b.id = c.id) ON a.id = b.id"),
+            "SELECT * FROM a LEFT JOIN (b JOIN c ON b.id = c.id) \
+             ON a.id = b.id AND b.tenant_id = ? AND c.tenant_id = ? WHERE a.tenant_id = ?"
+        );
+    }
+
+    #[test]
+    fn test_subqueries_and_ctes() {
+        assert_eq!(
+            restrict(
+                "WITH x AS (SELECT * FROM a), y AS (SELECT * FROM x, y) \
+                 SELECT * FROM y, (SELECT * FROM b) AS d WHERE EXISTS (SELECT 1 FROM x)"
+            ),
+            "WITH x AS (SELECT * FROM a WHERE a.tenant_id = ?), \
+             y AS (SELECT * FROM x, y WHERE y.tenant_id = ?) \
+             SELECT * FROM y, (SELECT * FROM b WHERE b.tenant_id = ?) AS d \
+             WHERE EXISTS (SELECT 1 FROM x)"
+        );
+        // Only the predicate's own column references are qualified
+        let statements =
+            Parser::parse_sql(&GenericDialect {}, "SELECT * FROM t AS x".to_string()).unwrap();
+        let policy = Parser::parse_sql(
+            &GenericDialect {},
+            "SELECT * FROM t WHERE owner IN (SELECT id FROM users WHERE name = 'me') AND t.open"
+                .to_string(),
+        )
+        .unwrap();
+        let predicate = match &policy[0] {
+            Statement::Query(query) => match &query.body {
+                SetExpr::Select(select) => select.selection.clone(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            apply_row_policies(&statements[0], |_| predicate.clone()).to_string(),
+            "SELECT * FROM t AS x \
+             WHERE x.owner IN (SELECT id FROM users WHERE name = 'me') AND t.open"
+        );
+    }
+}
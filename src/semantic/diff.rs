@@ -0,0 +1,718 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::schema::{
+    column_constraint, constraint_columns, constraint_name, error, names_match, view_columns,
+    Schema, SchemaError, SchemaErrorKind, Table, View,
+};
+use crate::access::table_access;
+use crate::ast::*;
+use crate::dialect::Dialect;
+
+/// A table or column that [diff_schemas] should rename, rather than drop
+/// and create again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rename {
+    /// The table `from` of the old schema is the table `to` of the new one
+    Table { from: ObjectName, to: ObjectName },
+    /// The column `from` is renamed to `to`. `table` is the name of the
+    /// table in the new schema.
+    Column {
+        table: ObjectName,
+        from: Ident,
+        to: Ident,
+    },
+}
+
+/// Return the statements that migrate the schema `from` to the schema `to`.
+///
+/// Without a [Rename] for them, renamed tables and columns are dropped and
+/// created again. Views and indexes that changed are dropped and created
+/// again, unless they differ only in their comments. So are the views that
+/// read a table whose columns change type, or read a view that is dropped.
+/// Columns are compared by data type, nullability and default, and constraints are compared
+/// whether they are declared on the table or on a column; other
+/// differences, such as column order, are not migrated. Constraints that
+/// have no name are dropped by the name `dialect` gives them by default;
+/// a schema made by [Schema::with_dialect] knows these names, so the
+/// migration can be applied to it.
+///
+/// Fails if a rename refers to a table or column that does not exist, or
+/// if the migration has to drop a constraint that has no name and whose
+/// default name `dialect` cannot tell.
+pub fn diff_schemas(
+    dialect: &dyn Dialect,
+    from: &Schema,
+    to: &Schema,
+    renames: &[Rename],
+) -> Result<Vec<Statement>, SchemaError> {
+    let mut diff = Diff {
+        dialect,
+        current: from.clone(),
+        target: to,
+        statements: vec![],
+    };
+    diff.migrate(renames)?;
+    Ok(diff.statements)
+}
+
+struct Diff<'a> {
+    dialect: &'a dyn Dialect,
+    /// The old schema with the statements so far applied to it
+    current: Schema,
+    target: &'a Schema,
+    statements: Vec<Statement>,
+}
+
+impl Diff<'_> {
+    /// Add `statement` to the migration
+    fn emit(&mut self, statement: Statement) -> Result<(), SchemaError> {
+        self.current.apply(&statement)?;
+        self.statements.push(statement);
+        Ok(())
+    }
+
+    fn alter(
+        &mut self,
+        name: &ObjectName,
+        operation: AlterTableOperation,
+    ) -> Result<(), SchemaError> {
+        self.emit(Statement::AlterTable {
+            name: name.clone(),
+            operation,
+            span: NodeSpan::empty(),
+        })
+    }
+
+    fn drop(&mut self, object_type: ObjectType, name: &ObjectName) -> Result<(), SchemaError> {
+        self.emit(Statement::Drop {
+            object_type,
+            if_exists: false,
+            names: vec![name.clone()],
+            cascade: false,
+            span: NodeSpan::empty(),
+        })
+    }
+
+    /// Drop the views for which `is_stale` holds, and the views that read
+    /// them
+    fn drop_views(&mut self, is_stale: impl Fn(&View) -> bool) -> Result<(), SchemaError> {
+        let mut stale: Vec<ObjectName> = vec![];
+        // Later views may read earlier ones
+        for view in self.current.views() {
+            if is_stale(view) || reads_any(view, &stale) {
+                stale.push(view.name.clone());
+            }
+        }
+        for name in stale.iter().rev() {
+            self.drop(ObjectType::View, name)?;
+        }
+        Ok(())
+    }
+
+    fn migrate(&mut self, renames: &[Rename]) -> Result<(), SchemaError> {
+        let target = self.target;
+        self.drop_views(|view| match target.view(&view.name) {
+            Some(target) => !same_view(view, target),
+            None => true,
+        })?;
+
+        for rename in renames {
+            if let Rename::Table { from, to } = rename {
+                let table_name = to.clone();
+                self.alter(from, AlterTableOperation::RenameTable { table_name })?;
+            }
+        }
+        for rename in renames {
+            if let Rename::Column { table, from, to } = rename {
+                let operation = AlterTableOperation::RenameColumn {
+                    old_column_name: from.clone(),
+                    new_column_name: to.clone(),
+                };
+                self.alter(table, operation)?;
+            }
+        }
+
+        // The type of a column cannot change while a view reads it
+        let retyped: Vec<ObjectName> = target
+            .tables()
+            .iter()
+            .filter(|table| match self.current.table(&table.name) {
+                Some(current) => table.columns.iter().any(|column| {
+                    matches!(current.column(&column.name),
+                        Some(existing) if existing.data_type != column.data_type)
+                }),
+                None => false,
+            })
+            .map(|table| table.name.clone())
+            .collect();
+        self.drop_views(|view| reads_any(view, &retyped))?;
+
+        let stale_indexes: Vec<ObjectName> = self
+            .current
+            .indexes()
+            .iter()
+            .filter(|index| self.target.index(&index.name) != Some(index))
+            .map(|index| index.name.clone())
+            .collect();
+        for name in &stale_indexes {
+            self.drop(ObjectType::Index, name)?;
+        }
+
+        self.drop_constraints()?;
+        let removed: Vec<ObjectName> = self
+            .current
+            .tables()
+            .iter()
+            .filter(|table| self.target.table(&table.name).is_none())
+            .map(|table| table.name.clone())
+            .collect();
+        for name in removed.iter().rev() {
+            self.drop(ObjectType::Table, name)?;
+        }
+
+        for target in self.target.tables() {
+            match self.current.table(&target.name).cloned() {
+                Some(current) => self.alter_columns(&current, target)?,
+                None => self.create_table(target)?,
+            }
+        }
+        // Foreign keys go last, as they may refer to tables created or
+        // constraints added after their own table
+        for &foreign_keys in &[false, true] {
+            for target in self.target.tables() {
+                let current = constraints(self.current.table(&target.name).unwrap());
+                for constraint in constraints(target) {
+                    if is_foreign_key(&constraint) == foreign_keys && !current.contains(&constraint)
+                    {
+                        self.alter(&target.name, AlterTableOperation::AddConstraint(constraint))?;
+                    }
+                }
+            }
+        }
+
+        for index in self.target.indexes() {
+            if self.current.index(&index.name).is_none() {
+                self.emit(Statement::CreateIndex {
+                    name: index.name.clone(),
+                    table_name: index.table_name.clone(),
+                    columns: index.columns.clone(),
+                    unique: index.unique,
+                    if_not_exists: false,
+                    span: NodeSpan::empty(),
+                })?;
+            }
+        }
+        for view in self.target.views() {
+            if self.current.view(&view.name).is_none() {
+                // Only list the columns if they are not the query's own
+                let columns = if view.columns == view_columns(&self.current, &view.query) {
+                    vec![]
+                } else {
+                    view.columns.clone()
+                };
+                self.emit(Statement::CreateView {
+                    name: view.name.clone(),
+                    columns,
+                    query: Box::new(view.query.clone()),
+                    materialized: view.materialized,
+                    with_options: view.with_options.clone(),
+                    span: NodeSpan::empty(),
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop the constraints of tables in both schemas that are not in the
+    /// new schema, except those that go with a dropped column. Constraints
+    /// without a name are given their default name first, so that the
+    /// `DROP CONSTRAINT` applies to the current schema.
+    fn drop_constraints(&mut self) -> Result<(), SchemaError> {
+        let mut stale = vec![];
+        for current in self.current.tables() {
+            let target = match self.target.table(&current.name) {
+                Some(target) => target,
+                None => continue,
+            };
+            let wanted = constraints(target);
+            let is_dropped = |column: &Ident| target.column(column).is_none();
+            for constraint in &current.constraints {
+                if !wanted.contains(constraint)
+                    && !constraint_columns(constraint).iter().any(is_dropped)
+                {
+                    stale.push((current.name.clone(), constraint.clone()));
+                }
+            }
+            for column in &current.columns {
+                if is_dropped(&column.name) {
+                    continue;
+                }
+                for option in &column.options {
+                    match column_constraint(&column.name, option) {
+                        Some(constraint) if !wanted.contains(&constraint) => {
+                            stale.push((current.name.clone(), constraint))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        let mut statements = vec![];
+        for (table, constraint) in stale {
+            let name = match constraint_name(&constraint) {
+                Some(name) => name.clone(),
+                None => match self.dialect.default_constraint_name(&table, &constraint) {
+                    Some(name) => {
+                        self.name_constraint(&table, &constraint, &name);
+                        name
+                    }
+                    None => {
+                        let message = format!(
+                            "cannot drop constraint {} of table {} because it has no name",
+                            constraint, table
+                        );
+                        let span = table.span();
+                        return Err(error(SchemaErrorKind::UnnamedConstraint, message, span));
+                    }
+                },
+            };
+            statements.push(Statement::AlterTable {
+                name: table,
+                operation: AlterTableOperation::DropConstraint { name },
+                span: NodeSpan::empty(),
+            });
+        }
+        statements
+            .into_iter()
+            .try_for_each(|statement| self.emit(statement))
+    }
+
+    /// Give the unnamed `constraint` of `table` in the current schema the
+    /// name `name`, whether it is a table constraint or a column option
+    fn name_constraint(&mut self, table: &ObjectName, constraint: &TableConstraint, name: &Ident) {
+        let table = self.current.table_mut(table).unwrap();
+        if let Some(c) = table.constraints.iter_mut().find(|c| *c == constraint) {
+            set_constraint_name(c, name);
+            return;
+        }
+        for column in &mut table.columns {
+            let column_name = column.name.clone();
+            for option in &mut column.options {
+                if column_constraint(&column_name, option).as_ref() == Some(constraint) {
+                    option.name = Some(name.clone());
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Create the new table `target`, except for its foreign keys
+    fn create_table(&mut self, target: &Table) -> Result<(), SchemaError> {
+        let columns = target
+            .columns
+            .iter()
+            .map(|column| {
+                let mut column = column.clone();
+                column
+                    .options
+                    .retain(|o| !matches!(o.option, ColumnOption::ForeignKey { .. }));
+                Commented::new(column)
+            })
+            .collect();
+        let constraints = target
+            .constraints
+            .iter()
+            .filter(|c| !is_foreign_key(c))
+            .cloned()
+            .collect();
+        self.emit(Statement::CreateTable {
+            name: target.name.clone(),
+            columns,
+            constraints,
+            with_options: target.with_options.clone(),
+            external: false,
+            file_format: None,
+            location: None,
+            span: NodeSpan::empty(),
+        })
+    }
+
+    /// Drop, add and alter the columns of `current` to match `target`. The
+    /// constraints of added columns are added later.
+    fn alter_columns(&mut self, current: &Table, target: &Table) -> Result<(), SchemaError> {
+        for column in &current.columns {
+            if target.column(&column.name).is_none() {
+                let operation = AlterTableOperation::DropColumn {
+                    column_name: column.name.clone(),
+                    if_exists: false,
+                    cascade: false,
+                };
+                self.alter(&target.name, operation)?;
+            }
+        }
+        for column in &target.columns {
+            let existing = match current.column(&column.name) {
+                Some(existing) => existing,
+                None => {
+                    let mut column_def = column.clone();
+                    column_def
+                        .options
+                        .retain(|o| column_constraint(&column.name, o).is_none());
+                    self.alter(&target.name, AlterTableOperation::AddColumn { column_def })?;
+                    continue;
+                }
+            };
+            let mut ops = vec![];
+            if existing.data_type != column.data_type {
+                let data_type = column.data_type.clone();
+                ops.push(AlterColumnOperation::SetDataType { data_type });
+            }
+            match (is_not_null(existing), is_not_null(column)) {
+                (false, true) => ops.push(AlterColumnOperation::SetNotNull),
+                (true, false) => ops.push(AlterColumnOperation::DropNotNull),
+                _ => {}
+            }
+            match (default(existing), default(column)) {
+                (old, Some(value)) if old != Some(value) => {
+                    let value = value.clone();
+                    ops.push(AlterColumnOperation::SetDefault { value });
+                }
+                (Some(_), None) => ops.push(AlterColumnOperation::DropDefault),
+                _ => {}
+            }
+            for op in ops {
+                let column_name = column.name.clone();
+                self.alter(
+                    &target.name,
+                    AlterTableOperation::AlterColumn { column_name, op },
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The constraints of `table`, with those declared as column options
+/// turned into table constraints
+fn constraints(table: &Table) -> Vec<TableConstraint> {
+    let options = table.columns.iter().flat_map(|column| {
+        column
+            .options
+            .iter()
+            .filter_map(move |option| column_constraint(&column.name, option))
+    });
+    table.constraints.iter().cloned().chain(options).collect()
+}
+
+fn set_constraint_name(constraint: &mut TableConstraint, new_name: &Ident) {
+    match constraint {
+        TableConstraint::Unique { name, .. }
+        | TableConstraint::ForeignKey { name, .. }
+        | TableConstraint::Check { name, .. } => *name = Some(new_name.clone()),
+    }
+}
+
+/// Whether the views `a` and `b` are the same but for their comments
+fn same_view(a: &View, b: &View) -> bool {
+    let strip = |view: &View| {
+        let mut view = view.clone();
+        strip_query_comments(&mut view.query);
+        view
+    };
+    strip(a) == strip(b)
+}
+
+/// Whether `view` reads any of the tables and views `names`
+fn reads_any(view: &View, names: &[ObjectName]) -> bool {
+    let reads = table_access(&Statement::Query(Box::new(view.query.clone()))).reads;
+    reads
+        .iter()
+        .any(|read| names.iter().any(|name| names_match(read, name)))
+}
+
+fn is_foreign_key(constraint: &TableConstraint) -> bool {
+    matches!(constraint, TableConstraint::ForeignKey { .. })
+}
+
+fn is_not_null(column: &ColumnDef) -> bool {
+    let primary_key =
+        |option: &ColumnOption| matches!(option, ColumnOption::Unique { is_primary: true });
+    column
+        .options
+        .iter()
+        .any(|o| o.option == ColumnOption::NotNull || primary_key(&o.option))
+}
+
+fn default(column: &ColumnDef) -> Option<&Expr> {
+    column.options.iter().find_map(|o| match &o.option {
+        ColumnOption::Default(expr) => Some(expr),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::{GenericDialect, PostgreSqlDialect};
+    use crate::parser::Parser;
+
+    fn schema(sql: &str) -> Schema {
+        schema_after(&Schema::new(), sql)
+    }
+
+    /// Diff `from` and `to`, and check that the migration, printed and
+    /// parsed again, turns `from` into `to`
+    fn migrate(from: &str, to: &str, renames: &[Rename]) -> Vec<String> {
+        let (from, to) = (schema(from), schema(to));
+        let statements = diff_schemas(&GenericDialect {}, &from, &to, renames).unwrap();
+        let sql: Vec<String> = statements.iter().map(ToString::to_string).collect();
+
+        let migrated = schema_after(&from, &sql.join(";\n"));
+        assert_eq!(
+            diff_schemas(&GenericDialect {}, &migrated, &to, &[]).unwrap(),
+            vec![]
+        );
+        sql
+    }
+
+    fn schema_after(from: &Schema, sql: &str) -> Schema {
+        let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        let mut schema = from.clone();
+        schema.apply_all(&statements).unwrap();
+        schema
+    }
+
+    fn name(name: &str) -> ObjectName {
+        ObjectName(vec![Ident::new(name)])
+    }
+
+    #[test]
+    fn test_diff_tables() {
+        let from = "CREATE TABLE customers (id INT PRIMARY KEY, name TEXT, region TEXT);
+             CREATE TABLE orders (id INT, customer_id INT, total INT DEFAULT 0,
+                 CONSTRAINT orders_pk PRIMARY KEY (id));
+             CREATE TABLE scratch (x INT)";
+        let to = "CREATE TABLE clients (id INT PRIMARY KEY, full_name TEXT NOT NULL, email TEXT);
+             CREATE TABLE orders (id INT, client_id INT, total BIGINT,
+                 CONSTRAINT orders_pk PRIMARY KEY (id),
+                 CONSTRAINT orders_client FOREIGN KEY (client_id) REFERENCES clients (id));
+             CREATE TABLE items (order_id INT REFERENCES orders (id), sku TEXT)";
+        let renames = [
+            Rename::Table {
+                from: name("customers"),
+                to: name("clients"),
+            },
+            Rename::Column {
+                table: name("clients"),
+                from: Ident::new("name"),
+                to: Ident::new("full_name"),
+            },
+            Rename::Column {
+                table: name("orders"),
+                from: Ident::new("customer_id"),
+                to: Ident::new("client_id"),
+            },
+        ];
+        assert_eq!(
+            migrate(from, to, &renames),
+            vec![
+                "ALTER TABLE customers RENAME TO clients",
+                "ALTER TABLE clients RENAME COLUMN name TO full_name",
+                "ALTER TABLE orders RENAME COLUMN customer_id TO client_id",
+                "DROP TABLE scratch",
+                "ALTER TABLE clients DROP COLUMN region",
+                "ALTER TABLE clients ALTER COLUMN full_name SET NOT NULL",
+                "ALTER TABLE clients ADD COLUMN email text",
+                "ALTER TABLE orders ALTER COLUMN total SET DATA TYPE bigint",
+                "ALTER TABLE orders ALTER COLUMN total DROP DEFAULT",
+                "CREATE TABLE items (order_id int, sku text)",
+                "ALTER TABLE orders ADD CONSTRAINT orders_client \
+                 FOREIGN KEY (client_id) REFERENCES clients(id)",
+                "ALTER TABLE items ADD FOREIGN KEY (order_id) REFERENCES orders(id)",
+            ]
+        );
+
+        // Without the renames, the old tables and columns are dropped
+        let sql = migrate(from, to, &[]);
+        assert!(sql.contains(&"DROP TABLE customers".to_string()));
+        assert!(sql.contains(&"ALTER TABLE orders DROP COLUMN customer_id".to_string()));
+    }
+
+    #[test]
+    fn test_diff_constraints_indexes_and_views() {
+        let from = "CREATE TABLE t (a INT CONSTRAINT a_positive CHECK (a > 0), b INT,
+                 CONSTRAINT t_b UNIQUE (b));
+             CREATE INDEX t_a ON t (a);
+             CREATE INDEX t_b_desc ON t (b DESC);
+             CREATE VIEW v AS SELECT a FROM t;
+             CREATE VIEW w AS SELECT b FROM t";
+        let to = "CREATE TABLE t (a INT, b INT, CONSTRAINT t_b UNIQUE (b),
+                 CONSTRAINT a_nonnegative CHECK (a >= 0));
+             CREATE INDEX t_a ON t (a);
+             CREATE UNIQUE INDEX t_b_desc ON t (b DESC);
+             CREATE VIEW v AS SELECT a FROM t;
+             CREATE VIEW w (total) AS SELECT a + b FROM t";
+        assert_eq!(
+            migrate(from, to, &[]),
+            vec![
+                "DROP VIEW w",
+                "DROP INDEX t_b_desc",
+                "ALTER TABLE t DROP CONSTRAINT a_positive",
+                "ALTER TABLE t ADD CONSTRAINT a_nonnegative CHECK (a >= 0)",
+                "CREATE UNIQUE INDEX t_b_desc ON t (b DESC)",
+                "CREATE VIEW w (total) AS SELECT a + b FROM t",
+            ]
+        );
+
+        // A constraint written as a column option is the same as the table
+        // constraint
+        let from = "CREATE TABLE t (a INT CONSTRAINT t_a UNIQUE)";
+        let to = "CREATE TABLE t (a INT, CONSTRAINT t_a UNIQUE (a))";
+        assert_eq!(migrate(from, to, &[]), Vec::<String>::new());
+
+        // Views that read a retyped column, or a dropped view, are dropped
+        // and created again
+        let from = "CREATE TABLE t (a INT, b INT);
+             CREATE TABLE s (c INT);
+             CREATE VIEW v AS SELECT a FROM t;
+             CREATE VIEW w AS SELECT a FROM v;
+             CREATE VIEW x AS SELECT c FROM s";
+        let to = "CREATE TABLE t (a BIGINT, b INT);
+             CREATE TABLE s (c INT);
+             CREATE VIEW v AS SELECT a FROM t;
+             CREATE VIEW w AS SELECT a FROM v;
+             CREATE VIEW x AS SELECT c FROM s";
+        assert_eq!(
+            migrate(from, to, &[]),
+            vec![
+                "DROP VIEW w",
+                "DROP VIEW v",
+                "ALTER TABLE t ALTER COLUMN a SET DATA TYPE bigint",
+                "CREATE VIEW v AS SELECT a FROM t",
+                "CREATE VIEW w AS SELECT a FROM v",
+            ]
+        );
+        let to = "CREATE TABLE t (a INT, b INT);
+             CREATE TABLE s (c INT);
+             CREATE VIEW v AS SELECT a FROM t WHERE b > 0;
+             CREATE VIEW w AS SELECT a FROM v;
+             CREATE VIEW x AS SELECT c FROM s";
+        assert_eq!(
+            migrate(from, to, &[]),
+            vec![
+                "DROP VIEW w",
+                "DROP VIEW v",
+                "CREATE VIEW v AS SELECT a FROM t WHERE b > 0",
+                "CREATE VIEW w AS SELECT a FROM v",
+            ]
+        );
+
+        // The columns of a dropped primary key stay NOT NULL
+        let from = "CREATE TABLE t (a INT CONSTRAINT t_pk PRIMARY KEY, b INT NOT NULL,
+                 c INT, CONSTRAINT t_bc_pk PRIMARY KEY (b, c))";
+        let to = "CREATE TABLE t (a INT, b INT NOT NULL, c INT)";
+        assert_eq!(
+            migrate(from, to, &[]),
+            vec![
+                "ALTER TABLE t DROP CONSTRAINT t_bc_pk",
+                "ALTER TABLE t DROP CONSTRAINT t_pk",
+                "ALTER TABLE t ALTER COLUMN a DROP NOT NULL",
+                "ALTER TABLE t ALTER COLUMN c DROP NOT NULL",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_views_ignores_comments() {
+        let from = "CREATE TABLE t (a INT);
+             CREATE VIEW v AS SELECT a -- the total
+             FROM t WHERE a > 0 /* positive */";
+        let to = "CREATE TABLE t (a INT);
+             CREATE VIEW v AS SELECT a FROM t WHERE a > 0";
+        assert_ne!(schema(from), schema(to));
+        assert_eq!(migrate(from, to, &[]), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_diff_unnamed_constraints() {
+        let from = schema_after(
+            &Schema::with_dialect(&PostgreSqlDialect {}),
+            "CREATE TABLE u (id INT PRIMARY KEY);
+             CREATE TABLE t (a INT UNIQUE, b INT REFERENCES u (id), c INT CHECK (c > 0),
+                 CHECK (a < c), PRIMARY KEY (a, b));
+             CREATE TABLE \"Mixed\" (x INT, y INT, UNIQUE (x, y))",
+        );
+        let to = schema(
+            "CREATE TABLE u (id INT PRIMARY KEY);
+             CREATE TABLE t (a INT, b INT, c INT);
+             CREATE TABLE \"Mixed\" (x INT, y INT)",
+        );
+        let sql: Vec<String> = diff_schemas(&PostgreSqlDialect {}, &from, &to, &[])
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            sql,
+            vec![
+                "ALTER TABLE t DROP CONSTRAINT t_check",
+                "ALTER TABLE t DROP CONSTRAINT t_pkey",
+                "ALTER TABLE t DROP CONSTRAINT t_a_key",
+                "ALTER TABLE t DROP CONSTRAINT t_b_fkey",
+                "ALTER TABLE t DROP CONSTRAINT t_c_check",
+                "ALTER TABLE \"Mixed\" DROP CONSTRAINT \"Mixed_x_y_key\"",
+                "ALTER TABLE t ALTER COLUMN a DROP NOT NULL",
+                "ALTER TABLE t ALTER COLUMN b DROP NOT NULL",
+            ]
+        );
+
+        // The schema knows the default names, so the migration applies to it
+        let migrated = schema_after(&from, &sql.join(";\n"));
+        assert_eq!(migrated, to);
+        assert_eq!(
+            diff_schemas(&PostgreSqlDialect {}, &migrated, &to, &[]).unwrap(),
+            vec![]
+        );
+        // Without the dialect, the names are unknown
+        let mut schema = schema("CREATE TABLE t (a INT UNIQUE)");
+        let sql = "ALTER TABLE t DROP CONSTRAINT t_a_key".to_string();
+        let statements = Parser::parse_sql(&GenericDialect {}, sql).unwrap();
+        let err = schema.apply(&statements[0]).unwrap_err();
+        assert_eq!(err.kind, SchemaErrorKind::UnknownConstraint);
+    }
+
+    #[test]
+    fn test_diff_errors() {
+        let from = schema("CREATE TABLE t (a INT UNIQUE, b INT)");
+        let to = schema("CREATE TABLE t (a INT, b INT)");
+        let err = diff_schemas(&GenericDialect {}, &from, &to, &[]).unwrap_err();
+        assert_eq!(err.kind, SchemaErrorKind::UnnamedConstraint);
+        assert_eq!(
+            err.to_string(),
+            "schema error: cannot drop constraint UNIQUE (a) of table t because it has no name"
+        );
+
+        // Unnamed constraints on dropped columns go with the column
+        let to = schema("CREATE TABLE t (b INT)");
+        let statements = diff_schemas(&GenericDialect {}, &from, &to, &[]).unwrap();
+        assert_eq!(statements.len(), 1);
+
+        let rename = Rename::Column {
+            table: name("t"),
+            from: Ident::new("c"),
+            to: Ident::new("d"),
+        };
+        let err = diff_schemas(&GenericDialect {}, &from, &from, &[rename]).unwrap_err();
+        assert_eq!(err.kind, SchemaErrorKind::UnknownColumn);
+    }
+}
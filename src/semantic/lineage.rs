@@ -0,0 +1,596 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::scope::{self, Analysis, Column, Lookup};
+use super::{idents_match, Catalog, Diagnostic, DiagnosticKind};
+use crate::ast::*;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The lineage of the columns that a statement writes, as returned by
+/// [column_lineage]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lineage {
+    /// The table inserted into, or the view created
+    pub target: ObjectName,
+    /// The columns written, in order
+    pub columns: Vec<ColumnLineage>,
+    /// The problems found in the statement. If it writes a different number
+    /// of columns than its query returns, only the columns that pair up have
+    /// a lineage.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Where the values of a column written by a statement come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnLineage {
+    pub target: Ident,
+    /// The columns of tables and views that the values are computed from,
+    /// each once, in order of appearance. Constants such as `SELECT 1` have
+    /// none.
+    pub sources: Vec<SourceColumn>,
+    pub kind: LineageKind,
+}
+
+/// A column of a table or view that a query reads
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceColumn {
+    /// The name of the table or view, as written in the query
+    pub table: ObjectName,
+    pub column: Ident,
+}
+
+impl fmt::Display for SourceColumn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.table, self.column)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineageKind {
+    /// The values are copied unchanged from the only source column, though
+    /// perhaps through aliases, CTEs, derived tables and `UNION`s
+    Direct,
+    /// The values are computed by an expression, such as a function call,
+    /// an arithmetic operation or a constant
+    Transformed,
+}
+
+/// Return the lineage of the columns written by `statement`, an `INSERT`
+/// or a `CREATE VIEW`, or `None` for other statements.
+///
+/// The sources of a column are the columns its expression refers to,
+/// followed through aliases, CTEs, derived tables, wildcards and the
+/// branches of set operations, including the outputs of subqueries in the
+/// expression. Columns that only filter, join or group rows, e.g. in a
+/// `WHERE`, are not sources. A reference to a table that is not in
+/// `catalog` is taken to be a column of that table, if the query has no
+/// other relation it may belong to.
+///
+/// Also returns `None` if the columns of the target cannot be known, as for
+/// an `INSERT` into a table that is not in `catalog` without a column list,
+/// or if a wildcard covers a relation whose columns are unknown.
+pub fn column_lineage(catalog: &dyn Catalog, statement: &Statement) -> Option<Lineage> {
+    let mut tracer = Tracer { catalog };
+    let (target, names, columns, message) = match statement {
+        Statement::Insert {
+            table_name,
+            columns,
+            source,
+            ..
+        } => {
+            let names = match columns.as_slice() {
+                [] => catalog.table_columns(table_name)?,
+                columns => columns.to_vec(),
+            };
+            let columns = tracer.query(source, None)?;
+            let message = match names.len().cmp(&columns.len()) {
+                Ordering::Less => Some("INSERT has more expressions than target columns"),
+                Ordering::Greater => Some("INSERT has more target columns than expressions"),
+                Ordering::Equal => None,
+            };
+            (table_name, names, columns, message)
+        }
+        Statement::CreateView {
+            name,
+            columns: names,
+            query,
+            ..
+        } => {
+            let columns = tracer.query(query, None)?;
+            let message = if names.len() > columns.len() {
+                Some("CREATE VIEW specifies more column names than columns")
+            } else {
+                None
+            };
+            let unnamed = || Ident::new("?column?");
+            let names = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| match names.get(i) {
+                    Some(name) => name.clone(),
+                    None => column.name.clone().unwrap_or_else(unnamed),
+                })
+                .collect();
+            (name, names, columns, message)
+        }
+        Statement::Commented { statement, .. } => return column_lineage(catalog, statement),
+        _ => return None,
+    };
+    Some(Lineage {
+        target: target.clone(),
+        columns: names
+            .into_iter()
+            .zip(columns)
+            .map(|(target, column)| ColumnLineage {
+                target,
+                sources: column.sources,
+                kind: if column.direct {
+                    LineageKind::Direct
+                } else {
+                    LineageKind::Transformed
+                },
+            })
+            .collect(),
+        diagnostics: message
+            .map(|message| Diagnostic {
+                kind: DiagnosticKind::ColumnCountMismatch,
+                message: message.to_string(),
+                span: target.span(),
+            })
+            .into_iter()
+            .collect(),
+    })
+}
+
+/// What the lineage of a column of a relation is known to be
+#[derive(Clone)]
+struct TracedColumn {
+    name: Option<Ident>,
+    sources: Vec<SourceColumn>,
+    /// Whether the values are copied unchanged from the only source
+    direct: bool,
+}
+
+impl Column for TracedColumn {
+    fn name(&self) -> Option<&Ident> {
+        self.name.as_ref()
+    }
+
+    fn rename(&mut self, name: Ident) {
+        self.name = Some(name);
+    }
+
+    fn unknown(name: Ident) -> Self {
+        TracedColumn {
+            name: Some(name),
+            sources: vec![],
+            direct: false,
+        }
+    }
+}
+
+type Columns = scope::Columns<TracedColumn>;
+type Scope<'s> = scope::Scope<'s, TracedColumn>;
+
+struct Tracer<'c> {
+    catalog: &'c dyn Catalog,
+}
+
+impl<'c> Analysis for Tracer<'c> {
+    type Column = TracedColumn;
+
+    /// The columns of the table or view `name` in the catalog, each its
+    /// own source
+    fn table_columns(&mut self, name: &ObjectName) -> Columns {
+        let columns = self.catalog.table_columns(name)?;
+        Some(
+            columns
+                .into_iter()
+                .map(|column| TracedColumn {
+                    name: Some(column.clone()),
+                    sources: vec![SourceColumn {
+                        table: name.clone(),
+                        column,
+                    }],
+                    direct: true,
+                })
+                .collect(),
+        )
+    }
+
+    /// Trace the output columns of `query`
+    fn query(&mut self, query: &Query, parent: Option<&Scope>) -> Columns {
+        let mut scope = Scope::new(parent);
+        for cte in &query.ctes {
+            let columns = self.query(&cte.query, Some(&scope));
+            let columns = scope::rename(columns, Some(&cte.alias));
+            scope.ctes.push((cte.alias.name.clone(), columns));
+        }
+        self.set_expr(&query.body, &scope)
+    }
+}
+
+impl<'c> Tracer<'c> {
+    fn set_expr(&mut self, body: &SetExpr, scope: &Scope) -> Columns {
+        match body {
+            SetExpr::Select(select) => self.select(select, scope),
+            SetExpr::Query(query) => self.query(query, Some(scope)),
+            SetExpr::SetOperation { left, right, .. } => {
+                let mut columns = self.set_expr(left, scope)?;
+                let right = self.set_expr(right, scope)?;
+                for (column, right) in columns.iter_mut().zip(right) {
+                    for source in right.sources {
+                        add(&mut column.sources, source);
+                    }
+                    column.direct &= right.direct;
+                }
+                Some(columns)
+            }
+            SetExpr::Values(values) => {
+                let width = values.0.first().map_or(0, Vec::len);
+                let mut columns = vec![];
+                for i in 0..width {
+                    let mut sources = vec![];
+                    for expr in values.0.iter().filter_map(|row| row.get(i)) {
+                        self.expr(expr, scope, &mut sources);
+                    }
+                    columns.push(TracedColumn {
+                        name: Some(Ident::new(format!("column{}", i + 1))),
+                        sources,
+                        direct: false,
+                    });
+                }
+                Some(columns)
+            }
+        }
+    }
+
+    fn select(&mut self, select: &Select, parent: &Scope) -> Columns {
+        let mut scope = Scope::select(parent);
+        for table in &select.from {
+            scope::table_with_joins(self, table, &mut scope);
+        }
+        let mut columns = vec![];
+        for item in &select.projection {
+            columns.extend(self.select_item(item, &scope)?);
+        }
+        Some(columns)
+    }
+
+    fn select_item(&mut self, item: &SelectItem, scope: &Scope) -> Columns {
+        let (expr, name) = match item {
+            SelectItem::UnnamedExpr(expr) => {
+                let name = match expr {
+                    Expr::Identifier(ident) => Some(ident.clone()),
+                    Expr::CompoundIdentifier(idents) => idents.last().cloned(),
+                    _ => None,
+                };
+                (expr, name)
+            }
+            SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias.clone())),
+            SelectItem::Wildcard(_) => return scope.wildcard(),
+            SelectItem::QualifiedWildcard(name, _) => return scope.qualified_wildcard(&name.0),
+            SelectItem::Commented { item, .. } => return self.select_item(item, scope),
+        };
+        let mut sources = vec![];
+        self.expr(expr, scope, &mut sources);
+        let direct = match without_parentheses(expr) {
+            Expr::Identifier(ident) => is_direct(scope, std::slice::from_ref(ident)),
+            Expr::CompoundIdentifier(idents) => is_direct(scope, idents),
+            _ => false,
+        };
+        Some(vec![TracedColumn {
+            name,
+            sources,
+            direct,
+        }])
+    }
+
+    /// Add the sources of the column `reference` to `sources`
+    fn column(&mut self, reference: &[Ident], scope: &Scope, sources: &mut Vec<SourceColumn>) {
+        match scope.column(reference) {
+            Lookup::Column(_, column, _) => {
+                for source in &column.sources {
+                    add(sources, source.clone());
+                }
+            }
+            Lookup::Unknown(relation, _) => {
+                if let (Some(table), Some(column)) = (relation.table(), reference.last()) {
+                    let table = table.clone();
+                    let column = column.clone();
+                    add(sources, SourceColumn { table, column });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Add the sources of the value of `expr` to `sources`
+    fn expr(&mut self, expr: &Expr, scope: &Scope, sources: &mut Vec<SourceColumn>) {
+        match expr {
+            Expr::Identifier(ident) => self.column(std::slice::from_ref(ident), scope, sources),
+            Expr::CompoundIdentifier(idents) => self.column(idents, scope, sources),
+            Expr::Wildcard(_)
+            | Expr::QualifiedWildcard(_, _)
+            | Expr::Value(_, _)
+            | Expr::Placeholder(_, _)
+            | Expr::Exists(_, _) => {}
+            Expr::IsNull(expr, _)
+            | Expr::IsNotNull(expr, _)
+            | Expr::UnaryOp { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Extract { expr, .. }
+            | Expr::Collate { expr, .. }
+            | Expr::Nested(expr, _) => self.expr(expr, scope, sources),
+            Expr::InList { expr, list, .. } => {
+                for expr in std::iter::once(&**expr).chain(list) {
+                    self.expr(expr, scope, sources);
+                }
+            }
+            Expr::InSubquery { expr, subquery, .. } => {
+                self.expr(expr, scope, sources);
+                self.subquery(subquery, scope, sources);
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                for expr in &[expr, low, high] {
+                    self.expr(expr, scope, sources);
+                }
+            }
+            Expr::BinaryOp { left, right, .. } => {
+                self.expr(left, scope, sources);
+                self.expr(right, scope, sources);
+            }
+            Expr::Function(function) => {
+                for arg in &function.args {
+                    self.expr(arg, scope, sources);
+                }
+                if let Some(over) = &function.over {
+                    for expr in &over.partition_by {
+                        self.expr(expr, scope, sources);
+                    }
+                    for order_by in &over.order_by {
+                        self.expr(&order_by.expr, scope, sources);
+                    }
+                }
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+                ..
+            } => {
+                let branches = conditions.iter().zip(results);
+                let exprs = branches.flat_map(|(condition, result)| vec![condition, result]);
+                let operand = operand.as_deref().into_iter();
+                for expr in operand.chain(exprs).chain(else_result.as_deref()) {
+                    self.expr(expr, scope, sources);
+                }
+            }
+            Expr::Subquery(query, _) => self.subquery(query, scope, sources),
+        }
+    }
+
+    /// Add the sources of the output columns of `query` to `sources`
+    fn subquery(&mut self, query: &Query, scope: &Scope, sources: &mut Vec<SourceColumn>) {
+        for column in self.query(query, Some(scope)).unwrap_or_default() {
+            for source in column.sources {
+                add(sources, source);
+            }
+        }
+    }
+}
+
+/// Whether the column `reference` has its values copied from its source
+fn is_direct(scope: &Scope, reference: &[Ident]) -> bool {
+    match scope.column(reference) {
+        Lookup::Column(_, column, _) => column.direct,
+        Lookup::Unknown(relation, _) => relation.table().is_some(),
+        _ => false,
+    }
+}
+
+fn without_parentheses(mut expr: &Expr) -> &Expr {
+    while let Expr::Nested(nested, _) = expr {
+        expr = nested;
+    }
+    expr
+}
+
+/// Add `source` to `sources`, unless it is already there
+fn add(sources: &mut Vec<SourceColumn>, source: SourceColumn) {
+    let same = |other: &SourceColumn| {
+        idents_match(&other.column, &source.column)
+            && other.table.0.len() == source.table.0.len()
+            && other
+                .table
+                .0
+                .iter()
+                .zip(&source.table.0)
+                .all(|(a, b)| idents_match(a, b))
+    };
+    if !sources.iter().any(same) {
+        sources.push(source);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+    use crate::semantic::Schema;
+
+    const DDL: &str = "CREATE TABLE customers (id INT, name TEXT, country TEXT);\
+                       CREATE TABLE orders (id INT, customer_id INT, total DECIMAL(10,2));\
+                       CREATE TABLE summary (customer TEXT, spent DECIMAL(10,2), n INT)";
+
+    fn lineage(sql: &str) -> Option<Lineage> {
+        let mut schema = Schema::new();
+        let ddl = Parser::parse_sql(&GenericDialect {}, DDL.to_string()).unwrap();
+        schema.apply_all(&ddl).unwrap();
+        let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        column_lineage(&schema, &statements[0])
+    }
+
+    /// Describe the columns as `target = sources`, with `~` before the
+    /// sources of transformed columns
+    fn columns(sql: &str) -> Vec<String> {
+        let lineage = lineage(sql).unwrap();
+        lineage
+            .columns
+            .iter()
+            .map(|column| {
+                let sources: Vec<String> = column.sources.iter().map(ToString::to_string).collect();
+                let kind = match column.kind {
+                    LineageKind::Direct => "",
+                    LineageKind::Transformed => "~",
+                };
+                format!("{} = {}{}", column.target, kind, sources.join(", "))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_select() {
+        assert_eq!(
+            columns(
+                "INSERT INTO summary SELECT c.name, SUM(o.total), COUNT(*) \
+                 FROM customers AS c JOIN orders AS o ON o.customer_id = c.id \
+                 WHERE c.country = 'NZ' GROUP BY c.name"
+            ),
+            vec![
+                "customer = customers.name",
+                "spent = ~orders.total",
+                "n = ~",
+            ]
+        );
+        assert_eq!(
+            columns(
+                "INSERT INTO summary (n, customer) SELECT (id), concat(name, country) FROM customers"
+            ),
+            vec![
+                "n = customers.id",
+                "customer = ~customers.name, customers.country"
+            ]
+        );
+        assert_eq!(
+            columns("INSERT INTO summary (customer, n) VALUES ('x', 1)"),
+            vec!["customer = ~", "n = ~"]
+        );
+        // Without a column list, the columns of an unknown table are unknown
+        assert_eq!(
+            lineage("INSERT INTO elsewhere SELECT * FROM customers"),
+            None
+        );
+        assert_eq!(lineage("SELECT * FROM customers"), None);
+    }
+
+    #[test]
+    fn test_create_view() {
+        let sql = "-- latest\nCREATE VIEW v (who) AS SELECT name, id + 1, CASE WHEN id > 0 \
+                   THEN (SELECT MAX(total) FROM orders) END AS biggest FROM customers";
+        assert_eq!(lineage(sql).unwrap().target.to_string(), "v");
+        assert_eq!(
+            columns(sql),
+            vec![
+                "who = customers.name",
+                "?column? = ~customers.id",
+                "biggest = ~customers.id, orders.total",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lineage_through_queries() {
+        assert_eq!(
+            columns(
+                "CREATE VIEW v AS WITH big (cid, amount) AS \
+                 (SELECT customer_id, total FROM orders WHERE total > 100) \
+                 SELECT s.*, amount * 2 AS doubled FROM (SELECT cid AS k FROM big) AS s, big"
+            ),
+            vec!["k = orders.customer_id", "doubled = ~orders.total"]
+        );
+        assert_eq!(
+            columns(
+                "CREATE VIEW v AS SELECT id, name FROM customers \
+                 UNION ALL SELECT customer_id, 'unknown' FROM orders"
+            ),
+            vec![
+                "id = customers.id, orders.customer_id",
+                "name = ~customers.name"
+            ]
+        );
+        assert_eq!(
+            columns("CREATE VIEW v AS SELECT * FROM customers JOIN orders USING (id)"),
+            vec![
+                "id = customers.id",
+                "name = customers.name",
+                "country = customers.country",
+                "customer_id = orders.customer_id",
+                "total = orders.total",
+            ]
+        );
+        // Columns of tables missing from the catalog are taken on trust
+        assert_eq!(
+            columns("CREATE VIEW v AS SELECT x.a, upper(b) FROM s.external AS x"),
+            vec!["a = s.external.a", "?column? = ~s.external.b"]
+        );
+        // Only a LATERAL derived table sees the tables before it
+        assert_eq!(
+            columns(
+                "CREATE VIEW v AS SELECT d.x FROM orders, (SELECT total AS x FROM external) AS d"
+            ),
+            vec!["x = external.total"]
+        );
+        assert_eq!(
+            columns("CREATE VIEW v AS SELECT d.x FROM orders, LATERAL (SELECT total AS x) AS d"),
+            vec!["x = orders.total"]
+        );
+    }
+
+    #[test]
+    fn test_column_count_mismatch() {
+        let messages = |sql: &str| -> Vec<String> {
+            let lineage = lineage(sql).unwrap();
+            for diagnostic in &lineage.diagnostics {
+                assert_eq!(diagnostic.kind, DiagnosticKind::ColumnCountMismatch);
+            }
+            let messages = lineage.diagnostics.iter().map(ToString::to_string);
+            messages.collect()
+        };
+        let sql = "INSERT INTO summary SELECT name FROM customers";
+        assert_eq!(
+            messages(sql),
+            vec!["INSERT has more target columns than expressions"]
+        );
+        assert_eq!(columns(sql), vec!["customer = customers.name"]);
+        let span = lineage(sql).unwrap().diagnostics[0].span;
+        assert_eq!(&sql[span.start.offset..span.end.offset], "summary");
+
+        assert_eq!(
+            messages("INSERT INTO summary (n) SELECT id, name FROM customers"),
+            vec!["INSERT has more expressions than target columns"]
+        );
+        assert_eq!(
+            messages("CREATE VIEW v (a, b) AS SELECT id FROM customers"),
+            vec!["CREATE VIEW specifies more column names than columns"]
+        );
+        assert_eq!(
+            messages("CREATE VIEW v (a) AS SELECT id, name FROM customers"),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,100 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Semantic analysis of parsed queries against a [Catalog] of tables, such
+//! as the [Schema] built by applying DDL statements: name resolution (see
+//! [resolve_query]), type inference (see [infer_query_types]), column
+//! lineage (see [column_lineage]), and migrations between schemas (see
+//! [diff_schemas]).
+//!
+//! Names are compared the way most databases do: unquoted identifiers are
+//! case-insensitive, and quoted identifiers match exactly (see
+//! [normalize_ident]).
+
+mod diff;
+mod lineage;
+mod resolve;
+mod schema;
+mod scope;
+mod types;
+
+pub use self::diff::{diff_schemas, Rename};
+pub use self::lineage::{column_lineage, ColumnLineage, Lineage, LineageKind, SourceColumn};
+pub use self::resolve::{resolve_query, BoundColumn, Relation, RelationKind, Resolution};
+pub use self::schema::{Index, Schema, SchemaError, SchemaErrorKind, Table, View};
+pub use self::types::{infer_expr_type, infer_query_types, ExprType, QueryTypes, TypedColumn};
+
+use crate::ast::{DataType, Ident, ObjectName, Span};
+use std::fmt;
+
+/// The tables and views that queries can refer to
+pub trait Catalog {
+    /// Return the columns of the table or view `name`, in order, or `None`
+    /// if there is no such table. `name` is written as in the query, so it
+    /// may or may not be qualified with a schema.
+    fn table_columns(&self, name: &ObjectName) -> Option<Vec<Ident>>;
+
+    /// Return the type of `column` of the table or view `name`, or `None`
+    /// if it is unknown. Catalogs that only know the names of columns can
+    /// leave this out.
+    fn column_type(&self, _name: &ObjectName, _column: &Ident) -> Option<DataType> {
+        None
+    }
+}
+
+/// A problem found in an otherwise well-formed query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    /// A human-readable description of the problem
+    pub message: String,
+    /// The location of the offending name, or `Span::empty()` if the query
+    /// was not produced by the parser
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// A table is not in the catalog, or a qualifier does not name any
+    /// relation in scope
+    UnknownTable,
+    /// A column does not exist in any relation in scope
+    UnknownColumn,
+    /// A column or qualifier matches more than one relation in scope
+    AmbiguousColumn,
+    /// The operands of an expression have types it cannot combine, such as
+    /// a boolean compared with a date
+    TypeMismatch,
+    /// A statement writes a different number of columns than its query
+    /// returns
+    ColumnCountMismatch,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Return the form of `ident` used to compare names: unquoted identifiers
+/// are folded to lower case, quoted ones are kept as they are.
+pub fn normalize_ident(ident: &Ident) -> String {
+    match ident.quote_style {
+        Some(_) => ident.value.clone(),
+        None => ident.value.to_lowercase(),
+    }
+}
+
+/// Whether `a` and `b` name the same object
+pub(crate) fn idents_match(a: &Ident, b: &Ident) -> bool {
+    normalize_ident(a) == normalize_ident(b)
+}
@@ -0,0 +1,721 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::scope::{self, Analysis, Column, Lookup, ScopeRelation};
+use super::{idents_match, Catalog, Diagnostic, DiagnosticKind};
+use crate::ast::*;
+use std::slice;
+
+/// A relation in the FROM clause of a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relation {
+    /// The name the query refers to the relation by: its alias, or else the
+    /// last part of its table name. `None` for a derived table without an
+    /// alias.
+    pub name: Option<Ident>,
+    pub kind: RelationKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelationKind {
+    /// A table or view from the catalog
+    Table(ObjectName),
+    /// A table-valued function, whose columns are unknown unless the alias
+    /// lists them
+    TableFunction(ObjectName),
+    /// A common table expression with the given name
+    Cte(Ident),
+    /// A subquery in the FROM clause
+    Derived,
+}
+
+/// A column reference, bound to the relation it comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundColumn {
+    /// The reference as written, e.g. `[t, a]` for `t.a`
+    pub reference: Vec<Ident>,
+    pub relation: Relation,
+    /// The name of the column in the relation, spelled as the catalog or
+    /// the subquery spells it
+    pub column: Ident,
+    /// How many queries out the relation is: 0 if it is in the FROM clause
+    /// of the query containing the reference, 1 for a correlated reference
+    /// to the enclosing query, and so on
+    pub depth: usize,
+}
+
+/// The result of [resolve_query]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Resolution {
+    /// The column references that were bound. The FROM clause of each
+    /// `SELECT` is resolved before its other clauses.
+    pub columns: Vec<BoundColumn>,
+    /// The references that could not be bound, and the tables missing from
+    /// the catalog
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Bind every column reference in `query` to the relation it comes from,
+/// looking up the tables it reads in `catalog`.
+///
+/// An unqualified reference to a column merged by a `USING` or `NATURAL`
+/// join is bound to the left-most relation that has it. The aliases of the
+/// output columns of a `SELECT` may be referenced in its ORDER BY and
+/// GROUP BY clauses; these are not column references, so they are skipped.
+/// The columns of a table missing from the catalog, or of a table-valued
+/// function, are unknown, so any column that no other relation has is bound
+/// to it.
+pub fn resolve_query(catalog: &dyn Catalog, query: &Query) -> Resolution {
+    let mut resolver = Resolver {
+        catalog,
+        resolution: Resolution::default(),
+    };
+    resolver.query(query, None);
+    resolver.resolution
+}
+
+/// Return the output columns of `query`, with `None` for unnamed ones, or
+/// `None` if they depend on tables missing from `catalog`
+pub(crate) fn query_columns(catalog: &dyn Catalog, query: &Query) -> Columns {
+    let mut resolver = Resolver {
+        catalog,
+        resolution: Resolution::default(),
+    };
+    resolver.query(query, None)
+}
+
+/// The columns of a relation, or the output columns of a query, with `None`
+/// for unnamed ones such as `SELECT 1`. The whole list is `None` when the
+/// columns are unknown.
+type Columns = scope::Columns<Option<Ident>>;
+type Scope<'s> = scope::Scope<'s, Option<Ident>>;
+
+impl Column for Option<Ident> {
+    fn name(&self) -> Option<&Ident> {
+        self.as_ref()
+    }
+
+    fn rename(&mut self, name: Ident) {
+        *self = Some(name);
+    }
+
+    fn unknown(name: Ident) -> Self {
+        Some(name)
+    }
+}
+
+/// Whether `expr` names one of the output `columns` of a query
+fn names_output_column(expr: &Expr, columns: &Columns) -> bool {
+    match (expr, columns) {
+        (Expr::Identifier(ident), Some(columns)) => columns
+            .iter()
+            .flatten()
+            .any(|column| idents_match(column, ident)),
+        (Expr::Identifier(_), None) => true,
+        _ => false,
+    }
+}
+
+fn without_comments(item: &SelectItem) -> &SelectItem {
+    match item {
+        SelectItem::Commented { item, .. } => without_comments(item),
+        item => item,
+    }
+}
+
+struct Resolver<'c> {
+    catalog: &'c dyn Catalog,
+    resolution: Resolution,
+}
+
+impl<'c> Analysis for Resolver<'c> {
+    type Column = Option<Ident>;
+
+    /// The columns of the table or view `name` in the catalog, reporting it
+    /// if it is missing
+    fn table_columns(&mut self, name: &ObjectName) -> Columns {
+        let columns = self.catalog.table_columns(name);
+        if columns.is_none() {
+            let message = format!("table {} does not exist", name);
+            self.report(DiagnosticKind::UnknownTable, message, name.span());
+        }
+        columns.map(|columns| columns.into_iter().map(Some).collect())
+    }
+
+    /// Resolve `query`, returning its output columns
+    fn query(&mut self, query: &Query, parent: Option<&Scope>) -> Columns {
+        let mut scope = Scope::new(parent);
+        for cte in &query.ctes {
+            let columns = self.query(&cte.query, Some(&scope));
+            let columns = scope::rename(columns, Some(&cte.alias));
+            scope.ctes.push((cte.alias.name.clone(), columns));
+        }
+        let columns = match &query.body {
+            SetExpr::Select(select) => self.select(select, &scope, &query.order_by),
+            body => {
+                let columns = self.set_expr(body, &scope);
+                for order_by in &query.order_by {
+                    if !names_output_column(&order_by.expr, &columns) {
+                        self.expr(&order_by.expr, &scope);
+                    }
+                }
+                columns
+            }
+        };
+        let fetch = query
+            .fetch
+            .as_ref()
+            .and_then(|fetch| fetch.quantity.as_ref());
+        for expr in query.limit.iter().chain(&query.offset).chain(fetch) {
+            self.expr(expr, &scope);
+        }
+        columns
+    }
+
+    fn join_condition(&mut self, condition: &Expr, scope: &Scope) {
+        self.expr(condition, scope);
+    }
+
+    fn table_function_args(&mut self, args: &[Expr], scope: &Scope) {
+        for arg in args {
+            self.expr(arg, scope);
+        }
+    }
+
+    fn diagnostic(&mut self, diagnostic: Diagnostic) {
+        self.resolution.diagnostics.push(diagnostic);
+    }
+}
+
+impl<'c> Resolver<'c> {
+    fn report(&mut self, kind: DiagnosticKind, message: String, span: Span) {
+        self.diagnostic(Diagnostic {
+            kind,
+            message,
+            span,
+        });
+    }
+
+    fn set_expr(&mut self, body: &SetExpr, scope: &Scope) -> Columns {
+        match body {
+            SetExpr::Select(select) => self.select(select, scope, &[]),
+            SetExpr::Query(query) => self.query(query, Some(scope)),
+            SetExpr::SetOperation { left, right, .. } => {
+                let columns = self.set_expr(left, scope);
+                self.set_expr(right, scope);
+                columns
+            }
+            SetExpr::Values(values) => {
+                for expr in values.0.iter().flatten() {
+                    self.expr(expr, scope);
+                }
+                let width = values.0.first().map_or(0, Vec::len);
+                Some(
+                    (1..=width)
+                        .map(|i| Some(Ident::new(format!("column{}", i))))
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    /// Resolve `select` and the ORDER BY of its query, returning its output
+    /// columns
+    fn select(&mut self, select: &Select, parent: &Scope, order_by: &[OrderByExpr]) -> Columns {
+        let mut scope = Scope::select(parent);
+        for table in &select.from {
+            scope::table_with_joins(self, table, &mut scope);
+        }
+        for item in &select.projection {
+            self.select_item(item, &scope);
+        }
+        if let Some(selection) = &select.selection {
+            self.expr(selection, &scope);
+        }
+        let aliases: Vec<&Ident> = select
+            .projection
+            .iter()
+            .filter_map(|item| match without_comments(item) {
+                SelectItem::ExprWithAlias { alias, .. } => Some(alias),
+                _ => None,
+            })
+            .collect();
+        let is_alias = |expr: &Expr| match expr {
+            Expr::Identifier(ident) => aliases.iter().any(|alias| idents_match(alias, ident)),
+            _ => false,
+        };
+        for expr in &select.group_by {
+            // Unlike ORDER BY, GROUP BY prefers input columns to aliases
+            if let Expr::Identifier(ident) = expr {
+                let lookup = scope.column(slice::from_ref(ident));
+                if is_alias(expr) && matches!(lookup, Lookup::NoColumn) {
+                    continue;
+                }
+            }
+            self.expr(expr, &scope);
+        }
+        if let Some(having) = &select.having {
+            self.expr(having, &scope);
+        }
+        for order_by in order_by {
+            if !is_alias(&order_by.expr) {
+                self.expr(&order_by.expr, &scope);
+            }
+        }
+        output_columns(select, &scope)
+    }
+
+    fn select_item(&mut self, item: &SelectItem, scope: &Scope) {
+        match item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                self.expr(expr, scope)
+            }
+            SelectItem::QualifiedWildcard(name, _) => self.qualifier(&name.0, scope),
+            SelectItem::Wildcard(_) => {}
+            SelectItem::Commented { item, .. } => self.select_item(item, scope),
+        }
+    }
+
+    /// Check that `qualifier` names exactly one relation in scope
+    fn qualifier(&mut self, qualifier: &[Ident], scope: &Scope) {
+        let name = ObjectName(qualifier.to_vec());
+        let (kind, message) = match scope.relation(qualifier) {
+            Lookup::Ambiguous => (
+                DiagnosticKind::AmbiguousColumn,
+                format!("table reference {} is ambiguous", name),
+            ),
+            Lookup::NoRelation => (
+                DiagnosticKind::UnknownTable,
+                format!("no relation named {} is in scope", name),
+            ),
+            _ => return,
+        };
+        self.report(kind, message, name.span());
+    }
+
+    fn column(&mut self, reference: &[Ident], scope: &Scope) {
+        let name = ObjectName(reference.to_vec());
+        let (kind, message) = match scope.column(reference) {
+            Lookup::Column(relation, Some(column), depth) => {
+                return self.bind(reference, relation, column, depth)
+            }
+            // A column found by name has one, so this is a relation whose
+            // columns are unknown, and the reference names the column
+            Lookup::Column(relation, None, depth) | Lookup::Unknown(relation, depth) => {
+                let column = &reference[reference.len() - 1];
+                return self.bind(reference, relation, column, depth);
+            }
+            Lookup::Relation(..) | Lookup::Undetermined => return,
+            Lookup::NoRelation => return self.qualifier(&reference[..reference.len() - 1], scope),
+            Lookup::NoColumn => (
+                DiagnosticKind::UnknownColumn,
+                format!("column {} does not exist", name),
+            ),
+            Lookup::Ambiguous => (
+                DiagnosticKind::AmbiguousColumn,
+                format!("column reference {} is ambiguous", name),
+            ),
+        };
+        self.report(kind, message, name.span());
+    }
+
+    fn bind(
+        &mut self,
+        reference: &[Ident],
+        relation: &ScopeRelation<Option<Ident>>,
+        column: &Ident,
+        depth: usize,
+    ) {
+        self.resolution.columns.push(BoundColumn {
+            reference: reference.to_vec(),
+            relation: relation.relation.clone(),
+            column: column.clone(),
+            depth,
+        });
+    }
+
+    fn expr(&mut self, expr: &Expr, scope: &Scope) {
+        match expr {
+            Expr::Identifier(ident) => self.column(slice::from_ref(ident), scope),
+            Expr::CompoundIdentifier(idents) => self.column(idents, scope),
+            Expr::QualifiedWildcard(idents, _) => self.qualifier(idents, scope),
+            Expr::Wildcard(_) | Expr::Value(_, _) | Expr::Placeholder(_, _) => {}
+            Expr::IsNull(expr, _)
+            | Expr::IsNotNull(expr, _)
+            | Expr::UnaryOp { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Extract { expr, .. }
+            | Expr::Collate { expr, .. }
+            | Expr::Nested(expr, _) => self.expr(expr, scope),
+            Expr::InList { expr, list, .. } => {
+                self.expr(expr, scope);
+                for item in list {
+                    self.expr(item, scope);
+                }
+            }
+            Expr::InSubquery { expr, subquery, .. } => {
+                self.expr(expr, scope);
+                self.query(subquery, Some(scope));
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                self.expr(expr, scope);
+                self.expr(low, scope);
+                self.expr(high, scope);
+            }
+            Expr::BinaryOp { left, right, .. } => {
+                self.expr(left, scope);
+                self.expr(right, scope);
+            }
+            Expr::Function(function) => {
+                for arg in &function.args {
+                    self.expr(arg, scope);
+                }
+                if let Some(over) = &function.over {
+                    for expr in &over.partition_by {
+                        self.expr(expr, scope);
+                    }
+                    for order_by in &over.order_by {
+                        self.expr(&order_by.expr, scope);
+                    }
+                }
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+                ..
+            } => {
+                let operands = operand.iter().chain(else_result).map(|expr| &**expr);
+                for expr in operands.chain(conditions).chain(results) {
+                    self.expr(expr, scope);
+                }
+            }
+            Expr::Exists(query, _) | Expr::Subquery(query, _) => {
+                self.query(query, Some(scope));
+            }
+        }
+    }
+}
+
+/// The output columns of `select`, whose FROM clause is in `scope`
+fn output_columns(select: &Select, scope: &Scope) -> Columns {
+    let mut columns = vec![];
+    for item in &select.projection {
+        match without_comments(item) {
+            SelectItem::UnnamedExpr(Expr::Identifier(ident)) => columns.push(Some(ident.clone())),
+            SelectItem::UnnamedExpr(Expr::CompoundIdentifier(idents)) => {
+                columns.push(idents.last().cloned())
+            }
+            SelectItem::UnnamedExpr(_) => columns.push(None),
+            SelectItem::ExprWithAlias { alias, .. } => columns.push(Some(alias.clone())),
+            SelectItem::Wildcard(_) => columns.extend(scope.wildcard()?),
+            SelectItem::QualifiedWildcard(name, _) => {
+                columns.extend(scope.qualified_wildcard(&name.0)?)
+            }
+            SelectItem::Commented { .. } => unreachable!(),
+        }
+    }
+    Some(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+    use crate::semantic::normalize_ident;
+
+    struct TestCatalog;
+
+    impl Catalog for TestCatalog {
+        fn table_columns(&self, name: &ObjectName) -> Option<Vec<Ident>> {
+            let columns: &[&str] = match normalize_ident(name.0.last()?).as_str() {
+                "customers" => &["id", "name", "region"],
+                "orders" => &["id", "customer_id", "total"],
+                _ => return None,
+            };
+            Some(columns.iter().map(|&column| Ident::new(column)).collect())
+        }
+    }
+
+    fn resolve(sql: &str) -> Resolution {
+        let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        match &statements[0] {
+            Statement::Query(query) => resolve_query(&TestCatalog, query),
+            _ => panic!("Expected a query: {}", sql),
+        }
+    }
+
+    /// Describe the bound columns as `reference -> relation.column`, with the
+    /// depth of correlated references after a `^`
+    fn bindings(resolution: &Resolution) -> Vec<String> {
+        resolution
+            .columns
+            .iter()
+            .map(|bound| {
+                let relation = match &bound.relation.name {
+                    Some(name) => name.to_string(),
+                    None => "?".to_string(),
+                };
+                let depth = match bound.depth {
+                    0 => String::new(),
+                    depth => format!("^{}", depth),
+                };
+                let reference = ObjectName(bound.reference.clone());
+                format!("{} -> {}.{}{}", reference, relation, bound.column, depth)
+            })
+            .collect()
+    }
+
+    fn diagnostics(resolution: &Resolution) -> Vec<(DiagnosticKind, &str)> {
+        resolution
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.kind, diagnostic.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_bind_columns() {
+        let resolution = resolve(
+            "SELECT c.name, TOTAL FROM customers AS c JOIN public.orders ON c.id = customer_id \
+             WHERE region = 'EU' AND public.orders.id > 1",
+        );
+        assert_eq!(
+            bindings(&resolution),
+            vec![
+                "c.id -> c.id",
+                "customer_id -> orders.customer_id",
+                "c.name -> c.name",
+                "TOTAL -> orders.total",
+                "region -> c.region",
+                "public.orders.id -> orders.id",
+            ]
+        );
+        assert_eq!(diagnostics(&resolution), vec![]);
+        assert_eq!(
+            resolution.columns[0].relation.kind,
+            RelationKind::Table(ObjectName(vec![Ident::new("customers")]))
+        );
+    }
+
+    #[test]
+    fn test_unknown_and_ambiguous_columns() {
+        let resolution =
+            resolve("SELECT id, nope, x.id, customers.\"ID\" FROM customers JOIN orders ON true");
+        assert_eq!(bindings(&resolution), Vec::<String>::new());
+        assert_eq!(
+            diagnostics(&resolution),
+            vec![
+                (
+                    DiagnosticKind::AmbiguousColumn,
+                    "column reference id is ambiguous"
+                ),
+                (DiagnosticKind::UnknownColumn, "column nope does not exist"),
+                (
+                    DiagnosticKind::UnknownTable,
+                    "no relation named x is in scope"
+                ),
+                (
+                    DiagnosticKind::UnknownColumn,
+                    "column customers.\"ID\" does not exist"
+                ),
+            ]
+        );
+
+        let sql = "SELECT customers.id FROM customers AS c";
+        let resolution = resolve(sql);
+        assert_eq!(
+            diagnostics(&resolution),
+            vec![(
+                DiagnosticKind::UnknownTable,
+                "no relation named customers is in scope"
+            )]
+        );
+        let span = resolution.diagnostics[0].span;
+        assert_eq!(&sql[span.start.offset..span.end.offset], "customers");
+    }
+
+    #[test]
+    fn test_unknown_tables() {
+        let resolution = resolve("SELECT a, m.b, id FROM missing AS m, customers");
+        assert_eq!(
+            bindings(&resolution),
+            vec!["a -> m.a", "m.b -> m.b", "id -> customers.id"]
+        );
+        assert_eq!(
+            diagnostics(&resolution),
+            vec![(DiagnosticKind::UnknownTable, "table missing does not exist")]
+        );
+
+        let resolution = resolve("SELECT a, g.n FROM missing, generate_series(1, 3) AS g (n)");
+        assert_eq!(bindings(&resolution), vec!["a -> missing.a", "g.n -> g.n"]);
+        assert_eq!(diagnostics(&resolution).len(), 1);
+    }
+
+    #[test]
+    fn test_ctes_and_derived_tables() {
+        let resolution = resolve(
+            "WITH big (cid, amount) AS (SELECT customer_id, total FROM orders WHERE total > 100) \
+             SELECT d.n, amount FROM big JOIN (SELECT name AS n, id FROM customers) AS d \
+             ON d.id = big.cid",
+        );
+        assert_eq!(
+            bindings(&resolution),
+            vec![
+                "customer_id -> orders.customer_id",
+                "total -> orders.total",
+                "total -> orders.total",
+                "name -> customers.name",
+                "id -> customers.id",
+                "d.id -> d.id",
+                "big.cid -> big.cid",
+                "d.n -> d.n",
+                "amount -> big.amount",
+            ]
+        );
+        assert_eq!(diagnostics(&resolution), vec![]);
+        assert_eq!(
+            resolution.columns[8].relation.kind,
+            RelationKind::Cte(Ident::new("big"))
+        );
+
+        let resolution = resolve(
+            "WITH big (cid) AS (SELECT customer_id FROM orders) \
+             SELECT customer_id FROM big, (SELECT 1) AS one",
+        );
+        assert_eq!(
+            diagnostics(&resolution),
+            vec![(
+                DiagnosticKind::UnknownColumn,
+                "column customer_id does not exist"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_using_and_natural_joins() {
+        let resolution =
+            resolve("SELECT id, customers.id, orders.id FROM customers JOIN orders USING (id)");
+        assert_eq!(
+            bindings(&resolution),
+            vec![
+                "id -> customers.id",
+                "customers.id -> customers.id",
+                "orders.id -> orders.id",
+            ]
+        );
+        assert_eq!(diagnostics(&resolution), vec![]);
+
+        let resolution = resolve("SELECT id FROM customers NATURAL JOIN orders");
+        assert_eq!(bindings(&resolution), vec!["id -> customers.id"]);
+        assert_eq!(diagnostics(&resolution), vec![]);
+
+        let resolution = resolve("SELECT * FROM customers JOIN orders USING (total)");
+        assert_eq!(
+            diagnostics(&resolution),
+            vec![(
+                DiagnosticKind::UnknownColumn,
+                "column total in USING does not exist in the left table"
+            )]
+        );
+
+        // The merged column appears once in the expansion of `*`
+        let resolution = resolve(
+            "SELECT * FROM (SELECT * FROM customers JOIN orders USING (id)) AS j (a, b, c) \
+             WHERE j.id IS NULL",
+        );
+        assert_eq!(
+            diagnostics(&resolution),
+            vec![(DiagnosticKind::UnknownColumn, "column j.id does not exist")]
+        );
+    }
+
+    #[test]
+    fn test_correlated_subqueries() {
+        let resolution = resolve(
+            "SELECT name FROM customers AS c WHERE EXISTS \
+             (SELECT 1 FROM orders WHERE customer_id = c.id AND id IN (SELECT region))",
+        );
+        assert_eq!(
+            bindings(&resolution),
+            vec![
+                "name -> c.name",
+                "customer_id -> orders.customer_id",
+                "c.id -> c.id^1",
+                "id -> orders.id",
+                "region -> c.region^2",
+            ]
+        );
+
+        // Only a LATERAL derived table sees the tables before it
+        let resolution = resolve("SELECT * FROM customers AS c, (SELECT c.id) AS d");
+        assert_eq!(
+            diagnostics(&resolution),
+            vec![(
+                DiagnosticKind::UnknownTable,
+                "no relation named c is in scope"
+            )]
+        );
+        let resolution = resolve("SELECT * FROM customers AS c, LATERAL (SELECT c.id) AS d");
+        assert_eq!(bindings(&resolution), vec!["c.id -> c.id^1"]);
+        assert_eq!(diagnostics(&resolution), vec![]);
+
+        // but it does see the queries around its own
+        let resolution = resolve(
+            "SELECT * FROM customers AS c WHERE EXISTS \
+             (SELECT * FROM orders, (SELECT c.id, total) AS d)",
+        );
+        assert_eq!(bindings(&resolution), vec!["c.id -> c.id^2"]);
+        assert_eq!(
+            diagnostics(&resolution),
+            vec![(DiagnosticKind::UnknownColumn, "column total does not exist")]
+        );
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let resolution = resolve(
+            "SELECT x.*, d.*, a, total FROM customers AS c, (SELECT * FROM orders) AS d (a)",
+        );
+        assert_eq!(bindings(&resolution), vec!["a -> d.a", "total -> d.total"]);
+        assert_eq!(
+            diagnostics(&resolution),
+            vec![(
+                DiagnosticKind::UnknownTable,
+                "no relation named x is in scope"
+            )]
+        );
+
+        let resolution = resolve("SELECT d.name FROM (SELECT c.*, 1 FROM customers AS c) AS d");
+        assert_eq!(bindings(&resolution), vec!["d.name -> d.name"]);
+        assert_eq!(diagnostics(&resolution), vec![]);
+    }
+
+    #[test]
+    fn test_output_column_aliases() {
+        let resolution =
+            resolve("SELECT region AS r, COUNT(*) AS n FROM customers GROUP BY r ORDER BY n DESC");
+        assert_eq!(bindings(&resolution), vec!["region -> customers.region"]);
+        assert_eq!(diagnostics(&resolution), vec![]);
+
+        let resolution =
+            resolve("SELECT id FROM customers UNION SELECT id FROM orders ORDER BY id, total");
+        assert_eq!(
+            diagnostics(&resolution),
+            vec![(DiagnosticKind::UnknownColumn, "column total does not exist")]
+        );
+    }
+}
@@ -0,0 +1,1006 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::resolve::query_columns;
+use super::{idents_match, infer_query_types, Catalog};
+use crate::ast::*;
+use crate::dialect::Dialect;
+use std::error::Error;
+use std::fmt;
+
+/// The tables, views and indexes produced by a sequence of DDL statements.
+///
+/// Feed the statements to [Schema::apply] in order. Statements that do not
+/// change the schema, such as queries and `INSERT`s, are ignored. Names are
+/// compared part by part, so `public.t` and `t` are different tables.
+///
+/// A schema made by [Schema::with_dialect] can drop the constraints
+/// declared without a name by the name the dialect gives them, such as the
+/// ones in the migrations of [crate::semantic::diff_schemas]. Two schemas
+/// are equal if they have the same tables, views and indexes, whatever
+/// their dialect.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    tables: Vec<Table>,
+    views: Vec<View>,
+    indexes: Vec<Index>,
+    dialect: Option<&'static (dyn Dialect + Sync)>,
+}
+
+impl PartialEq for Schema {
+    fn eq(&self, other: &Self) -> bool {
+        self.tables == other.tables && self.views == other.views && self.indexes == other.indexes
+    }
+}
+
+impl Eq for Schema {}
+
+/// A table in a [Schema]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub name: ObjectName,
+    pub columns: Vec<ColumnDef>,
+    /// The table constraints, including the ones added by `ALTER TABLE`.
+    /// Constraints declared as column options stay in [Table::columns].
+    pub constraints: Vec<TableConstraint>,
+    pub with_options: Vec<SqlOption>,
+}
+
+impl Table {
+    /// Find the column `name`
+    pub fn column(&self, name: &Ident) -> Option<&ColumnDef> {
+        self.columns
+            .iter()
+            .find(|column| idents_match(&column.name, name))
+    }
+}
+
+/// A view in a [Schema]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct View {
+    pub name: ObjectName,
+    /// The columns listed after the view name or, if there are none, the
+    /// output columns of the query. Empty if the query reads tables that
+    /// were not in the schema when the view was created.
+    pub columns: Vec<Ident>,
+    pub query: Query,
+    pub materialized: bool,
+    pub with_options: Vec<SqlOption>,
+}
+
+/// An index in a [Schema]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Index {
+    pub name: ObjectName,
+    pub table_name: ObjectName,
+    pub columns: Vec<OrderByExpr>,
+    pub unique: bool,
+}
+
+/// An error applying a statement to a [Schema]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    pub kind: SchemaErrorKind,
+    /// A human-readable description of the error
+    pub message: String,
+    /// The location of the offending name, or `Span::empty()` if the
+    /// statement was not produced by the parser
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SchemaErrorKind {
+    /// A table, view, column or constraint is created with the name of an
+    /// existing one
+    AlreadyExists,
+    /// A table, view or index that does not exist is altered, dropped or
+    /// referenced
+    UnknownTable,
+    /// A column that does not exist is referenced
+    UnknownColumn,
+    /// A constraint that does not exist is dropped
+    UnknownConstraint,
+    /// A table is dropped as a view, or the other way around
+    WrongObjectType,
+    /// A migration would have to drop a constraint that has no name
+    UnnamedConstraint,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "schema error: {}", self.message)
+    }
+}
+
+impl Error for SchemaError {}
+
+pub(crate) fn error(kind: SchemaErrorKind, message: String, span: Span) -> SchemaError {
+    SchemaError {
+        kind,
+        message,
+        span,
+    }
+}
+
+pub(crate) fn names_match(a: &ObjectName, b: &ObjectName) -> bool {
+    a.0.len() == b.0.len() && a.0.iter().zip(&b.0).all(|(a, b)| idents_match(a, b))
+}
+
+pub(crate) fn constraint_name(constraint: &TableConstraint) -> Option<&Ident> {
+    match constraint {
+        TableConstraint::Unique { name, .. }
+        | TableConstraint::ForeignKey { name, .. }
+        | TableConstraint::Check { name, .. } => name.as_ref(),
+    }
+}
+
+/// The table constraint equivalent to the option of `column`, if it is a
+/// constraint
+pub(crate) fn column_constraint(
+    column: &Ident,
+    option: &ColumnOptionDef,
+) -> Option<TableConstraint> {
+    let name = option.name.clone();
+    match &option.option {
+        ColumnOption::Unique { is_primary } => Some(TableConstraint::Unique {
+            name,
+            columns: vec![column.clone()],
+            is_primary: *is_primary,
+        }),
+        ColumnOption::ForeignKey {
+            foreign_table,
+            referred_columns,
+        } => Some(TableConstraint::ForeignKey {
+            name,
+            columns: vec![column.clone()],
+            foreign_table: foreign_table.clone(),
+            referred_columns: referred_columns.clone(),
+        }),
+        ColumnOption::Check(expr) => Some(TableConstraint::Check {
+            name,
+            expr: Box::new(expr.clone()),
+        }),
+        ColumnOption::Null | ColumnOption::NotNull | ColumnOption::Default(_) => None,
+    }
+}
+
+/// The columns of the table that `constraint` is on
+pub(crate) fn constraint_columns(constraint: &TableConstraint) -> &[Ident] {
+    match constraint {
+        TableConstraint::Unique { columns, .. } | TableConstraint::ForeignKey { columns, .. } => {
+            columns
+        }
+        TableConstraint::Check { .. } => &[],
+    }
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Schema::default()
+    }
+
+    /// An empty schema that knows the default constraint names of `dialect`
+    pub fn with_dialect(dialect: &'static (dyn Dialect + Sync)) -> Self {
+        Schema {
+            dialect: Some(dialect),
+            ..Schema::default()
+        }
+    }
+
+    /// The tables, in the order they were created
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    /// The views, in the order they were created
+    pub fn views(&self) -> &[View] {
+        &self.views
+    }
+
+    /// The indexes, in the order they were created
+    pub fn indexes(&self) -> &[Index] {
+        &self.indexes
+    }
+
+    pub fn table(&self, name: &ObjectName) -> Option<&Table> {
+        self.tables
+            .iter()
+            .find(|table| names_match(&table.name, name))
+    }
+
+    pub(crate) fn table_mut(&mut self, name: &ObjectName) -> Option<&mut Table> {
+        self.tables
+            .iter_mut()
+            .find(|table| names_match(&table.name, name))
+    }
+
+    pub fn view(&self, name: &ObjectName) -> Option<&View> {
+        self.views.iter().find(|view| names_match(&view.name, name))
+    }
+
+    pub fn index(&self, name: &ObjectName) -> Option<&Index> {
+        self.indexes
+            .iter()
+            .find(|index| names_match(&index.name, name))
+    }
+
+    /// Apply each of `statements` in turn, stopping at the first error
+    pub fn apply_all<'a, I>(&mut self, statements: I) -> Result<(), SchemaError>
+    where
+        I: IntoIterator<Item = &'a Statement>,
+    {
+        statements
+            .into_iter()
+            .try_for_each(|statement| self.apply(statement))
+    }
+
+    /// Update the schema with the effect of `statement`. If the statement
+    /// fails, the schema is left unchanged.
+    pub fn apply(&mut self, statement: &Statement) -> Result<(), SchemaError> {
+        match statement {
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                with_options,
+                ..
+            } => {
+                let table = Table {
+                    name: name.clone(),
+                    columns: columns.iter().map(|column| column.node.clone()).collect(),
+                    constraints: vec![],
+                    with_options: with_options.clone(),
+                };
+                self.create_table(table, constraints)
+            }
+            Statement::CreateView {
+                name,
+                columns,
+                query,
+                materialized,
+                with_options,
+                ..
+            } => {
+                self.check_new_name(name)?;
+                let columns = if columns.is_empty() {
+                    view_columns(self, query)
+                } else {
+                    columns.clone()
+                };
+                self.views.push(View {
+                    name: name.clone(),
+                    columns,
+                    query: (**query).clone(),
+                    materialized: *materialized,
+                    with_options: with_options.clone(),
+                });
+                Ok(())
+            }
+            Statement::CreateIndex {
+                name,
+                table_name,
+                columns,
+                unique,
+                if_not_exists,
+                ..
+            } => {
+                if *if_not_exists && self.index(name).is_some() {
+                    return Ok(());
+                }
+                self.check_new_name(name)?;
+                let table = match self.table(table_name) {
+                    Some(table) => table,
+                    None => return Err(self.missing(table_name, "table")),
+                };
+                let indexed: Vec<Ident> = columns.iter().filter_map(indexed_column).collect();
+                check_columns(table, &indexed)?;
+                self.indexes.push(Index {
+                    name: name.clone(),
+                    table_name: table_name.clone(),
+                    columns: columns.clone(),
+                    unique: *unique,
+                });
+                Ok(())
+            }
+            Statement::AlterTable {
+                name, operation, ..
+            } => self.alter_table(name, operation),
+            Statement::Drop {
+                object_type,
+                if_exists,
+                names,
+                ..
+            } => self.drop(object_type, *if_exists, names),
+            Statement::Commented { statement, .. } => self.apply(statement),
+            _ => Ok(()),
+        }
+    }
+
+    /// The kind of object named `name`, if there is one
+    fn object_kind(&self, name: &ObjectName) -> Option<&'static str> {
+        if self.table(name).is_some() {
+            Some("table")
+        } else if self.view(name).is_some() {
+            Some("view")
+        } else if self.index(name).is_some() {
+            Some("index")
+        } else {
+            None
+        }
+    }
+
+    /// Fail if a table, view or index named `name` already exists
+    fn check_new_name(&self, name: &ObjectName) -> Result<(), SchemaError> {
+        if self.object_kind(name).is_some() {
+            let message = format!("relation {} already exists", name);
+            return Err(error(SchemaErrorKind::AlreadyExists, message, name.span()));
+        }
+        Ok(())
+    }
+
+    fn create_table(
+        &mut self,
+        mut table: Table,
+        constraints: &[TableConstraint],
+    ) -> Result<(), SchemaError> {
+        self.check_new_name(&table.name)?;
+        for (i, column) in table.columns.iter().enumerate() {
+            if table.columns[..i]
+                .iter()
+                .any(|c| idents_match(&c.name, &column.name))
+            {
+                let message = format!("column {} specified more than once", column.name);
+                return Err(error(
+                    SchemaErrorKind::AlreadyExists,
+                    message,
+                    column.name.span,
+                ));
+            }
+            for option in &column.options {
+                if let ColumnOption::ForeignKey {
+                    foreign_table,
+                    referred_columns,
+                } = &option.option
+                {
+                    self.check_foreign_key(&table, foreign_table, referred_columns)?;
+                }
+            }
+        }
+        for constraint in constraints {
+            self.check_constraint(&table, constraint)?;
+            table.constraints.push(constraint.clone());
+        }
+        self.tables.push(table);
+        Ok(())
+    }
+
+    /// Check that `constraint` can be added to `table`: its name is new, and
+    /// the columns it references exist
+    fn check_constraint(
+        &self,
+        table: &Table,
+        constraint: &TableConstraint,
+    ) -> Result<(), SchemaError> {
+        if let Some(name) = constraint_name(constraint) {
+            let exists = table.constraints.iter().any(|existing| {
+                matches!(constraint_name(existing), Some(existing) if idents_match(existing, name))
+            });
+            if exists {
+                let message = format!("constraint {} already exists on {}", name, table.name);
+                return Err(error(SchemaErrorKind::AlreadyExists, message, name.span));
+            }
+        }
+        match constraint {
+            TableConstraint::Unique { columns, .. } => check_columns(table, columns),
+            TableConstraint::ForeignKey {
+                columns,
+                foreign_table,
+                referred_columns,
+                ..
+            } => {
+                check_columns(table, columns)?;
+                self.check_foreign_key(table, foreign_table, referred_columns)
+            }
+            TableConstraint::Check { .. } => Ok(()),
+        }
+    }
+
+    /// Check that `foreign_table`, referenced from `table`, has the
+    /// `referred_columns`
+    fn check_foreign_key(
+        &self,
+        table: &Table,
+        foreign_table: &ObjectName,
+        referred_columns: &[Ident],
+    ) -> Result<(), SchemaError> {
+        let referred = if names_match(&table.name, foreign_table) {
+            table
+        } else {
+            match self.table(foreign_table) {
+                Some(referred) => referred,
+                None => {
+                    let message = format!("table {} does not exist", foreign_table);
+                    let span = foreign_table.span();
+                    return Err(error(SchemaErrorKind::UnknownTable, message, span));
+                }
+            }
+        };
+        check_columns(referred, referred_columns)
+    }
+
+    fn alter_table(
+        &mut self,
+        name: &ObjectName,
+        operation: &AlterTableOperation,
+    ) -> Result<(), SchemaError> {
+        let index = match self.tables.iter().position(|t| names_match(&t.name, name)) {
+            Some(index) => index,
+            None => return Err(self.missing(name, "table")),
+        };
+        match operation {
+            AlterTableOperation::AddConstraint(constraint) => {
+                self.check_constraint(&self.tables[index], constraint)?;
+                self.tables[index].constraints.push(constraint.clone());
+            }
+            AlterTableOperation::AddColumn { column_def } => {
+                let table = &self.tables[index];
+                if table.column(&column_def.name).is_some() {
+                    let message = format!(
+                        "column {} of table {} already exists",
+                        column_def.name, table.name
+                    );
+                    return Err(error(
+                        SchemaErrorKind::AlreadyExists,
+                        message,
+                        column_def.name.span,
+                    ));
+                }
+                for option in &column_def.options {
+                    if let ColumnOption::ForeignKey {
+                        foreign_table,
+                        referred_columns,
+                    } = &option.option
+                    {
+                        self.check_foreign_key(table, foreign_table, referred_columns)?;
+                    }
+                }
+                self.tables[index].columns.push(column_def.clone());
+            }
+            AlterTableOperation::DropConstraint { name: constraint } => {
+                let dialect = self.dialect;
+                let table = &mut self.tables[index];
+                let table_name = table.name.clone();
+                // A constraint without a name goes by its default name
+                let is_named = |name: Option<&Ident>, declared: &TableConstraint| {
+                    let default =
+                        || dialect.and_then(|d| d.default_constraint_name(&table_name, declared));
+                    match name {
+                        Some(name) => idents_match(name, constraint),
+                        None => matches!(default(), Some(name) if idents_match(&name, constraint)),
+                    }
+                };
+                // The columns of a dropped primary key stay NOT NULL, as in
+                // PostgreSQL
+                if let Some(i) = table
+                    .constraints
+                    .iter()
+                    .position(|c| is_named(constraint_name(c), c))
+                {
+                    if let TableConstraint::Unique {
+                        columns,
+                        is_primary: true,
+                        ..
+                    } = table.constraints.remove(i)
+                    {
+                        for column in &mut table.columns {
+                            if columns.iter().any(|c| idents_match(c, &column.name)) {
+                                set_not_null(&mut column.options);
+                            }
+                        }
+                    }
+                    return Ok(());
+                }
+                for column in &mut table.columns {
+                    let column_name = &column.name;
+                    let is_option_named = |option: &ColumnOptionDef| {
+                        let declared = column_constraint(column_name, option);
+                        matches!(declared, Some(c) if is_named(option.name.as_ref(), &c))
+                    };
+                    if let Some(i) = column.options.iter().position(is_option_named) {
+                        let option = column.options.remove(i);
+                        if option.option == (ColumnOption::Unique { is_primary: true }) {
+                            set_not_null(&mut column.options);
+                        }
+                        return Ok(());
+                    }
+                }
+                let message = format!("constraint {} of table {} does not exist", constraint, name);
+                return Err(error(
+                    SchemaErrorKind::UnknownConstraint,
+                    message,
+                    constraint.span,
+                ));
+            }
+            AlterTableOperation::DropColumn {
+                column_name,
+                if_exists,
+                ..
+            } => {
+                let table = &mut self.tables[index];
+                match table
+                    .columns
+                    .iter()
+                    .position(|c| idents_match(&c.name, column_name))
+                {
+                    Some(i) => table.columns.remove(i),
+                    None if *if_exists => return Ok(()),
+                    None => return Err(unknown_column(table, column_name)),
+                };
+                // The table's own constraints and indexes that use the column
+                // go with it, as in PostgreSQL
+                let uses_column =
+                    |columns: &[Ident]| columns.iter().any(|c| idents_match(c, column_name));
+                table
+                    .constraints
+                    .retain(|constraint| !uses_column(constraint_columns(constraint)));
+                let table_name = &table.name;
+                self.indexes.retain(|index| {
+                    let columns: Vec<Ident> =
+                        index.columns.iter().filter_map(indexed_column).collect();
+                    !names_match(&index.table_name, table_name) || !uses_column(&columns)
+                });
+            }
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                let table = &self.tables[index];
+                if table.column(old_column_name).is_none() {
+                    return Err(unknown_column(table, old_column_name));
+                }
+                if table.column(new_column_name).is_some() {
+                    let message = format!(
+                        "column {} of table {} already exists",
+                        new_column_name, table.name
+                    );
+                    return Err(error(
+                        SchemaErrorKind::AlreadyExists,
+                        message,
+                        new_column_name.span,
+                    ));
+                }
+                let table_name = table.name.clone();
+                let rename = |ident: &mut Ident| {
+                    if idents_match(ident, old_column_name) {
+                        *ident = new_column_name.clone();
+                    }
+                };
+                let table = &mut self.tables[index];
+                for column in &mut table.columns {
+                    rename(&mut column.name);
+                }
+                for constraint in &mut table.constraints {
+                    if let TableConstraint::Unique { columns, .. }
+                    | TableConstraint::ForeignKey { columns, .. } = constraint
+                    {
+                        columns.iter_mut().for_each(rename);
+                    }
+                }
+                for table in &mut self.tables {
+                    for (foreign_table, referred_columns) in foreign_keys_mut(table) {
+                        if names_match(foreign_table, &table_name) {
+                            referred_columns.iter_mut().for_each(rename);
+                        }
+                    }
+                }
+                for index in &mut self.indexes {
+                    if names_match(&index.table_name, &table_name) {
+                        for column in &mut index.columns {
+                            if let Expr::Identifier(ident) = &mut column.expr {
+                                rename(ident);
+                            }
+                        }
+                    }
+                }
+            }
+            AlterTableOperation::RenameTable { table_name } => {
+                self.check_new_name(table_name)?;
+                let old_name = std::mem::replace(&mut self.tables[index].name, table_name.clone());
+                for table in &mut self.tables {
+                    for (foreign_table, _) in foreign_keys_mut(table) {
+                        if names_match(foreign_table, &old_name) {
+                            *foreign_table = table_name.clone();
+                        }
+                    }
+                }
+                for index in &mut self.indexes {
+                    if names_match(&index.table_name, &old_name) {
+                        index.table_name = table_name.clone();
+                    }
+                }
+            }
+            AlterTableOperation::AlterColumn { column_name, op } => {
+                let table = &mut self.tables[index];
+                let column = match table
+                    .columns
+                    .iter()
+                    .position(|c| idents_match(&c.name, column_name))
+                {
+                    Some(i) => &mut table.columns[i],
+                    None => return Err(unknown_column(table, column_name)),
+                };
+                let options = &mut column.options;
+                match op {
+                    AlterColumnOperation::SetNotNull => set_not_null(options),
+                    AlterColumnOperation::DropNotNull => {
+                        options.retain(|o| o.option != ColumnOption::NotNull)
+                    }
+                    AlterColumnOperation::SetDefault { value } => {
+                        options.retain(|o| !matches!(o.option, ColumnOption::Default(_)));
+                        options.push(ColumnOptionDef {
+                            name: None,
+                            option: ColumnOption::Default(value.clone()),
+                        });
+                    }
+                    AlterColumnOperation::DropDefault => {
+                        options.retain(|o| !matches!(o.option, ColumnOption::Default(_)))
+                    }
+                    AlterColumnOperation::SetDataType { data_type } => {
+                        column.data_type = data_type.clone()
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn drop(
+        &mut self,
+        object_type: &ObjectType,
+        if_exists: bool,
+        names: &[ObjectName],
+    ) -> Result<(), SchemaError> {
+        let expected = match object_type {
+            ObjectType::Table => "table",
+            ObjectType::View => "view",
+            ObjectType::Index => "index",
+        };
+        // Check all the names before dropping anything, so that a failed
+        // statement has no effect
+        for name in names {
+            match self.object_kind(name) {
+                Some(kind) if kind != expected => {
+                    let message = format!("{} is a {}, not a {}", name, kind, expected);
+                    return Err(error(
+                        SchemaErrorKind::WrongObjectType,
+                        message,
+                        name.span(),
+                    ));
+                }
+                None if !if_exists => return Err(self.missing(name, expected)),
+                _ => {}
+            }
+        }
+        for name in names {
+            match object_type {
+                ObjectType::Table => {
+                    self.tables.retain(|table| !names_match(&table.name, name));
+                    self.indexes
+                        .retain(|index| !names_match(&index.table_name, name));
+                }
+                ObjectType::View => self.views.retain(|view| !names_match(&view.name, name)),
+                ObjectType::Index => self.indexes.retain(|index| !names_match(&index.name, name)),
+            }
+        }
+        Ok(())
+    }
+
+    fn missing(&self, name: &ObjectName, expected: &str) -> SchemaError {
+        let message = format!("{} {} does not exist", expected, name);
+        error(SchemaErrorKind::UnknownTable, message, name.span())
+    }
+}
+
+/// The output columns of a view defined as `query`, without a list of
+/// column names
+pub(crate) fn view_columns(schema: &Schema, query: &Query) -> Vec<Ident> {
+    let unnamed = || Ident::new("?column?");
+    let columns = query_columns(schema, query).unwrap_or_default();
+    columns
+        .into_iter()
+        .map(|column| column.unwrap_or_else(unnamed))
+        .collect()
+}
+
+/// Make the column with the `options` NOT NULL
+fn set_not_null(options: &mut Vec<ColumnOptionDef>) {
+    options.retain(|o| o.option != ColumnOption::Null);
+    if !options.iter().any(|o| o.option == ColumnOption::NotNull) {
+        options.push(ColumnOptionDef {
+            name: None,
+            option: ColumnOption::NotNull,
+        });
+    }
+}
+
+/// Check that `table` has all the `columns`
+fn check_columns(table: &Table, columns: &[Ident]) -> Result<(), SchemaError> {
+    match columns.iter().find(|column| table.column(column).is_none()) {
+        Some(column) => Err(unknown_column(table, column)),
+        None => Ok(()),
+    }
+}
+
+fn unknown_column(table: &Table, column: &Ident) -> SchemaError {
+    let message = format!("column {} of table {} does not exist", column, table.name);
+    error(SchemaErrorKind::UnknownColumn, message, column.span)
+}
+
+/// The column an index entry is on, unless it is an expression
+fn indexed_column(column: &OrderByExpr) -> Option<Ident> {
+    match &column.expr {
+        Expr::Identifier(ident) => Some(ident.clone()),
+        _ => None,
+    }
+}
+
+/// The foreign tables and referred columns of all the foreign keys of
+/// `table`, whether declared as table constraints or column options
+fn foreign_keys_mut(
+    table: &mut Table,
+) -> impl Iterator<Item = (&mut ObjectName, &mut Vec<Ident>)> + '_ {
+    let constraints = table.constraints.iter_mut().filter_map(|c| match c {
+        TableConstraint::ForeignKey {
+            foreign_table,
+            referred_columns,
+            ..
+        } => Some((foreign_table, referred_columns)),
+        _ => None,
+    });
+    let options = table
+        .columns
+        .iter_mut()
+        .flat_map(|column| column.options.iter_mut())
+        .filter_map(|option| match &mut option.option {
+            ColumnOption::ForeignKey {
+                foreign_table,
+                referred_columns,
+            } => Some((foreign_table, referred_columns)),
+            _ => None,
+        });
+    constraints.chain(options)
+}
+
+impl Catalog for Schema {
+    fn table_columns(&self, name: &ObjectName) -> Option<Vec<Ident>> {
+        match self.table(name) {
+            Some(table) => Some(table.columns.iter().map(|c| c.name.clone()).collect()),
+            None => self.view(name).map(|view| view.columns.clone()),
+        }
+    }
+
+    fn column_type(&self, name: &ObjectName, column: &Ident) -> Option<DataType> {
+        if let Some(table) = self.table(name) {
+            return Some(table.column(column)?.data_type.clone());
+        }
+        let view = self.view(name)?;
+        let i = view.columns.iter().position(|c| idents_match(c, column))?;
+        let columns = infer_query_types(self, &view.query).columns?;
+        columns.into_iter().nth(i)?.data_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+    use crate::semantic::resolve_query;
+
+    fn parse(sql: &str) -> Vec<Statement> {
+        Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap()
+    }
+
+    fn schema(sql: &str) -> Result<Schema, SchemaError> {
+        let mut schema = Schema::new();
+        schema.apply_all(&parse(sql))?;
+        Ok(schema)
+    }
+
+    fn error_kind(sql: &str) -> SchemaErrorKind {
+        schema(sql).unwrap_err().kind
+    }
+
+    fn name(name: &str) -> ObjectName {
+        ObjectName(vec![Ident::new(name)])
+    }
+
+    fn column_names(schema: &Schema, table: &str) -> Vec<String> {
+        let columns = schema.table_columns(&name(table)).unwrap();
+        columns.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_apply_migration() {
+        let schema = schema(
+            "CREATE TABLE customers (id INT PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE orders (id INT, customer_id INT CONSTRAINT fk REFERENCES customers (id));
+             CREATE TABLE scratch (x INT);
+             INSERT INTO scratch VALUES (1);
+             ALTER TABLE orders ADD CONSTRAINT pk PRIMARY KEY (id);
+             ALTER TABLE orders DROP CONSTRAINT fk;
+             DROP TABLE scratch;
+             DROP TABLE IF EXISTS scratch;",
+        )
+        .unwrap();
+        let tables: Vec<String> = schema.tables().iter().map(|t| t.name.to_string()).collect();
+        assert_eq!(tables, vec!["customers", "orders"]);
+        assert_eq!(column_names(&schema, "ORDERS"), vec!["id", "customer_id"]);
+
+        let orders = schema.table(&name("orders")).unwrap();
+        assert_eq!(
+            orders.column(&Ident::new("id")).unwrap().data_type,
+            DataType::Int
+        );
+        assert_eq!(
+            orders.column(&Ident::new("customer_id")).unwrap().options,
+            vec![]
+        );
+        assert_eq!(
+            orders.constraints,
+            vec![TableConstraint::Unique {
+                name: Some(Ident::new("pk")),
+                columns: vec![Ident::new("id")],
+                is_primary: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_alter_columns_and_indexes() {
+        let mut schema = schema(
+            "CREATE TABLE a (id INT PRIMARY KEY, x INT DEFAULT 0, y INT);
+             CREATE TABLE b (a_id INT REFERENCES a (id), z INT);
+             CREATE INDEX a_x ON a (x);
+             CREATE INDEX a_y ON a (y, x);
+             CREATE INDEX IF NOT EXISTS a_x ON a (y);
+             ALTER TABLE a RENAME COLUMN id TO key;
+             ALTER TABLE a RENAME TO c;
+             ALTER TABLE c ADD COLUMN w TEXT NOT NULL;
+             ALTER TABLE c DROP COLUMN y;
+             ALTER TABLE c DROP COLUMN IF EXISTS y;
+             ALTER TABLE c ALTER COLUMN x SET NOT NULL;
+             ALTER TABLE c ALTER COLUMN x DROP DEFAULT;
+             ALTER TABLE c ALTER COLUMN x SET DATA TYPE BIGINT;",
+        )
+        .unwrap();
+        assert_eq!(column_names(&schema, "c"), vec!["key", "x", "w"]);
+        let x = schema.table(&name("c")).unwrap().column(&Ident::new("x"));
+        assert_eq!(x.unwrap().to_string(), "x bigint NOT NULL");
+        assert_eq!(
+            schema.table(&name("b")).unwrap().columns[0].to_string(),
+            "a_id int REFERENCES c (key)"
+        );
+        // The index on the dropped column went with it
+        let indexes: Vec<String> = schema
+            .indexes()
+            .iter()
+            .map(|i| i.name.to_string())
+            .collect();
+        assert_eq!(indexes, vec!["a_x"]);
+        assert_eq!(schema.index(&name("a_x")).unwrap().table_name, name("c"));
+
+        schema.apply(&parse("DROP INDEX a_x")[0]).unwrap();
+        assert!(schema.indexes().is_empty());
+        schema
+            .apply(&parse("CREATE INDEX c_w ON c (w)")[0])
+            .unwrap();
+        schema.apply(&parse("DROP TABLE c")[0]).unwrap();
+        assert!(schema.indexes().is_empty());
+    }
+
+    #[test]
+    fn test_views() {
+        let mut schema = schema(
+            "CREATE TABLE t (a INT, b INT);
+             CREATE VIEW v AS SELECT a, b + 1 AS c, 2 FROM t;
+             CREATE VIEW w (x) AS SELECT a FROM v;",
+        )
+        .unwrap();
+        assert_eq!(column_names(&schema, "v"), vec!["a", "c", "?column?"]);
+        assert_eq!(column_names(&schema, "w"), vec!["x"]);
+
+        // The schema can be the catalog for name resolution
+        let resolution = match &parse("SELECT v.c, x FROM v, w")[0] {
+            Statement::Query(query) => resolve_query(&schema, query),
+            _ => unreachable!(),
+        };
+        assert_eq!(resolution.columns.len(), 2);
+        assert_eq!(resolution.diagnostics, vec![]);
+
+        schema.apply(&parse("DROP VIEW w")[0]).unwrap();
+        assert!(schema.view(&name("w")).is_none());
+        assert_eq!(schema.views().len(), 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        use SchemaErrorKind::*;
+        assert_eq!(error_kind("DROP TABLE t"), UnknownTable);
+        assert_eq!(error_kind("ALTER TABLE t ADD UNIQUE (a)"), UnknownTable);
+        assert_eq!(
+            error_kind("CREATE TABLE t (a INT); CREATE VIEW t AS SELECT 1"),
+            AlreadyExists
+        );
+        assert_eq!(error_kind("CREATE TABLE t (a INT, A TEXT)"), AlreadyExists);
+        assert_eq!(
+            error_kind("CREATE TABLE t (a INT, UNIQUE (b))"),
+            UnknownColumn
+        );
+        assert_eq!(
+            error_kind("CREATE TABLE t (a INT REFERENCES u (a))"),
+            UnknownTable
+        );
+        assert_eq!(
+            error_kind("CREATE TABLE t (a INT, b INT, FOREIGN KEY (b) REFERENCES t (c))"),
+            UnknownColumn
+        );
+        assert_eq!(
+            error_kind(
+                "CREATE TABLE t (a INT, CONSTRAINT c UNIQUE (a)); \
+                 ALTER TABLE t ADD CONSTRAINT c CHECK (a > 0)"
+            ),
+            AlreadyExists
+        );
+        assert_eq!(
+            error_kind("CREATE TABLE t (a INT); DROP VIEW t"),
+            WrongObjectType
+        );
+        assert_eq!(
+            error_kind("CREATE TABLE t (a INT); CREATE INDEX i ON t (a); DROP TABLE i"),
+            WrongObjectType
+        );
+        assert_eq!(
+            error_kind("CREATE TABLE t (a INT); CREATE INDEX i ON t (b)"),
+            UnknownColumn
+        );
+        assert_eq!(
+            error_kind("CREATE TABLE t (a INT); ALTER TABLE t ADD COLUMN A INT"),
+            AlreadyExists
+        );
+        assert_eq!(
+            error_kind("CREATE TABLE t (a INT, b INT); ALTER TABLE t RENAME a TO b"),
+            AlreadyExists
+        );
+        assert_eq!(
+            error_kind("CREATE TABLE t (a INT); ALTER TABLE t ALTER b DROP NOT NULL"),
+            UnknownColumn
+        );
+
+        let err = schema("CREATE TABLE t (a INT);\nDROP TABLE u").unwrap_err();
+        assert_eq!(err.to_string(), "schema error: table u does not exist");
+        assert_eq!((err.span.start.line, err.span.start.column), (2, 12));
+
+        // A failed statement has no effect
+        let mut schema = schema("CREATE TABLE t (a INT)").unwrap();
+        let before = schema.clone();
+        assert!(schema.apply(&parse("DROP TABLE t, u")[0]).is_err());
+        let err = schema
+            .apply(&parse("ALTER TABLE t DROP CONSTRAINT c")[0])
+            .unwrap_err();
+        assert_eq!(err.kind, UnknownConstraint);
+        assert!(schema
+            .apply(&parse("ALTER TABLE t ADD UNIQUE (b)")[0])
+            .is_err());
+        assert_eq!(schema, before);
+    }
+}
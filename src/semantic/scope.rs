@@ -0,0 +1,479 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The relations visible to the expressions of a query, for the analyses
+//! that follow something about each column, such as its type, through
+//! queries.
+
+use super::{idents_match, Diagnostic, DiagnosticKind, Relation, RelationKind};
+use crate::ast::*;
+use std::ops::Range;
+
+/// What an analysis knows about a column
+pub(super) trait Column: Clone {
+    /// The name of the column, or `None` for an unnamed one
+    fn name(&self) -> Option<&Ident>;
+
+    /// Rename the column, as the column list of a table alias does
+    fn rename(&mut self, name: Ident);
+
+    /// A column named by a column list of a table alias, which the relation
+    /// it renames is not known to have
+    fn unknown(name: Ident) -> Self;
+}
+
+/// The columns of a relation, or `None` if they are unknown
+pub(super) type Columns<C> = Option<Vec<C>>;
+
+/// A relation in a FROM clause, as seen by the column references in its
+/// query
+pub(super) struct ScopeRelation<C> {
+    pub relation: Relation,
+    /// Whether the relation has an alias, which hides the table name
+    aliased: bool,
+    pub columns: Columns<C>,
+    /// The columns merged into a column to the left by a `USING` or
+    /// `NATURAL` join, which unqualified references and `*` don't see
+    merged: Vec<Ident>,
+}
+
+impl<C: Column> ScopeRelation<C> {
+    /// A relation without a name, whose columns are `columns`
+    pub fn unnamed(columns: Vec<C>) -> Self {
+        ScopeRelation {
+            relation: Relation {
+                name: None,
+                kind: RelationKind::Derived,
+            },
+            aliased: false,
+            columns: Some(columns),
+            merged: vec![],
+        }
+    }
+
+    /// The name of the table or view from the catalog the relation reads,
+    /// if it does
+    pub fn table(&self) -> Option<&ObjectName> {
+        match &self.relation.kind {
+            RelationKind::Table(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Whether `qualifier` refers to this relation
+    fn matches(&self, qualifier: &[Ident]) -> bool {
+        match (self.table(), qualifier) {
+            (_, [ident]) => {
+                matches!(&self.relation.name, Some(name) if idents_match(name, ident))
+            }
+            (Some(name), _) if !self.aliased => {
+                name.0.len() >= qualifier.len()
+                    && name.0[name.0.len() - qualifier.len()..]
+                        .iter()
+                        .zip(qualifier)
+                        .all(|(a, b)| idents_match(a, b))
+            }
+            _ => false,
+        }
+    }
+
+    /// Find the column `name`, ignoring the merged columns unless the
+    /// reference is `qualified`
+    fn column(&self, name: &Ident, qualified: bool) -> Option<&C> {
+        self.columns.as_ref()?.iter().find(|column| {
+            matches!(column.name(), Some(column) if idents_match(column, name))
+                && (qualified || !self.is_merged(name))
+        })
+    }
+
+    fn is_merged(&self, name: &Ident) -> bool {
+        self.merged.iter().any(|merged| idents_match(merged, name))
+    }
+
+    /// The columns that an unqualified `*` expands to
+    fn visible_columns(&self) -> Columns<C> {
+        let columns = self.columns.as_ref()?;
+        Some(
+            columns
+                .iter()
+                .filter(|column| !matches!(column.name(), Some(name) if self.is_merged(name)))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+/// What a column reference or a qualifier refers to. The relations come
+/// with the number of queries out they are: 0 for the FROM clause of the
+/// query containing the reference, 1 for the enclosing query, and so on.
+pub(super) enum Lookup<'a, C> {
+    /// The column and the relation it comes from
+    Column(&'a ScopeRelation<C>, &'a C, usize),
+    /// The only relation that may have the column, whose columns are
+    /// unknown
+    Unknown(&'a ScopeRelation<C>, usize),
+    /// The relation named by a qualifier
+    Relation(&'a ScopeRelation<C>, usize),
+    /// The column may come from more than one relation whose columns are
+    /// unknown
+    Undetermined,
+    NoColumn,
+    NoRelation,
+    Ambiguous,
+}
+
+/// The names visible to a query or a `SELECT`
+pub(super) struct Scope<'s, C> {
+    parent: Option<&'s Scope<'s, C>>,
+    /// Whether the relations of the parent scope are visible, which they are
+    /// not from a derived table unless it is `LATERAL`
+    parent_relations_visible: bool,
+    /// Whether this is the scope of a `SELECT`, rather than of the CTEs of
+    /// a query
+    select: bool,
+    pub ctes: Vec<(Ident, Columns<C>)>,
+    pub relations: Vec<ScopeRelation<C>>,
+}
+
+impl<'s, C: Column> Scope<'s, C> {
+    /// The scope of the CTEs of a query
+    pub fn new(parent: Option<&'s Scope<'s, C>>) -> Self {
+        Scope {
+            parent,
+            parent_relations_visible: true,
+            select: false,
+            ctes: vec![],
+            relations: vec![],
+        }
+    }
+
+    /// The scope of a `SELECT`, whose FROM clause adds the relations
+    pub fn select(parent: &'s Scope<'s, C>) -> Self {
+        Scope {
+            select: true,
+            ..Scope::new(Some(parent))
+        }
+    }
+
+    /// The scope around a derived table that is not `LATERAL`, which does
+    /// not see the relations before it in the FROM clause of `parent`
+    fn derived(parent: &'s Scope<'s, C>) -> Self {
+        Scope {
+            parent_relations_visible: false,
+            ..Scope::new(Some(parent))
+        }
+    }
+
+    /// The scopes whose relations are visible from this one, innermost
+    /// first, with the number of queries out they are
+    fn visible_scopes(&self) -> Vec<(&Scope<'s, C>, usize)> {
+        let mut scopes = vec![];
+        let mut current = Some(self);
+        let mut visible = true;
+        let mut depth = 0;
+        while let Some(scope) = current {
+            if visible {
+                scopes.push((scope, depth));
+            }
+            if scope.select {
+                depth += 1;
+            }
+            visible = scope.parent_relations_visible;
+            current = scope.parent;
+        }
+        scopes
+    }
+
+    /// Find the CTE `name`, searching outward from this scope
+    fn cte(&self, name: &Ident) -> Option<&Columns<C>> {
+        self.ctes
+            .iter()
+            .rev()
+            .find(|(cte, _)| idents_match(cte, name))
+            .map(|(_, columns)| columns)
+            .or_else(|| self.parent?.cte(name))
+    }
+
+    /// Find the relation named by `qualifier`, searching outward from this
+    /// scope
+    pub fn relation(&self, qualifier: &[Ident]) -> Lookup<'_, C> {
+        for (scope, depth) in self.visible_scopes() {
+            let mut relations = scope.relations.iter().filter(|r| r.matches(qualifier));
+            if let Some(relation) = relations.next() {
+                return match relations.next() {
+                    Some(_) => Lookup::Ambiguous,
+                    None => Lookup::Relation(relation, depth),
+                };
+            }
+        }
+        Lookup::NoRelation
+    }
+
+    /// Find the column `reference`, searching outward from this scope
+    pub fn column(&self, reference: &[Ident]) -> Lookup<'_, C> {
+        let (name, qualifier) = match reference.split_last() {
+            Some(split) => split,
+            None => return Lookup::NoColumn,
+        };
+        if !qualifier.is_empty() {
+            return match self.relation(qualifier) {
+                Lookup::Relation(relation, depth) if relation.columns.is_none() => {
+                    Lookup::Unknown(relation, depth)
+                }
+                Lookup::Relation(relation, depth) => match relation.column(name, true) {
+                    Some(column) => Lookup::Column(relation, column, depth),
+                    None => Lookup::NoColumn,
+                },
+                other => other,
+            };
+        }
+        for (scope, depth) in self.visible_scopes() {
+            let mut found = scope
+                .relations
+                .iter()
+                .filter_map(|relation| Some((relation, relation.column(name, false)?)));
+            match (found.next(), found.next()) {
+                (Some((relation, column)), None) => return Lookup::Column(relation, column, depth),
+                (Some(_), Some(_)) => return Lookup::Ambiguous,
+                _ => {}
+            }
+            let mut unknown = scope.relations.iter().filter(|r| r.columns.is_none());
+            match (unknown.next(), unknown.next()) {
+                (Some(relation), None) => return Lookup::Unknown(relation, depth),
+                (Some(_), Some(_)) => return Lookup::Undetermined,
+                _ => {}
+            }
+        }
+        Lookup::NoColumn
+    }
+
+    /// The columns that `*` expands to
+    pub fn wildcard(&self) -> Columns<C> {
+        let mut columns = vec![];
+        for relation in &self.relations {
+            columns.extend(relation.visible_columns()?);
+        }
+        Some(columns)
+    }
+
+    /// The columns that `qualifier.*` expands to
+    pub fn qualified_wildcard(&self, qualifier: &[Ident]) -> Columns<C> {
+        match self.relation(qualifier) {
+            Lookup::Relation(relation, _) => relation.columns.clone(),
+            _ => None,
+        }
+    }
+}
+
+/// An analysis of the columns of queries, which the FROM clause of a query
+/// calls back into
+pub(super) trait Analysis {
+    type Column: Column;
+
+    /// The columns of the table or view `name`, or `None` if it is unknown
+    fn table_columns(&mut self, name: &ObjectName) -> Columns<Self::Column>;
+
+    /// Analyse `query`, a subquery of the query with `parent`
+    fn query(
+        &mut self,
+        query: &Query,
+        parent: Option<&Scope<Self::Column>>,
+    ) -> Columns<Self::Column>;
+
+    /// Analyse the `ON` condition of a join
+    fn join_condition(&mut self, _condition: &Expr, _scope: &Scope<Self::Column>) {}
+
+    /// Analyse the arguments of a table-valued function
+    fn table_function_args(&mut self, _args: &[Expr], _scope: &Scope<Self::Column>) {}
+
+    /// Report a `USING` column that is missing or ambiguous, which only
+    /// the analyses that check names need to
+    fn diagnostic(&mut self, _diagnostic: Diagnostic) {}
+}
+
+/// Add the relations of `table` to `scope`
+pub(super) fn table_with_joins<A: Analysis>(
+    analysis: &mut A,
+    table: &TableWithJoins,
+    scope: &mut Scope<A::Column>,
+) {
+    let start = scope.relations.len();
+    table_factor(analysis, &table.relation, scope, false);
+    for join in &table.joins {
+        let left = start..scope.relations.len();
+        let lateral = matches!(
+            join.join_operator,
+            JoinOperator::CrossApply | JoinOperator::OuterApply
+        );
+        table_factor(analysis, &join.relation, scope, lateral);
+        let constraint = match &join.join_operator {
+            JoinOperator::Inner(constraint)
+            | JoinOperator::LeftOuter(constraint)
+            | JoinOperator::RightOuter(constraint)
+            | JoinOperator::FullOuter(constraint) => constraint,
+            _ => continue,
+        };
+        match constraint {
+            JoinConstraint::On(expr) => analysis.join_condition(expr, scope),
+            JoinConstraint::Using(columns) => using(analysis, columns, scope, left),
+            JoinConstraint::Natural => natural(scope, left),
+        }
+    }
+}
+
+/// Add the relation of `factor` to `scope`. A derived table only sees the
+/// relations before it if it is `lateral`.
+fn table_factor<A: Analysis>(
+    analysis: &mut A,
+    factor: &TableFactor,
+    scope: &mut Scope<A::Column>,
+    lateral: bool,
+) {
+    let (kind, name, columns, alias) = match factor {
+        TableFactor::Table {
+            name, alias, args, ..
+        } if !args.is_empty() => {
+            analysis.table_function_args(args, scope);
+            let kind = RelationKind::TableFunction(name.clone());
+            (kind, name.0.last(), None, alias)
+        }
+        TableFactor::Table { name, alias, .. } => {
+            let cte = match name.0.as_slice() {
+                [ident] => scope.cte(ident).cloned(),
+                _ => None,
+            };
+            let (kind, columns) = match cte {
+                Some(columns) => (RelationKind::Cte(name.0[0].clone()), columns),
+                None => {
+                    let columns = analysis.table_columns(name);
+                    (RelationKind::Table(name.clone()), columns)
+                }
+            };
+            (kind, name.0.last(), columns, alias)
+        }
+        TableFactor::Derived {
+            lateral: is_lateral,
+            subquery,
+            alias,
+            ..
+        } => {
+            let columns = if lateral || *is_lateral {
+                analysis.query(subquery, Some(scope))
+            } else {
+                analysis.query(subquery, Some(&Scope::derived(scope)))
+            };
+            (RelationKind::Derived, None, columns, alias)
+        }
+        TableFactor::NestedJoin(table, _) => return table_with_joins(analysis, table, scope),
+    };
+    let name = alias.as_ref().map(|alias| &alias.name).or(name);
+    scope.relations.push(ScopeRelation {
+        relation: Relation {
+            name: name.cloned(),
+            kind,
+        },
+        aliased: alias.is_some(),
+        columns: rename(columns, alias.as_ref()),
+        merged: vec![],
+    });
+}
+
+/// Merge `columns` of the relations after `left` into the same columns of
+/// the relations in `left`
+fn using<A: Analysis>(
+    analysis: &mut A,
+    columns: &[Ident],
+    scope: &mut Scope<A::Column>,
+    left: Range<usize>,
+) {
+    let right = left.end..scope.relations.len();
+    for column in columns {
+        join_side(analysis, column, &scope.relations[left.clone()], "left");
+        let relations = &scope.relations[right.clone()];
+        if let Some(i) = join_side(analysis, column, relations, "right") {
+            scope.relations[right.start + i].merged.push(column.clone());
+        }
+    }
+}
+
+/// Find the relation with the `USING` column `column` on one side of a
+/// join
+fn join_side<A: Analysis>(
+    analysis: &mut A,
+    column: &Ident,
+    relations: &[ScopeRelation<A::Column>],
+    side: &str,
+) -> Option<usize> {
+    let mut found = relations
+        .iter()
+        .enumerate()
+        .filter(|(_, relation)| relation.column(column, false).is_some());
+    let (kind, message) = match (found.next(), found.next()) {
+        (Some((i, _)), None) => return Some(i),
+        (Some(_), Some(_)) => (
+            DiagnosticKind::AmbiguousColumn,
+            format!(
+                "column {} in USING is ambiguous in the {} table",
+                column, side
+            ),
+        ),
+        (None, _) if relations.iter().all(|r| r.columns.is_some()) => (
+            DiagnosticKind::UnknownColumn,
+            format!(
+                "column {} in USING does not exist in the {} table",
+                column, side
+            ),
+        ),
+        (None, _) => return None,
+    };
+    analysis.diagnostic(Diagnostic {
+        kind,
+        message,
+        span: column.span,
+    });
+    None
+}
+
+/// Merge the columns of the relations after `left` into the columns of the
+/// relations in `left` with the same names
+fn natural<C: Column>(scope: &mut Scope<C>, left: Range<usize>) {
+    let right = left.end..scope.relations.len();
+    let mut merged = vec![];
+    for i in right {
+        let columns = scope.relations[i].visible_columns().unwrap_or_default();
+        for name in columns.iter().filter_map(Column::name) {
+            let relations = &scope.relations[left.clone()];
+            if relations.iter().any(|r| r.column(name, false).is_some()) {
+                merged.push((i, name.clone()));
+            }
+        }
+    }
+    for (i, name) in merged {
+        scope.relations[i].merged.push(name);
+    }
+}
+
+/// Rename the first columns to the ones listed by `alias`, if any
+pub(super) fn rename<C: Column>(columns: Columns<C>, alias: Option<&TableAlias>) -> Columns<C> {
+    let names = match alias {
+        Some(alias) if !alias.columns.is_empty() => &alias.columns,
+        _ => return columns,
+    };
+    let mut columns = columns.unwrap_or_default();
+    for (i, name) in names.iter().enumerate() {
+        match columns.get_mut(i) {
+            Some(column) => column.rename(name.clone()),
+            None => columns.push(C::unknown(name.clone())),
+        }
+    }
+    Some(columns)
+}
@@ -0,0 +1,1165 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::scope::{self, Analysis, Column, Lookup, ScopeRelation};
+use super::{Catalog, Diagnostic, DiagnosticKind};
+use crate::ast::*;
+
+/// An output column of a query and its type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedColumn {
+    /// The name of the column, or `None` for an unnamed one such as
+    /// `SELECT 1`
+    pub name: Option<Ident>,
+    /// The type of the column, or `None` if it is unknown, e.g. for `NULL`,
+    /// a placeholder or a function this module does not know about
+    pub data_type: Option<DataType>,
+}
+
+/// The result of [infer_query_types]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryTypes {
+    /// The output columns of the query, or `None` if a wildcard covers a
+    /// relation whose columns are unknown
+    pub columns: Option<Vec<TypedColumn>>,
+    /// The type errors found in the query
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// The result of [infer_expr_type]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExprType {
+    /// The type of the expression, or `None` if it is unknown
+    pub data_type: Option<DataType>,
+    /// The type errors found in the expression
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Infer the types of the output columns of `query`, taking the types of
+/// the columns it reads from `catalog`, and check the types of all of its
+/// expressions.
+///
+/// Numeric operands are promoted to the wider type, e.g. `int + double` is
+/// a `double`, and the branches of a `CASE`, the arguments of `COALESCE`
+/// and the sides of a `UNION` are unified the same way. A string literal
+/// takes the type of whatever it is compared with, as it does in most
+/// databases. Column references that [super::resolve_query] cannot bind
+/// have an unknown type, and are not reported again here.
+pub fn infer_query_types(catalog: &dyn Catalog, query: &Query) -> QueryTypes {
+    let mut inferrer = Inferrer {
+        catalog,
+        diagnostics: vec![],
+        spans: vec![],
+    };
+    let columns = inferrer.query(query, None);
+    QueryTypes {
+        columns,
+        diagnostics: inferrer.diagnostics,
+    }
+}
+
+/// Infer the type of `expr`, whose column references are unqualified names
+/// of `columns`, e.g. the expression of a `CHECK` constraint or of a column
+/// default. Subqueries in `expr` read tables with unknown columns.
+pub fn infer_expr_type(expr: &Expr, columns: &[ColumnDef]) -> ExprType {
+    let mut inferrer = Inferrer {
+        catalog: &NoTables,
+        diagnostics: vec![],
+        spans: vec![],
+    };
+    let mut scope = Scope::new(None);
+    scope.relations.push(ScopeRelation::unnamed(
+        columns
+            .iter()
+            .map(|column| TypedColumn {
+                name: Some(column.name.clone()),
+                data_type: Some(column.data_type.clone()),
+            })
+            .collect(),
+    ));
+    let data_type = inferrer.expr(expr, &scope);
+    ExprType {
+        data_type,
+        diagnostics: inferrer.diagnostics,
+    }
+}
+
+/// A catalog without any tables
+struct NoTables;
+
+impl Catalog for NoTables {
+    fn table_columns(&self, _name: &ObjectName) -> Option<Vec<Ident>> {
+        None
+    }
+}
+
+type Columns = scope::Columns<TypedColumn>;
+type Scope<'s> = scope::Scope<'s, TypedColumn>;
+
+impl Column for TypedColumn {
+    fn name(&self) -> Option<&Ident> {
+        self.name.as_ref()
+    }
+
+    fn rename(&mut self, name: Ident) {
+        self.name = Some(name);
+    }
+
+    fn unknown(name: Ident) -> Self {
+        TypedColumn {
+            name: Some(name),
+            data_type: None,
+        }
+    }
+}
+
+/// The type of the column `reference`, or `None` if it is unknown or
+/// cannot be bound
+fn column_type(scope: &Scope, reference: &[Ident]) -> Option<DataType> {
+    match scope.column(reference) {
+        Lookup::Column(_, column, _) => column.data_type.clone(),
+        _ => None,
+    }
+}
+
+/// The kind of values a type holds, which decides the types it can be
+/// compared with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Numeric,
+    String,
+    Binary,
+    Boolean,
+    Temporal,
+    Interval,
+    Uuid,
+    /// Custom and array types, which are not checked
+    Other,
+}
+
+fn category(data_type: &DataType) -> Category {
+    match data_type {
+        DataType::SmallInt
+        | DataType::Int
+        | DataType::BigInt
+        | DataType::Decimal(..)
+        | DataType::Real
+        | DataType::Float(_)
+        | DataType::Double => Category::Numeric,
+        DataType::Char(_) | DataType::Varchar(_) | DataType::Clob(_) | DataType::Text => {
+            Category::String
+        }
+        DataType::Binary(_) | DataType::Varbinary(_) | DataType::Blob(_) | DataType::Bytea => {
+            Category::Binary
+        }
+        DataType::Boolean => Category::Boolean,
+        DataType::Date | DataType::Time | DataType::Timestamp => Category::Temporal,
+        DataType::Interval => Category::Interval,
+        DataType::Uuid => Category::Uuid,
+        DataType::Regclass | DataType::Custom(_) | DataType::Array(_) => Category::Other,
+    }
+}
+
+/// The rank of a numeric type: a numeric operation on two types has the
+/// type of higher rank
+fn numeric_rank(data_type: &DataType) -> u8 {
+    match data_type {
+        DataType::SmallInt => 1,
+        DataType::Int => 2,
+        DataType::BigInt => 3,
+        DataType::Decimal(..) => 4,
+        DataType::Real => 5,
+        DataType::Float(_) => 6,
+        _ => 7,
+    }
+}
+
+/// The type that both numeric types `a` and `b` convert to
+fn promote(a: &DataType, b: &DataType) -> DataType {
+    use std::cmp::Ordering;
+    match numeric_rank(a).cmp(&numeric_rank(b)) {
+        Ordering::Greater => a.clone(),
+        Ordering::Less => b.clone(),
+        Ordering::Equal if a == b => a.clone(),
+        Ordering::Equal => match a {
+            DataType::Decimal(..) => DataType::Decimal(None, None),
+            DataType::Float(_) => DataType::Float(None),
+            _ => a.clone(),
+        },
+    }
+}
+
+/// The type that values of types `a` and `b` convert to, e.g. for the
+/// branches of a `CASE`, or `None` if there is none
+fn unify(a: &DataType, b: &DataType) -> Option<DataType> {
+    if a == b {
+        return Some(a.clone());
+    }
+    match (category(a), category(b)) {
+        (Category::Numeric, Category::Numeric) => Some(promote(a, b)),
+        (Category::String, Category::String) => match (a, b) {
+            (DataType::Char(_), DataType::Char(_))
+            | (DataType::Char(_), DataType::Varchar(_))
+            | (DataType::Varchar(_), DataType::Char(_))
+            | (DataType::Varchar(_), DataType::Varchar(_)) => Some(DataType::Varchar(None)),
+            _ => Some(DataType::Text),
+        },
+        (Category::Binary, Category::Binary) => Some(DataType::Bytea),
+        (Category::Temporal, Category::Temporal) => match (a, b) {
+            (DataType::Time, _) | (_, DataType::Time) => None,
+            _ => Some(DataType::Timestamp),
+        },
+        _ => None,
+    }
+}
+
+/// Whether values of types `a` and `b` can be compared
+fn comparable(a: &DataType, b: &DataType) -> bool {
+    match (category(a), category(b)) {
+        (Category::Other, _) | (_, Category::Other) => true,
+        (a, b) => a == b,
+    }
+}
+
+/// The type of the literal `value`, or `None` for `NULL`
+fn value_type(value: &Value) -> Option<DataType> {
+    Some(match value {
+        Value::Number(n) => {
+            let n = n.to_string();
+            if n.parse::<i32>().is_ok() {
+                DataType::Int
+            } else if n.parse::<i64>().is_ok() {
+                DataType::BigInt
+            } else {
+                DataType::Decimal(None, None)
+            }
+        }
+        Value::SingleQuotedString(_) | Value::NationalStringLiteral(_) => DataType::Text,
+        Value::HexStringLiteral(_) => DataType::Bytea,
+        Value::Boolean(_) => DataType::Boolean,
+        Value::Date(_) => DataType::Date,
+        Value::Time(_) => DataType::Time,
+        Value::Timestamp(_) => DataType::Timestamp,
+        Value::Interval { .. } => DataType::Interval,
+        Value::Null => return None,
+    })
+}
+
+/// Whether `expr` is a string literal, which takes the type of whatever it
+/// is compared with
+fn is_string_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Value(Value::SingleQuotedString(_), _)
+        | Expr::Value(Value::NationalStringLiteral(_), _) => true,
+        Expr::Nested(expr, _) => is_string_literal(expr),
+        _ => false,
+    }
+}
+
+/// The type of the arithmetic `left op right`, or `None` if the operator
+/// does not apply to the types
+fn arithmetic(left: &DataType, op: &BinaryOperator, right: &DataType) -> Option<DataType> {
+    use BinaryOperator::*;
+    use DataType::*;
+    match (category(left), category(right)) {
+        (Category::Numeric, Category::Numeric) => return Some(promote(left, right)),
+        (Category::Other, _) | (_, Category::Other) => return None,
+        _ => {}
+    }
+    let is_integer = |ty: &DataType| matches!(ty, SmallInt | Int | BigInt);
+    let shifted = |ty: &DataType| match ty {
+        Time => Time,
+        _ => Timestamp,
+    };
+    Some(match (left, op, right) {
+        (Date, Plus, n) | (Date, Minus, n) if is_integer(n) => Date,
+        (n, Plus, Date) if is_integer(n) => Date,
+        (Date, Minus, Date) => Int,
+        (Date, Minus, Timestamp) | (Timestamp, Minus, Date) | (Timestamp, Minus, Timestamp) => {
+            Interval
+        }
+        (Time, Minus, Time) => Interval,
+        (ty, Plus, Interval) | (ty, Minus, Interval) | (Interval, Plus, ty)
+            if category(ty) == Category::Temporal =>
+        {
+            shifted(ty)
+        }
+        (Interval, Plus, Interval) | (Interval, Minus, Interval) => Interval,
+        (Interval, Multiply, n) | (Interval, Divide, n) | (n, Multiply, Interval)
+            if category(n) == Category::Numeric =>
+        {
+            Interval
+        }
+        _ => return None,
+    })
+}
+
+/// The type of the result of the built-in function `name` called with
+/// arguments of types `args`, or `Err` with the reason the arguments are
+/// wrong. Functions this module does not know have an unknown type.
+fn function_type(name: &str, args: &[Option<DataType>]) -> Result<Option<DataType>, String> {
+    let first = args.first().cloned().flatten();
+    let numeric_arg = || match &first {
+        Some(ty) if !matches!(category(ty), Category::Numeric | Category::Other) => Err(format!(
+            "function {} requires a numeric argument, not {}",
+            name, ty
+        )),
+        _ => Ok(()),
+    };
+    Ok(match name {
+        "COUNT" | "ROW_NUMBER" | "RANK" | "DENSE_RANK" | "NTILE" => Some(DataType::BigInt),
+        "SUM" | "AVG" if first == Some(DataType::Interval) => first,
+        "SUM" => {
+            numeric_arg()?;
+            match first {
+                Some(DataType::SmallInt) | Some(DataType::Int) => Some(DataType::BigInt),
+                Some(DataType::BigInt) => Some(DataType::Decimal(None, None)),
+                ty => ty,
+            }
+        }
+        "AVG" => {
+            numeric_arg()?;
+            match first {
+                Some(DataType::Real) | Some(DataType::Float(_)) | Some(DataType::Double) => {
+                    Some(DataType::Double)
+                }
+                Some(_) => Some(DataType::Decimal(None, None)),
+                None => None,
+            }
+        }
+        "MIN" | "MAX" | "FIRST_VALUE" | "LAST_VALUE" | "LAG" | "LEAD" | "NULLIF" => first,
+        "ABS" | "CEIL" | "CEILING" | "FLOOR" | "ROUND" | "TRUNC" => {
+            numeric_arg()?;
+            first
+        }
+        "LOWER" | "UPPER" | "TRIM" | "LTRIM" | "RTRIM" | "SUBSTRING" | "SUBSTR" | "REPLACE"
+        | "CONCAT" | "LEFT" | "RIGHT" | "LPAD" | "RPAD" | "REVERSE" => Some(DataType::Text),
+        "LENGTH" | "CHAR_LENGTH" | "CHARACTER_LENGTH" | "OCTET_LENGTH" | "POSITION" => {
+            Some(DataType::Int)
+        }
+        "NOW" | "CURRENT_TIMESTAMP" | "LOCALTIMESTAMP" => Some(DataType::Timestamp),
+        "CURRENT_DATE" => Some(DataType::Date),
+        _ => None,
+    })
+}
+
+struct Inferrer<'c> {
+    catalog: &'c dyn Catalog,
+    diagnostics: Vec<Diagnostic>,
+    /// The spans of the queries and expressions being inferred, innermost
+    /// last, leaving out the empty ones. An error in an expression without
+    /// identifiers, such as `1 + TRUE`, is located at the innermost one.
+    spans: Vec<Span>,
+}
+
+impl<'c> Analysis for Inferrer<'c> {
+    type Column = TypedColumn;
+
+    /// The columns of the table or view `name` in the catalog
+    fn table_columns(&mut self, name: &ObjectName) -> Columns {
+        let columns = self.catalog.table_columns(name)?;
+        Some(
+            columns
+                .into_iter()
+                .map(|column| TypedColumn {
+                    data_type: self.catalog.column_type(name, &column),
+                    name: Some(column),
+                })
+                .collect(),
+        )
+    }
+
+    /// Infer the types of the output columns of `query`
+    fn query(&mut self, query: &Query, parent: Option<&Scope>) -> Columns {
+        self.within(query.span(), |inferrer| inferrer.query_types(query, parent))
+    }
+
+    fn join_condition(&mut self, condition: &Expr, scope: &Scope) {
+        self.condition(condition, scope, "JOIN/ON");
+    }
+
+    fn table_function_args(&mut self, args: &[Expr], scope: &Scope) {
+        for arg in args {
+            self.expr(arg, scope);
+        }
+    }
+}
+
+impl<'c> Inferrer<'c> {
+    /// Run `f`, locating the errors it finds without a span of their own at
+    /// `span`, unless it is empty
+    fn within<T>(&mut self, span: Span, f: impl FnOnce(&mut Self) -> T) -> T {
+        if span.is_empty() {
+            return f(self);
+        }
+        self.spans.push(span);
+        let result = f(self);
+        self.spans.pop();
+        result
+    }
+
+    fn mismatch(&mut self, message: String, span: Span) {
+        let span = match self.spans.last() {
+            Some(enclosing) if span.is_empty() => *enclosing,
+            _ => span,
+        };
+        self.diagnostics.push(Diagnostic {
+            kind: DiagnosticKind::TypeMismatch,
+            message,
+            span,
+        });
+    }
+
+    fn query_types(&mut self, query: &Query, parent: Option<&Scope>) -> Columns {
+        let mut scope = Scope::new(parent);
+        for cte in &query.ctes {
+            let columns = self.query(&cte.query, Some(&scope));
+            let columns = scope::rename(columns, Some(&cte.alias));
+            scope.ctes.push((cte.alias.name.clone(), columns));
+        }
+        let columns = match &query.body {
+            SetExpr::Select(select) => self.select(select, &scope, &query.order_by),
+            body => {
+                let columns = self.set_expr(body, &scope);
+                for order_by in &query.order_by {
+                    self.expr(&order_by.expr, &scope);
+                }
+                columns
+            }
+        };
+        let fetch = query
+            .fetch
+            .as_ref()
+            .and_then(|fetch| fetch.quantity.as_ref());
+        for expr in query.limit.iter().chain(&query.offset).chain(fetch) {
+            self.expr(expr, &scope);
+        }
+        columns
+    }
+
+    fn set_expr(&mut self, body: &SetExpr, scope: &Scope) -> Columns {
+        match body {
+            SetExpr::Select(select) => self.select(select, scope, &[]),
+            SetExpr::Query(query) => self.query(query, Some(scope)),
+            SetExpr::SetOperation {
+                left, op, right, ..
+            } => {
+                let left_columns = self.set_expr(left, scope);
+                let right_columns = self.set_expr(right, scope);
+                let (mut columns, right_columns) = match (left_columns, right_columns) {
+                    (Some(left), Some(right)) => (left, right),
+                    (left, _) => return left,
+                };
+                for (column, right) in columns.iter_mut().zip(right_columns) {
+                    if let (Some(a), Some(b)) = (&column.data_type, &right.data_type) {
+                        let unified = unify(a, b);
+                        if unified.is_none() {
+                            let message = format!("{} types {} and {} cannot be matched", op, a, b);
+                            self.mismatch(message, right_side_span(body));
+                        }
+                        column.data_type = unified;
+                    } else if column.data_type.is_none() {
+                        column.data_type = right.data_type;
+                    }
+                }
+                Some(columns)
+            }
+            SetExpr::Values(values) => {
+                let width = values.0.first().map_or(0, Vec::len);
+                let mut columns = vec![];
+                for i in 0..width {
+                    let exprs: Vec<&Expr> = values.0.iter().filter_map(|row| row.get(i)).collect();
+                    columns.push(TypedColumn {
+                        name: Some(Ident::new(format!("column{}", i + 1))),
+                        data_type: self.common_type(&exprs, scope, "VALUES"),
+                    });
+                }
+                Some(columns)
+            }
+        }
+    }
+
+    /// Infer the types of the output columns of `select`, checking the
+    /// ORDER BY of its query too
+    fn select(&mut self, select: &Select, parent: &Scope, order_by: &[OrderByExpr]) -> Columns {
+        self.within(select.span(), |inferrer| {
+            inferrer.select_types(select, parent, order_by)
+        })
+    }
+
+    fn select_types(
+        &mut self,
+        select: &Select,
+        parent: &Scope,
+        order_by: &[OrderByExpr],
+    ) -> Columns {
+        let mut scope = Scope::select(parent);
+        for table in &select.from {
+            scope::table_with_joins(self, table, &mut scope);
+        }
+        let mut columns = Some(vec![]);
+        for item in &select.projection {
+            let item_columns = self.select_item(item, &scope);
+            columns = columns.and_then(|mut columns: Vec<TypedColumn>| {
+                columns.extend(item_columns?);
+                Some(columns)
+            });
+        }
+        if let Some(selection) = &select.selection {
+            self.condition(selection, &scope, "WHERE");
+        }
+        for expr in &select.group_by {
+            self.expr(expr, &scope);
+        }
+        if let Some(having) = &select.having {
+            self.condition(having, &scope, "HAVING");
+        }
+        for order_by in order_by {
+            self.expr(&order_by.expr, &scope);
+        }
+        columns
+    }
+
+    fn select_item(&mut self, item: &SelectItem, scope: &Scope) -> Columns {
+        let (expr, name) = match item {
+            SelectItem::UnnamedExpr(expr) => {
+                let name = match expr {
+                    Expr::Identifier(ident) => Some(ident.clone()),
+                    Expr::CompoundIdentifier(idents) => idents.last().cloned(),
+                    _ => None,
+                };
+                (expr, name)
+            }
+            SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias.clone())),
+            SelectItem::Wildcard(_) => return scope.wildcard(),
+            SelectItem::QualifiedWildcard(name, _) => return scope.qualified_wildcard(&name.0),
+            SelectItem::Commented { item, .. } => return self.select_item(item, scope),
+        };
+        let data_type = self.expr(expr, scope);
+        Some(vec![TypedColumn { name, data_type }])
+    }
+
+    /// Check that `expr`, the condition of `clause`, is a boolean
+    fn condition(&mut self, expr: &Expr, scope: &Scope, clause: &str) {
+        let data_type = self.expr(expr, scope);
+        self.expect_boolean(expr, data_type, clause);
+    }
+
+    fn expect_boolean(&mut self, expr: &Expr, data_type: Option<DataType>, context: &str) {
+        match data_type {
+            Some(DataType::Boolean) | None => {}
+            Some(ty) if category(&ty) == Category::Other => {}
+            Some(ty) => {
+                let message = format!("argument of {} must be boolean, not {}", context, ty);
+                self.mismatch(message, expr.span());
+            }
+        }
+    }
+
+    /// Check that `left` and `right`, with their types, can be compared by
+    /// the whole expression `expr`
+    fn expect_comparable(
+        &mut self,
+        expr: &Expr,
+        (left, left_type): (&Expr, &Option<DataType>),
+        (right, right_type): (Option<&Expr>, &Option<DataType>),
+    ) {
+        let literal =
+            is_string_literal(left) || matches!(right, Some(right) if is_string_literal(right));
+        if let (Some(a), Some(b)) = (left_type, right_type) {
+            if !comparable(a, b) && !literal {
+                let message = format!("cannot compare {} with {}", a, b);
+                self.mismatch(message, expr.span());
+            }
+        }
+    }
+
+    /// The type that all of `exprs` convert to, reporting an error for the
+    /// first one that does not convert in `context`. String literals take
+    /// the type of the other expressions, if they have one.
+    fn common_type(&mut self, exprs: &[&Expr], scope: &Scope, context: &str) -> Option<DataType> {
+        let mut result: Option<DataType> = None;
+        let mut literal = false;
+        for expr in exprs {
+            let data_type = self.expr(expr, scope);
+            if is_string_literal(expr) {
+                literal = true;
+                continue;
+            }
+            result = match (result, data_type) {
+                (Some(a), Some(b)) => match unify(&a, &b) {
+                    Some(ty) => Some(ty),
+                    None => {
+                        let message =
+                            format!("{} types {} and {} cannot be matched", context, a, b);
+                        self.mismatch(message, expr.span());
+                        return None;
+                    }
+                },
+                (a, b) => a.or(b),
+            };
+        }
+        match result {
+            None if literal => Some(DataType::Text),
+            result => result,
+        }
+    }
+
+    /// Infer the type of `expr`, or `None` if it is unknown
+    fn expr(&mut self, expr: &Expr, scope: &Scope) -> Option<DataType> {
+        self.within(expr.span(), |inferrer| inferrer.expr_type(expr, scope))
+    }
+
+    fn expr_type(&mut self, expr: &Expr, scope: &Scope) -> Option<DataType> {
+        match expr {
+            Expr::Identifier(ident) => column_type(scope, std::slice::from_ref(ident)),
+            Expr::CompoundIdentifier(idents) => column_type(scope, idents),
+            Expr::Wildcard(_) | Expr::QualifiedWildcard(_, _) | Expr::Placeholder(_, _) => None,
+            Expr::Value(value, _) => value_type(value),
+            Expr::IsNull(expr, _) | Expr::IsNotNull(expr, _) => {
+                self.expr(expr, scope);
+                Some(DataType::Boolean)
+            }
+            Expr::InList {
+                expr: operand,
+                list,
+                ..
+            } => {
+                let operand_type = self.expr(operand, scope);
+                for item in list {
+                    let item_type = self.expr(item, scope);
+                    let item = (Some(item), &item_type);
+                    self.expect_comparable(expr, (operand, &operand_type), item);
+                }
+                Some(DataType::Boolean)
+            }
+            Expr::InSubquery {
+                expr: operand,
+                subquery,
+                ..
+            } => {
+                let operand_type = self.expr(operand, scope);
+                let columns = self.query(subquery, Some(scope));
+                if let Some([column]) = columns.as_deref() {
+                    let item = (None, &column.data_type);
+                    self.expect_comparable(expr, (operand, &operand_type), item);
+                }
+                Some(DataType::Boolean)
+            }
+            Expr::Between {
+                expr: operand,
+                low,
+                high,
+                ..
+            } => {
+                let operand_type = self.expr(operand, scope);
+                for bound in &[low, high] {
+                    let bound_type = self.expr(bound, scope);
+                    let bound = (Some(&***bound), &bound_type);
+                    self.expect_comparable(expr, (operand, &operand_type), bound);
+                }
+                Some(DataType::Boolean)
+            }
+            Expr::BinaryOp { left, op, right } => {
+                let left_type = self.expr(left, scope);
+                let right_type = self.expr(right, scope);
+                self.binary_op(expr, (left, left_type), op, (right, right_type))
+            }
+            Expr::UnaryOp {
+                op, expr: operand, ..
+            } => {
+                let data_type = self.expr(operand, scope);
+                match (op, data_type) {
+                    (UnaryOperator::Not, data_type) => {
+                        self.expect_boolean(operand, data_type, "NOT");
+                        Some(DataType::Boolean)
+                    }
+                    (_, Some(ty))
+                        if !matches!(
+                            category(&ty),
+                            Category::Numeric | Category::Interval | Category::Other
+                        ) =>
+                    {
+                        let message = format!("operator {} cannot be applied to {}", op, ty);
+                        self.mismatch(message, expr.span());
+                        None
+                    }
+                    (_, data_type) => data_type,
+                }
+            }
+            Expr::Cast {
+                expr, data_type, ..
+            } => {
+                self.expr(expr, scope);
+                Some(data_type.clone())
+            }
+            Expr::Extract {
+                field,
+                expr: operand,
+                ..
+            } => {
+                match self.expr(operand, scope) {
+                    Some(ty)
+                        if !matches!(
+                            category(&ty),
+                            Category::Temporal | Category::Interval | Category::Other
+                        ) && !is_string_literal(operand) =>
+                    {
+                        let message = format!("cannot extract {} from {}", field, ty);
+                        self.mismatch(message, expr.span());
+                    }
+                    _ => {}
+                }
+                Some(match field {
+                    DateTimeField::Second => DataType::Decimal(None, None),
+                    _ => DataType::Int,
+                })
+            }
+            Expr::Collate { expr, .. } | Expr::Nested(expr, _) => self.expr(expr, scope),
+            Expr::Function(function) => self.function(expr, function, scope),
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+                ..
+            } => {
+                match operand {
+                    Some(operand) => {
+                        let operand_type = self.expr(operand, scope);
+                        for condition in conditions {
+                            let condition_type = self.expr(condition, scope);
+                            self.expect_comparable(
+                                condition,
+                                (operand, &operand_type),
+                                (Some(condition), &condition_type),
+                            );
+                        }
+                    }
+                    None => {
+                        for condition in conditions {
+                            self.condition(condition, scope, "CASE/WHEN");
+                        }
+                    }
+                }
+                let results: Vec<&Expr> = results.iter().chain(else_result.as_deref()).collect();
+                self.common_type(&results, scope, "CASE")
+            }
+            Expr::Exists(query, _) => {
+                self.query(query, Some(scope));
+                Some(DataType::Boolean)
+            }
+            Expr::Subquery(query, _) => match self.query(query, Some(scope)) {
+                Some(mut columns) if columns.len() == 1 => columns.remove(0).data_type,
+                _ => None,
+            },
+        }
+    }
+
+    fn binary_op(
+        &mut self,
+        expr: &Expr,
+        (left, left_type): (&Expr, Option<DataType>),
+        op: &BinaryOperator,
+        (right, right_type): (&Expr, Option<DataType>),
+    ) -> Option<DataType> {
+        use BinaryOperator::*;
+        match op {
+            Plus | Minus | Multiply | Divide | Modulus => {
+                if is_string_literal(left) || is_string_literal(right) {
+                    return None;
+                }
+                let (a, b) = (left_type?, right_type?);
+                let result = arithmetic(&a, op, &b);
+                let unchecked = category(&a) == Category::Other || category(&b) == Category::Other;
+                if result.is_none() && !unchecked {
+                    let message = format!("operator {} cannot be applied to {} and {}", op, a, b);
+                    self.mismatch(message, expr.span());
+                }
+                result
+            }
+            Gt | Lt | GtEq | LtEq | Eq | NotEq => {
+                self.expect_comparable(expr, (left, &left_type), (Some(right), &right_type));
+                Some(DataType::Boolean)
+            }
+            And | Or => {
+                let context = op.to_string();
+                self.expect_boolean(left, left_type, &context);
+                self.expect_boolean(right, right_type, &context);
+                Some(DataType::Boolean)
+            }
+            Like | NotLike => {
+                for ty in left_type.iter().chain(&right_type) {
+                    if !matches!(category(ty), Category::String | Category::Other) {
+                        let message = format!("operator {} cannot be applied to {}", op, ty);
+                        self.mismatch(message, expr.span());
+                        break;
+                    }
+                }
+                Some(DataType::Boolean)
+            }
+            Custom(_) => None,
+        }
+    }
+
+    fn function(&mut self, expr: &Expr, function: &Function, scope: &Scope) -> Option<DataType> {
+        let name = function.name.0.last()?.value.to_uppercase();
+        if let "COALESCE" | "GREATEST" | "LEAST" = name.as_str() {
+            let args: Vec<&Expr> = function.args.iter().collect();
+            return self.common_type(&args, scope, &name);
+        }
+        let args: Vec<Option<DataType>> = function
+            .args
+            .iter()
+            .map(|arg| match self.expr(arg, scope) {
+                None if is_string_literal(arg) => None,
+                data_type => data_type,
+            })
+            .collect();
+        if let Some(over) = &function.over {
+            for expr in &over.partition_by {
+                self.expr(expr, scope);
+            }
+            for order_by in &over.order_by {
+                self.expr(&order_by.expr, scope);
+            }
+        }
+        match function_type(&name, &args) {
+            Ok(data_type) => data_type,
+            Err(message) => {
+                self.mismatch(message, expr.span());
+                None
+            }
+        }
+    }
+}
+
+fn right_side_span(body: &SetExpr) -> Span {
+    match body {
+        SetExpr::SetOperation { right, .. } => right.span(),
+        body => body.span(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+    use crate::semantic::Schema;
+
+    const DDL: &str = "CREATE TABLE customers (id INT, name VARCHAR(100), active BOOLEAN, \
+                       joined DATE, credit DECIMAL(10,2));\
+                       CREATE TABLE orders (id BIGINT, customer_id INT, total DOUBLE PRECISION, \
+                       placed TIMESTAMP, note TEXT, status order_status);\
+                       CREATE VIEW big_orders AS SELECT id, total * 2 AS doubled FROM orders";
+
+    fn types(sql: &str) -> QueryTypes {
+        let mut schema = Schema::new();
+        let ddl = Parser::parse_sql(&GenericDialect {}, DDL.to_string()).unwrap();
+        schema.apply_all(&ddl).unwrap();
+        let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        match &statements[0] {
+            Statement::Query(query) => infer_query_types(&schema, query),
+            _ => panic!("Expected a query: {}", sql),
+        }
+    }
+
+    /// Describe the output columns as `name: type`, with `?` for an unknown
+    /// name or type
+    fn columns(sql: &str) -> Vec<String> {
+        let types = types(sql);
+        assert_eq!(types.diagnostics, vec![], "{}", sql);
+        types
+            .columns
+            .unwrap()
+            .iter()
+            .map(|column| {
+                let name = column.name.as_ref().map_or("?".into(), Ident::to_string);
+                let data_type = column
+                    .data_type
+                    .as_ref()
+                    .map_or("?".into(), DataType::to_string);
+                format!("{}: {}", name, data_type)
+            })
+            .collect()
+    }
+
+    fn errors(sql: &str) -> Vec<String> {
+        types(sql)
+            .diagnostics
+            .into_iter()
+            .map(|diagnostic| {
+                assert_eq!(diagnostic.kind, DiagnosticKind::TypeMismatch);
+                diagnostic.message
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_column_and_literal_types() {
+        assert_eq!(
+            columns("SELECT c.*, o.total AS t FROM customers AS c JOIN orders AS o ON c.id = o.customer_id"),
+            vec![
+                "id: int",
+                "name: character varying(100)",
+                "active: boolean",
+                "joined: date",
+                "credit: numeric(10,2)",
+                "t: double",
+            ]
+        );
+        assert_eq!(
+            columns("SELECT 1, 10000000000, 1.5, 'x', TRUE, NULL, DATE '2020-01-01', $1"),
+            vec![
+                "?: int",
+                "?: bigint",
+                "?: numeric",
+                "?: text",
+                "?: boolean",
+                "?: ?",
+                "?: date",
+                "?: ?"
+            ]
+        );
+        // Types flow out of CTEs and derived tables
+        assert_eq!(
+            columns(
+                "WITH c (n) AS (SELECT name FROM customers) \
+                 SELECT n, d.x FROM c, (SELECT status AS x FROM orders) AS d"
+            ),
+            vec!["n: character varying(100)", "x: order_status"]
+        );
+        assert_eq!(
+            columns("SELECT * FROM big_orders"),
+            vec!["id: bigint", "doubled: double"]
+        );
+        // Unknown tables have unknown columns
+        assert_eq!(types("SELECT * FROM missing").columns, None);
+        assert_eq!(columns("SELECT a FROM missing"), vec!["a: ?"]);
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(
+            columns(
+                "SELECT c.id + 1, c.id * total, credit - c.id, -credit, customer_id % 2, \
+                 c.id > 1 AND NOT active, name LIKE 'a%', c.id IN (1, 2), \
+                 joined BETWEEN '2020-01-01' AND '2021-01-01', note IS NULL \
+                 FROM customers AS c JOIN orders ON c.id = orders.customer_id"
+            ),
+            vec![
+                "?: int",
+                "?: double",
+                "?: numeric(10,2)",
+                "?: numeric(10,2)",
+                "?: int",
+                "?: boolean",
+                "?: boolean",
+                "?: boolean",
+                "?: boolean",
+                "?: boolean",
+            ]
+        );
+        assert_eq!(
+            columns(
+                "SELECT joined + 7, joined - joined, placed - joined, \
+                 placed + INTERVAL '1' DAY, INTERVAL '1' DAY * 2 \
+                 FROM customers, orders"
+            ),
+            vec![
+                "?: date",
+                "?: int",
+                "?: interval",
+                "?: timestamp",
+                "?: interval"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cast_case_extract_and_functions() {
+        assert_eq!(
+            columns(
+                "SELECT CAST(id AS TEXT), \
+                 CASE WHEN active THEN id ELSE credit END, \
+                 CASE id WHEN 1 THEN 'one' ELSE name END, \
+                 CASE WHEN active THEN 'yes' END, \
+                 EXTRACT(YEAR FROM joined), EXTRACT(SECOND FROM joined) \
+                 FROM customers"
+            ),
+            vec![
+                "?: text",
+                "?: numeric(10,2)",
+                "?: character varying(100)",
+                "?: text",
+                "?: int",
+                "?: numeric",
+            ]
+        );
+        assert_eq!(
+            columns(
+                "SELECT COUNT(*), SUM(customer_id), SUM(id), AVG(total), AVG(customer_id), \
+                 MAX(placed), upper(note), length(note), COALESCE(customer_id, id, 0), \
+                 ROW_NUMBER() OVER (ORDER BY placed), my_function(id) \
+                 FROM orders GROUP BY id"
+            ),
+            vec![
+                "?: bigint",
+                "?: bigint",
+                "?: numeric",
+                "?: double",
+                "?: numeric",
+                "?: timestamp",
+                "?: text",
+                "?: int",
+                "?: bigint",
+                "?: bigint",
+                "?: ?",
+            ]
+        );
+        assert_eq!(
+            columns("SELECT (SELECT MAX(joined) FROM customers) AS latest"),
+            vec!["latest: date"]
+        );
+    }
+
+    #[test]
+    fn test_set_operations_and_values() {
+        assert_eq!(
+            columns("SELECT id, name FROM customers UNION SELECT id, note FROM orders"),
+            vec!["id: bigint", "name: text"]
+        );
+        assert_eq!(
+            columns("VALUES (1, 'a'), (2.5, NULL)"),
+            vec!["column1: numeric", "column2: text"]
+        );
+        assert_eq!(
+            errors("SELECT active FROM customers UNION ALL SELECT placed FROM orders"),
+            vec!["UNION types boolean and timestamp cannot be matched"]
+        );
+    }
+
+    #[test]
+    fn test_type_errors() {
+        assert_eq!(
+            errors("SELECT * FROM customers WHERE active = joined"),
+            vec!["cannot compare boolean with date"]
+        );
+        assert_eq!(
+            errors("SELECT * FROM customers WHERE id"),
+            vec!["argument of WHERE must be boolean, not int"]
+        );
+        assert_eq!(
+            errors(
+                "SELECT name + 1, -active, CASE WHEN active THEN joined ELSE 1 END, \
+                 EXTRACT(DAY FROM name), SUM(name) \
+                 FROM customers WHERE active AND id AND name LIKE 1"
+            ),
+            vec![
+                "operator + cannot be applied to character varying(100) and int",
+                "operator - cannot be applied to boolean",
+                "CASE types date and int cannot be matched",
+                "cannot extract DAY from character varying(100)",
+                "function SUM requires a numeric argument, not character varying(100)",
+                "argument of AND must be boolean, not int",
+                "operator LIKE cannot be applied to int",
+            ]
+        );
+        // String literals, custom types and unknown columns are not checked
+        assert_eq!(
+            errors(
+                "SELECT * FROM customers, orders \
+                 WHERE joined = '2020-01-01' AND status = 'new' AND status = 1 \
+                 AND missing = 1"
+            ),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            errors("SELECT id FROM customers WHERE id IN (SELECT active FROM customers)"),
+            vec!["cannot compare int with boolean"]
+        );
+    }
+
+    #[test]
+    fn test_derived_tables() {
+        // Only a LATERAL derived table sees the tables before it
+        assert_eq!(
+            columns("SELECT d.x FROM orders, (SELECT total AS x FROM missing) AS d"),
+            vec!["x: ?"]
+        );
+        assert_eq!(
+            columns("SELECT d.x FROM orders, LATERAL (SELECT total AS x) AS d"),
+            vec!["x: double"]
+        );
+        assert_eq!(
+            errors("SELECT * FROM customers, (SELECT id FROM missing WHERE id) AS d"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_error_spans() {
+        let span = |sql: &str| {
+            let types = types(sql);
+            let span = types.diagnostics[0].span;
+            sql[span.start.offset..span.end.offset].to_string()
+        };
+        assert_eq!(
+            span("SELECT * FROM customers WHERE active = joined"),
+            "active = joined"
+        );
+        // Expressions without identifiers are located too
+        assert_eq!(span("SELECT * FROM customers WHERE active AND 1"), "1");
+        assert_eq!(span("SELECT 1 + TRUE, name FROM customers"), "1 + TRUE");
+    }
+
+    #[test]
+    fn test_infer_expr_type() {
+        let sql = "CREATE TABLE t (a INT, b REAL, c DATE, \
+                   CHECK (a + b > 0), CHECK (c > '2020-01-01'), CHECK (a = c))";
+        let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        let (columns, constraints) = match &statements[0] {
+            Statement::CreateTable {
+                columns,
+                constraints,
+                ..
+            } => (columns, constraints),
+            _ => unreachable!(),
+        };
+        let columns: Vec<ColumnDef> = columns.iter().map(|c| c.node.clone()).collect();
+        let checks: Vec<ExprType> = constraints
+            .iter()
+            .map(|constraint| match constraint {
+                TableConstraint::Check { expr, .. } => infer_expr_type(expr, &columns),
+                _ => unreachable!(),
+            })
+            .collect();
+        let a_plus_b = match &constraints[0] {
+            TableConstraint::Check { expr, .. } => match &**expr {
+                Expr::BinaryOp { left, .. } => infer_expr_type(left, &columns),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        assert_eq!(a_plus_b.data_type, Some(DataType::Real));
+        assert_eq!(checks[0].diagnostics, vec![]);
+        assert_eq!(checks[1].data_type, Some(DataType::Boolean));
+        assert_eq!(checks[1].diagnostics, vec![]);
+        assert_eq!(checks[2].data_type, Some(DataType::Boolean));
+        assert_eq!(
+            checks[2].diagnostics[0].message,
+            "cannot compare int with date"
+        );
+        assert_eq!(checks[2].diagnostics[0].span.start.column, 92);
+    }
+}
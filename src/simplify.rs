@@ -0,0 +1,852 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Constant folding and boolean simplification of expressions.
+//!
+//! ```
+//! use sqlparser::dialect::GenericDialect;
+//! use sqlparser::parser::Parser;
+//! use sqlparser::simplify::simplify_statement;
+//!
+//! let sql = "SELECT * FROM t WHERE 1 = 1 AND ((a > 2 + 3)) AND NOT NOT b";
+//! let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+//! let simplified = simplify_statement(&statements[0]);
+//! assert_eq!(simplified.to_string(), "SELECT * FROM t WHERE a > 5 AND b");
+//! ```
+
+use crate::ast::*;
+use std::cmp::Ordering;
+use std::ops::ControlFlow;
+
+/// Return a simplified copy of `expr`.
+///
+/// Arithmetic and comparisons of literals are folded, following SQL's
+/// three-valued logic: an operation with a `NULL` operand is `NULL`,
+/// `NULL AND FALSE` is `FALSE` and `NULL OR TRUE` is `TRUE`. Arithmetic is
+/// exact, so it is only folded when the result is: division is folded only
+/// for integers that divide evenly. Strings are only known to be equal when
+/// they are identical, as other comparisons depend on the collation.
+///
+/// Boolean operations with a constant operand are reduced, e.g. `x AND TRUE`
+/// to `x` and `x OR TRUE` to `TRUE`, as are `NOT NOT x`, `NOT x IS NULL`
+/// and the like, `CASE` branches with constant conditions, and `BETWEEN`
+/// with equal bounds. Parentheses are removed except where they are needed.
+/// Subqueries are left as they are; use [simplify_statement] to simplify
+/// them too.
+pub fn simplify(expr: &Expr) -> Expr {
+    fold(expr.clone())
+}
+
+/// Return a copy of `statement` with every expression simplified as by
+/// [simplify], including the ones in subqueries. `WHERE` and `HAVING`
+/// clauses that simplify to `TRUE` are removed. `ORDER BY` and `GROUP BY`
+/// items that simplify to a literal are left as they are, as an integer
+/// there would refer to a column by its position.
+pub fn simplify_statement(statement: &Statement) -> Statement {
+    let mut statement = statement.clone();
+    let mut simplifier = Simplifier {
+        depths: vec![0],
+        positional: vec![],
+    };
+    let _ = walk_statement_mut(&mut simplifier, &mut statement);
+    statement
+}
+
+struct Simplifier {
+    /// For each query being walked, innermost last, how many expressions
+    /// deep the walk is. Only the outermost expressions of a query are
+    /// folded, which folds everything below them but subqueries.
+    depths: Vec<usize>,
+    /// The `ORDER BY` or `GROUP BY` items of each query and select being
+    /// walked, innermost last, as they were before folding
+    positional: Vec<Vec<Expr>>,
+}
+
+impl VisitorMut for Simplifier {
+    type Break = ();
+
+    fn post_visit_statement(&mut self, statement: &mut Statement) -> ControlFlow<()> {
+        match statement {
+            Statement::Update { selection, .. } | Statement::Delete { selection, .. } => {
+                remove_if_true(selection)
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_query(&mut self, query: &mut Query) -> ControlFlow<()> {
+        self.depths.push(0);
+        let order_by = query.order_by.iter().map(|item| item.expr.clone());
+        self.positional.push(order_by.collect());
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, query: &mut Query) -> ControlFlow<()> {
+        self.depths.pop();
+        let order_by = query.order_by.iter_mut().map(|item| &mut item.expr);
+        restore_literals(order_by, self.positional.pop().unwrap());
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_select(&mut self, select: &mut Select) -> ControlFlow<()> {
+        self.positional.push(select.group_by.clone());
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_select(&mut self, select: &mut Select) -> ControlFlow<()> {
+        remove_if_true(&mut select.selection);
+        remove_if_true(&mut select.having);
+        restore_literals(select.group_by.iter_mut(), self.positional.pop().unwrap());
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<()> {
+        if let Some(depth) = self.depths.last_mut() {
+            if *depth == 0 {
+                *expr = fold(std::mem::replace(expr, Expr::Wildcard(NodeSpan::empty())));
+            }
+            *depth += 1;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expr(&mut self, _expr: &mut Expr) -> ControlFlow<()> {
+        if let Some(depth) = self.depths.last_mut() {
+            *depth -= 1;
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Put back the `items` that were folded into a literal as they were
+/// before, so that they do not become column positions
+fn restore_literals<'a>(items: impl Iterator<Item = &'a mut Expr>, originals: Vec<Expr>) {
+    for (item, original) in items.zip(originals) {
+        if matches!(item, Expr::Value(_, _)) && *item != original {
+            *item = original;
+        }
+    }
+}
+
+fn remove_if_true(condition: &mut Option<Expr>) {
+    if matches!(condition, Some(Expr::Value(Value::Boolean(true), _))) {
+        *condition = None;
+    }
+}
+
+/// Simplify `expr`, returning it without parentheses around it
+fn fold(expr: Expr) -> Expr {
+    match expr {
+        Expr::Nested(expr, _) => fold(*expr),
+        Expr::BinaryOp { left, op, right } => fold_binary_op(fold(*left), op, fold(*right)),
+        Expr::UnaryOp { op, expr, .. } => fold_unary_op(op, fold(*expr)),
+        Expr::IsNull(expr, span) => match fold(*expr) {
+            Expr::Value(value, _) => boolean(value == Value::Null),
+            expr => Expr::IsNull(Box::new(expr.left_operand(precedence::IS)), span),
+        },
+        Expr::IsNotNull(expr, span) => match fold(*expr) {
+            Expr::Value(value, _) => boolean(value != Value::Null),
+            expr => Expr::IsNotNull(Box::new(expr.left_operand(precedence::IS)), span),
+        },
+        Expr::InList {
+            expr,
+            list,
+            negated,
+            ..
+        } => fold_in_list(fold(*expr), list.into_iter().map(fold).collect(), negated),
+        Expr::InSubquery {
+            expr,
+            subquery,
+            negated,
+            span,
+        } => Expr::InSubquery {
+            expr: Box::new(fold(*expr).left_operand(precedence::COMPARISON)),
+            subquery,
+            negated,
+            span,
+        },
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => fold_between(fold(*expr), negated, fold(*low), fold(*high)),
+        Expr::Cast {
+            expr,
+            data_type,
+            span,
+        } => Expr::Cast {
+            expr: Box::new(fold(*expr)),
+            data_type,
+            span,
+        },
+        Expr::Extract { field, expr, span } => Expr::Extract {
+            field,
+            expr: Box::new(fold(*expr)),
+            span,
+        },
+        Expr::Collate { expr, collation } => Expr::Collate {
+            expr: Box::new(fold(*expr).left_operand(100)),
+            collation,
+        },
+        Expr::Function(function) => Expr::Function(Function {
+            args: function.args.into_iter().map(fold).collect(),
+            over: function.over.map(|over| WindowSpec {
+                partition_by: over.partition_by.into_iter().map(fold).collect(),
+                order_by: over
+                    .order_by
+                    .into_iter()
+                    .map(|order_by| OrderByExpr {
+                        expr: fold(order_by.expr),
+                        ..order_by
+                    })
+                    .collect(),
+                ..over
+            }),
+            ..function
+        }),
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+            ..
+        } => fold_case(
+            operand.map(|operand| fold(*operand)),
+            conditions
+                .into_iter()
+                .map(fold)
+                .zip(results.into_iter().map(fold)),
+            else_result.map(|result| fold(*result)),
+        ),
+        expr => expr,
+    }
+}
+
+fn without_parentheses(expr: Expr) -> Expr {
+    match expr {
+        Expr::Nested(expr, _) => without_parentheses(*expr),
+        expr => expr,
+    }
+}
+
+fn boolean(value: bool) -> Expr {
+    Expr::Value(Value::Boolean(value), NodeSpan::empty())
+}
+
+fn null() -> Expr {
+    Expr::Value(Value::Null, NodeSpan::empty())
+}
+
+fn is_null(expr: &Expr) -> bool {
+    matches!(expr, Expr::Value(Value::Null, _))
+}
+
+/// The expression for a truth value, with `None` for unknown
+fn truth_value(truth: Option<bool>) -> Expr {
+    match truth {
+        Some(value) => boolean(value),
+        None => null(),
+    }
+}
+
+/// The truth value of `expr` if it is a constant: `Some(None)` for `NULL`,
+/// and `None` if it is not a constant truth value
+fn truth(expr: &Expr) -> Option<Option<bool>> {
+    match expr {
+        Expr::Value(Value::Boolean(value), _) => Some(Some(*value)),
+        Expr::Value(Value::Null, _) => Some(None),
+        _ => None,
+    }
+}
+
+fn fold_binary_op(left: Expr, op: BinaryOperator, right: Expr) -> Expr {
+    use BinaryOperator::*;
+    match &op {
+        And => match (truth(&left), truth(&right)) {
+            (Some(Some(false)), _) | (_, Some(Some(false))) => return boolean(false),
+            (Some(Some(true)), _) => return right,
+            (_, Some(Some(true))) => return left,
+            (Some(None), Some(None)) => return null(),
+            _ => {}
+        },
+        Or => match (truth(&left), truth(&right)) {
+            (Some(Some(true)), _) | (_, Some(Some(true))) => return boolean(true),
+            (Some(Some(false)), _) => return right,
+            (_, Some(Some(false))) => return left,
+            (Some(None), Some(None)) => return null(),
+            _ => {}
+        },
+        Plus | Minus | Multiply | Divide | Modulus | Gt | Lt | GtEq | LtEq | Eq | NotEq | Like
+        | NotLike
+            if is_null(&left) || is_null(&right) =>
+        {
+            return null()
+        }
+        Plus | Minus | Multiply | Divide | Modulus => {
+            if let (Some(a), Some(b)) = (number(&left), number(&right)) {
+                if let Some(result) = arithmetic(a, &op, b).and_then(number_expr) {
+                    return result;
+                }
+            }
+        }
+        Gt | Lt | GtEq | LtEq | Eq | NotEq => {
+            if let Some(truth) = compare(&left, &op, &right) {
+                return truth_value(truth);
+            }
+        }
+        Like | NotLike | Custom(_) => {}
+    }
+    let (left_precedence, right_precedence) = match op.precedence() {
+        // The precedence of a custom operator is unknown, so its operands
+        // are parenthesized unless they are prefix expressions
+        0 => (100, 100),
+        precedence => (precedence, precedence + 1),
+    };
+    Expr::BinaryOp {
+        left: Box::new(left.left_operand(left_precedence)),
+        op,
+        right: Box::new(right.operand(right_precedence)),
+    }
+}
+
+fn fold_unary_op(op: UnaryOperator, expr: Expr) -> Expr {
+    match op {
+        UnaryOperator::Not => return fold_not(expr),
+        UnaryOperator::Plus if number(&expr).is_some() || is_null(&expr) => return expr,
+        UnaryOperator::Minus if is_null(&expr) => return expr,
+        UnaryOperator::Minus => {
+            if let Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr: inner,
+                ..
+            } = &expr
+            {
+                if number(inner).is_some() {
+                    return *inner.clone();
+                }
+            }
+        }
+        UnaryOperator::Plus => {}
+    }
+    Expr::UnaryOp {
+        expr: Box::new(expr.operand(op.precedence() + 1)),
+        op,
+        span: NodeSpan::empty(),
+    }
+}
+
+/// Simplify `NOT expr`, pushing the `NOT` into the operators that have a
+/// negated form
+fn fold_not(expr: Expr) -> Expr {
+    match expr {
+        Expr::Value(Value::Boolean(value), _) => boolean(!value),
+        Expr::Value(Value::Null, _) => null(),
+        Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+            ..
+        } => without_parentheses(*expr),
+        Expr::IsNull(expr, _) => Expr::IsNotNull(expr, NodeSpan::empty()),
+        Expr::IsNotNull(expr, _) => Expr::IsNull(expr, NodeSpan::empty()),
+        Expr::InList {
+            expr,
+            list,
+            negated,
+            ..
+        } => Expr::InList {
+            expr,
+            list,
+            negated: !negated,
+            span: NodeSpan::empty(),
+        },
+        Expr::InSubquery {
+            expr,
+            subquery,
+            negated,
+            ..
+        } => Expr::InSubquery {
+            expr,
+            subquery,
+            negated: !negated,
+            span: NodeSpan::empty(),
+        },
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => Expr::Between {
+            expr,
+            negated: !negated,
+            low,
+            high,
+        },
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Like,
+            right,
+        } => Expr::BinaryOp {
+            left,
+            op: BinaryOperator::NotLike,
+            right,
+        },
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::NotLike,
+            right,
+        } => Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Like,
+            right,
+        },
+        expr => Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr: Box::new(expr.operand(precedence::UNARY_NOT + 1)),
+            span: NodeSpan::empty(),
+        },
+    }
+}
+
+fn fold_in_list(expr: Expr, list: Vec<Expr>, negated: bool) -> Expr {
+    // `expr IN (a, b)` is `expr = a OR expr = b`
+    let mut result = Some(Some(false));
+    for item in &list {
+        result = match (result, compare(&expr, &BinaryOperator::Eq, item)) {
+            (Some(Some(true)), _) | (_, Some(Some(true))) => Some(Some(true)),
+            (None, _) | (_, None) => None,
+            (Some(None), _) | (_, Some(None)) => Some(None),
+            (Some(Some(false)), Some(Some(false))) => Some(Some(false)),
+        };
+    }
+    match result {
+        Some(truth) if !list.is_empty() => truth_value(if negated {
+            truth.map(|value| !value)
+        } else {
+            truth
+        }),
+        _ => Expr::InList {
+            expr: Box::new(expr.left_operand(precedence::COMPARISON)),
+            list,
+            negated,
+            span: NodeSpan::empty(),
+        },
+    }
+}
+
+fn fold_between(expr: Expr, negated: bool, low: Expr, high: Expr) -> Expr {
+    if low == high && is_repeatable(&low) {
+        let op = if negated {
+            BinaryOperator::NotEq
+        } else {
+            BinaryOperator::Eq
+        };
+        return fold_binary_op(expr, op, low);
+    }
+    let above = compare(&expr, &BinaryOperator::GtEq, &low);
+    let below = compare(&expr, &BinaryOperator::LtEq, &high);
+    if let (Some(above), Some(below)) = (above, below) {
+        let truth = match (above, below) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), Some(true)) => Some(true),
+            _ => None,
+        };
+        return truth_value(if negated {
+            truth.map(|value| !value)
+        } else {
+            truth
+        });
+    }
+    Expr::Between {
+        expr: Box::new(expr.left_operand(precedence::COMPARISON)),
+        negated,
+        low: Box::new(low.operand(precedence::COMPARISON + 1)),
+        high: Box::new(high.operand(precedence::COMPARISON + 1)),
+    }
+}
+
+/// Whether `expr` has the same value every time it is evaluated in a row,
+/// unlike e.g. `RANDOM()`
+fn is_repeatable(expr: &Expr) -> bool {
+    match expr {
+        Expr::Value(_, _)
+        | Expr::Placeholder(_, _)
+        | Expr::Identifier(_)
+        | Expr::CompoundIdentifier(_) => true,
+        Expr::UnaryOp { expr, .. } => is_repeatable(expr),
+        _ => false,
+    }
+}
+
+fn fold_case<I>(operand: Option<Expr>, branches: I, else_result: Option<Expr>) -> Expr
+where
+    I: Iterator<Item = (Expr, Expr)>,
+{
+    let mut conditions = vec![];
+    let mut results = vec![];
+    let mut else_result = else_result;
+    for (condition, result) in branches {
+        let truth = match &operand {
+            Some(operand) => compare(operand, &BinaryOperator::Eq, &condition),
+            None => truth(&condition),
+        };
+        match truth {
+            // The branches after one that is always taken never are
+            Some(Some(true)) => {
+                else_result = Some(result);
+                break;
+            }
+            // A branch that is never taken can be dropped
+            Some(_) => {}
+            None => {
+                conditions.push(condition);
+                results.push(result);
+            }
+        }
+    }
+    if conditions.is_empty() {
+        return else_result.unwrap_or_else(null);
+    }
+    Expr::Case {
+        operand: operand.map(Box::new),
+        conditions,
+        results,
+        else_result: else_result.map(Box::new),
+        span: NodeSpan::empty(),
+    }
+}
+
+/// The truth value of `left op right`, where `op` is a comparison, if both
+/// sides are constants whose order is known
+fn compare(left: &Expr, op: &BinaryOperator, right: &Expr) -> Option<Option<bool>> {
+    if is_null(left) || is_null(right) {
+        return Some(None);
+    }
+    let ordering = match (left, right) {
+        (Expr::Value(Value::Boolean(a), _), Expr::Value(Value::Boolean(b), _)) => a.cmp(b),
+        (
+            Expr::Value(Value::SingleQuotedString(a), _),
+            Expr::Value(Value::SingleQuotedString(b), _),
+        ) if a == b => Ordering::Equal,
+        _ => number(left)?.cmp(number(right)?),
+    };
+    Some(Some(match op {
+        BinaryOperator::Gt => ordering == Ordering::Greater,
+        BinaryOperator::Lt => ordering == Ordering::Less,
+        BinaryOperator::GtEq => ordering != Ordering::Less,
+        BinaryOperator::LtEq => ordering != Ordering::Greater,
+        BinaryOperator::Eq => ordering == Ordering::Equal,
+        BinaryOperator::NotEq => ordering != Ordering::Equal,
+        _ => return None,
+    }))
+}
+
+/// An exact decimal number: `digits` divided by 10 to the power of `scale`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Decimal {
+    digits: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Parse a number as printed by [Value::Number], e.g. `1.50`
+    fn parse(text: &str) -> Option<Decimal> {
+        let (whole, fraction) = match text.find('.') {
+            Some(i) => (&text[..i], &text[i + 1..]),
+            None => (text, ""),
+        };
+        if whole.is_empty() && fraction.is_empty() {
+            return None;
+        }
+        let mut digits: i128 = 0;
+        for ch in whole.chars().chain(fraction.chars()) {
+            let digit = ch.to_digit(10)?;
+            digits = digits.checked_mul(10)?.checked_add(i128::from(digit))?;
+        }
+        Some(Decimal {
+            digits,
+            scale: fraction.len() as u32,
+        })
+    }
+
+    fn with_scale(self, scale: u32) -> Option<Decimal> {
+        let factor = 10i128.checked_pow(scale.checked_sub(self.scale)?)?;
+        Some(Decimal {
+            digits: self.digits.checked_mul(factor)?,
+            scale,
+        })
+    }
+
+    /// The digits of `self` and `other` at the same scale
+    fn aligned(self, other: Decimal) -> Option<(i128, i128, u32)> {
+        let scale = self.scale.max(other.scale);
+        let (a, b) = (self.with_scale(scale)?, other.with_scale(scale)?);
+        Some((a.digits, b.digits, scale))
+    }
+
+    fn cmp(self, other: Decimal) -> Ordering {
+        match self.aligned(other) {
+            Some((a, b, _)) => a.cmp(&b),
+            // One of them is too large to rescale, so the one with more
+            // whole digits is further from zero
+            None => {
+                let whole = |d: Decimal| d.digits / 10i128.pow(d.scale.min(38));
+                whole(self).cmp(&whole(other))
+            }
+        }
+    }
+
+    fn to_text(self) -> String {
+        let digits = format!(
+            "{:0width$}",
+            self.digits.abs(),
+            width = self.scale as usize + 1
+        );
+        let (whole, fraction) = digits.split_at(digits.len() - self.scale as usize);
+        if fraction.is_empty() {
+            whole.to_string()
+        } else {
+            format!("{}.{}", whole, fraction)
+        }
+    }
+}
+
+/// The value of `expr` if it is a number literal, possibly negated
+fn number(expr: &Expr) -> Option<Decimal> {
+    match expr {
+        Expr::Value(Value::Number(n), _) => Decimal::parse(&n.to_string()),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+            ..
+        } => {
+            let n = number(expr)?;
+            Some(Decimal {
+                digits: -n.digits,
+                ..n
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The result of the arithmetic `a op b`, if it is exact and does not
+/// overflow
+fn arithmetic(a: Decimal, op: &BinaryOperator, b: Decimal) -> Option<Decimal> {
+    let (x, y, scale) = a.aligned(b)?;
+    let digits = match op {
+        BinaryOperator::Plus => x.checked_add(y)?,
+        BinaryOperator::Minus => x.checked_sub(y)?,
+        BinaryOperator::Multiply => {
+            return Some(Decimal {
+                digits: a.digits.checked_mul(b.digits)?,
+                scale: a.scale.checked_add(b.scale)?,
+            })
+        }
+        // Integer division truncates in some databases and not in others,
+        // so only an exact quotient is the same in all of them
+        BinaryOperator::Divide if scale == 0 && y != 0 && x % y == 0 => x / y,
+        BinaryOperator::Modulus if scale == 0 && y != 0 => x % y,
+        _ => return None,
+    };
+    Some(Decimal { digits, scale })
+}
+
+/// The literal for `n`, with the sign as a unary operator as the parser
+/// would produce it
+fn number_expr(n: Decimal) -> Option<Expr> {
+    let expr = Expr::Value(Value::Number(n.to_text().parse().ok()?), NodeSpan::empty());
+    Some(if n.digits < 0 {
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr: Box::new(expr),
+            span: NodeSpan::empty(),
+        }
+    } else {
+        expr
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GenericDialect;
+    use crate::parser::Parser;
+
+    /// Simplify the projection of `SELECT <sql>`
+    fn simplified(sql: &str) -> String {
+        let sql = format!("SELECT {}", sql);
+        let statements = Parser::parse_sql(&GenericDialect {}, sql).unwrap();
+        let projection = match &statements[0] {
+            Statement::Query(query) => match &query.body {
+                SetExpr::Select(select) => &select.projection,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        match &projection[0] {
+            SelectItem::UnnamedExpr(expr) => simplify(expr).to_string(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn simplified_statement(sql: &str) -> String {
+        let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+        simplify_statement(&statements[0]).to_string()
+    }
+
+    #[test]
+    fn test_fold_arithmetic() {
+        assert_eq!(simplified("1 + 2 * 3"), "7");
+        assert_eq!(simplified("1.5 * 2 - 0.25"), "2.75");
+        assert_eq!(simplified("2 - 5"), "- 3");
+        assert_eq!(simplified("-(-(4))"), "4");
+        assert_eq!(simplified("+2"), "2");
+        assert_eq!(simplified("10 / 2 + 7 % 4"), "8");
+        // Inexact division and division by zero are left to the database
+        assert_eq!(simplified("7 / 2"), "7 / 2");
+        assert_eq!(simplified("1 / 0"), "1 / 0");
+        assert_eq!(simplified("a + (1 + 1)"), "a + 2");
+        assert_eq!(simplified("(a + 1) + 1"), "a + 1 + 1");
+        assert_eq!(simplified("NULL * a"), "NULL");
+        assert_eq!(
+            simplified(
+                "99999999999999999999999999999999999999 + 99999999999999999999999999999999999999"
+            ),
+            "99999999999999999999999999999999999999 + 99999999999999999999999999999999999999"
+        );
+    }
+
+    #[test]
+    fn test_fold_comparisons() {
+        assert_eq!(simplified("1 = 1"), "true");
+        assert_eq!(simplified("2.0 = 2"), "true");
+        assert_eq!(simplified("-1 > 0.5"), "false");
+        assert_eq!(simplified("'a' = 'a'"), "true");
+        // Whether different strings are equal depends on the collation
+        assert_eq!(simplified("'a' = 'A'"), "'a' = 'A'");
+        assert_eq!(simplified("TRUE <> FALSE"), "true");
+        assert_eq!(simplified("NULL = NULL"), "NULL");
+        assert_eq!(simplified("a = NULL"), "NULL");
+        assert_eq!(simplified("2 IN (1, 2)"), "true");
+        assert_eq!(simplified("3 NOT IN (1, 2)"), "true");
+        assert_eq!(simplified("3 IN (1, NULL)"), "NULL");
+        assert_eq!(simplified("1 IN (1, NULL)"), "true");
+        assert_eq!(simplified("a IN (1, 1 + 1)"), "a IN (1, 2)");
+        assert_eq!(simplified("NULL IS NULL"), "true");
+        assert_eq!(simplified("1 IS NULL"), "false");
+        assert_eq!(simplified("(a + 1) IS NOT NULL"), "a + 1 IS NOT NULL");
+    }
+
+    #[test]
+    fn test_boolean_logic() {
+        assert_eq!(simplified("a AND TRUE"), "a");
+        assert_eq!(simplified("TRUE AND a"), "a");
+        assert_eq!(simplified("a AND FALSE"), "false");
+        assert_eq!(simplified("a OR FALSE"), "a");
+        assert_eq!(simplified("a OR TRUE"), "true");
+        assert_eq!(simplified("1 = 1 AND (b OR 1 = 0)"), "b");
+        // Three-valued logic
+        assert_eq!(simplified("NULL AND FALSE"), "false");
+        assert_eq!(simplified("NULL AND TRUE"), "NULL");
+        assert_eq!(simplified("NULL OR TRUE"), "true");
+        assert_eq!(simplified("NULL OR FALSE"), "NULL");
+        assert_eq!(simplified("NULL AND a"), "NULL AND a");
+        assert_eq!(simplified("NOT NULL"), "NULL");
+        assert_eq!(simplified("NOT NOT a"), "a");
+        assert_eq!(simplified("NOT (NOT (a = 1))"), "a = 1");
+        assert_eq!(simplified("NOT 1 = 2"), "true");
+        assert_eq!(simplified("NOT (a IS NULL)"), "a IS NOT NULL");
+        assert_eq!(simplified("NOT a IN (1, 2)"), "a NOT IN (1, 2)");
+        assert_eq!(simplified("NOT a LIKE 'x%'"), "a NOT LIKE 'x%'");
+        assert_eq!(simplified("NOT (a OR b)"), "NOT (a OR b)");
+        assert_eq!(simplified("(a OR b) AND (c)"), "(a OR b) AND c");
+        assert_eq!(simplified("((a))"), "a");
+    }
+
+    #[test]
+    fn test_case_and_between() {
+        assert_eq!(
+            simplified("CASE WHEN 1 = 0 THEN 'a' WHEN b THEN 'b' WHEN TRUE THEN 'c' ELSE 'd' END"),
+            "CASE WHEN b THEN 'b' ELSE 'c' END"
+        );
+        assert_eq!(simplified("CASE WHEN NULL THEN 1 END"), "NULL");
+        assert_eq!(simplified("CASE WHEN 2 > 1 THEN x ELSE y END"), "x");
+        assert_eq!(
+            simplified("CASE 2 WHEN 1 THEN 'a' WHEN 2 THEN 'b' END"),
+            "'b'"
+        );
+        assert_eq!(
+            simplified("CASE a WHEN 1 THEN 'a' WHEN NULL THEN 'b' END"),
+            "CASE a WHEN 1 THEN 'a' END"
+        );
+        assert_eq!(simplified("a BETWEEN 1 AND 1"), "a = 1");
+        assert_eq!(simplified("a NOT BETWEEN b AND b"), "a <> b");
+        assert_eq!(
+            simplified("a BETWEEN random() AND random()"),
+            "a BETWEEN random() AND random()"
+        );
+        assert_eq!(simplified("2 BETWEEN 1 AND 3"), "true");
+        assert_eq!(simplified("2 NOT BETWEEN 1 AND NULL"), "NULL");
+        assert_eq!(simplified("5 BETWEEN 1 AND NULL"), "NULL");
+        assert_eq!(simplified("0 BETWEEN 1 AND NULL"), "false");
+        assert_eq!(
+            simplified("(a + 1) BETWEEN (b) AND c"),
+            "a + 1 BETWEEN b AND c"
+        );
+    }
+
+    #[test]
+    fn test_simplify_statement() {
+        assert_eq!(
+            simplified_statement(
+                "SELECT (a), 1 + 1 FROM t JOIN u ON 1 = 1 AND t.id = u.id \
+                 WHERE 1 = 1 AND x IN (SELECT y FROM v WHERE TRUE AND (y > 2 * 2)) \
+                 GROUP BY a HAVING TRUE ORDER BY (a)"
+            ),
+            "SELECT a, 2 FROM t JOIN u ON t.id = u.id \
+             WHERE x IN (SELECT y FROM v WHERE y > 4) GROUP BY a ORDER BY a"
+        );
+        // Constant items of ORDER BY and GROUP BY are not turned into
+        // column positions
+        assert_eq!(
+            simplified_statement(
+                "SELECT a, b FROM t GROUP BY 1 + 0, (2), 3 * a \
+                 ORDER BY (1), 0 + 2, -(-1), 1, a + (1 + 1)"
+            ),
+            "SELECT a, b FROM t GROUP BY 1 + 0, (2), 3 * a \
+             ORDER BY (1), 0 + 2, - (- 1), 1, a + 2"
+        );
+        assert_eq!(
+            simplified_statement("DELETE FROM t WHERE 1 = 1 OR a"),
+            "DELETE FROM t"
+        );
+        assert_eq!(
+            simplified_statement("UPDATE t SET a = 2 * 3 WHERE NOT NOT b"),
+            "UPDATE t SET a = 6 WHERE b"
+        );
+    }
+
+    #[test]
+    fn test_simplified_sql_round_trips() {
+        for sql in &[
+            "SELECT - (-a), a - (b - c), NOT (a AND b), (a = b) = c, a COLLATE c",
+            "SELECT * FROM t WHERE (a OR b) AND (c OR NOT d) AND (x BETWEEN 1 AND (2 + y))",
+        ] {
+            let simplified = simplified_statement(sql);
+            let reparsed = Parser::parse_sql(&GenericDialect {}, simplified.clone()).unwrap();
+            assert_eq!(reparsed[0].to_string(), simplified);
+            assert_eq!(simplify_statement(&reparsed[0]).to_string(), simplified);
+        }
+    }
+}
@@ -0,0 +1,423 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parse SQL statements one at a time from a reader, without holding the
+//! whole input in memory.
+//!
+//! ```
+//! use sqlparser::dialect::GenericDialect;
+//! use sqlparser::stream::StatementStream;
+//!
+//! let dump = "CREATE TABLE t (a INT);\nINSERT INTO t VALUES (1);\n";
+//! let dialect = GenericDialect {};
+//! for statement in StatementStream::from_read(&dialect, dump.as_bytes()) {
+//!     println!("{}", statement.unwrap());
+//! }
+//! ```
+
+use std::io::{BufRead, BufReader, Read};
+use std::mem;
+
+use crate::ast::Statement;
+use crate::dialect::keywords::Keyword;
+use crate::dialect::Dialect;
+use crate::parser::{Parser, ParserError, ParserErrorKind, ParserOptions};
+use crate::tokenizer::{Location, Span, Token, Tokenizer, Word};
+
+/// What the character at the end of the scanned input is part of
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scan {
+    Code,
+    String,
+    /// A delimited identifier, ending with the given quote
+    DelimitedIdentifier(char),
+    LineComment,
+    BlockComment,
+}
+
+/// An iterator over the statements read from a [BufRead].
+///
+/// The input is read a line at a time, and split into statements at the
+/// semicolons that are not inside strings, delimited identifiers or comments.
+/// The data that follows a `COPY ... FROM STDIN;` statement, up to the
+/// terminating `\.` line, is kept with that statement. Only the statement
+/// being parsed is held in memory. The locations in the AST and in errors are
+/// relative to the start of the input.
+///
+/// A statement that cannot be parsed produces an error, after which iteration
+/// continues with the next statement. An error reading the input ends the
+/// iteration.
+pub struct StatementStream<'a, R> {
+    dialect: &'a dyn Dialect,
+    reader: R,
+    /// The input that has been read, but not returned as part of a statement
+    buffer: String,
+    /// The location of the start of `buffer` in the input
+    location: Location,
+    /// The length of the prefix of `buffer` that has been scanned for the end
+    /// of the statement, and the state at the end of that prefix
+    scanned: usize,
+    scan: Scan,
+    /// Whether the scanned part of `buffer` has any tokens other than
+    /// whitespace and comments
+    has_content: bool,
+    /// The statements parsed, but not yet returned
+    pending: std::vec::IntoIter<Statement>,
+    done: bool,
+    options: ParserOptions,
+}
+
+impl<'a, R: Read> StatementStream<'a, BufReader<R>> {
+    /// Parse the statements read from `reader`, buffering it as needed
+    pub fn from_read(dialect: &'a dyn Dialect, reader: R) -> Self {
+        StatementStream::new(dialect, BufReader::new(reader))
+    }
+}
+
+impl<'a, R: BufRead> StatementStream<'a, R> {
+    /// Parse the statements read from `reader`
+    pub fn new(dialect: &'a dyn Dialect, reader: R) -> Self {
+        StatementStream {
+            dialect,
+            reader,
+            buffer: String::new(),
+            location: Location::start(),
+            scanned: 0,
+            scan: Scan::Code,
+            has_content: false,
+            pending: vec![].into_iter(),
+            done: false,
+            options: ParserOptions::default(),
+        }
+    }
+
+    /// Limit the input that each statement is parsed from. A statement whose
+    /// text is longer than `options.max_input_length` ends the iteration with
+    /// an error, as the start of the next statement cannot be found without
+    /// reading it.
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Read the next statement's text, and return it with its location, or
+    /// `None` at the end of the input.
+    fn next_statement_text(&mut self) -> Result<Option<(String, Location)>, ParserError> {
+        loop {
+            let end = match self.scan_for_semicolon() {
+                Some(semicolon) if self.is_copy_from_stdin(&self.buffer[..=semicolon]) => {
+                    self.read_copy_data(semicolon + 1)?
+                }
+                Some(semicolon) => self.skip_trailing_comments(semicolon + 1),
+                None if self.read_more()? => continue,
+                // The last statement need not end with a semicolon
+                None if !self.buffer.is_empty() => self.buffer.len(),
+                None => return Ok(None),
+            };
+            let text: String = self.buffer.drain(..end).collect();
+            let location = self.location;
+            self.location.advance(&text);
+            self.scanned = self.scanned.saturating_sub(end);
+            if mem::replace(&mut self.has_content, false) {
+                return Ok(Some((text, location)));
+            }
+        }
+    }
+
+    /// Append the next line of input to `buffer`, returning its length, which
+    /// is 0 at the end of the input
+    fn read_line(&mut self) -> Result<usize, ParserError> {
+        self.reader
+            .read_line(&mut self.buffer)
+            .map_err(|e| ParserError::new(ParserErrorKind::IoError, e.to_string()))
+    }
+
+    /// Read more of the statement at the start of `buffer`, which has been
+    /// scanned to its end, returning whether there was more input
+    fn read_more(&mut self) -> Result<bool, ParserError> {
+        match self.options.max_input_length {
+            Some(max) if self.buffer.len() > max => Err(ParserError {
+                span: Span::new(self.location, self.location),
+                ..ParserError::new(
+                    ParserErrorKind::LimitExceeded,
+                    format!("Exceeded the maximum input length of {} bytes", max),
+                )
+            }),
+            _ => Ok(self.read_line()? > 0),
+        }
+    }
+
+    /// Scan the rest of `buffer` for a semicolon that ends a statement, and
+    /// return its offset.
+    fn scan_for_semicolon(&mut self) -> Option<usize> {
+        let start = self.scanned;
+        let mut chars = self.buffer[start..]
+            .char_indices()
+            .map(|(i, ch)| (start + i, ch))
+            .peekable();
+        while let Some((i, ch)) = chars.next() {
+            let mut next_is = |expected| match chars.peek() {
+                Some(&(_, next)) if next == expected => chars.next().is_some(),
+                _ => false,
+            };
+            self.scan = match (self.scan, ch) {
+                (Scan::Code, ';') => {
+                    self.scanned = i + 1;
+                    return Some(i);
+                }
+                (Scan::Code, '-') if next_is('-') => Scan::LineComment,
+                (Scan::Code, '/') if next_is('*') => Scan::BlockComment,
+                (Scan::Code, ch) => {
+                    self.has_content |= !ch.is_whitespace();
+                    if ch == '\'' {
+                        Scan::String
+                    } else if self.dialect.is_delimited_identifier_start(ch) {
+                        Scan::DelimitedIdentifier(Word::matching_end_quote(ch))
+                    } else {
+                        Scan::Code
+                    }
+                }
+                (Scan::String, '\'') => Scan::Code,
+                (Scan::DelimitedIdentifier(end), ch) if ch == end => Scan::Code,
+                (Scan::LineComment, '\n') => Scan::Code,
+                (Scan::BlockComment, '*') if next_is('/') => Scan::Code,
+                (scan, _) => scan,
+            };
+        }
+        self.scanned = self.buffer.len();
+        None
+    }
+
+    /// Return the offset of the end of the comments following `start` on the
+    /// same line, so that they are kept with the statement before them.
+    fn skip_trailing_comments(&mut self, start: usize) -> usize {
+        let mut end = start;
+        loop {
+            let rest = &self.buffer[end..];
+            let code = rest.trim_start_matches(&[' ', '\t'][..]);
+            let offset = end + rest.len() - code.len();
+            if code.starts_with("--") {
+                end = match code.find('\n') {
+                    Some(newline) => offset + newline + 1,
+                    None => self.buffer.len(),
+                };
+                break;
+            }
+            match code.strip_prefix("/*").and_then(|c| c.find("*/")) {
+                Some(close) if !code[..close + 2].contains('\n') => end = offset + close + 4,
+                _ => break,
+            }
+        }
+        self.scanned = self.scanned.max(end);
+        end
+    }
+
+    /// Whether `text` is a `COPY ... FROM STDIN;` statement, which is followed
+    /// by data rather than by another statement
+    fn is_copy_from_stdin(&self, text: &str) -> bool {
+        // Avoid tokenizing statements that cannot match
+        if !text
+            .as_bytes()
+            .windows(5)
+            .any(|w| w.eq_ignore_ascii_case(b"STDIN"))
+        {
+            return false;
+        }
+        let tokens: Result<Vec<_>, _> = Tokenizer::new(self.dialect, text)
+            .map(|t| t.map(|t| t.token))
+            .filter(|t| !matches!(t, Ok(Token::Whitespace(_))))
+            .collect();
+        let keyword = |token: &Token| match token {
+            Token::Word(w) => w.keyword,
+            _ => Keyword::NoKeyword,
+        };
+        match tokens.as_ref().map(|t| t.as_slice()) {
+            Ok([first, .., from, stdin, Token::SemiColon]) => {
+                keyword(first) == Keyword::COPY
+                    && keyword(from) == Keyword::FROM
+                    && keyword(stdin) == Keyword::STDIN
+            }
+            _ => false,
+        }
+    }
+
+    /// Read the data of a `COPY` statement, which starts at `start` in
+    /// `buffer`, up to and including the `\.` line that ends it, and return
+    /// the offset of its end.
+    fn read_copy_data(&mut self, start: usize) -> Result<usize, ParserError> {
+        let mut line_start = start;
+        loop {
+            match self.buffer[line_start..].find('\n') {
+                Some(len) => {
+                    let line = &self.buffer[line_start..line_start + len];
+                    line_start += len + 1;
+                    if line.trim_end_matches('\r') == "\\." {
+                        break;
+                    }
+                }
+                None if self.read_line()? > 0 => {}
+                None => {
+                    line_start = self.buffer.len();
+                    break;
+                }
+            }
+        }
+        // The data is not scanned for semicolons
+        self.scanned = line_start;
+        Ok(line_start)
+    }
+}
+
+impl<'a, R: BufRead> Iterator for StatementStream<'a, R> {
+    type Item = Result<Statement, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(statement) = self.pending.next() {
+                return Some(Ok(statement));
+            }
+            if self.done {
+                return None;
+            }
+            match self.next_statement_text() {
+                Ok(Some((text, location))) => {
+                    let tokenizer = Tokenizer::new_at(self.dialect, &text, location);
+                    let mut parser = Parser::from_tokenizer(tokenizer).with_options(self.options);
+                    match parser.parse_statements() {
+                        Ok(statements) => self.pending = statements.into_iter(),
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+                Ok(None) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::{GenericDialect, MsSqlDialect, PostgreSqlDialect};
+    use std::io;
+
+    fn stream_statements(dialect: &dyn Dialect, sql: &str) -> Vec<Result<String, ParserError>> {
+        // A tiny buffer makes sure that statements are split across reads
+        let reader = BufReader::with_capacity(3, sql.as_bytes());
+        StatementStream::new(dialect, reader)
+            .map(|s| s.map(|s| s.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_stream_statements() {
+        let sql = "SELECT 'a;b', \"c;\" -- d;\n\
+                   FROM t /* e; */;;\n\
+                   \n\
+                   SELECT 1;  -- trailing comment\n\
+                   SELECT 2";
+        assert_eq!(
+            stream_statements(&GenericDialect {}, sql),
+            vec![
+                Ok("SELECT 'a;b', \"c;\" -- d;\n FROM t /* e; */".to_string()),
+                Ok("SELECT 1 -- trailing comment\n".to_string()),
+                Ok("SELECT 2".to_string()),
+            ]
+        );
+        assert_eq!(
+            stream_statements(&MsSqlDialect {}, "SELECT [a;b] FROM t; SELECT 1"),
+            vec![
+                Ok("SELECT [a;b] FROM t".to_string()),
+                Ok("SELECT 1".to_string()),
+            ]
+        );
+        assert!(stream_statements(&GenericDialect {}, " -- nothing\n").is_empty());
+    }
+
+    #[test]
+    fn test_stream_copy_from_stdin() {
+        let sql = "COPY t (a, b) FROM STDIN;\n\
+                   1\tx;y\n\
+                   \\.\n\
+                   SELECT 1;";
+        let statements: Vec<_> = StatementStream::from_read(&PostgreSqlDialect {}, sql.as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(statements.len(), 2);
+        match &statements[0] {
+            Statement::Copy { values, .. } => {
+                assert_eq!(values.last(), Some(&Some("x;y".to_string())))
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(statements[1].to_string(), "SELECT 1");
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        let results = stream_statements(&GenericDialect {}, "SELECT 1;\nDROP x;\nSELECT\n  a b c");
+        assert_eq!(results[0], Ok("SELECT 1".to_string()));
+        assert_eq!(
+            results[1].as_ref().unwrap_err().message,
+            "Expected TABLE, VIEW or INDEX after DROP, found: x"
+        );
+        // Locations are relative to the whole input
+        let err = results[2].as_ref().unwrap_err();
+        assert_eq!(err.message, "Expected end of statement, found: c");
+        assert_eq!(
+            (
+                err.span.start.offset,
+                err.span.start.line,
+                err.span.start.column
+            ),
+            (31, 4, 7)
+        );
+        assert_eq!(results.len(), 3);
+
+        struct FailingReader;
+        impl Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "access denied",
+                ))
+            }
+        }
+        let results: Vec<_> =
+            StatementStream::from_read(&GenericDialect {}, FailingReader).collect();
+        assert_eq!(results.len(), 1);
+        let err = results[0].as_ref().unwrap_err();
+        assert_eq!(err.kind, ParserErrorKind::IoError);
+        assert_eq!(err.message, "access denied");
+    }
+
+    #[test]
+    fn test_stream_limits() {
+        let options = ParserOptions {
+            max_input_length: Some(20),
+            ..ParserOptions::default()
+        };
+        let sql = "SELECT 1; SELECT 2; SELECT 3;\nSELECT a,\nb, c, d, e, f\nFROM t;\nSELECT 4";
+        let results: Vec<_> = StatementStream::from_read(&GenericDialect {}, sql.as_bytes())
+            .with_options(options)
+            .collect();
+        // The limit applies to each statement rather than to the whole input
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[2].as_ref().unwrap().to_string(), "SELECT 3");
+        let err = results[3].as_ref().unwrap_err();
+        assert_eq!(err.kind, ParserErrorKind::LimitExceeded);
+        assert_eq!(err.span.start.offset, 29);
+    }
+}
@@ -26,9 +26,9 @@ pub struct TestedDialects {
 impl TestedDialects {
     /// Run the given function for all of `self.dialects`, assert that they
     /// return the same result, and return that result.
-    pub fn one_of_identical_results<F, T: Debug + PartialEq>(&self, f: F) -> T
+    pub fn one_of_identical_results<'a, F, T: Debug + PartialEq>(&'a self, f: F) -> T
     where
-        F: Fn(&dyn Dialect) -> T,
+        F: Fn(&'a dyn Dialect) -> T,
     {
         let parse_results = self.dialects.iter().map(|dialect| (dialect, f(&**dialect)));
         parse_results
@@ -46,14 +46,14 @@ impl TestedDialects {
             .1
     }
 
-    pub fn run_parser_method<F, T: Debug + PartialEq>(&self, sql: &str, f: F) -> T
+    pub fn run_parser_method<'a, F, T: Debug + PartialEq>(&'a self, sql: &'a str, f: F) -> T
     where
-        F: Fn(&mut Parser) -> T,
+        F: Fn(&mut Parser<'a>) -> T,
     {
         self.one_of_identical_results(|dialect| {
             let mut tokenizer = Tokenizer::new(dialect, sql);
-            let tokens = tokenizer.tokenize().unwrap();
-            f(&mut Parser::new(tokens))
+            let tokens = tokenizer.tokenize_with_location().unwrap();
+            f(&mut Parser::new_with_locations(tokens, dialect))
         })
     }
 
@@ -121,6 +121,20 @@ pub fn all_dialects() -> TestedDialects {
     }
 }
 
+/// Ensures that printing `statement` and parsing the result with `dialect`
+/// gives back the same [Statement], e.g. for a randomly generated one.
+pub fn assert_round_trip(dialect: &dyn Dialect, statement: &Statement) {
+    let sql = statement.to_string();
+    let parsed = Parser::parse_sql(dialect, sql.clone())
+        .unwrap_or_else(|e| panic!("Failed to reparse {:?}: {}", sql, e));
+    assert_eq!(
+        vec![statement.clone()],
+        parsed,
+        "Statement reparsed differently from {:?}",
+        sql
+    );
+}
+
 pub fn only<T>(v: impl IntoIterator<Item = T>) -> T {
     let mut iter = v.into_iter();
     if let (Some(item), None) = (iter.next(), iter.next()) {
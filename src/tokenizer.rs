@@ -16,32 +16,38 @@
 //!
 //! The tokens then form the input for the parser, which outputs an Abstract Syntax Tree (AST).
 
+use std::borrow::Cow;
+use std::cmp;
 use std::iter::Peekable;
 use std::str::Chars;
 
-use super::dialect::keywords::ALL_KEYWORDS;
-use super::dialect::Dialect;
+use super::dialect::keywords::Keyword;
+use super::dialect::{Dialect, PlaceholderStyle};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-/// SQL Token enumeration
+/// SQL Token enumeration. Tokens borrow their text from the input where
+/// possible; use [Token::into_owned] to detach a token from it.
 #[derive(Debug, Clone, PartialEq)]
-pub enum Token {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Token<'a> {
     /// A keyword (like SELECT) or an optionally quoted SQL identifier
-    Word(Word),
+    Word(Word<'a>),
     /// An unsigned numeric literal
-    Number(String),
+    Number(Cow<'a, str>),
     /// A character that could not be tokenized
     Char(char),
     /// Single quoted string: i.e: 'string'
-    SingleQuotedString(String),
+    SingleQuotedString(Cow<'a, str>),
     /// "National" string literal: i.e: N'string'
-    NationalStringLiteral(String),
+    NationalStringLiteral(Cow<'a, str>),
     /// Hexadecimal string literal: i.e.: X'deadbeef'
-    HexStringLiteral(String),
+    HexStringLiteral(Cow<'a, str>),
     /// Comma
     Comma,
     /// Whitespace (space, tab, etc)
-    Whitespace(Whitespace),
+    Whitespace(Whitespace<'a>),
     /// Equality operator `=`
     Eq,
     /// Not Equals operator `<>` (or `!=` in some dialects)
@@ -88,9 +94,12 @@ pub enum Token {
     LBrace,
     /// Right brace `}`
     RBrace,
+    /// A bind parameter placeholder: `?`, `$1`, `:name` or `@name`,
+    /// depending on the dialect
+    Placeholder(Cow<'a, str>),
 }
 
-impl fmt::Display for Token {
+impl fmt::Display for Token<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Token::Word(ref w) => write!(f, "{}", w),
@@ -124,48 +133,87 @@ impl fmt::Display for Token {
             Token::Ampersand => f.write_str("&"),
             Token::LBrace => f.write_str("{"),
             Token::RBrace => f.write_str("}"),
+            Token::Placeholder(ref s) => f.write_str(s),
         }
     }
 }
 
-impl Token {
-    pub fn make_keyword(keyword: &str) -> Self {
+impl<'a> Token<'a> {
+    pub fn make_keyword(keyword: &'a str) -> Self {
         Token::make_word(keyword, None)
     }
-    pub fn make_word(word: &str, quote_style: Option<char>) -> Self {
-        let word_uppercase = word.to_uppercase();
-        //TODO: need to reintroduce FnvHashSet at some point .. iterating over keywords is
-        // not fast but I want the simplicity for now while I experiment with pluggable
-        // dialects
-        let is_keyword = quote_style == None && ALL_KEYWORDS.contains(&word_uppercase.as_str());
+    pub fn make_word(word: &'a str, quote_style: Option<char>) -> Self {
         Token::Word(Word {
-            value: word.to_string(),
+            value: Cow::Borrowed(word),
             quote_style,
-            keyword: if is_keyword {
-                word_uppercase
+            keyword: if quote_style.is_none() {
+                Keyword::lookup(word)
             } else {
-                "".to_string()
+                Keyword::NoKeyword
             },
         })
     }
 }
 
+impl Token<'_> {
+    /// Copy any text in the token, so that it no longer borrows the input
+    pub fn into_owned(self) -> Token<'static> {
+        let own = |s: Cow<'_, str>| Cow::Owned(s.into_owned());
+        match self {
+            Token::Word(w) => Token::Word(w.into_owned()),
+            Token::Number(n) => Token::Number(own(n)),
+            Token::Char(c) => Token::Char(c),
+            Token::SingleQuotedString(s) => Token::SingleQuotedString(own(s)),
+            Token::NationalStringLiteral(s) => Token::NationalStringLiteral(own(s)),
+            Token::HexStringLiteral(s) => Token::HexStringLiteral(own(s)),
+            Token::Comma => Token::Comma,
+            Token::Whitespace(ws) => Token::Whitespace(ws.into_owned()),
+            Token::Eq => Token::Eq,
+            Token::Neq => Token::Neq,
+            Token::Lt => Token::Lt,
+            Token::Gt => Token::Gt,
+            Token::LtEq => Token::LtEq,
+            Token::GtEq => Token::GtEq,
+            Token::Plus => Token::Plus,
+            Token::Minus => Token::Minus,
+            Token::Mult => Token::Mult,
+            Token::Div => Token::Div,
+            Token::Mod => Token::Mod,
+            Token::LParen => Token::LParen,
+            Token::RParen => Token::RParen,
+            Token::Period => Token::Period,
+            Token::Colon => Token::Colon,
+            Token::DoubleColon => Token::DoubleColon,
+            Token::SemiColon => Token::SemiColon,
+            Token::Backslash => Token::Backslash,
+            Token::LBracket => Token::LBracket,
+            Token::RBracket => Token::RBracket,
+            Token::Ampersand => Token::Ampersand,
+            Token::LBrace => Token::LBrace,
+            Token::RBrace => Token::RBrace,
+            Token::Placeholder(s) => Token::Placeholder(own(s)),
+        }
+    }
+}
+
 /// A keyword (like SELECT) or an optionally quoted SQL identifier
 #[derive(Debug, Clone, PartialEq)]
-pub struct Word {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Word<'a> {
     /// The value of the token, without the enclosing quotes, and with the
     /// escape sequences (if any) processed (TODO: escapes are not handled)
-    pub value: String,
+    pub value: Cow<'a, str>,
     /// An identifier can be "quoted" (&lt;delimited identifier> in ANSI parlance).
     /// The standard and most implementations allow using double quotes for this,
     /// but some implementations support other quoting styles as well (e.g. \[MS SQL])
     pub quote_style: Option<char>,
     /// If the word was not quoted and it matched one of the known keywords,
-    /// this will have one of the values from dialect::keywords, otherwise empty
-    pub keyword: String,
+    /// this will have one of the values from dialect::keywords, otherwise
+    /// `Keyword::NoKeyword`
+    pub keyword: Keyword,
 }
 
-impl fmt::Display for Word {
+impl fmt::Display for Word<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.quote_style {
             Some(s) if s == '"' || s == '[' || s == '`' => {
@@ -176,8 +224,17 @@ impl fmt::Display for Word {
         }
     }
 }
-impl Word {
-    fn matching_end_quote(ch: char) -> char {
+impl Word<'_> {
+    /// Copy the value of the word, so that it no longer borrows the input
+    pub fn into_owned(self) -> Word<'static> {
+        Word {
+            value: Cow::Owned(self.value.into_owned()),
+            quote_style: self.quote_style,
+            keyword: self.keyword,
+        }
+    }
+
+    pub(crate) fn matching_end_quote(ch: char) -> char {
         match ch {
             '"' => '"', // ANSI and most dialects
             '[' => ']', // MS SQL
@@ -188,15 +245,33 @@ impl Word {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum Whitespace {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Whitespace<'a> {
     Space,
     Newline,
     Tab,
-    SingleLineComment(String),
-    MultiLineComment(String),
+    SingleLineComment(Cow<'a, str>),
+    MultiLineComment(Cow<'a, str>),
+}
+
+impl Whitespace<'_> {
+    /// Copy any text in the whitespace, so that it no longer borrows the input
+    pub fn into_owned(self) -> Whitespace<'static> {
+        match self {
+            Whitespace::Space => Whitespace::Space,
+            Whitespace::Newline => Whitespace::Newline,
+            Whitespace::Tab => Whitespace::Tab,
+            Whitespace::SingleLineComment(s) => {
+                Whitespace::SingleLineComment(Cow::Owned(s.into_owned()))
+            }
+            Whitespace::MultiLineComment(s) => {
+                Whitespace::MultiLineComment(Cow::Owned(s.into_owned()))
+            }
+        }
+    }
 }
 
-impl fmt::Display for Whitespace {
+impl fmt::Display for Whitespace<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Whitespace::Space => f.write_str(" "),
@@ -208,57 +283,242 @@ impl fmt::Display for Whitespace {
     }
 }
 
+/// A position in the source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Location {
+    /// Byte offset from the start of the input
+    pub offset: usize,
+    /// Line number, starting from 1
+    pub line: u64,
+    /// Column number (in characters), starting from 1
+    pub column: u64,
+}
+
+impl Location {
+    /// The location of the first character of the input
+    pub fn start() -> Self {
+        Location {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// Move past `text`
+    pub fn advance(&mut self, text: &str) {
+        let mut chars = text.chars().peekable();
+        while let Some(ch) = chars.next() {
+            self.step(ch, chars.peek());
+        }
+    }
+
+    /// Move past `ch`, which is followed by `next`
+    fn step(&mut self, ch: char, next: Option<&char>) {
+        self.offset += ch.len_utf8();
+        // A `\r\n` sequence is counted as a single line break
+        if ch == '\n' || (ch == '\r' && next != Some(&'\n')) {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line: {}, Col: {}", self.line, self.column)
+    }
+}
+
+/// A range of the source text, from `start` (inclusive) to `end` (exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Span {
+    pub start: Location,
+    pub end: Location,
+}
+
+impl Span {
+    pub fn new(start: Location, end: Location) -> Self {
+        Span { start, end }
+    }
+
+    /// A span that does not correspond to any source text, used for tokens
+    /// and AST nodes that were not produced by the tokenizer, and for nodes
+    /// whose location is unknown. It is on line 0, so it cannot be mistaken
+    /// for a location in the source, not even for the start of the input.
+    pub fn empty() -> Self {
+        let unknown = Location {
+            offset: 0,
+            line: 0,
+            column: 0,
+        };
+        Span::new(unknown, unknown)
+    }
+
+    /// Whether this is [Span::empty], rather than a location in the source.
+    /// A span of no characters at a location in the source, like that of
+    /// the end of the input, is not empty.
+    pub fn is_empty(&self) -> bool {
+        self.start.line == 0
+    }
+
+    /// Return the smallest span covering both `self` and `other`. Empty spans
+    /// are ignored.
+    pub fn union(&self, other: &Span) -> Span {
+        if self.is_empty() {
+            *other
+        } else if other.is_empty() {
+            *self
+        } else {
+            Span::new(
+                cmp::min(self.start, other.start),
+                cmp::max(self.end, other.end),
+            )
+        }
+    }
+
+    /// Return the text covered by this span in `source`, which must be the
+    /// string the span was produced from.
+    pub fn source_text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start.offset..self.end.offset]
+    }
+}
+
+impl Default for Span {
+    fn default() -> Self {
+        Span::empty()
+    }
+}
+
+/// A [Token] together with the span of source text it was read from
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TokenWithLocation<'a> {
+    pub token: Token<'a>,
+    pub span: Span,
+}
+
+impl<'a> TokenWithLocation<'a> {
+    pub fn new(token: Token<'a>, span: Span) -> Self {
+        TokenWithLocation { token, span }
+    }
+}
+
+impl fmt::Display for TokenWithLocation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.token.fmt(f)
+    }
+}
+
 /// Tokenizer error
-#[derive(Debug, PartialEq)]
-pub struct TokenizerError(String);
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TokenizerError {
+    pub message: String,
+    /// The location at which the error was detected
+    pub location: Location,
+}
+
+impl TokenizerError {
+    fn new(message: String, location: Location) -> Self {
+        TokenizerError { message, location }
+    }
+}
+
+impl fmt::Display for TokenizerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.location)
+    }
+}
+
+/// The characters of the input, along with the location of the next one
+struct State<'a> {
+    query: &'a str,
+    peekable: Peekable<Chars<'a>>,
+    /// The byte offset of the next character in `query`. This differs from
+    /// `location.offset` if `query` does not start at the beginning of the
+    /// input.
+    pos: usize,
+    location: Location,
+}
+
+impl<'a> State<'a> {
+    fn new(query: &'a str, location: Location) -> Self {
+        State {
+            query,
+            peekable: query.chars().peekable(),
+            pos: 0,
+            location,
+        }
+    }
 
-/// SQL Tokenizer
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peekable.next()?;
+        self.pos += ch.len_utf8();
+        self.location.step(ch, self.peekable.peek());
+        Some(ch)
+    }
+
+    fn peek(&mut self) -> Option<&char> {
+        self.peekable.peek()
+    }
+
+    /// Return the input from byte offset `start` up to the next character
+    fn slice_from(&self, start: usize) -> &'a str {
+        &self.query[start..self.pos]
+    }
+}
+
+/// SQL Tokenizer. The tokens borrow from the input, and are produced one at
+/// a time by iterating over the tokenizer; iteration stops after the first
+/// error.
 pub struct Tokenizer<'a> {
     dialect: &'a dyn Dialect,
-    pub query: String,
+    state: State<'a>,
+    failed: bool,
     pub line: u64,
     pub col: u64,
 }
 
 impl<'a> Tokenizer<'a> {
     /// Create a new SQL tokenizer for the specified SQL statement
-    pub fn new(dialect: &'a dyn Dialect, query: &str) -> Self {
+    pub fn new(dialect: &'a dyn Dialect, query: &'a str) -> Self {
+        Tokenizer::new_at(dialect, query, Location::start())
+    }
+
+    /// Create a new SQL tokenizer for a part of a larger input, which begins
+    /// at `location`. The spans of the tokens are relative to the larger input.
+    pub fn new_at(dialect: &'a dyn Dialect, query: &'a str, location: Location) -> Self {
         Self {
             dialect,
-            query: query.to_string(),
-            line: 1,
-            col: 1,
+            state: State::new(query, location),
+            failed: false,
+            line: location.line,
+            col: location.column,
         }
     }
 
-    /// Tokenize the statement and produce a vector of tokens
-    pub fn tokenize(&mut self) -> Result<Vec<Token>, TokenizerError> {
-        let mut peekable = self.query.chars().peekable();
-
-        let mut tokens: Vec<Token> = vec![];
-
-        while let Some(token) = self.next_token(&mut peekable)? {
-            match &token {
-                Token::Whitespace(Whitespace::Newline) => {
-                    self.line += 1;
-                    self.col = 1;
-                }
+    /// Return the dialect used to tokenize the input
+    pub fn dialect(&self) -> &'a dyn Dialect {
+        self.dialect
+    }
 
-                Token::Whitespace(Whitespace::Tab) => self.col += 4,
-                Token::Word(w) if w.quote_style == None => self.col += w.value.len() as u64,
-                Token::Word(w) if w.quote_style != None => self.col += w.value.len() as u64 + 2,
-                Token::Number(s) => self.col += s.len() as u64,
-                Token::SingleQuotedString(s) => self.col += s.len() as u64,
-                _ => self.col += 1,
-            }
+    /// Tokenize the statement and produce a vector of tokens
+    pub fn tokenize(&mut self) -> Result<Vec<Token<'a>>, TokenizerError> {
+        self.map(|t| t.map(|t| t.token)).collect()
+    }
 
-            tokens.push(token);
-        }
-        Ok(tokens)
+    /// Tokenize the statement and produce a vector of tokens, each annotated
+    /// with the span of source text it was read from
+    pub fn tokenize_with_location(&mut self) -> Result<Vec<TokenWithLocation<'a>>, TokenizerError> {
+        self.collect()
     }
 
     /// Get the next token or return None
-    fn next_token(&self, chars: &mut Peekable<Chars<'_>>) -> Result<Option<Token>, TokenizerError> {
+    fn next_token(&self, chars: &mut State<'a>) -> Result<Option<Token<'a>>, TokenizerError> {
         //println!("next_token: {:?}", chars.peek());
         match chars.peek() {
             Some(&ch) => match ch {
@@ -284,7 +544,7 @@ impl<'a> Tokenizer<'a> {
                         _ => {
                             // regular identifier starting with an "N"
                             let s = self.tokenize_word('N', chars);
-                            Ok(Some(Token::make_word(&s, None)))
+                            Ok(Some(Token::make_word(s, None)))
                         }
                     }
                 }
@@ -301,15 +561,57 @@ impl<'a> Tokenizer<'a> {
                         _ => {
                             // regular identifier starting with an "X"
                             let s = self.tokenize_word(x, chars);
-                            Ok(Some(Token::make_word(&s, None)))
+                            Ok(Some(Token::make_word(s, None)))
+                        }
+                    }
+                }
+                '?' if self
+                    .dialect
+                    .supports_placeholder(PlaceholderStyle::QuestionMark) =>
+                {
+                    self.consume_and_return(chars, Token::Placeholder(Cow::Borrowed("?")))
+                }
+                '$' if self.dialect.supports_placeholder(PlaceholderStyle::Dollar) => {
+                    let start = chars.pos;
+                    let location = chars.location;
+                    chars.next();
+                    match chars.peek() {
+                        Some(ch) if ch.is_ascii_digit() => {
+                            let digits = peeking_take_while(chars, |ch| ch.is_ascii_digit());
+                            let placeholder = chars.slice_from(start);
+                            // Parameters are numbered from 1, and `$01` would
+                            // be another spelling of `$1`
+                            if digits.starts_with('0') {
+                                return Err(TokenizerError::new(
+                                    format!(
+                                        "Invalid placeholder {}: parameters are numbered \
+                                         from $1, without leading zeros",
+                                        placeholder
+                                    ),
+                                    location,
+                                ));
+                            }
+                            Ok(Some(Token::Placeholder(Cow::Borrowed(placeholder))))
+                        }
+                        _ => self.tokenize_after_sigil('$', chars),
+                    }
+                }
+                '@' if self.dialect.supports_placeholder(PlaceholderStyle::At) => {
+                    let start = chars.pos;
+                    chars.next();
+                    match chars.peek() {
+                        // `@@name` is not a placeholder
+                        Some(&ch) if ch != '@' && self.dialect.is_identifier_start(ch) => {
+                            Ok(Some(self.tokenize_placeholder(start, chars)))
                         }
+                        _ => self.tokenize_after_sigil('@', chars),
                     }
                 }
                 // identifier or keyword
                 ch if self.dialect.is_identifier_start(ch) => {
                     chars.next(); // consume the first char
                     let s = self.tokenize_word(ch, chars);
-                    Ok(Some(Token::make_word(&s, None)))
+                    Ok(Some(Token::make_word(s, None)))
                 }
                 // string
                 '\'' => {
@@ -322,12 +624,12 @@ impl<'a> Tokenizer<'a> {
                     let quote_end = Word::matching_end_quote(quote_start);
                     let s = peeking_take_while(chars, |ch| ch != quote_end);
                     if chars.next() == Some(quote_end) {
-                        Ok(Some(Token::make_word(&s, Some(quote_start))))
+                        Ok(Some(Token::make_word(s, Some(quote_start))))
                     } else {
-                        Err(TokenizerError(format!(
-                            "Expected close delimiter '{}' before EOF.",
-                            quote_end
-                        )))
+                        Err(TokenizerError::new(
+                            format!("Expected close delimiter '{}' before EOF.", quote_end),
+                            chars.location,
+                        ))
                     }
                 }
                 // numbers
@@ -337,7 +639,7 @@ impl<'a> Tokenizer<'a> {
                         '0'..='9' | '.' => true,
                         _ => false,
                     });
-                    Ok(Some(Token::Number(s)))
+                    Ok(Some(Token::Number(Cow::Borrowed(s))))
                 }
                 // punctuation
                 '(' => self.consume_and_return(chars, Token::LParen),
@@ -349,12 +651,15 @@ impl<'a> Tokenizer<'a> {
                     match chars.peek() {
                         Some('-') => {
                             chars.next(); // consume the second '-', starting a single-line comment
-                            let mut s = peeking_take_while(chars, |ch| ch != '\n');
+                            let start = chars.pos;
+                            peeking_take_while(chars, |ch| ch != '\n');
                             if let Some(ch) = chars.next() {
                                 assert_eq!(ch, '\n');
-                                s.push(ch);
                             }
-                            Ok(Some(Token::Whitespace(Whitespace::SingleLineComment(s))))
+                            let s = chars.slice_from(start);
+                            Ok(Some(Token::Whitespace(Whitespace::SingleLineComment(
+                                Cow::Borrowed(s),
+                            ))))
                         }
                         // a regular '-' operator
                         _ => Ok(Some(Token::Minus)),
@@ -380,10 +685,10 @@ impl<'a> Tokenizer<'a> {
                     chars.next(); // consume
                     match chars.peek() {
                         Some('=') => self.consume_and_return(chars, Token::Neq),
-                        _ => Err(TokenizerError(format!(
-                            "Tokenizer Error at Line: {}, Col: {}",
-                            self.line, self.col
-                        ))),
+                        _ => Err(TokenizerError::new(
+                            "Expected '=' after '!'".to_string(),
+                            chars.location,
+                        )),
                     }
                 }
                 '<' => {
@@ -402,9 +707,16 @@ impl<'a> Tokenizer<'a> {
                     }
                 }
                 ':' => {
+                    let start = chars.pos;
                     chars.next();
                     match chars.peek() {
                         Some(':') => self.consume_and_return(chars, Token::DoubleColon),
+                        Some(&ch)
+                            if self.dialect.supports_placeholder(PlaceholderStyle::Colon)
+                                && self.dialect.is_identifier_start(ch) =>
+                        {
+                            Ok(Some(self.tokenize_placeholder(start, chars)))
+                        }
                         _ => Ok(Some(Token::Colon)),
                     }
                 }
@@ -421,69 +733,91 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    /// Tokenize a named placeholder, after its sigil (which started at byte
+    /// offset `start`) is already consumed.
+    fn tokenize_placeholder(&self, start: usize, chars: &mut State<'a>) -> Token<'a> {
+        peeking_take_while(chars, |ch| self.dialect.is_identifier_part(ch));
+        Token::Placeholder(Cow::Borrowed(chars.slice_from(start)))
+    }
+
+    /// Tokenize what a placeholder `sigil`, already consumed, would have
+    /// been without placeholders: a word, or else a single character.
+    fn tokenize_after_sigil(
+        &self,
+        sigil: char,
+        chars: &mut State<'a>,
+    ) -> Result<Option<Token<'a>>, TokenizerError> {
+        if self.dialect.is_identifier_start(sigil) {
+            let s = self.tokenize_word(sigil, chars);
+            Ok(Some(Token::make_word(s, None)))
+        } else {
+            Ok(Some(Token::Char(sigil)))
+        }
+    }
+
     /// Tokenize an identifier or keyword, after the first char is already consumed.
-    fn tokenize_word(&self, first_char: char, chars: &mut Peekable<Chars<'_>>) -> String {
-        let mut s = first_char.to_string();
-        s.push_str(&peeking_take_while(chars, |ch| {
-            self.dialect.is_identifier_part(ch)
-        }));
-        s
+    fn tokenize_word(&self, first_char: char, chars: &mut State<'a>) -> &'a str {
+        let start = chars.pos - first_char.len_utf8();
+        peeking_take_while(chars, |ch| self.dialect.is_identifier_part(ch));
+        chars.slice_from(start)
     }
 
-    /// Read a single quoted string, starting with the opening quote.
-    fn tokenize_single_quoted_string(&self, chars: &mut Peekable<Chars<'_>>) -> String {
-        //TODO: handle escaped quotes in string
+    /// Read a single quoted string, starting with the opening quote. The
+    /// result borrows from the input unless it contains escaped quotes.
+    fn tokenize_single_quoted_string(&self, chars: &mut State<'a>) -> Cow<'a, str> {
         //TODO: handle newlines in string
         //TODO: handle EOF before terminating quote
         //TODO: handle 'string' <white space> 'string continuation'
-        let mut s = String::new();
         chars.next(); // consume the opening quote
+        let mut unescaped: Option<String> = None;
+        let mut start = chars.pos;
+        let mut end = start;
         while let Some(&ch) = chars.peek() {
-            match ch {
-                '\'' => {
-                    chars.next(); // consume
-                    let escaped_quote = chars.peek().map(|c| *c == '\'').unwrap_or(false);
-                    if escaped_quote {
-                        s.push('\'');
-                        chars.next();
-                    } else {
-                        break;
-                    }
-                }
-                _ => {
-                    chars.next(); // consume
-                    s.push(ch);
+            end = chars.pos;
+            chars.next(); // consume
+            if ch == '\'' {
+                if chars.peek() != Some(&'\'') {
+                    break;
                 }
+                // an escaped quote: keep everything up to and including the
+                // first quote, and skip the second one
+                let s = unescaped.get_or_insert_with(String::new);
+                s.push_str(chars.slice_from(start));
+                chars.next();
+                start = chars.pos;
             }
+            end = chars.pos;
+        }
+        let rest = &chars.query[start..end];
+        match unescaped {
+            Some(mut s) => {
+                s.push_str(rest);
+                Cow::Owned(s)
+            }
+            None => Cow::Borrowed(rest),
         }
-        s
     }
 
     fn tokenize_multiline_comment(
         &self,
-        chars: &mut Peekable<Chars<'_>>,
-    ) -> Result<Option<Token>, TokenizerError> {
-        let mut s = String::new();
-        let mut maybe_closing_comment = false;
+        chars: &mut State<'a>,
+    ) -> Result<Option<Token<'a>>, TokenizerError> {
+        let start = chars.pos;
         // TODO: deal with nested comments
         loop {
             match chars.next() {
-                Some(ch) => {
-                    if maybe_closing_comment {
-                        if ch == '/' {
-                            break Ok(Some(Token::Whitespace(Whitespace::MultiLineComment(s))));
-                        } else {
-                            s.push('*');
-                        }
-                    }
-                    maybe_closing_comment = ch == '*';
-                    if !maybe_closing_comment {
-                        s.push(ch);
-                    }
+                Some('*') if chars.peek() == Some(&'/') => {
+                    let s = &chars.query[start..chars.pos - 1];
+                    chars.next();
+                    break Ok(Some(Token::Whitespace(Whitespace::MultiLineComment(
+                        Cow::Borrowed(s),
+                    ))));
                 }
+                Some(_) => {}
                 None => {
-                    break Err(TokenizerError(
+                    break Err(TokenizerError::new(
                         "Unexpected EOF while in a multi-line comment".to_string(),
+                        chars.location,
                     ));
                 }
             }
@@ -492,31 +826,58 @@ impl<'a> Tokenizer<'a> {
 
     fn consume_and_return(
         &self,
-        chars: &mut Peekable<Chars<'_>>,
-        t: Token,
-    ) -> Result<Option<Token>, TokenizerError> {
+        chars: &mut State<'a>,
+        t: Token<'a>,
+    ) -> Result<Option<Token<'a>>, TokenizerError> {
         chars.next();
         Ok(Some(t))
     }
 }
 
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<TokenWithLocation<'a>, TokenizerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let mut state = std::mem::replace(&mut self.state, State::new("", Location::start()));
+        let start = state.location;
+        let token = self.next_token(&mut state);
+        self.state = state;
+        self.line = self.state.location.line;
+        self.col = self.state.location.column;
+        match token {
+            Ok(token) => token.map(|token| {
+                Ok(TokenWithLocation::new(
+                    token,
+                    Span::new(start, self.state.location),
+                ))
+            }),
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
 /// Read from `chars` until `predicate` returns `false` or EOF is hit.
-/// Return the characters read as String, and keep the first non-matching
-/// char available as `chars.next()`.
-fn peeking_take_while(
-    chars: &mut Peekable<Chars<'_>>,
+/// Return the characters read, and keep the first non-matching char
+/// available as `chars.next()`.
+fn peeking_take_while<'a>(
+    chars: &mut State<'a>,
     mut predicate: impl FnMut(char) -> bool,
-) -> String {
-    let mut s = String::new();
+) -> &'a str {
+    let start = chars.pos;
     while let Some(&ch) = chars.peek() {
         if predicate(ch) {
             chars.next(); // consume
-            s.push(ch);
         } else {
             break;
         }
     }
-    s
+    chars.slice_from(start)
 }
 
 #[cfg(test)]
@@ -534,7 +895,7 @@ mod tests {
         let expected = vec![
             Token::make_keyword("SELECT"),
             Token::Whitespace(Whitespace::Space),
-            Token::Number(String::from("1")),
+            Token::Number("1".into()),
         ];
 
         compare(expected, tokens);
@@ -552,7 +913,7 @@ mod tests {
             Token::Whitespace(Whitespace::Space),
             Token::make_word("sqrt", None),
             Token::LParen,
-            Token::Number(String::from("1")),
+            Token::Number("1".into()),
             Token::RParen,
         ];
 
@@ -581,11 +942,11 @@ mod tests {
             Token::Whitespace(Whitespace::Space),
             Token::Eq,
             Token::Whitespace(Whitespace::Space),
-            Token::Number(String::from("1")),
+            Token::Number("1".into()),
             Token::Whitespace(Whitespace::Space),
             Token::make_keyword("LIMIT"),
             Token::Whitespace(Whitespace::Space),
-            Token::Number(String::from("5")),
+            Token::Number("5".into()),
         ];
 
         compare(expected, tokens);
@@ -613,7 +974,7 @@ mod tests {
             Token::Whitespace(Whitespace::Space),
             Token::Neq,
             Token::Whitespace(Whitespace::Space),
-            Token::SingleQuotedString(String::from("Not Provided")),
+            Token::SingleQuotedString("Not Provided".into()),
         ];
 
         compare(expected, tokens);
@@ -694,11 +1055,9 @@ mod tests {
         let mut tokenizer = Tokenizer::new(&dialect, &sql);
         let tokens = tokenizer.tokenize().unwrap();
         let expected = vec![
-            Token::Number("0".to_string()),
-            Token::Whitespace(Whitespace::SingleLineComment(
-                "this is a comment\n".to_string(),
-            )),
-            Token::Number("1".to_string()),
+            Token::Number("0".into()),
+            Token::Whitespace(Whitespace::SingleLineComment("this is a comment\n".into())),
+            Token::Number("1".into()),
         ];
         compare(expected, tokens);
     }
@@ -711,7 +1070,7 @@ mod tests {
         let mut tokenizer = Tokenizer::new(&dialect, &sql);
         let tokens = tokenizer.tokenize().unwrap();
         let expected = vec![Token::Whitespace(Whitespace::SingleLineComment(
-            "this is a comment".to_string(),
+            "this is a comment".into(),
         ))];
         compare(expected, tokens);
     }
@@ -724,11 +1083,11 @@ mod tests {
         let mut tokenizer = Tokenizer::new(&dialect, &sql);
         let tokens = tokenizer.tokenize().unwrap();
         let expected = vec![
-            Token::Number("0".to_string()),
+            Token::Number("0".into()),
             Token::Whitespace(Whitespace::MultiLineComment(
-                "multi-line\n* /comment".to_string(),
+                "multi-line\n* /comment".into(),
             )),
-            Token::Number("1".to_string()),
+            Token::Number("1".into()),
         ];
         compare(expected, tokens);
     }
@@ -742,7 +1101,7 @@ mod tests {
         let tokens = tokenizer.tokenize().unwrap();
         let expected = vec![
             Token::Whitespace(Whitespace::Newline),
-            Token::Whitespace(Whitespace::MultiLineComment("* Comment *".to_string())),
+            Token::Whitespace(Whitespace::MultiLineComment("* Comment *".into())),
             Token::Whitespace(Whitespace::Newline),
         ];
         compare(expected, tokens);
@@ -756,9 +1115,14 @@ mod tests {
         let mut tokenizer = Tokenizer::new(&dialect, &sql);
         assert_eq!(
             tokenizer.tokenize(),
-            Err(TokenizerError(
-                "Expected close delimiter '\"' before EOF.".to_string(),
-            ))
+            Err(TokenizerError {
+                message: "Expected close delimiter '\"' before EOF.".into(),
+                location: Location {
+                    offset: 4,
+                    line: 1,
+                    column: 5,
+                },
+            })
         );
     }
 
@@ -782,6 +1146,156 @@ mod tests {
         compare(expected, tokens);
     }
 
+    #[test]
+    fn tokenize_with_location() {
+        let sql = String::from("SELECT 'a',\r\n  ü");
+
+        let dialect = GenericDialect {};
+        let mut tokenizer = Tokenizer::new(&dialect, &sql);
+        let tokens = tokenizer.tokenize_with_location().unwrap();
+        let spans: Vec<_> = tokens
+            .iter()
+            .map(|t| {
+                (
+                    t.span.source_text(&sql),
+                    t.span.start.line,
+                    t.span.start.column,
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("SELECT", 1, 1),
+                (" ", 1, 7),
+                ("'a'", 1, 8),
+                (",", 1, 11),
+                ("\r\n", 1, 12),
+                (" ", 2, 1),
+                (" ", 2, 2),
+                ("ü", 2, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_borrows_from_input() {
+        let sql = String::from("SELECT \"a b\", 'it''s', 'x' -- c\n");
+
+        let dialect = GenericDialect {};
+        let mut tokenizer = Tokenizer::new(&dialect, &sql);
+        let tokens = tokenizer.tokenize().unwrap();
+        let borrowed = |token: &Token| match token {
+            Token::Word(w) => matches!(w.value, Cow::Borrowed(_)),
+            Token::SingleQuotedString(s) => matches!(s, Cow::Borrowed(_)),
+            Token::Whitespace(Whitespace::SingleLineComment(s)) => {
+                matches!(s, Cow::Borrowed(_))
+            }
+            _ => true,
+        };
+        let expected = vec![
+            Token::make_keyword("SELECT"),
+            Token::Whitespace(Whitespace::Space),
+            Token::make_word("a b", Some('"')),
+            Token::Comma,
+            Token::Whitespace(Whitespace::Space),
+            Token::SingleQuotedString("it's".into()),
+            Token::Comma,
+            Token::Whitespace(Whitespace::Space),
+            Token::SingleQuotedString("x".into()),
+            Token::Whitespace(Whitespace::Space),
+            Token::Whitespace(Whitespace::SingleLineComment(" c\n".into())),
+        ];
+        // Only the string with an escaped quote needs to be copied
+        assert_eq!(
+            tokens.iter().map(borrowed).collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|t| t != &Token::SingleQuotedString("it's".into()))
+                .collect::<Vec<_>>()
+        );
+        compare(expected, tokens);
+    }
+
+    #[test]
+    fn tokenize_placeholders() {
+        use super::super::dialect::{MsSqlDialect, MySqlDialect, PostgreSqlDialect};
+
+        fn non_whitespace(dialect: &dyn Dialect, sql: &str) -> Vec<Token<'static>> {
+            let tokens = Tokenizer::new(dialect, sql).tokenize().unwrap();
+            tokens
+                .into_iter()
+                .filter(|t| !matches!(t, Token::Whitespace(_)))
+                .map(Token::into_owned)
+                .collect()
+        }
+        let placeholder = |s: &'static str| Token::Placeholder(s.into());
+
+        compare(
+            vec![
+                placeholder("?"),
+                placeholder("$12"),
+                Token::Char('$'),
+                Token::make_word("x", None),
+                placeholder(":name"),
+                Token::DoubleColon,
+                Token::make_word("@p", None),
+            ],
+            non_whitespace(&GenericDialect {}, "? $12 $x :name :: @p"),
+        );
+        compare(
+            vec![placeholder("?"), Token::make_word("$1", None)],
+            non_whitespace(&MySqlDialect {}, "? $1"),
+        );
+        compare(
+            vec![placeholder("$1"), Token::Char('?'), Token::Colon],
+            non_whitespace(&PostgreSqlDialect {}, "$1 ? :"),
+        );
+        for sql in &["SELECT $0", "SELECT $01"] {
+            let err = Tokenizer::new(&PostgreSqlDialect {}, sql)
+                .tokenize()
+                .unwrap_err();
+            assert_eq!(
+                err.message,
+                format!(
+                    "Invalid placeholder {}: parameters are numbered from $1, \
+                     without leading zeros",
+                    &sql[7..]
+                )
+            );
+            assert_eq!(err.location.offset, 7);
+        }
+        compare(
+            vec![
+                placeholder("@p1"),
+                Token::make_word("@@version", None),
+                Token::Char('?'),
+            ],
+            non_whitespace(&MsSqlDialect {}, "@p1 @@version ?"),
+        );
+    }
+
+    #[test]
+    fn tokenize_lazily() {
+        let sql = String::from("a ! b");
+
+        let dialect = GenericDialect {};
+        let mut tokenizer = Tokenizer::new(&dialect, &sql);
+        assert_eq!(
+            tokenizer.next().unwrap().unwrap().token,
+            Token::make_word("a", None)
+        );
+        assert_eq!(
+            tokenizer.next().unwrap().unwrap().token,
+            Token::Whitespace(Whitespace::Space)
+        );
+        assert_eq!(
+            tokenizer.next().unwrap().unwrap_err().message,
+            "Expected '=' after '!'"
+        );
+        assert_eq!(tokenizer.next(), None);
+    }
+
     fn compare(expected: Vec<Token>, actual: Vec<Token>) {
         //println!("------------------------------");
         //println!("tokens   = {:?}", actual);
@@ -21,15 +21,19 @@
 use matches::assert_matches;
 
 use sqlparser::ast::*;
+use sqlparser::dialect::keywords::Keyword;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::formatter::{FormatOptions, Formatter};
 use sqlparser::parser::*;
 use sqlparser::test_utils::{all_dialects, expr_from_projection, number, only};
+use sqlparser::tokenizer::{Token, Tokenizer};
 
 #[test]
 fn parse_insert_values() {
     let row = vec![
-        Expr::Value(number("1")),
-        Expr::Value(number("2")),
-        Expr::Value(number("3")),
+        Expr::Value(number("1"), NodeSpan::empty()),
+        Expr::Value(number("2"), NodeSpan::empty()),
+        Expr::Value(number("3"), NodeSpan::empty()),
     ];
     let rows1 = vec![row.clone()];
     let rows2 = vec![row.clone(), row];
@@ -89,8 +93,8 @@ fn parse_insert_invalid() {
     let sql = "INSERT public.customer (id, name, active) VALUES (1, 2, 3)";
     let res = parse_sql_statements(sql);
     assert_eq!(
-        ParserError::ParserError("Expected INTO, found: public".to_string()),
-        res.unwrap_err()
+        "Expected INTO, found: public".to_string(),
+        res.unwrap_err().message
     );
 }
 
@@ -110,15 +114,15 @@ fn parse_update() {
                 vec![
                     Assignment {
                         id: "a".into(),
-                        value: Expr::Value(number("1")),
+                        value: Expr::Value(number("1"), NodeSpan::empty()),
                     },
                     Assignment {
                         id: "b".into(),
-                        value: Expr::Value(number("2")),
+                        value: Expr::Value(number("2"), NodeSpan::empty()),
                     },
                     Assignment {
                         id: "c".into(),
-                        value: Expr::Value(number("3")),
+                        value: Expr::Value(number("3"), NodeSpan::empty()),
                     },
                 ]
             );
@@ -132,15 +136,15 @@ fn parse_update() {
     let sql = "UPDATE t WHERE 1";
     let res = parse_sql_statements(sql);
     assert_eq!(
-        ParserError::ParserError("Expected SET, found: WHERE".to_string()),
-        res.unwrap_err()
+        "Expected SET, found: WHERE".to_string(),
+        res.unwrap_err().message
     );
 
     let sql = "UPDATE t SET a = 1 extrabadstuff";
     let res = parse_sql_statements(sql);
     assert_eq!(
-        ParserError::ParserError("Expected end of statement, found: extrabadstuff".to_string()),
-        res.unwrap_err()
+        "Expected end of statement, found: extrabadstuff".to_string(),
+        res.unwrap_err().message
     );
 }
 
@@ -187,7 +191,7 @@ fn parse_where_delete_statement() {
                 Expr::BinaryOp {
                     left: Box::new(Expr::Identifier(Ident::new("name"))),
                     op: Eq,
-                    right: Box::new(Expr::Value(number("5"))),
+                    right: Box::new(Expr::Value(number("5"), NodeSpan::empty())),
                 },
                 selection.unwrap(),
             );
@@ -211,17 +215,20 @@ fn parse_simple_select() {
     assert_eq!(false, select.distinct);
     assert_eq!(3, select.projection.len());
     let select = verified_query(sql);
-    assert_eq!(Some(Expr::Value(number("5"))), select.limit);
+    assert_eq!(
+        Some(Expr::Value(number("5"), NodeSpan::empty())),
+        select.limit
+    );
 }
 
 #[test]
 fn parse_limit_is_not_an_alias() {
     // In dialects supporting LIMIT it shouldn't be parsed as a table alias
     let ast = verified_query("SELECT id FROM customer LIMIT 1");
-    assert_eq!(Some(Expr::Value(number("1"))), ast.limit);
+    assert_eq!(Some(Expr::Value(number("1"), NodeSpan::empty())), ast.limit);
 
     let ast = verified_query("SELECT 1 LIMIT 5");
-    assert_eq!(Some(Expr::Value(number("5"))), ast.limit);
+    assert_eq!(Some(Expr::Value(number("5"), NodeSpan::empty())), ast.limit);
 }
 
 #[test]
@@ -244,8 +251,8 @@ fn parse_select_all() {
 fn parse_select_all_distinct() {
     let result = parse_sql_statements("SELECT ALL DISTINCT name FROM customer");
     assert_eq!(
-        ParserError::ParserError("Cannot specify both ALL and DISTINCT in SELECT".to_string()),
-        result.unwrap_err(),
+        "Cannot specify both ALL and DISTINCT in SELECT".to_string(),
+        result.unwrap_err().message,
     );
 }
 
@@ -253,22 +260,25 @@ fn parse_select_all_distinct() {
 fn parse_select_wildcard() {
     let sql = "SELECT * FROM foo";
     let select = verified_only_select(sql);
-    assert_eq!(&SelectItem::Wildcard, only(&select.projection));
+    assert_eq!(
+        &SelectItem::Wildcard(NodeSpan::empty()),
+        only(&select.projection)
+    );
 
     let sql = "SELECT foo.* FROM foo";
     let select = verified_only_select(sql);
     assert_eq!(
-        &SelectItem::QualifiedWildcard(ObjectName(vec![Ident::new("foo")])),
+        &SelectItem::QualifiedWildcard(ObjectName(vec![Ident::new("foo")]), NodeSpan::empty()),
         only(&select.projection)
     );
 
     let sql = "SELECT myschema.mytable.* FROM myschema.mytable";
     let select = verified_only_select(sql);
     assert_eq!(
-        &SelectItem::QualifiedWildcard(ObjectName(vec![
-            Ident::new("myschema"),
-            Ident::new("mytable"),
-        ])),
+        &SelectItem::QualifiedWildcard(
+            ObjectName(vec![Ident::new("myschema"), Ident::new("mytable"),]),
+            NodeSpan::empty()
+        ),
         only(&select.projection)
     );
 }
@@ -292,7 +302,7 @@ fn parse_column_aliases() {
     } = only(&select.projection)
     {
         assert_eq!(&BinaryOperator::Plus, op);
-        assert_eq!(&Expr::Value(number("1")), right.as_ref());
+        assert_eq!(&Expr::Value(number("1"), NodeSpan::empty()), right.as_ref());
         assert_eq!(&Ident::new("newname"), alias);
     } else {
         panic!("Expected ExprWithAlias")
@@ -306,14 +316,14 @@ fn parse_column_aliases() {
 fn test_eof_after_as() {
     let res = parse_sql_statements("SELECT foo AS");
     assert_eq!(
-        ParserError::ParserError("Expected an identifier after AS, found: EOF".to_string()),
-        res.unwrap_err()
+        "Expected an identifier after AS, found: EOF".to_string(),
+        res.unwrap_err().message
     );
 
     let res = parse_sql_statements("SELECT 1 FROM foo AS");
     assert_eq!(
-        ParserError::ParserError("Expected an identifier after AS, found: EOF".to_string()),
-        res.unwrap_err()
+        "Expected an identifier after AS, found: EOF".to_string(),
+        res.unwrap_err().message
     );
 }
 
@@ -324,9 +334,10 @@ fn parse_select_count_wildcard() {
     assert_eq!(
         &Expr::Function(Function {
             name: ObjectName(vec![Ident::new("COUNT")]),
-            args: vec![Expr::Wildcard],
+            args: vec![Expr::Wildcard(NodeSpan::empty())],
             over: None,
             distinct: false,
+            span: NodeSpan::empty(),
         }),
         expr_from_projection(only(&select.projection))
     );
@@ -341,10 +352,12 @@ fn parse_select_count_distinct() {
             name: ObjectName(vec![Ident::new("COUNT")]),
             args: vec![Expr::UnaryOp {
                 op: UnaryOperator::Plus,
-                expr: Box::new(Expr::Identifier(Ident::new("x")))
+                expr: Box::new(Expr::Identifier(Ident::new("x"))),
+                span: NodeSpan::empty(),
             }],
             over: None,
             distinct: true,
+            span: NodeSpan::empty(),
         }),
         expr_from_projection(only(&select.projection))
     );
@@ -357,10 +370,8 @@ fn parse_select_count_distinct() {
     let sql = "SELECT COUNT(ALL DISTINCT + x) FROM customer";
     let res = parse_sql_statements(sql);
     assert_eq!(
-        ParserError::ParserError(
-            "Cannot specify both ALL and DISTINCT in function: COUNT".to_string()
-        ),
-        res.unwrap_err()
+        "Cannot specify both ALL and DISTINCT in function: COUNT".to_string(),
+        res.unwrap_err().message
     );
 }
 
@@ -375,8 +386,8 @@ fn parse_not() {
 fn parse_invalid_infix_not() {
     let res = parse_sql_statements("SELECT c FROM t WHERE c NOT (");
     assert_eq!(
-        ParserError::ParserError("Expected end of statement, found: NOT".to_string()),
-        res.unwrap_err(),
+        "Expected end of statement, found: NOT".to_string(),
+        res.unwrap_err().message,
     );
 }
 
@@ -409,7 +420,7 @@ fn parse_null_in_select() {
     let sql = "SELECT NULL";
     let select = verified_only_select(sql);
     assert_eq!(
-        &Expr::Value(Value::Null),
+        &Expr::Value(Value::Null, NodeSpan::empty()),
         expr_from_projection(only(&select.projection)),
     );
 }
@@ -424,9 +435,10 @@ fn parse_escaped_single_quote_string_predicate() {
         Some(Expr::BinaryOp {
             left: Box::new(Expr::Identifier(Ident::new("salary"))),
             op: NotEq,
-            right: Box::new(Expr::Value(Value::SingleQuotedString(
-                "Jim's salary".to_string()
-            )))
+            right: Box::new(Expr::Value(
+                Value::SingleQuotedString("Jim's salary".to_string()),
+                NodeSpan::empty()
+            ))
         }),
         ast.selection,
     );
@@ -439,11 +451,17 @@ fn parse_number() {
     #[cfg(feature = "bigdecimal")]
     assert_eq!(
         expr,
-        Expr::Value(Value::Number(bigdecimal::BigDecimal::from(1)))
+        Expr::Value(
+            Value::Number(bigdecimal::BigDecimal::from(1)),
+            NodeSpan::empty()
+        )
     );
 
     #[cfg(not(feature = "bigdecimal"))]
-    assert_eq!(expr, Expr::Value(Value::Number("1.0".into())));
+    assert_eq!(
+        expr,
+        Expr::Value(Value::Number("1.0".into()), NodeSpan::empty())
+    );
 }
 
 #[test]
@@ -493,11 +511,13 @@ fn parse_unary_math() {
             left: Box::new(UnaryOp {
                 op: UnaryOperator::Minus,
                 expr: Box::new(Identifier(Ident::new("a"))),
+                span: NodeSpan::empty(),
             }),
             op: BinaryOperator::Plus,
             right: Box::new(UnaryOp {
                 op: UnaryOperator::Minus,
                 expr: Box::new(Identifier(Ident::new("b"))),
+                span: NodeSpan::empty(),
             }),
         },
         verified_expr(sql)
@@ -509,7 +529,7 @@ fn parse_is_null() {
     use self::Expr::*;
     let sql = "a IS NULL";
     assert_eq!(
-        IsNull(Box::new(Identifier(Ident::new("a")))),
+        IsNull(Box::new(Identifier(Ident::new("a"))), NodeSpan::empty()),
         verified_expr(sql)
     );
 }
@@ -519,7 +539,7 @@ fn parse_is_not_null() {
     use self::Expr::*;
     let sql = "a IS NOT NULL";
     assert_eq!(
-        IsNotNull(Box::new(Identifier(Ident::new("a")))),
+        IsNotNull(Box::new(Identifier(Ident::new("a"))), NodeSpan::empty()),
         verified_expr(sql)
     );
 }
@@ -528,17 +548,23 @@ fn parse_is_not_null() {
 fn parse_not_precedence() {
     // NOT has higher precedence than OR/AND, so the following must parse as (NOT true) OR true
     let sql = "NOT true OR true";
-    assert_matches!(verified_expr(sql), Expr::BinaryOp {
-        op: BinaryOperator::Or,
-        ..
-    });
+    assert_matches!(
+        verified_expr(sql),
+        Expr::BinaryOp {
+            op: BinaryOperator::Or,
+            ..
+        }
+    );
 
     // But NOT has lower precedence than comparison operators, so the following parses as NOT (a IS NULL)
     let sql = "NOT a IS NULL";
-    assert_matches!(verified_expr(sql), Expr::UnaryOp {
-        op: UnaryOperator::Not,
-        ..
-    });
+    assert_matches!(
+        verified_expr(sql),
+        Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            ..
+        }
+    );
 
     // NOT has lower precedence than BETWEEN, so the following parses as NOT (1 NOT BETWEEN 1 AND 2)
     let sql = "NOT 1 NOT BETWEEN 1 AND 2";
@@ -547,11 +573,12 @@ fn parse_not_precedence() {
         Expr::UnaryOp {
             op: UnaryOperator::Not,
             expr: Box::new(Expr::Between {
-                expr: Box::new(Expr::Value(number("1"))),
-                low: Box::new(Expr::Value(number("1"))),
-                high: Box::new(Expr::Value(number("2"))),
+                expr: Box::new(Expr::Value(number("1"), NodeSpan::empty())),
+                low: Box::new(Expr::Value(number("1"), NodeSpan::empty())),
+                high: Box::new(Expr::Value(number("2"), NodeSpan::empty())),
                 negated: true,
             }),
+            span: NodeSpan::empty(),
         },
     );
 
@@ -562,10 +589,17 @@ fn parse_not_precedence() {
         Expr::UnaryOp {
             op: UnaryOperator::Not,
             expr: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Value(Value::SingleQuotedString("a".into()))),
+                left: Box::new(Expr::Value(
+                    Value::SingleQuotedString("a".into()),
+                    NodeSpan::empty()
+                )),
                 op: BinaryOperator::NotLike,
-                right: Box::new(Expr::Value(Value::SingleQuotedString("b".into()))),
+                right: Box::new(Expr::Value(
+                    Value::SingleQuotedString("b".into()),
+                    NodeSpan::empty()
+                )),
             }),
+            span: NodeSpan::empty(),
         },
     );
 
@@ -577,9 +611,14 @@ fn parse_not_precedence() {
             op: UnaryOperator::Not,
             expr: Box::new(Expr::InList {
                 expr: Box::new(Expr::Identifier("a".into())),
-                list: vec![Expr::Value(Value::SingleQuotedString("a".into()))],
+                list: vec![Expr::Value(
+                    Value::SingleQuotedString("a".into()),
+                    NodeSpan::empty()
+                )],
                 negated: true,
+                span: NodeSpan::empty(),
             }),
+            span: NodeSpan::empty(),
         },
     );
 }
@@ -600,7 +639,10 @@ fn parse_like() {
                 } else {
                     BinaryOperator::Like
                 },
-                right: Box::new(Expr::Value(Value::SingleQuotedString("%a".to_string()))),
+                right: Box::new(Expr::Value(
+                    Value::SingleQuotedString("%a".to_string()),
+                    NodeSpan::empty()
+                )),
             },
             select.selection.unwrap()
         );
@@ -613,15 +655,21 @@ fn parse_like() {
         );
         let select = verified_only_select(sql);
         assert_eq!(
-            Expr::IsNull(Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Identifier(Ident::new("name"))),
-                op: if negated {
-                    BinaryOperator::NotLike
-                } else {
-                    BinaryOperator::Like
-                },
-                right: Box::new(Expr::Value(Value::SingleQuotedString("%a".to_string()))),
-            })),
+            Expr::IsNull(
+                Box::new(Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier(Ident::new("name"))),
+                    op: if negated {
+                        BinaryOperator::NotLike
+                    } else {
+                        BinaryOperator::Like
+                    },
+                    right: Box::new(Expr::Value(
+                        Value::SingleQuotedString("%a".to_string()),
+                        NodeSpan::empty()
+                    )),
+                }),
+                NodeSpan::empty()
+            ),
             select.selection.unwrap()
         );
     }
@@ -641,10 +689,17 @@ fn parse_in_list() {
             Expr::InList {
                 expr: Box::new(Expr::Identifier(Ident::new("segment"))),
                 list: vec![
-                    Expr::Value(Value::SingleQuotedString("HIGH".to_string())),
-                    Expr::Value(Value::SingleQuotedString("MED".to_string())),
+                    Expr::Value(
+                        Value::SingleQuotedString("HIGH".to_string()),
+                        NodeSpan::empty()
+                    ),
+                    Expr::Value(
+                        Value::SingleQuotedString("MED".to_string()),
+                        NodeSpan::empty()
+                    ),
                 ],
                 negated,
+                span: NodeSpan::empty(),
             },
             select.selection.unwrap()
         );
@@ -662,6 +717,7 @@ fn parse_in_subquery() {
             expr: Box::new(Expr::Identifier(Ident::new("segment"))),
             subquery: Box::new(verified_query("SELECT segm FROM bar")),
             negated: false,
+            span: NodeSpan::empty(),
         },
         select.selection.unwrap()
     );
@@ -678,8 +734,8 @@ fn parse_between() {
         assert_eq!(
             Expr::Between {
                 expr: Box::new(Expr::Identifier(Ident::new("age"))),
-                low: Box::new(Expr::Value(number("25"))),
-                high: Box::new(Expr::Value(number("32"))),
+                low: Box::new(Expr::Value(number("25"), NodeSpan::empty())),
+                high: Box::new(Expr::Value(number("32"), NodeSpan::empty())),
                 negated,
             },
             select.selection.unwrap()
@@ -695,20 +751,23 @@ fn parse_between_with_expr() {
     let sql = "SELECT * FROM t WHERE 1 BETWEEN 1 + 2 AND 3 + 4 IS NULL";
     let select = verified_only_select(sql);
     assert_eq!(
-        Expr::IsNull(Box::new(Expr::Between {
-            expr: Box::new(Expr::Value(number("1"))),
-            low: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Value(number("1"))),
-                op: Plus,
-                right: Box::new(Expr::Value(number("2"))),
-            }),
-            high: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Value(number("3"))),
-                op: Plus,
-                right: Box::new(Expr::Value(number("4"))),
+        Expr::IsNull(
+            Box::new(Expr::Between {
+                expr: Box::new(Expr::Value(number("1"), NodeSpan::empty())),
+                low: Box::new(Expr::BinaryOp {
+                    left: Box::new(Expr::Value(number("1"), NodeSpan::empty())),
+                    op: Plus,
+                    right: Box::new(Expr::Value(number("2"), NodeSpan::empty())),
+                }),
+                high: Box::new(Expr::BinaryOp {
+                    left: Box::new(Expr::Value(number("3"), NodeSpan::empty())),
+                    op: Plus,
+                    right: Box::new(Expr::Value(number("4"), NodeSpan::empty())),
+                }),
+                negated: false,
             }),
-            negated: false,
-        })),
+            NodeSpan::empty()
+        ),
         select.selection.unwrap()
     );
 
@@ -717,19 +776,19 @@ fn parse_between_with_expr() {
     assert_eq!(
         Expr::BinaryOp {
             left: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Value(number("1"))),
+                left: Box::new(Expr::Value(number("1"), NodeSpan::empty())),
                 op: BinaryOperator::Eq,
-                right: Box::new(Expr::Value(number("1"))),
+                right: Box::new(Expr::Value(number("1"), NodeSpan::empty())),
             }),
             op: BinaryOperator::And,
             right: Box::new(Expr::Between {
                 expr: Box::new(Expr::BinaryOp {
-                    left: Box::new(Expr::Value(number("1"))),
+                    left: Box::new(Expr::Value(number("1"), NodeSpan::empty())),
                     op: BinaryOperator::Plus,
                     right: Box::new(Expr::Identifier(Ident::new("x"))),
                 }),
-                low: Box::new(Expr::Value(number("1"))),
-                high: Box::new(Expr::Value(number("2"))),
+                low: Box::new(Expr::Value(number("1"), NodeSpan::empty())),
+                high: Box::new(Expr::Value(number("2"), NodeSpan::empty())),
                 negated: false,
             }),
         },
@@ -783,7 +842,10 @@ fn parse_select_order_by_limit() {
         ],
         select.order_by
     );
-    assert_eq!(Some(Expr::Value(number("2"))), select.limit);
+    assert_eq!(
+        Some(Expr::Value(number("2"), NodeSpan::empty())),
+        select.limit
+    );
 }
 
 #[test]
@@ -807,12 +869,13 @@ fn parse_select_having() {
         Some(Expr::BinaryOp {
             left: Box::new(Expr::Function(Function {
                 name: ObjectName(vec![Ident::new("COUNT")]),
-                args: vec![Expr::Wildcard],
+                args: vec![Expr::Wildcard(NodeSpan::empty())],
                 over: None,
-                distinct: false
+                distinct: false,
+                span: NodeSpan::empty(),
             })),
             op: BinaryOperator::Gt,
-            right: Box::new(Expr::Value(number("1")))
+            right: Box::new(Expr::Value(number("1"), NodeSpan::empty()))
         }),
         select.having
     );
@@ -837,7 +900,8 @@ fn parse_cast() {
     assert_eq!(
         &Expr::Cast {
             expr: Box::new(Expr::Identifier(Ident::new("id"))),
-            data_type: DataType::BigInt
+            data_type: DataType::BigInt,
+            span: NodeSpan::empty(),
         },
         expr_from_projection(only(&select.projection))
     );
@@ -867,6 +931,7 @@ fn parse_extract() {
         &Expr::Extract {
             field: DateTimeField::Year,
             expr: Box::new(Expr::Identifier(Ident::new("d"))),
+            span: NodeSpan::empty(),
         },
         expr_from_projection(only(&select.projection)),
     );
@@ -881,8 +946,8 @@ fn parse_extract() {
 
     let res = parse_sql_statements("SELECT EXTRACT(MILLISECOND FROM d)");
     assert_eq!(
-        ParserError::ParserError("Expected date/time field, found: MILLISECOND".to_string()),
-        res.unwrap_err()
+        "Expected date/time field, found: MILLISECOND".to_string(),
+        res.unwrap_err().message
     );
 }
 
@@ -912,10 +977,11 @@ fn parse_create_table() {
             external: false,
             file_format: None,
             location: None,
+            ..
         } => {
             assert_eq!("uk_cities", name.to_string());
             assert_eq!(
-                columns,
+                columns.into_iter().map(|c| c.node).collect::<Vec<_>>(),
                 vec![
                     ColumnDef {
                         name: "name".into(),
@@ -978,7 +1044,7 @@ fn parse_create_table() {
                                 foreign_table: ObjectName(vec!["othertable".into()]),
                                 referred_columns: vec!["a".into(), "b".into(),],
                             }
-                        }]
+                        }],
                     }
                 ]
             );
@@ -1048,10 +1114,11 @@ fn parse_create_external_table() {
             external,
             file_format,
             location,
+            ..
         } => {
             assert_eq!("uk_cities", name.to_string());
             assert_eq!(
-                columns,
+                columns.into_iter().map(|c| c.node).collect::<Vec<_>>(),
                 vec![
                     ColumnDef {
                         name: "name".into(),
@@ -1091,12 +1158,56 @@ fn parse_create_external_table() {
     }
 }
 
+#[test]
+fn parse_create_external_table_location() {
+    let sql = "CREATE EXTERNAL TABLE t (a int) STORED AS JSONFILE LOCATION '/tmp/it''s.json'";
+    match verified_stmt(sql) {
+        Statement::CreateTable {
+            file_format,
+            location,
+            ..
+        } => {
+            assert_eq!(Some(FileFormat::JSONFILE), file_format);
+            assert_eq!(Some("/tmp/it's.json".to_string()), location);
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn parse_create_table_empty() {
     // Zero-column tables are weird, but supported by at least PostgreSQL.
     let _ = verified_stmt("CREATE TABLE t ()");
 }
 
+#[test]
+fn parse_create_table_only_constraints() {
+    match verified_stmt("CREATE TABLE t (PRIMARY KEY (a), CHECK (a > 0))") {
+        Statement::CreateTable {
+            columns,
+            constraints,
+            ..
+        } => {
+            assert!(columns.is_empty());
+            assert_eq!(2, constraints.len());
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_create_table_with_collation() {
+    match verified_stmt("CREATE TABLE t (name text COLLATE \"de_DE\" NOT NULL)") {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(
+                Some(ObjectName(vec![Ident::with_quote('"', "de_DE")])),
+                only(columns).node.collation
+            );
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn parse_alter_table_constraints() {
     check_one("CONSTRAINT address_pkey PRIMARY KEY (address_id)");
@@ -1117,6 +1228,7 @@ fn parse_alter_table_constraints() {
             Statement::AlterTable {
                 name,
                 operation: AlterTableOperation::AddConstraint(constraint),
+                ..
             } => {
                 assert_eq!("tab", name.to_string());
                 assert_eq!(constraint_text, constraint.to_string());
@@ -1131,18 +1243,92 @@ fn parse_alter_table_constraints() {
 fn parse_bad_constraint() {
     let res = parse_sql_statements("ALTER TABLE tab ADD");
     assert_eq!(
-        ParserError::ParserError(
-            "Expected a constraint in ALTER TABLE .. ADD, found: EOF".to_string()
-        ),
-        res.unwrap_err()
+        "Expected a constraint or column definition in ALTER TABLE .. ADD, found: EOF".to_string(),
+        res.unwrap_err().message
     );
 
     let res = parse_sql_statements("CREATE TABLE tab (foo int,");
     assert_eq!(
-        ParserError::ParserError(
-            "Expected column name or constraint definition, found: EOF".to_string()
-        ),
-        res.unwrap_err()
+        "Expected column name or constraint definition, found: EOF".to_string(),
+        res.unwrap_err().message
+    );
+}
+
+#[test]
+fn parse_alter_table_columns() {
+    match verified_stmt("ALTER TABLE tab ADD COLUMN foo text NOT NULL") {
+        Statement::AlterTable {
+            name,
+            operation: AlterTableOperation::AddColumn { column_def },
+            ..
+        } => {
+            assert_eq!("tab", name.to_string());
+            assert_eq!("foo", column_def.name.to_string());
+            assert_eq!(DataType::Text, column_def.data_type);
+        }
+        _ => unreachable!(),
+    }
+    one_statement_parses_to(
+        "ALTER TABLE tab ADD foo INT",
+        "ALTER TABLE tab ADD COLUMN foo int",
+    );
+
+    match verified_stmt("ALTER TABLE tab DROP COLUMN IF EXISTS foo CASCADE") {
+        Statement::AlterTable {
+            operation:
+                AlterTableOperation::DropColumn {
+                    column_name,
+                    if_exists,
+                    cascade,
+                },
+            ..
+        } => {
+            assert_eq!("foo", column_name.to_string());
+            assert!(if_exists);
+            assert!(cascade);
+        }
+        _ => unreachable!(),
+    }
+    one_statement_parses_to(
+        "ALTER TABLE tab DROP foo",
+        "ALTER TABLE tab DROP COLUMN foo",
+    );
+    verified_stmt("ALTER TABLE tab DROP CONSTRAINT tab_pkey");
+
+    verified_stmt("ALTER TABLE tab RENAME TO myschema.tab2");
+    one_statement_parses_to(
+        "ALTER TABLE tab RENAME foo TO bar",
+        "ALTER TABLE tab RENAME COLUMN foo TO bar",
+    );
+
+    verified_stmt("ALTER TABLE tab ALTER COLUMN foo SET NOT NULL");
+    verified_stmt("ALTER TABLE tab ALTER COLUMN foo DROP NOT NULL");
+    verified_stmt("ALTER TABLE tab ALTER COLUMN foo SET DEFAULT 'x'");
+    verified_stmt("ALTER TABLE tab ALTER COLUMN foo DROP DEFAULT");
+    match verified_stmt("ALTER TABLE tab ALTER COLUMN foo SET DATA TYPE bigint") {
+        Statement::AlterTable {
+            operation: AlterTableOperation::AlterColumn { column_name, op },
+            ..
+        } => {
+            assert_eq!("foo", column_name.to_string());
+            assert_eq!(
+                AlterColumnOperation::SetDataType {
+                    data_type: DataType::BigInt
+                },
+                op
+            );
+        }
+        _ => unreachable!(),
+    }
+    one_statement_parses_to(
+        "ALTER TABLE tab ALTER foo TYPE bigint",
+        "ALTER TABLE tab ALTER COLUMN foo SET DATA TYPE bigint",
+    );
+
+    let res = parse_sql_statements("ALTER TABLE tab FOO");
+    assert_eq!(
+        "Expected ADD, DROP, RENAME or ALTER after ALTER TABLE, found: FOO".to_string(),
+        res.unwrap_err().message
     );
 }
 
@@ -1156,6 +1342,7 @@ fn parse_scalar_function_in_projection() {
             args: vec![Expr::Identifier(Ident::new("id"))],
             over: None,
             distinct: false,
+            span: NodeSpan::empty(),
         }),
         expr_from_projection(only(&select.projection))
     );
@@ -1186,6 +1373,7 @@ fn parse_window_functions() {
                 window_frame: None,
             }),
             distinct: false,
+            span: NodeSpan::empty(),
         }),
         expr_from_projection(&select.projection[0])
     );
@@ -1206,11 +1394,11 @@ fn parse_literal_decimal() {
     let select = verified_only_select(sql);
     assert_eq!(2, select.projection.len());
     assert_eq!(
-        &Expr::Value(number("0.300000000000000004")),
+        &Expr::Value(number("0.300000000000000004"), NodeSpan::empty()),
         expr_from_projection(&select.projection[0]),
     );
     assert_eq!(
-        &Expr::Value(number("9007199254740993.0")),
+        &Expr::Value(number("9007199254740993.0"), NodeSpan::empty()),
         expr_from_projection(&select.projection[1]),
     )
 }
@@ -1221,19 +1409,37 @@ fn parse_literal_string() {
     let select = verified_only_select(sql);
     assert_eq!(3, select.projection.len());
     assert_eq!(
-        &Expr::Value(Value::SingleQuotedString("one".to_string())),
+        &Expr::Value(
+            Value::SingleQuotedString("one".to_string()),
+            NodeSpan::empty()
+        ),
         expr_from_projection(&select.projection[0])
     );
     assert_eq!(
-        &Expr::Value(Value::NationalStringLiteral("national string".to_string())),
+        &Expr::Value(
+            Value::NationalStringLiteral("national string".to_string()),
+            NodeSpan::empty()
+        ),
         expr_from_projection(&select.projection[1])
     );
     assert_eq!(
-        &Expr::Value(Value::HexStringLiteral("deadBEEF".to_string())),
+        &Expr::Value(
+            Value::HexStringLiteral("deadBEEF".to_string()),
+            NodeSpan::empty()
+        ),
         expr_from_projection(&select.projection[2])
     );
 
     one_statement_parses_to("SELECT x'deadBEEF'", "SELECT X'deadBEEF'");
+
+    let select = verified_only_select("SELECT N'it''s'");
+    assert_eq!(
+        &Expr::Value(
+            Value::NationalStringLiteral("it's".to_string()),
+            NodeSpan::empty()
+        ),
+        expr_from_projection(only(&select.projection))
+    );
 }
 
 #[test]
@@ -1241,7 +1447,7 @@ fn parse_literal_date() {
     let sql = "SELECT DATE '1999-01-01'";
     let select = verified_only_select(sql);
     assert_eq!(
-        &Expr::Value(Value::Date("1999-01-01".into())),
+        &Expr::Value(Value::Date("1999-01-01".into()), NodeSpan::empty()),
         expr_from_projection(only(&select.projection)),
     );
 }
@@ -1251,7 +1457,7 @@ fn parse_literal_time() {
     let sql = "SELECT TIME '01:23:34'";
     let select = verified_only_select(sql);
     assert_eq!(
-        &Expr::Value(Value::Time("01:23:34".into())),
+        &Expr::Value(Value::Time("01:23:34".into()), NodeSpan::empty()),
         expr_from_projection(only(&select.projection)),
     );
 }
@@ -1261,7 +1467,10 @@ fn parse_literal_timestamp() {
     let sql = "SELECT TIMESTAMP '1999-01-01 01:23:34'";
     let select = verified_only_select(sql);
     assert_eq!(
-        &Expr::Value(Value::Timestamp("1999-01-01 01:23:34".into())),
+        &Expr::Value(
+            Value::Timestamp("1999-01-01 01:23:34".into()),
+            NodeSpan::empty()
+        ),
         expr_from_projection(only(&select.projection)),
     );
 }
@@ -1271,78 +1480,93 @@ fn parse_literal_interval() {
     let sql = "SELECT INTERVAL '1-1' YEAR TO MONTH";
     let select = verified_only_select(sql);
     assert_eq!(
-        &Expr::Value(Value::Interval {
-            value: "1-1".into(),
-            leading_field: DateTimeField::Year,
-            leading_precision: None,
-            last_field: Some(DateTimeField::Month),
-            fractional_seconds_precision: None,
-        }),
+        &Expr::Value(
+            Value::Interval {
+                value: "1-1".into(),
+                leading_field: DateTimeField::Year,
+                leading_precision: None,
+                last_field: Some(DateTimeField::Month),
+                fractional_seconds_precision: None,
+            },
+            NodeSpan::empty()
+        ),
         expr_from_projection(only(&select.projection)),
     );
 
     let sql = "SELECT INTERVAL '01:01.01' MINUTE (5) TO SECOND (5)";
     let select = verified_only_select(sql);
     assert_eq!(
-        &Expr::Value(Value::Interval {
-            value: "01:01.01".into(),
-            leading_field: DateTimeField::Minute,
-            leading_precision: Some(5),
-            last_field: Some(DateTimeField::Second),
-            fractional_seconds_precision: Some(5),
-        }),
+        &Expr::Value(
+            Value::Interval {
+                value: "01:01.01".into(),
+                leading_field: DateTimeField::Minute,
+                leading_precision: Some(5),
+                last_field: Some(DateTimeField::Second),
+                fractional_seconds_precision: Some(5),
+            },
+            NodeSpan::empty()
+        ),
         expr_from_projection(only(&select.projection)),
     );
 
     let sql = "SELECT INTERVAL '1' SECOND (5, 4)";
     let select = verified_only_select(sql);
     assert_eq!(
-        &Expr::Value(Value::Interval {
-            value: "1".into(),
-            leading_field: DateTimeField::Second,
-            leading_precision: Some(5),
-            last_field: None,
-            fractional_seconds_precision: Some(4),
-        }),
+        &Expr::Value(
+            Value::Interval {
+                value: "1".into(),
+                leading_field: DateTimeField::Second,
+                leading_precision: Some(5),
+                last_field: None,
+                fractional_seconds_precision: Some(4),
+            },
+            NodeSpan::empty()
+        ),
         expr_from_projection(only(&select.projection)),
     );
 
     let sql = "SELECT INTERVAL '10' HOUR";
     let select = verified_only_select(sql);
     assert_eq!(
-        &Expr::Value(Value::Interval {
-            value: "10".into(),
-            leading_field: DateTimeField::Hour,
-            leading_precision: None,
-            last_field: None,
-            fractional_seconds_precision: None,
-        }),
+        &Expr::Value(
+            Value::Interval {
+                value: "10".into(),
+                leading_field: DateTimeField::Hour,
+                leading_precision: None,
+                last_field: None,
+                fractional_seconds_precision: None,
+            },
+            NodeSpan::empty()
+        ),
         expr_from_projection(only(&select.projection)),
     );
 
     let sql = "SELECT INTERVAL '10' HOUR (1)";
     let select = verified_only_select(sql);
     assert_eq!(
-        &Expr::Value(Value::Interval {
-            value: "10".into(),
-            leading_field: DateTimeField::Hour,
-            leading_precision: Some(1),
-            last_field: None,
-            fractional_seconds_precision: None,
-        }),
+        &Expr::Value(
+            Value::Interval {
+                value: "10".into(),
+                leading_field: DateTimeField::Hour,
+                leading_precision: Some(1),
+                last_field: None,
+                fractional_seconds_precision: None,
+            },
+            NodeSpan::empty()
+        ),
         expr_from_projection(only(&select.projection)),
     );
 
     let result = parse_sql_statements("SELECT INTERVAL '1' SECOND TO SECOND");
     assert_eq!(
-        ParserError::ParserError("Expected end of statement, found: SECOND".to_string()),
-        result.unwrap_err(),
+        "Expected end of statement, found: SECOND".to_string(),
+        result.unwrap_err().message,
     );
 
     let result = parse_sql_statements("SELECT INTERVAL '10' HOUR (1) TO HOUR (2)");
     assert_eq!(
-        ParserError::ParserError("Expected end of statement, found: (".to_string()),
-        result.unwrap_err(),
+        "Expected end of statement, found: (".to_string(),
+        result.unwrap_err().message,
     );
 
     verified_only_select("SELECT INTERVAL '1' YEAR");
@@ -1385,6 +1609,7 @@ fn parse_delimited_identifiers() {
             alias,
             args,
             with_hints,
+            ..
         } => {
             assert_eq!(vec![Ident::with_quote('"', "a table")], name.0);
             assert_eq!(Ident::with_quote('"', "alias"), alias.unwrap().name);
@@ -1408,6 +1633,7 @@ fn parse_delimited_identifiers() {
             args: vec![],
             over: None,
             distinct: false,
+            span: NodeSpan::empty(),
         }),
         expr_from_projection(&select.projection[1]),
     );
@@ -1431,17 +1657,23 @@ fn parse_parens() {
     let sql = "(a + b) - (c + d)";
     assert_eq!(
         BinaryOp {
-            left: Box::new(Nested(Box::new(BinaryOp {
-                left: Box::new(Identifier(Ident::new("a"))),
-                op: Plus,
-                right: Box::new(Identifier(Ident::new("b")))
-            }))),
+            left: Box::new(Nested(
+                Box::new(BinaryOp {
+                    left: Box::new(Identifier(Ident::new("a"))),
+                    op: Plus,
+                    right: Box::new(Identifier(Ident::new("b")))
+                }),
+                NodeSpan::empty()
+            )),
             op: Minus,
-            right: Box::new(Nested(Box::new(BinaryOp {
-                left: Box::new(Identifier(Ident::new("c"))),
-                op: Plus,
-                right: Box::new(Identifier(Ident::new("d")))
-            })))
+            right: Box::new(Nested(
+                Box::new(BinaryOp {
+                    left: Box::new(Identifier(Ident::new("c"))),
+                    op: Plus,
+                    right: Box::new(Identifier(Ident::new("d")))
+                }),
+                NodeSpan::empty()
+            ))
         },
         verified_expr(sql)
     );
@@ -1457,26 +1689,37 @@ fn parse_searched_case_expr() {
         &Case {
             operand: None,
             conditions: vec![
-                IsNull(Box::new(Identifier(Ident::new("bar")))),
+                IsNull(Box::new(Identifier(Ident::new("bar"))), NodeSpan::empty()),
                 BinaryOp {
                     left: Box::new(Identifier(Ident::new("bar"))),
                     op: Eq,
-                    right: Box::new(Expr::Value(number("0")))
+                    right: Box::new(Expr::Value(number("0"), NodeSpan::empty()))
                 },
                 BinaryOp {
                     left: Box::new(Identifier(Ident::new("bar"))),
                     op: GtEq,
-                    right: Box::new(Expr::Value(number("0")))
+                    right: Box::new(Expr::Value(number("0"), NodeSpan::empty()))
                 }
             ],
             results: vec![
-                Expr::Value(Value::SingleQuotedString("null".to_string())),
-                Expr::Value(Value::SingleQuotedString("=0".to_string())),
-                Expr::Value(Value::SingleQuotedString(">=0".to_string()))
+                Expr::Value(
+                    Value::SingleQuotedString("null".to_string()),
+                    NodeSpan::empty()
+                ),
+                Expr::Value(
+                    Value::SingleQuotedString("=0".to_string()),
+                    NodeSpan::empty()
+                ),
+                Expr::Value(
+                    Value::SingleQuotedString(">=0".to_string()),
+                    NodeSpan::empty()
+                )
             ],
-            else_result: Some(Box::new(Expr::Value(Value::SingleQuotedString(
-                "<0".to_string()
-            ))))
+            else_result: Some(Box::new(Expr::Value(
+                Value::SingleQuotedString("<0".to_string()),
+                NodeSpan::empty()
+            ))),
+            span: NodeSpan::empty(),
         },
         expr_from_projection(only(&select.projection)),
     );
@@ -1491,11 +1734,16 @@ fn parse_simple_case_expr() {
     assert_eq!(
         &Case {
             operand: Some(Box::new(Identifier(Ident::new("foo")))),
-            conditions: vec![Expr::Value(number("1"))],
-            results: vec![Expr::Value(Value::SingleQuotedString("Y".to_string())),],
-            else_result: Some(Box::new(Expr::Value(Value::SingleQuotedString(
-                "N".to_string()
-            ))))
+            conditions: vec![Expr::Value(number("1"), NodeSpan::empty())],
+            results: vec![Expr::Value(
+                Value::SingleQuotedString("Y".to_string()),
+                NodeSpan::empty()
+            ),],
+            else_result: Some(Box::new(Expr::Value(
+                Value::SingleQuotedString("N".to_string()),
+                NodeSpan::empty()
+            ))),
+            span: NodeSpan::empty(),
         },
         expr_from_projection(only(&select.projection)),
     );
@@ -1519,6 +1767,7 @@ fn parse_implicit_join() {
                     alias: None,
                     args: vec![],
                     with_hints: vec![],
+                    span: NodeSpan::empty(),
                 },
                 joins: vec![],
             },
@@ -1528,6 +1777,7 @@ fn parse_implicit_join() {
                     alias: None,
                     args: vec![],
                     with_hints: vec![],
+                    span: NodeSpan::empty(),
                 },
                 joins: vec![],
             }
@@ -1545,6 +1795,7 @@ fn parse_implicit_join() {
                     alias: None,
                     args: vec![],
                     with_hints: vec![],
+                    span: NodeSpan::empty(),
                 },
                 joins: vec![Join {
                     relation: TableFactor::Table {
@@ -1552,6 +1803,7 @@ fn parse_implicit_join() {
                         alias: None,
                         args: vec![],
                         with_hints: vec![],
+                        span: NodeSpan::empty(),
                     },
                     join_operator: JoinOperator::Inner(JoinConstraint::Natural),
                 }]
@@ -1562,6 +1814,7 @@ fn parse_implicit_join() {
                     alias: None,
                     args: vec![],
                     with_hints: vec![],
+                    span: NodeSpan::empty(),
                 },
                 joins: vec![Join {
                     relation: TableFactor::Table {
@@ -1569,6 +1822,7 @@ fn parse_implicit_join() {
                         alias: None,
                         args: vec![],
                         with_hints: vec![],
+                        span: NodeSpan::empty(),
                     },
                     join_operator: JoinOperator::Inner(JoinConstraint::Natural),
                 }]
@@ -1589,6 +1843,7 @@ fn parse_cross_join() {
                 alias: None,
                 args: vec![],
                 with_hints: vec![],
+                span: NodeSpan::empty(),
             },
             join_operator: JoinOperator::CrossJoin
         },
@@ -1616,6 +1871,7 @@ fn parse_joins_on() {
                 alias,
                 args: vec![],
                 with_hints: vec![],
+                span: NodeSpan::empty(),
             },
             join_operator: f(JoinConstraint::On(Expr::BinaryOp {
                 left: Box::new(Expr::Identifier("c1".into())),
@@ -1669,6 +1925,7 @@ fn parse_joins_using() {
                 alias,
                 args: vec![],
                 with_hints: vec![],
+                span: NodeSpan::empty(),
             },
             join_operator: f(JoinConstraint::Using(vec!["c1".into()])),
         }
@@ -1714,6 +1971,7 @@ fn parse_natural_join() {
                 alias: None,
                 args: vec![],
                 with_hints: vec![],
+                span: NodeSpan::empty(),
             },
             join_operator: f(JoinConstraint::Natural),
         }
@@ -1737,8 +1995,8 @@ fn parse_natural_join() {
 
     let sql = "SELECT * FROM t1 natural";
     assert_eq!(
-        ParserError::ParserError("Expected a join type after NATURAL, found: EOF".to_string()),
-        parse_sql_statements(sql).unwrap_err(),
+        "Expected a join type after NATURAL, found: EOF".to_string(),
+        parse_sql_statements(sql).unwrap_err().message,
     );
 }
 
@@ -1756,6 +2014,7 @@ fn parse_join_nesting() {
             alias: None,
             args: vec![],
             with_hints: vec![],
+            span: NodeSpan::empty(),
         }
     }
 
@@ -1771,7 +2030,7 @@ fn parse_join_nesting() {
             TableFactor::NestedJoin(Box::new(TableWithJoins {
                 relation: $base,
                 joins: vec![$(join($join)),*]
-            }))
+            }), NodeSpan::empty())
         };
     }
 
@@ -1808,8 +2067,8 @@ fn parse_join_nesting() {
 
     let res = parse_sql_statements("SELECT * FROM (a NATURAL JOIN (b))");
     assert_eq!(
-        ParserError::ParserError("Expected joined table, found: )".to_string()),
-        res.unwrap_err()
+        "Expected joined table, found: )".to_string(),
+        res.unwrap_err().message
     );
 }
 
@@ -1834,8 +2093,8 @@ fn parse_join_syntax_variants() {
 
     let res = parse_sql_statements("SELECT * FROM a OUTER JOIN b ON 1");
     assert_eq!(
-        ParserError::ParserError("Expected APPLY, found: JOIN".to_string()),
-        res.unwrap_err()
+        "Expected APPLY, found: JOIN".to_string(),
+        res.unwrap_err().message
     );
 }
 
@@ -1871,7 +2130,7 @@ fn parse_ctes() {
     let sql = &format!("SELECT ({})", with);
     let select = verified_only_select(sql);
     match expr_from_projection(only(&select.projection)) {
-        Expr::Subquery(ref subquery) => {
+        Expr::Subquery(ref subquery, _) => {
             assert_ctes_in_select(&cte_sqls, subquery.as_ref());
         }
         _ => panic!("Expected subquery"),
@@ -1932,31 +2191,36 @@ fn parse_derived_tables() {
     let from = only(select.from);
     assert_eq!(
         from.relation,
-        TableFactor::NestedJoin(Box::new(TableWithJoins {
-            relation: TableFactor::Derived {
-                lateral: false,
-                subquery: Box::new(verified_query("(SELECT 1) UNION (SELECT 2)")),
-                alias: Some(TableAlias {
-                    name: "t1".into(),
-                    columns: vec![],
-                })
-            },
-            joins: vec![Join {
-                relation: TableFactor::Table {
-                    name: ObjectName(vec!["t2".into()]),
-                    alias: None,
-                    args: vec![],
-                    with_hints: vec![],
+        TableFactor::NestedJoin(
+            Box::new(TableWithJoins {
+                relation: TableFactor::Derived {
+                    lateral: false,
+                    subquery: Box::new(verified_query("(SELECT 1) UNION (SELECT 2)")),
+                    alias: Some(TableAlias {
+                        name: "t1".into(),
+                        columns: vec![],
+                    }),
+                    span: NodeSpan::empty(),
                 },
-                join_operator: JoinOperator::Inner(JoinConstraint::Natural),
-            }],
-        }))
+                joins: vec![Join {
+                    relation: TableFactor::Table {
+                        name: ObjectName(vec!["t2".into()]),
+                        alias: None,
+                        args: vec![],
+                        with_hints: vec![],
+                        span: NodeSpan::empty(),
+                    },
+                    join_operator: JoinOperator::Inner(JoinConstraint::Natural),
+                }],
+            }),
+            NodeSpan::empty()
+        )
     );
 
     let res = parse_sql_statements("SELECT * FROM ((SELECT 1) AS t)");
     assert_eq!(
-        ParserError::ParserError("Expected joined table, found: )".to_string()),
-        res.unwrap_err()
+        "Expected joined table, found: )".to_string(),
+        res.unwrap_err().message
     );
 }
 
@@ -2004,8 +2268,8 @@ fn parse_multiple_statements() {
         // Check that forgetting the semicolon results in an error:
         let res = parse_sql_statements(&(sql1.to_owned() + " " + sql2_kw + sql2_rest));
         assert_eq!(
-            ParserError::ParserError("Expected end of statement, found: ".to_string() + sql2_kw),
-            res.unwrap_err()
+            "Expected end of statement, found: ".to_string() + sql2_kw,
+            res.unwrap_err().message
         );
     }
     test_with("SELECT foo", "SELECT", " bar");
@@ -2029,11 +2293,14 @@ fn parse_multiple_statements() {
 #[test]
 fn parse_scalar_subqueries() {
     let sql = "(SELECT 1) + (SELECT 2)";
-    assert_matches!(verified_expr(sql), Expr::BinaryOp {
-        op: BinaryOperator::Plus, ..
-        //left: box Subquery { .. },
-        //right: box Subquery { .. },
-    });
+    assert_matches!(
+        verified_expr(sql),
+        Expr::BinaryOp {
+            op: BinaryOperator::Plus,
+            .. //left: box Subquery { .. },
+               //right: box Subquery { .. },
+        }
+    );
 }
 
 #[test]
@@ -2042,7 +2309,7 @@ fn parse_exists_subquery() {
     let sql = "SELECT * FROM t WHERE EXISTS (SELECT 1)";
     let select = verified_only_select(sql);
     assert_eq!(
-        Expr::Exists(Box::new(expected_inner.clone())),
+        Expr::Exists(Box::new(expected_inner.clone()), NodeSpan::empty()),
         select.selection.unwrap(),
     );
 
@@ -2051,7 +2318,8 @@ fn parse_exists_subquery() {
     assert_eq!(
         Expr::UnaryOp {
             op: UnaryOperator::Not,
-            expr: Box::new(Expr::Exists(Box::new(expected_inner))),
+            expr: Box::new(Expr::Exists(Box::new(expected_inner), NodeSpan::empty())),
+            span: NodeSpan::empty(),
         },
         select.selection.unwrap(),
     );
@@ -2061,18 +2329,14 @@ fn parse_exists_subquery() {
 
     let res = parse_sql_statements("SELECT EXISTS (");
     assert_eq!(
-        ParserError::ParserError(
-            "Expected SELECT, VALUES, or a subquery in the query body, found: EOF".to_string()
-        ),
-        res.unwrap_err(),
+        "Expected SELECT, VALUES, or a subquery in the query body, found: EOF".to_string(),
+        res.unwrap_err().message,
     );
 
     let res = parse_sql_statements("SELECT EXISTS (NULL)");
     assert_eq!(
-        ParserError::ParserError(
-            "Expected SELECT, VALUES, or a subquery in the query body, found: NULL".to_string()
-        ),
-        res.unwrap_err(),
+        "Expected SELECT, VALUES, or a subquery in the query body, found: NULL".to_string(),
+        res.unwrap_err().message,
     );
 }
 
@@ -2086,6 +2350,7 @@ fn parse_create_view() {
             query,
             materialized,
             with_options,
+            ..
         } => {
             assert_eq!("myschema.myview", name.to_string());
             assert_eq!(Vec::<Ident>::new(), columns);
@@ -2130,6 +2395,7 @@ fn parse_create_view_with_columns() {
             with_options,
             query,
             materialized,
+            ..
         } => {
             assert_eq!("v", name.to_string());
             assert_eq!(columns, vec![Ident::new("has"), Ident::new("cols")]);
@@ -2141,6 +2407,22 @@ fn parse_create_view_with_columns() {
     }
 }
 
+#[test]
+fn parse_create_view_with_columns_and_options() {
+    let sql = "CREATE VIEW v (a, b) WITH (foo = 'bar') AS SELECT 1, 2";
+    match verified_stmt(sql) {
+        Statement::CreateView {
+            columns,
+            with_options,
+            ..
+        } => {
+            assert_eq!(columns, vec![Ident::new("a"), Ident::new("b")]);
+            assert_eq!(1, with_options.len());
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn parse_create_materialized_view() {
     let sql = "CREATE MATERIALIZED VIEW myschema.myview AS SELECT foo FROM bar";
@@ -2151,6 +2433,7 @@ fn parse_create_materialized_view() {
             query,
             materialized,
             with_options,
+            ..
         } => {
             assert_eq!("myschema.myview", name.to_string());
             assert_eq!(Vec::<Ident>::new(), columns);
@@ -2171,6 +2454,7 @@ fn parse_drop_table() {
             if_exists,
             names,
             cascade,
+            ..
         } => {
             assert_eq!(false, if_exists);
             assert_eq!(ObjectType::Table, object_type);
@@ -2190,6 +2474,7 @@ fn parse_drop_table() {
             if_exists,
             names,
             cascade,
+            ..
         } => {
             assert_eq!(true, if_exists);
             assert_eq!(ObjectType::Table, object_type);
@@ -2204,14 +2489,14 @@ fn parse_drop_table() {
 
     let sql = "DROP TABLE";
     assert_eq!(
-        ParserError::ParserError("Expected identifier, found: EOF".to_string()),
-        parse_sql_statements(sql).unwrap_err(),
+        "Expected identifier, found: EOF".to_string(),
+        parse_sql_statements(sql).unwrap_err().message,
     );
 
     let sql = "DROP TABLE IF EXISTS foo, bar CASCADE RESTRICT";
     assert_eq!(
-        ParserError::ParserError("Cannot specify both CASCADE and RESTRICT in DROP".to_string()),
-        parse_sql_statements(sql).unwrap_err(),
+        "Cannot specify both CASCADE and RESTRICT in DROP".to_string(),
+        parse_sql_statements(sql).unwrap_err().message,
     );
 }
 
@@ -2232,38 +2517,89 @@ fn parse_drop_view() {
     }
 }
 
+#[test]
+fn parse_create_index() {
+    match verified_stmt("CREATE UNIQUE INDEX IF NOT EXISTS idx ON tab (a, lower(b) DESC)") {
+        Statement::CreateIndex {
+            name,
+            table_name,
+            columns,
+            unique,
+            if_not_exists,
+            ..
+        } => {
+            assert_eq!("idx", name.to_string());
+            assert_eq!("tab", table_name.to_string());
+            assert_eq!(
+                vec!["a", "lower(b) DESC"],
+                columns.iter().map(ToString::to_string).collect::<Vec<_>>()
+            );
+            assert!(unique);
+            assert!(if_not_exists);
+        }
+        _ => unreachable!(),
+    }
+    verified_stmt("CREATE INDEX idx ON myschema.tab (a)");
+
+    match verified_stmt("DROP INDEX IF EXISTS idx") {
+        Statement::Drop { object_type, .. } => assert_eq!(ObjectType::Index, object_type),
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn parse_invalid_subquery_without_parens() {
     let res = parse_sql_statements("SELECT SELECT 1 FROM bar WHERE 1=1 FROM baz");
     assert_eq!(
-        ParserError::ParserError("Expected end of statement, found: 1".to_string()),
-        res.unwrap_err()
+        "Expected end of statement, found: 1".to_string(),
+        res.unwrap_err().message
     );
 }
 
 #[test]
 fn parse_offset() {
     let ast = verified_query("SELECT foo FROM bar OFFSET 2 ROWS");
-    assert_eq!(ast.offset, Some(Expr::Value(number("2"))));
+    assert_eq!(
+        ast.offset,
+        Some(Expr::Value(number("2"), NodeSpan::empty()))
+    );
     let ast = verified_query("SELECT foo FROM bar WHERE foo = 4 OFFSET 2 ROWS");
-    assert_eq!(ast.offset, Some(Expr::Value(number("2"))));
+    assert_eq!(
+        ast.offset,
+        Some(Expr::Value(number("2"), NodeSpan::empty()))
+    );
     let ast = verified_query("SELECT foo FROM bar ORDER BY baz OFFSET 2 ROWS");
-    assert_eq!(ast.offset, Some(Expr::Value(number("2"))));
+    assert_eq!(
+        ast.offset,
+        Some(Expr::Value(number("2"), NodeSpan::empty()))
+    );
     let ast = verified_query("SELECT foo FROM bar WHERE foo = 4 ORDER BY baz OFFSET 2 ROWS");
-    assert_eq!(ast.offset, Some(Expr::Value(number("2"))));
+    assert_eq!(
+        ast.offset,
+        Some(Expr::Value(number("2"), NodeSpan::empty()))
+    );
     let ast = verified_query("SELECT foo FROM (SELECT * FROM bar OFFSET 2 ROWS) OFFSET 2 ROWS");
-    assert_eq!(ast.offset, Some(Expr::Value(number("2"))));
+    assert_eq!(
+        ast.offset,
+        Some(Expr::Value(number("2"), NodeSpan::empty()))
+    );
     match ast.body {
         SetExpr::Select(s) => match only(s.from).relation {
             TableFactor::Derived { subquery, .. } => {
-                assert_eq!(subquery.offset, Some(Expr::Value(number("2"))));
+                assert_eq!(
+                    subquery.offset,
+                    Some(Expr::Value(number("2"), NodeSpan::empty()))
+                );
             }
             _ => panic!("Test broke"),
         },
         _ => panic!("Test broke"),
     }
     let ast = verified_query("SELECT 'foo' OFFSET 0 ROWS");
-    assert_eq!(ast.offset, Some(Expr::Value(number("0"))));
+    assert_eq!(
+        ast.offset,
+        Some(Expr::Value(number("0"), NodeSpan::empty()))
+    );
 }
 
 #[test]
@@ -2279,7 +2615,7 @@ fn parse_fetch() {
     let fetch_first_two_rows_only = Some(Fetch {
         with_ties: false,
         percent: false,
-        quantity: Some(Expr::Value(number("2"))),
+        quantity: Some(Expr::Value(number("2"), NodeSpan::empty())),
     });
     let ast = verified_query("SELECT foo FROM bar FETCH FIRST 2 ROWS ONLY");
     assert_eq!(ast.fetch, fetch_first_two_rows_only);
@@ -2306,7 +2642,7 @@ fn parse_fetch() {
         Some(Fetch {
             with_ties: true,
             percent: false,
-            quantity: Some(Expr::Value(number("2"))),
+            quantity: Some(Expr::Value(number("2"), NodeSpan::empty())),
         })
     );
     let ast = verified_query("SELECT foo FROM bar FETCH FIRST 50 PERCENT ROWS ONLY");
@@ -2315,13 +2651,16 @@ fn parse_fetch() {
         Some(Fetch {
             with_ties: false,
             percent: true,
-            quantity: Some(Expr::Value(number("50"))),
+            quantity: Some(Expr::Value(number("50"), NodeSpan::empty())),
         })
     );
     let ast = verified_query(
         "SELECT foo FROM bar WHERE foo = 4 ORDER BY baz OFFSET 2 ROWS FETCH FIRST 2 ROWS ONLY",
     );
-    assert_eq!(ast.offset, Some(Expr::Value(number("2"))));
+    assert_eq!(
+        ast.offset,
+        Some(Expr::Value(number("2"), NodeSpan::empty()))
+    );
     assert_eq!(ast.fetch, fetch_first_two_rows_only);
     let ast = verified_query(
         "SELECT foo FROM (SELECT * FROM bar FETCH FIRST 2 ROWS ONLY) FETCH FIRST 2 ROWS ONLY",
@@ -2337,12 +2676,18 @@ fn parse_fetch() {
         _ => panic!("Test broke"),
     }
     let ast = verified_query("SELECT foo FROM (SELECT * FROM bar OFFSET 2 ROWS FETCH FIRST 2 ROWS ONLY) OFFSET 2 ROWS FETCH FIRST 2 ROWS ONLY");
-    assert_eq!(ast.offset, Some(Expr::Value(number("2"))));
+    assert_eq!(
+        ast.offset,
+        Some(Expr::Value(number("2"), NodeSpan::empty()))
+    );
     assert_eq!(ast.fetch, fetch_first_two_rows_only);
     match ast.body {
         SetExpr::Select(s) => match only(s.from).relation {
             TableFactor::Derived { subquery, .. } => {
-                assert_eq!(subquery.offset, Some(Expr::Value(number("2"))));
+                assert_eq!(
+                    subquery.offset,
+                    Some(Expr::Value(number("2"), NodeSpan::empty()))
+                );
                 assert_eq!(subquery.fetch, fetch_first_two_rows_only);
             }
             _ => panic!("Test broke"),
@@ -2390,12 +2735,16 @@ fn lateral_derived() {
         let join = &from.joins[0];
         assert_eq!(
             join.join_operator,
-            JoinOperator::LeftOuter(JoinConstraint::On(Expr::Value(Value::Boolean(true))))
+            JoinOperator::LeftOuter(JoinConstraint::On(Expr::Value(
+                Value::Boolean(true),
+                NodeSpan::empty()
+            )))
         );
         if let TableFactor::Derived {
             lateral,
             ref subquery,
             alias: Some(ref alias),
+            ..
         } = join.relation
         {
             assert_eq!(lateral_in, lateral);
@@ -2414,26 +2763,22 @@ fn lateral_derived() {
     let sql = "SELECT * FROM customer LEFT JOIN LATERAL generate_series(1, customer.id)";
     let res = parse_sql_statements(sql);
     assert_eq!(
-        ParserError::ParserError(
-            "Expected subquery after LATERAL, found: generate_series".to_string()
-        ),
-        res.unwrap_err()
+        "Expected subquery after LATERAL, found: generate_series".to_string(),
+        res.unwrap_err().message
     );
 
     let sql = "SELECT * FROM a LEFT JOIN LATERAL (b CROSS JOIN c)";
     let res = parse_sql_statements(sql);
     assert_eq!(
-        ParserError::ParserError(
-            "Expected SELECT, VALUES, or a subquery in the query body, found: b".to_string()
-        ),
-        res.unwrap_err()
+        "Expected SELECT, VALUES, or a subquery in the query body, found: b".to_string(),
+        res.unwrap_err().message
     );
 }
 
 #[test]
 fn parse_start_transaction() {
     match verified_stmt("START TRANSACTION READ ONLY, READ WRITE, ISOLATION LEVEL SERIALIZABLE") {
-        Statement::StartTransaction { modes } => assert_eq!(
+        Statement::StartTransaction { modes, .. } => assert_eq!(
             modes,
             vec![
                 TransactionMode::AccessMode(TransactionAccessMode::ReadOnly),
@@ -2450,7 +2795,7 @@ fn parse_start_transaction() {
         "START TRANSACTION READ ONLY READ WRITE ISOLATION LEVEL SERIALIZABLE",
         "START TRANSACTION READ ONLY, READ WRITE, ISOLATION LEVEL SERIALIZABLE",
     ) {
-        Statement::StartTransaction { modes } => assert_eq!(
+        Statement::StartTransaction { modes, .. } => assert_eq!(
             modes,
             vec![
                 TransactionMode::AccessMode(TransactionAccessMode::ReadOnly),
@@ -2484,20 +2829,20 @@ fn parse_start_transaction() {
 
     let res = parse_sql_statements("START TRANSACTION ISOLATION LEVEL BAD");
     assert_eq!(
-        ParserError::ParserError("Expected isolation level, found: BAD".to_string()),
-        res.unwrap_err()
+        "Expected isolation level, found: BAD".to_string(),
+        res.unwrap_err().message
     );
 
     let res = parse_sql_statements("START TRANSACTION BAD");
     assert_eq!(
-        ParserError::ParserError("Expected end of statement, found: BAD".to_string()),
-        res.unwrap_err()
+        "Expected end of statement, found: BAD".to_string(),
+        res.unwrap_err().message
     );
 
     let res = parse_sql_statements("START TRANSACTION READ ONLY,");
     assert_eq!(
-        ParserError::ParserError("Expected transaction mode, found: EOF".to_string()),
-        res.unwrap_err()
+        "Expected transaction mode, found: EOF".to_string(),
+        res.unwrap_err().message
     );
 }
 
@@ -2507,7 +2852,7 @@ fn parse_set_transaction() {
     // TRANSACTION, so no need to duplicate the tests here. We just do a quick
     // sanity check.
     match verified_stmt("SET TRANSACTION READ ONLY, READ WRITE, ISOLATION LEVEL SERIALIZABLE") {
-        Statement::SetTransaction { modes } => assert_eq!(
+        Statement::SetTransaction { modes, .. } => assert_eq!(
             modes,
             vec![
                 TransactionMode::AccessMode(TransactionAccessMode::ReadOnly),
@@ -2522,12 +2867,12 @@ fn parse_set_transaction() {
 #[test]
 fn parse_commit() {
     match verified_stmt("COMMIT") {
-        Statement::Commit { chain: false } => (),
+        Statement::Commit { chain: false, .. } => (),
         _ => unreachable!(),
     }
 
     match verified_stmt("COMMIT AND CHAIN") {
-        Statement::Commit { chain: true } => (),
+        Statement::Commit { chain: true, .. } => (),
         _ => unreachable!(),
     }
 
@@ -2543,12 +2888,12 @@ fn parse_commit() {
 #[test]
 fn parse_rollback() {
     match verified_stmt("ROLLBACK") {
-        Statement::Rollback { chain: false } => (),
+        Statement::Rollback { chain: false, .. } => (),
         _ => unreachable!(),
     }
 
     match verified_stmt("ROLLBACK AND CHAIN") {
-        Statement::Rollback { chain: true } => (),
+        Statement::Rollback { chain: true, .. } => (),
         _ => unreachable!(),
     }
 
@@ -2561,6 +2906,429 @@ fn parse_rollback() {
     one_statement_parses_to("ROLLBACK TRANSACTION", "ROLLBACK");
 }
 
+#[test]
+fn parse_error_details() {
+    let err = parse_sql_statements("SELECT a b\n  c FROM t").unwrap_err();
+    assert_eq!(ParserErrorKind::UnexpectedToken, err.kind);
+    assert_eq!(
+        (2, 3, 13),
+        (
+            err.span.start.line,
+            err.span.start.column,
+            err.span.start.offset
+        )
+    );
+    assert_eq!(Some(Box::new(Token::make_word("c", None))), err.found);
+    let expected: Vec<String> = err.expected.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        vec![
+            ",",
+            "FROM",
+            "WHERE",
+            "GROUP",
+            "HAVING",
+            "ORDER",
+            "LIMIT",
+            "OFFSET",
+            "FETCH",
+            ";",
+            "end of statement"
+        ],
+        expected
+    );
+
+    let err = parse_sql_statements("SELECT * FROM").unwrap_err();
+    assert_eq!(ParserErrorKind::UnexpectedEof, err.kind);
+    assert_eq!(None, err.found);
+    assert_eq!(13, err.span.start.offset);
+    assert_eq!(
+        vec![
+            Expected::Keyword(Keyword::LATERAL),
+            Expected::Token(Token::LParen),
+            Expected::Description("identifier".to_string()),
+        ],
+        err.expected
+    );
+
+    let err = parse_sql_statements("SELECT 1 FROM t1 CROSS JOIN t2 ON a").unwrap_err();
+    assert_eq!(ParserErrorKind::UnexpectedToken, err.kind);
+    assert_eq!(Some(Box::new(Token::make_keyword("ON"))), err.found);
+
+    let err = parse_sql_statements("DROP TABLE t CASCADE RESTRICT").unwrap_err();
+    assert_eq!(ParserErrorKind::InvalidSyntax, err.kind);
+
+    let err = parse_sql_statements("SELECT \"a").unwrap_err();
+    assert_eq!(ParserErrorKind::TokenizerError, err.kind);
+    assert_eq!(9, err.span.start.offset);
+}
+
+#[test]
+fn parse_statements_with_recovery() {
+    let sql = "CREATE TABLE a (x INT);\n\
+               CREATE FOO bar;\n\
+               INSERT INTO a VALUES (1)\n\
+               DROP TABLE a;\n\
+               SELECT 1 SELECT 2;\n\
+               SELECT 3";
+    let dialects = all_dialects();
+    let recovered =
+        dialects.one_of_identical_results(|dialect| Parser::parse_sql_recovering(dialect, sql));
+    let statements: Vec<String> = recovered.statements.iter().map(|s| s.to_string()).collect();
+    assert_eq!(
+        vec![
+            "CREATE TABLE a (x int)",
+            "INSERT INTO a VALUES (1)",
+            "DROP TABLE a",
+            "SELECT 1",
+            "SELECT 2",
+            "SELECT 3"
+        ],
+        statements
+    );
+    let failures: Vec<(usize, &str, &str)> = recovered
+        .failures
+        .iter()
+        .map(|f| {
+            (
+                f.position,
+                f.span().source_text(sql),
+                f.error.message.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                1,
+                "CREATE FOO bar",
+                "Expected TABLE, VIEW or INDEX after CREATE, found: FOO"
+            ),
+            // Statements missing only their semicolon are kept, and the
+            // failure is at the token where it should have been
+            (2, "DROP", "Expected end of statement, found: DROP"),
+            (4, "SELECT", "Expected end of statement, found: SELECT"),
+        ],
+        failures
+    );
+    assert!(recovered.failures[1].tokens.is_empty());
+
+    // Parsing stops at input that cannot be tokenized
+    let recovered = dialects
+        .one_of_identical_results(|dialect| Parser::parse_sql_recovering(dialect, "SELECT 1; !"));
+    assert_eq!(1, recovered.statements.len());
+    assert_eq!(1, recovered.failures.len());
+    assert_eq!(
+        ParserErrorKind::TokenizerError,
+        recovered.failures[0].error.kind
+    );
+    assert!(recovered.failures[0].tokens.is_empty());
+}
+
+#[test]
+fn parse_with_limits() {
+    let parse = |sql: &str, options: ParserOptions| {
+        all_dialects().one_of_identical_results(|dialect| {
+            Parser::parse_sql_with_options(dialect, sql.to_string(), options)
+        })
+    };
+    let limit_error = |sql: &str, options: ParserOptions| {
+        let err = parse(sql, options).unwrap_err();
+        assert_eq!(ParserErrorKind::LimitExceeded, err.kind);
+        (err.message, err.span.start.offset)
+    };
+
+    // The query and the expressions in it count towards the depth
+    let options = ParserOptions {
+        max_nesting_depth: 3,
+        ..ParserOptions::default()
+    };
+    assert!(parse("SELECT (1) FROM t", options).is_ok());
+    assert_eq!(
+        ("Exceeded the maximum nesting depth of 3".to_string(), 9),
+        limit_error("SELECT ((1)) FROM t", options)
+    );
+    // A chain of operators is not nested
+    let chain = |op: &str, n: usize| vec!["a = 1"; n].join(op);
+    let sql = format!("SELECT * FROM t WHERE {}", chain(" AND ", 300));
+    assert!(Parser::parse_sql(&GenericDialect {}, sql).is_ok());
+    let sql = format!("SELECT * FROM t WHERE {}", chain(" OR ", 1000));
+    assert!(Parser::parse_sql(&GenericDialect {}, sql).is_ok());
+    assert!(parse(
+        "SELECT 1 UNION SELECT 2 UNION SELECT 3 UNION SELECT 4",
+        options
+    )
+    .is_ok());
+    assert!(parse("SELECT a AND b AND c AND d AND e", options).is_ok());
+    let options = ParserOptions {
+        max_nesting_depth: 4,
+        ..ParserOptions::default()
+    };
+    // Exceeding the limit in a derived table is not mistaken for a nested
+    // join
+    assert!(parse("SELECT * FROM (SELECT 1) AS a", options).is_ok());
+    assert_eq!(
+        ("Exceeded the maximum nesting depth of 4".to_string(), 30),
+        limit_error(
+            "SELECT * FROM (SELECT * FROM (SELECT 1) AS a) AS b",
+            options
+        )
+    );
+
+    let options = ParserOptions {
+        max_tokens: Some(8),
+        ..ParserOptions::default()
+    };
+    assert!(parse("SELECT a FROM t", options).is_ok());
+    assert_eq!(
+        ("Exceeded the maximum of 8 tokens".to_string(), 16),
+        limit_error("SELECT a FROM t, u", options)
+    );
+
+    let options = ParserOptions {
+        max_input_length: Some(15),
+        ..ParserOptions::default()
+    };
+    assert!(parse("SELECT a FROM t", options).is_ok());
+    assert_eq!(
+        (
+            "Exceeded the maximum input length of 15 bytes".to_string(),
+            0
+        ),
+        limit_error("SELECT a FROM t2", options)
+    );
+    let tokens = Tokenizer::new(&GenericDialect {}, "SELECT a FROM t2");
+    let err = Parser::from_tokenizer(tokens)
+        .with_options(options)
+        .parse_statements()
+        .unwrap_err();
+    assert_eq!(ParserErrorKind::LimitExceeded, err.kind);
+    assert_eq!(14, err.span.start.offset);
+    // Tokens without locations are measured by their text
+    let dialect = GenericDialect {};
+    let parse_tokens = |sql: &str| {
+        let tokens = Tokenizer::new(&dialect, sql).tokenize().unwrap();
+        Parser::new(tokens, &dialect)
+            .with_options(options)
+            .parse_statements()
+    };
+    assert!(parse_tokens("SELECT a FROM t").is_ok());
+    let err = parse_tokens("SELECT a FROM t2").unwrap_err();
+    assert_eq!(ParserErrorKind::LimitExceeded, err.kind);
+    assert_eq!("Exceeded the maximum input length of 15 bytes", err.message);
+}
+
+#[test]
+fn parse_deeply_nested_input() {
+    // Parsing each level takes several times more stack in debug builds
+    // than in optimized ones, more than the default limit allows for in a
+    // test thread
+    let thread = std::thread::Builder::new().stack_size(16 * 1024 * 1024);
+    let test = || {
+        let parse = |sql: String| {
+            all_dialects()
+                .one_of_identical_results(|dialect| Parser::parse_sql(dialect, sql.clone()))
+        };
+        let limit_error = |sql: String| {
+            let err = parse(sql).unwrap_err();
+            assert_eq!(ParserErrorKind::LimitExceeded, err.kind);
+            err.message
+        };
+
+        // Deeply nested input fails instead of overflowing the stack
+        let depth = 100_000;
+        let sql = format!("SELECT {}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(
+            "Exceeded the maximum nesting depth of 256",
+            limit_error(sql)
+        );
+        limit_error(format!(
+            "SELECT * FROM {}t{}",
+            "(".repeat(depth),
+            ")".repeat(depth)
+        ));
+        limit_error(format!(
+            "{}SELECT 1{}",
+            "(".repeat(depth),
+            ")".repeat(depth)
+        ));
+        limit_error(format!(
+            "SELECT * FROM {}t{}",
+            "(SELECT * FROM ".repeat(depth),
+            ")".repeat(depth)
+        ));
+
+        // Reasonably nested input is accepted
+        let depth = 25;
+        assert!(parse(format!(
+            "SELECT * FROM {}t{}",
+            "(SELECT * FROM ".repeat(depth),
+            ") AS d".repeat(depth)
+        ))
+        .is_ok());
+
+        // Long chains of operators are not nested, so only the number of
+        // tokens limits them
+        let where_clause = |op: &str, n: usize| {
+            let terms: Vec<String> = (0..n).map(|i| format!("a = {}", i)).collect();
+            format!("SELECT * FROM t WHERE {}", terms.join(op))
+        };
+        assert!(parse(where_clause(" AND ", 300)).is_ok());
+        assert!(parse(where_clause(" OR ", 1000)).is_ok());
+        assert!(parse(vec!["SELECT 1"; 1000].join(" UNION ")).is_ok());
+        assert!(parse(format!("SELECT a{}", "::INT".repeat(1000))).is_ok());
+        let options = ParserOptions {
+            max_tokens: Some(1000),
+            ..ParserOptions::default()
+        };
+        let err =
+            Parser::parse_sql_with_options(&GenericDialect {}, where_clause(" OR ", 1000), options)
+                .unwrap_err();
+        assert_eq!(ParserErrorKind::LimitExceeded, err.kind);
+    };
+    thread.spawn(test).unwrap().join().unwrap();
+}
+
+#[test]
+fn parse_comments() {
+    let sql = "-- the users\n\
+               SELECT id, -- the key\n\
+               /* full */ name\n\
+               FROM users -- all of them\n\
+               /* active */ WHERE active; -- done";
+    let canonical = "-- the users\n\
+                     SELECT id -- the key\n, /* full */ name \
+                     FROM users -- all of them\n \
+                     /* active */ WHERE active -- done\n";
+    let statement = one_statement_parses_to(sql, canonical);
+    // The comments printed with a terminating semicolon before the ones
+    // trailing the statement are attached to the same nodes
+    let statements = vec![statement.clone()];
+    let script = Formatter::new(FormatOptions::default())
+        .format_statements(&statements)
+        .unwrap();
+    assert_eq!(
+        statements,
+        Parser::parse_sql(&GenericDialect {}, script).unwrap()
+    );
+    let (comments, statement) = match statement {
+        Statement::Commented {
+            comments,
+            statement,
+        } => (comments, statement),
+        _ => panic!("Expected a commented statement"),
+    };
+    assert_eq!(
+        vec![Comment::SingleLine(" the users".into())],
+        comments.leading
+    );
+    assert_eq!(vec![Comment::SingleLine(" done".into())], comments.trailing);
+    let select = match *statement {
+        Statement::Query(query) => match query.body {
+            SetExpr::Select(select) => select,
+            _ => panic!("Expected a SELECT"),
+        },
+        _ => panic!("Expected a query"),
+    };
+    assert_eq!(
+        vec![
+            SelectItem::UnnamedExpr(Expr::Identifier("id".into())).with_comments(Comments {
+                leading: vec![],
+                trailing: vec![Comment::SingleLine(" the key".into())],
+            }),
+            SelectItem::UnnamedExpr(Expr::Identifier("name".into())).with_comments(Comments {
+                leading: vec![Comment::MultiLine(" full ".into())],
+                trailing: vec![],
+            }),
+        ],
+        select.projection
+    );
+    assert_eq!(
+        ClauseComments {
+            from: Comments {
+                leading: vec![],
+                trailing: vec![Comment::SingleLine(" all of them".into())],
+            },
+            selection: Comments {
+                leading: vec![Comment::MultiLine(" active ".into())],
+                trailing: vec![],
+            },
+            ..ClauseComments::default()
+        },
+        select.comments
+    );
+
+    let sql = "CREATE TABLE t (\n\
+               -- the key\n\
+               a INT PRIMARY KEY, -- never null\n\
+               b TEXT /* free text */\n\
+               )";
+    match one_statement_parses_to(sql, "") {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(
+                Comments {
+                    leading: vec![Comment::SingleLine(" the key".into())],
+                    trailing: vec![Comment::SingleLine(" never null".into())],
+                },
+                columns[0].comments
+            );
+            assert_eq!(
+                Comments {
+                    leading: vec![],
+                    trailing: vec![Comment::MultiLine(" free text ".into())],
+                },
+                columns[1].comments
+            );
+        }
+        _ => unreachable!(),
+    }
+
+    // Comments elsewhere trail the nearest enclosing node, clause or
+    // statement that keeps comments
+    one_statement_parses_to(
+        "SELECT a + /* one */ 1 FROM t",
+        "SELECT a + 1 /* one */ FROM t",
+    );
+    let select = match one_statement_parses_to(
+        "SELECT a FROM t WHERE x = 1 -- cond1\n AND y = 2 -- cond2\n",
+        "SELECT a FROM t WHERE x = 1 AND y = 2 -- cond1\n -- cond2\n",
+    ) {
+        Statement::Query(query) => match query.body {
+            SetExpr::Select(select) => select,
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    assert_eq!(
+        vec![
+            Comment::SingleLine(" cond1".into()),
+            Comment::SingleLine(" cond2".into())
+        ],
+        select.comments.selection.trailing
+    );
+    let trailing = |sql: &str| match one_statement_parses_to(sql, "") {
+        Statement::Commented { comments, .. } => comments.trailing,
+        _ => vec![],
+    };
+    assert_eq!(
+        vec![
+            Comment::SingleLine(" order".into()),
+            Comment::MultiLine(" limit ".into())
+        ],
+        trailing("SELECT a FROM t ORDER BY a -- order\n LIMIT /* limit */ 1")
+    );
+    assert_eq!(
+        vec![Comment::SingleLine(" set".into())],
+        trailing("UPDATE t SET a = 1 -- set\n, b = 2")
+    );
+    assert_eq!(
+        vec![
+            Comment::SingleLine(" target".into()),
+            Comment::SingleLine(" values".into())
+        ],
+        trailing("INSERT INTO t -- target\nVALUES (1) -- values\n")
+    );
+}
+
 #[test]
 #[should_panic(expected = "Parse results with GenericDialect are different from PostgreSqlDialect")]
 fn ensure_multiple_dialects_are_tested() {
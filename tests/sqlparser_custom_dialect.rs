@@ -0,0 +1,166 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![warn(clippy::all)]
+
+//! Test the hooks that let a dialect extend the parser.
+
+use sqlparser::ast::*;
+use sqlparser::dialect::keywords::Keyword;
+use sqlparser::dialect::{Dialect, GenericDialect};
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::test_utils::*;
+use sqlparser::tokenizer::Token;
+
+/// A dialect with a `||` operator, `{name}` template parameters and a
+/// `TRUNCATE table` statement
+#[derive(Debug)]
+struct CustomDialect {}
+
+impl Dialect for CustomDialect {
+    fn is_identifier_start(&self, ch: char) -> bool {
+        GenericDialect {}.is_identifier_start(ch)
+    }
+
+    fn is_identifier_part(&self, ch: char) -> bool {
+        GenericDialect {}.is_identifier_part(ch)
+    }
+
+    fn parse_statement(&self, parser: &mut Parser) -> Option<Result<Statement, ParserError>> {
+        if parser.parse_keyword(Keyword::TRUNCATE) {
+            Some(
+                parser
+                    .parse_object_name()
+                    .map(|table_name| Statement::Delete {
+                        table_name,
+                        selection: None,
+                        span: NodeSpan::empty(),
+                    }),
+            )
+        } else {
+            None
+        }
+    }
+
+    fn parse_prefix(&self, parser: &mut Parser) -> Option<Result<Expr, ParserError>> {
+        if parser.consume_token(&Token::LBrace) {
+            Some(parser.parse_identifier().and_then(|ident| {
+                parser.expect_token(&Token::RBrace)?;
+                Ok(Expr::Identifier(Ident::new(format!("{{{}}}", ident))))
+            }))
+        } else {
+            None
+        }
+    }
+
+    fn parse_infix(
+        &self,
+        parser: &mut Parser,
+        expr: &Expr,
+        precedence: u8,
+    ) -> Option<Result<Expr, ParserError>> {
+        if is_concat(parser) {
+            parser.next_token();
+            parser.next_token();
+            Some(
+                parser
+                    .parse_subexpr(precedence)
+                    .map(|right| Expr::BinaryOp {
+                        left: Box::new(expr.clone()),
+                        op: BinaryOperator::Custom("||".to_string()),
+                        right: Box::new(right),
+                    }),
+            )
+        } else {
+            None
+        }
+    }
+
+    fn get_next_precedence(&self, parser: &Parser) -> Option<Result<u8, ParserError>> {
+        if is_concat(parser) {
+            Some(Ok(30))
+        } else {
+            None
+        }
+    }
+}
+
+fn is_concat(parser: &Parser) -> bool {
+    parser.peek_token() == Some(Token::Char('|'))
+        && parser.peek_nth_token(1) == Some(Token::Char('|'))
+}
+
+fn custom() -> TestedDialects {
+    TestedDialects {
+        dialects: vec![Box::new(CustomDialect {})],
+    }
+}
+
+#[test]
+fn custom_statement_parser() {
+    let sql = "TRUNCATE db.t";
+    assert_eq!(
+        custom().one_statement_parses_to(sql, "DELETE FROM db.t"),
+        Statement::Delete {
+            table_name: ObjectName(vec![Ident::new("db"), Ident::new("t")]),
+            selection: None,
+            span: NodeSpan::empty(),
+        }
+    );
+    // Other statements are still handled by the built-in parser
+    custom().verified_stmt("DELETE FROM t WHERE a = 1");
+}
+
+#[test]
+fn custom_prefix_parser() {
+    let select = custom().verified_only_select("SELECT {x} + 1");
+    assert_eq!(
+        &Expr::BinaryOp {
+            left: Box::new(Expr::Identifier(Ident::new("{x}"))),
+            op: BinaryOperator::Plus,
+            right: Box::new(Expr::Value(number("1"), NodeSpan::empty())),
+        },
+        expr_from_projection(only(&select.projection)),
+    );
+}
+
+#[test]
+fn custom_infix_parser() {
+    let select = custom().verified_only_select("SELECT a || b * c || d = e");
+    let concat = |left, right| Expr::BinaryOp {
+        left: Box::new(left),
+        op: BinaryOperator::Custom("||".to_string()),
+        right: Box::new(right),
+    };
+    let ident = |name| Expr::Identifier(Ident::new(name));
+    assert_eq!(
+        &Expr::BinaryOp {
+            left: Box::new(concat(
+                concat(
+                    ident("a"),
+                    Expr::BinaryOp {
+                        left: Box::new(ident("b")),
+                        op: BinaryOperator::Multiply,
+                        right: Box::new(ident("c")),
+                    }
+                ),
+                ident("d")
+            )),
+            op: BinaryOperator::Eq,
+            right: Box::new(ident("e")),
+        },
+        expr_from_projection(only(&select.projection)),
+    );
+
+    let res = custom().parse_sql_statements("SELECT a ||");
+    assert_eq!("Unexpected EOF".to_string(), res.unwrap_err().message);
+}
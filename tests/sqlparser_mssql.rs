@@ -68,6 +68,29 @@ fn parse_mssql_apply_join() {
     );
 }
 
+#[test]
+fn parse_mssql_placeholders() {
+    let select = ms().verified_only_select("SELECT @@version WHERE a = @p1");
+    assert_eq!(
+        &Expr::Identifier(Ident::new("@@version")),
+        expr_from_projection(&select.projection[0]),
+    );
+    assert_eq!(
+        Some(Expr::BinaryOp {
+            left: Box::new(Expr::Identifier(Ident::new("a"))),
+            op: BinaryOperator::Eq,
+            right: Box::new(Expr::Placeholder("@p1".to_string(), NodeSpan::empty())),
+        }),
+        select.selection
+    );
+
+    // Variables are spelled the same way
+    match ms().verified_stmt("SET @x = 1") {
+        Statement::SetVariable { variable, .. } => assert_eq!(Ident::new("@x"), variable),
+        _ => unreachable!(),
+    }
+}
+
 fn ms() -> TestedDialects {
     TestedDialects {
         dialects: vec![Box::new(MsSqlDialect {})],
@@ -34,6 +34,7 @@ fn parse_show_columns() {
             full: false,
             table_name: table_name.clone(),
             filter: None,
+            span: NodeSpan::empty(),
         }
     );
     assert_eq!(
@@ -43,6 +44,7 @@ fn parse_show_columns() {
             full: false,
             table_name: ObjectName(vec![Ident::new("mydb"), Ident::new("mytable")]),
             filter: None,
+            span: NodeSpan::empty(),
         }
     );
     assert_eq!(
@@ -52,6 +54,7 @@ fn parse_show_columns() {
             full: false,
             table_name: table_name.clone(),
             filter: None,
+            span: NodeSpan::empty(),
         }
     );
     assert_eq!(
@@ -61,6 +64,7 @@ fn parse_show_columns() {
             full: true,
             table_name: table_name.clone(),
             filter: None,
+            span: NodeSpan::empty(),
         }
     );
     assert_eq!(
@@ -70,6 +74,7 @@ fn parse_show_columns() {
             full: false,
             table_name: table_name.clone(),
             filter: Some(ShowStatementFilter::Like("pattern".into())),
+            span: NodeSpan::empty(),
         }
     );
     assert_eq!(
@@ -81,6 +86,7 @@ fn parse_show_columns() {
             filter: Some(ShowStatementFilter::Where(
                 mysql_and_generic().verified_expr("1 = 2")
             )),
+            span: NodeSpan::empty(),
         }
     );
     mysql_and_generic()
@@ -97,6 +103,54 @@ fn parse_show_columns() {
     }
 }
 
+#[test]
+fn parse_question_mark_placeholders() {
+    let sql = "INSERT INTO t (a, b) VALUES (?, ?)";
+    match mysql_and_generic().verified_stmt(sql) {
+        Statement::Insert { source, .. } => match source.body {
+            SetExpr::Values(Values(rows)) => assert_eq!(
+                vec![vec![
+                    Expr::Placeholder("?".to_string(), NodeSpan::empty()),
+                    Expr::Placeholder("?".to_string(), NodeSpan::empty())
+                ]],
+                rows
+            ),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+    mysql().verified_stmt("SELECT * FROM t WHERE a = ? LIMIT ?");
+}
+
+#[test]
+fn parse_backtick_identifiers() {
+    let select = mysql().verified_only_select("SELECT `a b` FROM `t`");
+    assert_eq!(
+        &Expr::Identifier(Ident::with_quote('`', "a b")),
+        expr_from_projection(&select.projection[0])
+    );
+}
+
+#[test]
+fn parse_limit_offset() {
+    // MySQL writes the offset without ROWS
+    let sql = "SELECT a FROM t LIMIT 10 OFFSET 5";
+    match mysql().one_statement_parses_to(sql, "SELECT a FROM t LIMIT 10 OFFSET 5 ROWS") {
+        Statement::Query(query) => {
+            assert_eq!(
+                Some(Expr::Value(number("10"), NodeSpan::empty())),
+                query.limit
+            );
+            assert_eq!(
+                Some(Expr::Value(number("5"), NodeSpan::empty())),
+                query.offset
+            );
+        }
+        _ => unreachable!(),
+    }
+    mysql().verified_query("SELECT a FROM t LIMIT 10 OFFSET 5 ROWS");
+}
+
 fn mysql() -> TestedDialects {
     TestedDialects {
         dialects: vec![Box::new(MySqlDialect {})],
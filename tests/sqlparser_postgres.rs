@@ -16,7 +16,6 @@
 
 use sqlparser::ast::*;
 use sqlparser::dialect::{GenericDialect, PostgreSqlDialect};
-use sqlparser::parser::ParserError;
 use sqlparser::test_utils::*;
 
 #[test]
@@ -42,10 +41,11 @@ fn parse_create_table_with_defaults() {
             external: false,
             file_format: None,
             location: None,
+            ..
         } => {
             assert_eq!("public.customer", name.to_string());
             assert_eq!(
-                columns,
+                columns.into_iter().map(|c| c.node).collect::<Vec<_>>(),
                 vec![
                     ColumnDef {
                         name: "customer_id".into(),
@@ -107,7 +107,10 @@ fn parse_create_table_with_defaults() {
                         options: vec![
                             ColumnOptionDef {
                                 name: None,
-                                option: ColumnOption::Default(Expr::Value(Value::Boolean(true))),
+                                option: ColumnOption::Default(Expr::Value(
+                                    Value::Boolean(true),
+                                    NodeSpan::empty()
+                                )),
                             },
                             ColumnOptionDef {
                                 name: None,
@@ -261,6 +264,7 @@ fn parse_set() {
             local: false,
             variable: "a".into(),
             value: SetVariableValue::Ident("b".into()),
+            span: NodeSpan::empty(),
         }
     );
 
@@ -271,6 +275,7 @@ fn parse_set() {
             local: false,
             variable: "a".into(),
             value: SetVariableValue::Literal(Value::SingleQuotedString("b".into())),
+            span: NodeSpan::empty(),
         }
     );
 
@@ -281,6 +286,7 @@ fn parse_set() {
             local: false,
             variable: "a".into(),
             value: SetVariableValue::Literal(number("0")),
+            span: NodeSpan::empty(),
         }
     );
 
@@ -291,6 +297,7 @@ fn parse_set() {
             local: false,
             variable: "a".into(),
             value: SetVariableValue::Ident("DEFAULT".into()),
+            span: NodeSpan::empty(),
         }
     );
 
@@ -301,6 +308,7 @@ fn parse_set() {
             local: true,
             variable: "a".into(),
             value: SetVariableValue::Ident("b".into()),
+            span: NodeSpan::empty(),
         }
     );
 
@@ -308,24 +316,27 @@ fn parse_set() {
     pg_and_generic().one_statement_parses_to("SET SESSION a = b", "SET a = b");
 
     assert_eq!(
-        pg_and_generic().parse_sql_statements("SET"),
-        Err(ParserError::ParserError(
-            "Expected identifier, found: EOF".to_string()
-        )),
+        pg_and_generic()
+            .parse_sql_statements("SET")
+            .unwrap_err()
+            .message,
+        "Expected identifier, found: EOF",
     );
 
     assert_eq!(
-        pg_and_generic().parse_sql_statements("SET a b"),
-        Err(ParserError::ParserError(
-            "Expected equals sign or TO, found: b".to_string()
-        )),
+        pg_and_generic()
+            .parse_sql_statements("SET a b")
+            .unwrap_err()
+            .message,
+        "Expected equals sign or TO, found: b",
     );
 
     assert_eq!(
-        pg_and_generic().parse_sql_statements("SET a ="),
-        Err(ParserError::ParserError(
-            "Expected variable value, found: EOF".to_string()
-        )),
+        pg_and_generic()
+            .parse_sql_statements("SET a =")
+            .unwrap_err()
+            .message,
+        "Expected variable value, found: EOF",
     );
 }
 
@@ -335,7 +346,8 @@ fn parse_show() {
     assert_eq!(
         stmt,
         Statement::ShowVariable {
-            variable: "a".into()
+            variable: "a".into(),
+            span: NodeSpan::empty(),
         }
     );
 
@@ -343,11 +355,32 @@ fn parse_show() {
     assert_eq!(
         stmt,
         Statement::ShowVariable {
-            variable: "ALL".into()
+            variable: "ALL".into(),
+            span: NodeSpan::empty(),
         }
     )
 }
 
+#[test]
+fn parse_dollar_placeholders() {
+    let sql = "SELECT * FROM t WHERE a = $1 AND b IN ($2, $10) LIMIT $3 OFFSET $4 ROWS";
+    let select = pg_and_generic().verified_query(sql);
+    assert_eq!(
+        Some(Expr::Placeholder("$3".to_string(), NodeSpan::empty())),
+        select.limit
+    );
+    assert_eq!(
+        Some(Expr::Placeholder("$4".to_string(), NodeSpan::empty())),
+        select.offset
+    );
+
+    let res = pg().parse_sql_statements("SELECT ?");
+    assert_eq!(
+        "Expected an expression, found: ?".to_string(),
+        res.unwrap_err().message
+    );
+}
+
 fn pg() -> TestedDialects {
     TestedDialects {
         dialects: vec![Box::new(PostgreSqlDialect {})],
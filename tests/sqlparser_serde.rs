@@ -0,0 +1,63 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![warn(clippy::all)]
+#![cfg(feature = "serde")]
+
+//! Test serializing the AST and tokens with serde (requires the `serde`
+//! feature).
+
+use sqlparser::ast::*;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+#[test]
+fn statements_round_trip_through_json() {
+    let sql = "WITH cte AS (SELECT a FROM t) \
+               SELECT DISTINCT a, COUNT(*) AS c FROM cte JOIN u ON cte.a = u.b \
+               WHERE a BETWEEN 1 AND 2.5 GROUP BY a ORDER BY c DESC LIMIT 10; \
+               CREATE TABLE t (a INT NOT NULL, b VARCHAR(10) DEFAULT 'x'); \
+               INSERT INTO t (a) VALUES (1), (NULL)";
+    let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+    let json = serde_json::to_string(&statements).unwrap();
+    let deserialized: Vec<Statement> = serde_json::from_str(&json).unwrap();
+    assert_eq!(statements, deserialized);
+
+    // Spans are not compared by `==`, so check that they survive separately
+    let span = |statements: &[Statement]| match &statements[0] {
+        Statement::Query(query) => query.ctes[0].alias.name.span,
+        _ => unreachable!(),
+    };
+    assert_eq!(span(&statements), span(&deserialized));
+    assert_eq!(span(&deserialized).source_text(sql), "cte");
+}
+
+#[test]
+fn tokens_round_trip_through_json() {
+    let sql = "SELECT 'it''s' -- comment\n";
+    let tokens = Tokenizer::new(&GenericDialect {}, sql).tokenize().unwrap();
+    let json = serde_json::to_string(&tokens).unwrap();
+    let deserialized: Vec<Token> = serde_json::from_str(&json).unwrap();
+    assert_eq!(tokens, deserialized);
+}
+
+#[cfg(feature = "bigdecimal")]
+#[test]
+fn numbers_serialize_without_losing_precision() {
+    let sql = "SELECT 123456789012345678901234567890.123456789";
+    let statements = Parser::parse_sql(&GenericDialect {}, sql.to_string()).unwrap();
+    let json = serde_json::to_string(&statements).unwrap();
+    assert!(json.contains("\"123456789012345678901234567890.123456789\""));
+    let deserialized: Vec<Statement> = serde_json::from_str(&json).unwrap();
+    assert_eq!(statements, deserialized);
+}